@@ -0,0 +1,249 @@
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::mod_config::{self, ModsConfig};
+use crate::{confirm, settings, storage};
+
+/// Format version of exported allowlist files.
+const ALLOWLIST_FORMAT: u32 = 1;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AllowedMod {
+    pub dev: String,
+    pub name: String,
+}
+
+impl AllowedMod {
+    fn label(&self) -> String {
+        format!("{}-{}", self.dev, self.name)
+    }
+}
+
+/// Locally pinned list of mods that strict mode lets through.
+///
+/// Exported by a group admin from the manifest they trust and imported by members, so a
+/// compromised manifest host can't push new mods to cautious groups.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Allowlist {
+    #[serde(default = "default_format")]
+    pub format: u32,
+    pub mods: Vec<AllowedMod>,
+}
+
+fn default_format() -> u32 {
+    ALLOWLIST_FORMAT
+}
+
+impl Allowlist {
+    pub fn allows(&self, dev: &str, name: &str) -> bool {
+        self.mods
+            .iter()
+            .any(|m| m.dev.eq_ignore_ascii_case(dev) && m.name.eq_ignore_ascii_case(name))
+    }
+}
+
+fn allowlist_path(app: &tauri::AppHandle) -> Result<PathBuf, String> {
+    Ok(storage::state_dir(app)?.join("allowlist.json"))
+}
+
+fn read_allowlist_file(path: &Path) -> Result<Allowlist, String> {
+    let text = std::fs::read_to_string(path).map_err(|e| format!("{}: {e}", path.display()))?;
+    let list: Allowlist = serde_json::from_str(&text)
+        .map_err(|e| format!("invalid allowlist {}: {e}", path.display()))?;
+    if list.format > ALLOWLIST_FORMAT {
+        return Err(format!(
+            "allowlist format {} is newer than supported ({ALLOWLIST_FORMAT})",
+            list.format
+        ));
+    }
+    Ok(list)
+}
+
+/// The pinned allowlist, if one was imported.
+pub fn load(app: &tauri::AppHandle) -> Result<Option<Allowlist>, String> {
+    let path = allowlist_path(app)?;
+    if !path.exists() {
+        return Ok(None);
+    }
+    read_allowlist_file(&path).map(Some)
+}
+
+/// Split `cfg` into the mods strict mode lets through and the labels (`dev-name`) it
+/// rejects. Everything passes when strict mode is off; nothing does without a list.
+pub fn enforce(
+    app: &tauri::AppHandle,
+    cfg: &ModsConfig,
+) -> Result<(ModsConfig, Vec<String>), String> {
+    if !settings::load(app).strict_allowlist {
+        return Ok((cfg.clone(), Vec::new()));
+    }
+    let list = load(app)?;
+    let (allowed, rejected): (Vec<_>, Vec<_>) = cfg
+        .mods
+        .iter()
+        .cloned()
+        .partition(|m| list.as_ref().is_some_and(|l| l.allows(&m.dev, &m.name)));
+    let rejected: Vec<String> = rejected
+        .iter()
+        .map(|m| format!("{}-{}", m.dev, m.name))
+        .collect();
+    for label in &rejected {
+        log::warn!("Strict mode: {label} is not on the allowlist; not installing");
+    }
+    Ok((ModsConfig { mods: allowed }, rejected))
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct AllowlistStatus {
+    pub strict: bool,
+    pub mods: Vec<AllowedMod>,
+    /// Manifest mods strict mode currently holds back (empty when strict mode is off).
+    pub pending: Vec<String>,
+}
+
+#[tauri::command]
+pub async fn get_allowlist_status(app: tauri::AppHandle) -> Result<AllowlistStatus, String> {
+    let strict = settings::load(&app).strict_allowlist;
+    let mods = load(&app)?.map(|l| l.mods).unwrap_or_default();
+    let pending = if strict {
+        let client = reqwest::Client::new();
+        let remote = mod_config::fetch_remote_manifest(&client).await?;
+        enforce(&app, &ModsConfig { mods: remote.mods })?.1
+    } else {
+        Vec::new()
+    };
+    Ok(AllowlistStatus {
+        strict,
+        mods,
+        pending,
+    })
+}
+
+/// Write every mod of the current remote manifest to `path` (group admin side).
+#[tauri::command]
+pub async fn export_allowlist(path: String) -> Result<u32, String> {
+    let client = reqwest::Client::new();
+    let remote = mod_config::fetch_remote_manifest(&client).await?;
+    let list = Allowlist {
+        format: ALLOWLIST_FORMAT,
+        mods: remote
+            .mods
+            .iter()
+            .map(|m| AllowedMod {
+                dev: m.dev.clone(),
+                name: m.name.clone(),
+            })
+            .collect(),
+    };
+    let json = serde_json::to_string_pretty(&list).map_err(|e| e.to_string())?;
+    crate::zip_utils::write_atomic(Path::new(&path), json)?;
+    log::info!("Exported allowlist with {} mods to {path}", list.mods.len());
+    Ok(list.mods.len() as u32)
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct AllowlistImport {
+    pub added: Vec<String>,
+    pub removed: Vec<String>,
+    /// Set on the dry run; pass it back to actually replace the pinned list.
+    pub confirm_token: Option<String>,
+    pub done: bool,
+}
+
+/// Two-phase import of an allowlist file (member side); see `confirm`.
+#[tauri::command]
+pub fn import_allowlist(
+    app: tauri::AppHandle,
+    path: String,
+    confirm_token: Option<String>,
+) -> Result<AllowlistImport, String> {
+    let incoming = read_allowlist_file(Path::new(&path))?;
+    let current = load(&app)?.map(|l| l.mods).unwrap_or_default();
+    let added = incoming
+        .mods
+        .iter()
+        .filter(|m| !current.contains(m))
+        .map(AllowedMod::label)
+        .collect();
+    let removed = current
+        .iter()
+        .filter(|m| !incoming.mods.contains(m))
+        .map(AllowedMod::label)
+        .collect();
+    let action = format!("import_allowlist:{path}");
+
+    let Some(token) = confirm_token else {
+        return Ok(AllowlistImport {
+            added,
+            removed,
+            confirm_token: Some(confirm::issue(&app, &action)?),
+            done: false,
+        });
+    };
+    confirm::consume(&app, &action, &token)?;
+
+    let dest = allowlist_path(&app)?;
+    if let Some(parent) = dest.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    let json = serde_json::to_string_pretty(&incoming).map_err(|e| e.to_string())?;
+    crate::zip_utils::write_atomic(&dest, json)?;
+    log::info!(
+        "Imported allowlist from {path} ({} mods)",
+        incoming.mods.len()
+    );
+
+    Ok(AllowlistImport {
+        added,
+        removed,
+        confirm_token: None,
+        done: true,
+    })
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct StrictModeChange {
+    pub enabled: bool,
+    /// Set when turning strict mode off; pass it back to confirm.
+    pub confirm_token: Option<String>,
+    pub done: bool,
+}
+
+/// Turn strict mode on (needs an imported allowlist) or off (two-phase, see `confirm`).
+#[tauri::command]
+pub fn set_strict_allowlist(
+    app: tauri::AppHandle,
+    enabled: bool,
+    confirm_token: Option<String>,
+) -> Result<StrictModeChange, String> {
+    if enabled {
+        if load(&app)?.is_none() {
+            return Err("import an allowlist before enabling strict mode".to_string());
+        }
+    } else {
+        let action = "set_strict_allowlist:off";
+        let Some(token) = confirm_token else {
+            return Ok(StrictModeChange {
+                enabled,
+                confirm_token: Some(confirm::issue(&app, action)?),
+                done: false,
+            });
+        };
+        confirm::consume(&app, action, &token)?;
+    }
+
+    let mut s = settings::load(&app);
+    s.strict_allowlist = enabled;
+    settings::save(&app, &s)?;
+    log::info!(
+        "Strict allowlist mode {}",
+        if enabled { "enabled" } else { "disabled" }
+    );
+
+    Ok(StrictModeChange {
+        enabled,
+        confirm_token: None,
+        done: true,
+    })
+}
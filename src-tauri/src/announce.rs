@@ -0,0 +1,157 @@
+//! Spoken task status for screen readers, so a progress bar doesn't move silently.
+//!
+//! Announcements go out as `a11y-announce`; the frontend writes them into an ARIA live
+//! region, which the webview hands to the platform accessibility API (UI Automation on
+//! Windows, AT-SPI on Linux, NSAccessibility on macOS). NVDA, Orca and VoiceOver then read
+//! them without moving focus.
+//!
+//! Driven from `progress` like `taskbar`. Step changes, completion and failures are always
+//! announced; progress within a step only at each quarter, and no more than once per
+//! `MIN_PROGRESS_INTERVAL`, so a fast download doesn't flood the speech queue.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use serde::Serialize;
+use tauri::AppHandle;
+
+use crate::progress;
+
+/// Shortest gap between two in-step progress announcements.
+const MIN_PROGRESS_INTERVAL: Duration = Duration::from_secs(10);
+
+static ENABLED: AtomicBool = AtomicBool::new(true);
+
+/// Last step and quarter announced per task, to announce only changes.
+static LAST: Mutex<Option<Announced>> = Mutex::new(None);
+
+#[derive(Debug, Clone, Copy)]
+struct Announced {
+    version: u32,
+    step: u32,
+    quarter: u8,
+    at: Instant,
+}
+
+#[derive(Debug, Clone, Copy, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Politeness {
+    /// Read once the screen reader is idle (`aria-live="polite"`).
+    Polite,
+    /// Interrupts current speech (`aria-live="assertive"`); failures only.
+    Assertive,
+}
+
+/// Event name: `a11y-announce`
+#[derive(Debug, Clone, Serialize)]
+pub struct AnnouncementPayload {
+    pub version: u32,
+    pub message: String,
+    pub politeness: Politeness,
+}
+
+/// Turn announcements on or off (`Settings::screen_reader_announcements`).
+pub fn set_enabled(enabled: bool) {
+    ENABLED.store(enabled, Ordering::Relaxed);
+}
+
+fn announce(app: &AppHandle, version: u32, message: String, politeness: Politeness) {
+    if !ENABLED.load(Ordering::Relaxed) {
+        return;
+    }
+    progress::emit(
+        app,
+        "a11y-announce",
+        AnnouncementPayload {
+            version,
+            message,
+            politeness,
+        },
+    );
+}
+
+/// A progress event went out; announce it if it starts a step or crosses a quarter.
+pub fn progress(app: &AppHandle, payload: &progress::TaskProgressPayload) {
+    let quarter = (payload.step_progress.clamp(0.0, 1.0) * 4.0).floor() as u8;
+    let message = {
+        let Ok(mut last) = LAST.lock() else {
+            return;
+        };
+        let now = Instant::now();
+        let message = match *last {
+            Some(prev) if prev.version == payload.version && prev.step == payload.step => {
+                // The step's end is covered by the next step or `finished`.
+                if quarter <= prev.quarter
+                    || quarter >= 4
+                    || now.duration_since(prev.at) < MIN_PROGRESS_INTERVAL
+                {
+                    return;
+                }
+                format!(
+                    "{}: {}% ({}% overall)",
+                    payload.step_name,
+                    u32::from(quarter) * 25,
+                    payload.overall_percent.clamp(0.0, 100.0).round()
+                )
+            }
+            _ => format!(
+                "Step {} of {}: {}",
+                payload.step, payload.steps_total, payload.step_name
+            ),
+        };
+        *last = Some(Announced {
+            version: payload.version,
+            step: payload.step,
+            quarter,
+            at: now,
+        });
+        message
+    };
+    announce(app, payload.version, message, Politeness::Polite);
+}
+
+fn reset() {
+    if let Ok(mut last) = LAST.lock() {
+        *last = None;
+    }
+}
+
+pub fn finished(app: &AppHandle, version: u32) {
+    reset();
+    announce(
+        app,
+        version,
+        format!("Version {version} is ready"),
+        Politeness::Polite,
+    );
+}
+
+pub fn failed(app: &AppHandle, version: u32, message: &str) {
+    reset();
+    announce(
+        app,
+        version,
+        format!("Version {version} failed: {message}"),
+        Politeness::Assertive,
+    );
+}
+
+pub fn cancelled(app: &AppHandle, version: u32) {
+    reset();
+    announce(
+        app,
+        version,
+        format!("Version {version} cancelled"),
+        Politeness::Polite,
+    );
+}
+
+pub fn stalled(app: &AppHandle, version: u32, step_name: &str) {
+    announce(
+        app,
+        version,
+        format!("{step_name} has stopped making progress"),
+        Politeness::Polite,
+    );
+}
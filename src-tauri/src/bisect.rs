@@ -0,0 +1,270 @@
+//! Binary search for the mod that breaks a game version.
+//!
+//! `start_bisect` takes every non-required plugin folder as a suspect and leaves only the
+//! first half enabled (plus required mods and dependencies); the rest moves to
+//! `BepInEx/plugins_bisect/`. After each launch the player calls `bisect_report` with
+//! `ok` or `crash`, halving the suspects until one is left. State lives in
+//! `{game_root}/.hq-launcher/bisect.json`, so a bisect survives launcher restarts.
+
+use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{crash_guard, mods};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BisectResult {
+    Ok,
+    Crash,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct BisectState {
+    step: u32,
+    /// Plugin folders that may still be the culprit.
+    suspects: Vec<String>,
+    /// Suspects enabled for the current test run.
+    testing: Vec<String>,
+    /// Folders currently moved to `plugins_bisect`.
+    aside: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct BisectStatus {
+    pub version: u32,
+    pub step: u32,
+    pub suspects: Vec<String>,
+    /// Suspects enabled for the next launch.
+    pub testing: Vec<String>,
+    /// Test launches left in the worst case.
+    pub remaining_steps: u32,
+    pub done: bool,
+    /// Set when the search narrowed down to one mod.
+    pub culprit: Option<String>,
+}
+
+fn aside_dir(game_root: &Path) -> PathBuf {
+    game_root.join("BepInEx").join("plugins_bisect")
+}
+
+fn state_path(game_root: &Path) -> PathBuf {
+    game_root.join(".hq-launcher").join("bisect.json")
+}
+
+fn read_state(game_root: &Path) -> Option<BisectState> {
+    let text = std::fs::read_to_string(state_path(game_root)).ok()?;
+    serde_json::from_str(&text).ok()
+}
+
+fn write_state(game_root: &Path, state: &BisectState) -> Result<(), String> {
+    let path = state_path(game_root);
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    let json = serde_json::to_string_pretty(state).map_err(|e| e.to_string())?;
+    crate::zip_utils::write_atomic(&path, json)
+}
+
+/// Whether a bisect is in progress for this install.
+pub fn is_active(game_root: &Path) -> bool {
+    state_path(game_root).exists()
+}
+
+/// Refuse to install or update mods in `game_root` while a bisect has some of them moved
+/// aside: installs are add-only and would put the moved folders back.
+pub fn ensure_inactive(game_root: &Path, version: u32) -> Result<(), String> {
+    if is_active(game_root) {
+        return Err(format!(
+            "a mod bisect is in progress for v{version}; finish or cancel it before syncing or updating mods"
+        ));
+    }
+    Ok(())
+}
+
+fn remaining_steps(suspects: usize) -> u32 {
+    suspects.max(1).next_power_of_two().trailing_zeros()
+}
+
+fn status(version: u32, state: &BisectState) -> BisectStatus {
+    BisectStatus {
+        version,
+        step: state.step,
+        suspects: state.suspects.clone(),
+        testing: state.testing.clone(),
+        remaining_steps: remaining_steps(state.suspects.len()),
+        done: false,
+        culprit: None,
+    }
+}
+
+/// Move every folder of `state.aside` back into `BepInEx/plugins`.
+fn restore_aside(game_root: &Path, state: &mut BisectState) -> Result<(), String> {
+    let plugins = mods::plugins_dir(game_root);
+    let aside = aside_dir(game_root);
+    for folder in std::mem::take(&mut state.aside) {
+        let from = aside.join(&folder);
+        let to = plugins.join(&folder);
+        if !from.exists() {
+            continue;
+        }
+        if to.exists() {
+            std::fs::remove_dir_all(&from).map_err(|e| e.to_string())?;
+        } else {
+            std::fs::rename(&from, &to).map_err(|e| e.to_string())?;
+        }
+    }
+    let _ = std::fs::remove_dir(&aside);
+    Ok(())
+}
+
+/// Enable `testing` (with required mods and dependencies) and move everything else aside.
+fn apply(game_root: &Path, state: &mut BisectState, testing: Vec<String>) -> Result<(), String> {
+    restore_aside(game_root, state)?;
+    let plugins = mods::plugins_dir(game_root);
+    let mut seeds = crash_guard::folders_to_keep(&plugins);
+    seeds.extend(testing.iter().map(|f| f.to_lowercase()));
+    let enabled = crash_guard::with_dependencies(&plugins, seeds);
+
+    let aside = aside_dir(game_root);
+    for e in std::fs::read_dir(&plugins)
+        .map_err(|e| e.to_string())?
+        .flatten()
+    {
+        let folder = e.file_name().to_string_lossy().to_string();
+        if !e.path().is_dir() || enabled.contains(&folder.to_lowercase()) {
+            continue;
+        }
+        std::fs::create_dir_all(&aside).map_err(|e| e.to_string())?;
+        std::fs::rename(e.path(), aside.join(&folder)).map_err(|e| e.to_string())?;
+        state.aside.push(folder);
+    }
+    // Suspects pulled in as dependencies are under test too.
+    state.testing = state
+        .suspects
+        .iter()
+        .filter(|s| enabled.contains(&s.to_lowercase()))
+        .cloned()
+        .collect();
+    write_state(game_root, state)
+}
+
+fn finish(game_root: &Path, mut state: BisectState) -> Result<(), String> {
+    restore_aside(game_root, &mut state)?;
+    let _ = std::fs::remove_file(state_path(game_root));
+    Ok(())
+}
+
+fn first_half(suspects: &[String]) -> Vec<String> {
+    suspects[..suspects.len().div_ceil(2)].to_vec()
+}
+
+fn ensure_game_stopped(app: &tauri::AppHandle) -> Result<(), String> {
+    if crate::is_game_running(app) {
+        return Err("stop the game before changing the bisect".to_string());
+    }
+    Ok(())
+}
+
+/// Start bisecting the installed mods of `version` (default: active version).
+#[tauri::command]
+pub fn start_bisect(app: tauri::AppHandle, version: Option<u32>) -> Result<BisectStatus, String> {
+    ensure_game_stopped(&app)?;
+    let version = crate::resolve_version(&app, version)?;
+    let game_root = crate::version_dir(&app, version)?;
+    if let Some(previous) = read_state(&game_root) {
+        finish(&game_root, previous)?;
+    }
+
+    let plugins = mods::plugins_dir(&game_root);
+    let keep = crash_guard::folders_to_keep(&plugins);
+    let mut suspects: Vec<String> = std::fs::read_dir(&plugins)
+        .map_err(|e| e.to_string())?
+        .flatten()
+        .filter(|e| e.path().is_dir())
+        .map(|e| e.file_name().to_string_lossy().to_string())
+        .filter(|f| !keep.contains(&f.to_lowercase()))
+        .collect();
+    suspects.sort();
+    if suspects.len() < 2 {
+        return Err("not enough optional mods installed to bisect".to_string());
+    }
+
+    let mut state = BisectState {
+        step: 1,
+        testing: vec![],
+        aside: vec![],
+        suspects,
+    };
+    let testing = first_half(&state.suspects);
+    apply(&game_root, &mut state, testing)?;
+    log::info!(
+        "Bisect started for v{version} with {} suspects",
+        state.suspects.len()
+    );
+    Ok(status(version, &state))
+}
+
+/// Report how the last launch went and move to the next half.
+#[tauri::command]
+pub fn bisect_report(
+    app: tauri::AppHandle,
+    version: Option<u32>,
+    result: BisectResult,
+) -> Result<BisectStatus, String> {
+    ensure_game_stopped(&app)?;
+    let version = crate::resolve_version(&app, version)?;
+    let game_root = crate::version_dir(&app, version)?;
+    let mut state =
+        read_state(&game_root).ok_or_else(|| format!("no bisect running for v{version}"))?;
+
+    let tested: BTreeSet<&String> = state.testing.iter().collect();
+    let next: Vec<String> = state
+        .suspects
+        .iter()
+        .filter(|s| tested.contains(s) == (result == BisectResult::Crash))
+        .cloned()
+        .collect();
+    log::info!(
+        "Bisect v{version} step {}: {result:?}, {} suspect(s) left",
+        state.step,
+        next.len()
+    );
+
+    // Done at one suspect, or when dependencies keep the halves from shrinking.
+    if next.len() <= 1 || next.len() == state.suspects.len() {
+        let culprit = (next.len() == 1).then(|| next[0].clone());
+        let step = state.step;
+        finish(&game_root, state)?;
+        return Ok(BisectStatus {
+            version,
+            step,
+            remaining_steps: 0,
+            testing: vec![],
+            done: true,
+            culprit,
+            suspects: next,
+        });
+    }
+
+    state.step += 1;
+    state.suspects = next;
+    let testing = first_half(&state.suspects);
+    apply(&game_root, &mut state, testing)?;
+    Ok(status(version, &state))
+}
+
+/// Stop bisecting and put every mod back.
+#[tauri::command]
+pub fn cancel_bisect(app: tauri::AppHandle, version: Option<u32>) -> Result<bool, String> {
+    ensure_game_stopped(&app)?;
+    let version = crate::resolve_version(&app, version)?;
+    let game_root = crate::version_dir(&app, version)?;
+    let Some(state) = read_state(&game_root) else {
+        return Ok(false);
+    };
+    finish(&game_root, state)?;
+    log::info!("Bisect cancelled for v{version}");
+    Ok(true)
+}
@@ -0,0 +1,180 @@
+//! Shared cache of downloaded Thunderstore packages, so installing a second game version
+//! doesn't download every mod again.
+//!
+//! Zips live in `cache/packages/{dev}-{name}-{version}.zip`. Downloads go straight into
+//! the cache through a `.part` file (`downloader::download_resumable`), so a file with its
+//! final name is always complete.
+//!
+//! A zip's mtime is bumped whenever an install uses it, so after a sync
+//! `evict_unreferenced` can trim the cache least-recently-used first.
+
+use std::collections::BTreeSet;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+use crate::mod_config::{self, BepInExPackage, ModEntry};
+use crate::{installer, mods, storage};
+
+/// `cache/packages/`, created on demand.
+pub fn packages_dir(app: &tauri::AppHandle) -> Result<PathBuf, String> {
+    let dir = storage::cache_dir(app)?.join("packages");
+    std::fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+    Ok(dir)
+}
+
+/// Cache path of a package version, whether cached or not.
+pub fn package_path(dir: &Path, dev: &str, name: &str, version: &str) -> PathBuf {
+    dir.join(format!("{dev}-{name}-{version}.zip"))
+}
+
+/// Whether `path` is a cached zip (starts with `PK`, not an HTML error page).
+pub fn is_cached(path: &Path) -> bool {
+    let mut header = [0u8; 2];
+    std::fs::File::open(path)
+        .and_then(|mut f| f.read_exact(&mut header))
+        .is_ok_and(|()| &header == b"PK")
+}
+
+/// Check a cached zip against the manifest's SHA-256 (`None`: nothing to check).
+///
+/// A mismatching zip is evicted, so a truncated or swapped download is fetched again.
+pub fn verify_sha256(path: &Path, label: &str, expected: Option<&str>) -> Result<(), String> {
+    let Some(expected) = expected.map(|h| h.trim().to_lowercase()) else {
+        return Ok(());
+    };
+    let actual = crate::integrity::sha256_file(path)?;
+    if actual == expected {
+        return Ok(());
+    }
+    evict(path);
+    Err(format!(
+        "{label} failed checksum verification (expected sha256 {expected}, got {actual}); \
+         the download was corrupted or tampered with. Please retry."
+    ))
+}
+
+/// Drop a cached zip that turned out to be broken, so the next install downloads it again.
+pub fn evict(path: &Path) {
+    if let Err(e) = std::fs::remove_file(path) {
+        log::warn!("Failed to evict cached package {}: {e}", path.display());
+    } else {
+        log::info!("Evicted cached package {}", path.display());
+    }
+}
+
+/// Mark a cached zip as just used (its mtime is the LRU clock for `evict_unreferenced`).
+pub fn touch(path: &Path) {
+    let res = std::fs::File::options()
+        .write(true)
+        .open(path)
+        .and_then(|f| f.set_modified(SystemTime::now()));
+    if let Err(e) = res {
+        log::debug!("Failed to touch cached package {}: {e}", path.display());
+    }
+}
+
+/// Cache file names still needed: the version installed in every version's plugin folders
+/// (enabled or disabled), every version the manifest pins for any game version, and the
+/// BepInEx packages (the manifest's and the default one).
+fn referenced_packages(
+    app: &tauri::AppHandle,
+    manifest_mods: &[ModEntry],
+    bepinex: &[BepInExPackage],
+) -> BTreeSet<String> {
+    let mut names = BTreeSet::new();
+    names.insert(
+        format!("BepInEx-BepInExPack-{}.zip", mod_config::DEFAULT_BEPINEXPACK_VERSION)
+            .to_lowercase(),
+    );
+    for p in bepinex {
+        names.insert(format!("{}-{}-{}.zip", p.namespace, p.name, p.version).to_lowercase());
+    }
+    for m in manifest_mods {
+        for version in m.version_config.values() {
+            names.insert(format!("{}-{}-{version}.zip", m.dev, m.name).to_lowercase());
+        }
+    }
+    let roots = installer::installed_version_dirs(app).unwrap_or_default();
+    for (_, game_root) in roots {
+        for dir in [mods::plugins_dir(&game_root), mods::plugins_disabled_dir(&game_root)] {
+            let Ok(rd) = std::fs::read_dir(&dir) else {
+                continue;
+            };
+            for entry in rd.flatten() {
+                let Ok(manifest) = mods::read_manifest_allow_old(&entry.path()) else {
+                    continue;
+                };
+                let folder = entry.file_name().to_string_lossy().to_string();
+                names.insert(format!("{folder}-{}.zip", manifest.version_number).to_lowercase());
+            }
+        }
+    }
+    names
+}
+
+/// What `evict_unreferenced` removed.
+#[derive(Debug, Default)]
+pub struct EvictionReport {
+    pub evicted: usize,
+    pub freed_bytes: u64,
+}
+
+/// Trim `cache/packages` down to `max_bytes` after a sync.
+///
+/// Only zips no installed version or manifest entry uses are candidates; they go least
+/// recently used first. Referenced zips are never evicted, so the cache can stay above
+/// the cap when every zip in it is still needed.
+pub fn evict_unreferenced(
+    app: &tauri::AppHandle,
+    manifest_mods: &[ModEntry],
+    bepinex: &[BepInExPackage],
+    max_bytes: u64,
+) -> Result<EvictionReport, String> {
+    let dir = packages_dir(app)?;
+    let referenced = referenced_packages(app, manifest_mods, bepinex);
+    let rd = std::fs::read_dir(&dir).map_err(|e| e.to_string())?;
+
+    let mut total = 0u64;
+    let mut candidates: Vec<(SystemTime, u64, PathBuf)> = Vec::new();
+    for entry in rd.flatten() {
+        let Ok(meta) = entry.metadata() else {
+            continue;
+        };
+        if !meta.is_file() {
+            continue;
+        }
+        total = total.saturating_add(meta.len());
+        let name = entry.file_name().to_string_lossy().to_lowercase();
+        // `.part` files belong to downloads that may still be resumed.
+        if !name.ends_with(".zip") || referenced.contains(&name) {
+            continue;
+        }
+        let used = meta.modified().unwrap_or(SystemTime::UNIX_EPOCH);
+        candidates.push((used, meta.len(), entry.path()));
+    }
+    candidates.sort();
+
+    let mut report = EvictionReport::default();
+    for (_, len, path) in candidates {
+        if total <= max_bytes {
+            break;
+        }
+        match std::fs::remove_file(&path) {
+            Ok(()) => {
+                total = total.saturating_sub(len);
+                report.evicted += 1;
+                report.freed_bytes = report.freed_bytes.saturating_add(len);
+            }
+            Err(e) => log::warn!("Failed to evict cached package {}: {e}", path.display()),
+        }
+    }
+    if report.evicted > 0 {
+        log::info!(
+            "Evicted {} unused cached package(s), freed {} bytes",
+            report.evicted,
+            report.freed_bytes
+        );
+    }
+    Ok(report)
+}
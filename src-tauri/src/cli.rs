@@ -0,0 +1,129 @@
+//! Headless command line, for scripts and machines without a desktop session:
+//!
+//! ```text
+//! hq-launcher --headless install <version> [--acknowledge-eol]
+//! hq-launcher --headless sync [--config-only | --mods-only] [--force]
+//! ```
+//!
+//! The window is closed before the frontend loads, every event goes to stdout as NDJSON
+//! (see `progress::emit`), questions a task asks get their default answer, and the last
+//! line is a `headless-result` event. Exit status: 0 on success, 1 if the task failed,
+//! 2 for a bad command line.
+
+use serde::Serialize;
+use tauri::{AppHandle, Manager};
+
+use crate::{installer, progress};
+
+pub(crate) const USAGE: &str = "usage: hq-launcher --headless install <version> [--acknowledge-eol]\n       hq-launcher --headless sync [--config-only | --mods-only] [--force]";
+
+#[derive(Debug, Clone, Copy)]
+pub(crate) enum Command {
+    Install { version: u32, acknowledge_eol: bool },
+    Sync(installer::SyncSteps),
+}
+
+/// Final NDJSON line of a headless run.
+///
+/// Event name: `headless-result`
+#[derive(Debug, Clone, Serialize)]
+struct HeadlessResultPayload {
+    ok: bool,
+    error: Option<String>,
+}
+
+/// Whether the launcher was started with `--headless`.
+pub(crate) fn requested() -> bool {
+    std::env::args().skip(1).any(|a| a == "--headless")
+}
+
+/// The headless command in `args` (without the program name). `None` without `--headless`.
+pub(crate) fn parse<I: IntoIterator<Item = String>>(args: I) -> Option<Result<Command, String>> {
+    let args: Vec<String> = args.into_iter().collect();
+    if !args.iter().any(|a| a == "--headless") {
+        return None;
+    }
+    let rest: Vec<&str> = args
+        .iter()
+        .map(String::as_str)
+        .filter(|a| *a != "--headless" && *a != "--ndjson")
+        .collect();
+    Some(match rest.as_slice() {
+        ["install", version, flags @ ..] => {
+            let version = version
+                .parse::<u32>()
+                .map_err(|_| format!("invalid game version: {version}"));
+            let acknowledge_eol = match flags {
+                [] => Ok(false),
+                ["--acknowledge-eol"] => Ok(true),
+                other => Err(format!("unexpected arguments: {}", other.join(" "))),
+            };
+            version.and_then(|version| {
+                acknowledge_eol.map(|acknowledge_eol| Command::Install {
+                    version,
+                    acknowledge_eol,
+                })
+            })
+        }
+        ["sync", flags @ ..] => {
+            let mut steps = installer::SyncSteps::default();
+            let mut result = Ok(());
+            for flag in flags {
+                match *flag {
+                    "--config-only" => steps.mods = false,
+                    "--mods-only" => steps.config = false,
+                    "--force" => steps.force = true,
+                    other => result = Err(format!("unexpected argument: {other}")),
+                }
+            }
+            if !steps.config && !steps.mods {
+                result = Err("--config-only and --mods-only exclude each other".to_string());
+            }
+            result.map(|()| Command::Sync(steps))
+        }
+        [] => Err("missing command".to_string()),
+        [other, ..] => Err(format!("unknown command: {other}")),
+    })
+}
+
+/// Run `command`, report the outcome and exit the app.
+pub(crate) async fn run(app: AppHandle, command: Command) {
+    let result = match command {
+        Command::Install {
+            version,
+            acknowledge_eol,
+        } => crate::run_install(
+            app.clone(),
+            version,
+            acknowledge_eol,
+            Some(false),
+            None,
+            app.state(),
+        )
+        .await
+        .map_err(|e| e.to_string())
+        .and_then(|installed| {
+            if installed {
+                Ok(())
+            } else {
+                Err("install did not complete".to_string())
+            }
+        }),
+        Command::Sync(steps) => installer::sync_latest_install_from_manifest(app.clone(), steps)
+            .await
+            .map(|_| ()),
+    };
+    if let Err(e) = &result {
+        log::error!("Headless {command:?} failed: {e}");
+    }
+    let code = if result.is_ok() { 0 } else { 1 };
+    progress::emit(
+        &app,
+        "headless-result",
+        HeadlessResultPayload {
+            ok: result.is_ok(),
+            error: result.err(),
+        },
+    );
+    app.exit(code);
+}
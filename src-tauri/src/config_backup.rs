@@ -0,0 +1,190 @@
+//! Snapshots of the shared config dir, taken before each config sync.
+//!
+//! The sync itself is add-only, but mods rewrite their own configs and manual edits go
+//! wrong; a snapshot lets the user go back. Snapshots are zips in `backups/config/`, named
+//! by creation time in milliseconds, with the reason in the zip comment. How many are
+//! kept is up to `retention`.
+
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+
+use serde::Serialize;
+
+use crate::{retention, storage, zip_utils};
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ConfigBackup {
+    pub id: String,
+    /// Unix seconds.
+    pub created_at: u64,
+    pub reason: String,
+    pub files: usize,
+    pub size_bytes: u64,
+}
+
+/// Ids are millisecond timestamps; anything else could name a path.
+fn backup_path(app: &tauri::AppHandle, id: &str) -> Result<PathBuf, String> {
+    if id.is_empty() || !id.bytes().all(|b| b.is_ascii_digit()) {
+        return Err(format!("invalid backup id: {id}"));
+    }
+    Ok(storage::config_backups_dir(app)?.join(format!("{id}.zip")))
+}
+
+/// Files under `root`, as `/`-separated paths relative to it.
+fn config_files(root: &Path) -> Vec<(String, PathBuf)> {
+    let mut out = vec![];
+    let mut stack = vec![root.to_path_buf()];
+    while let Some(dir) = stack.pop() {
+        let Ok(entries) = std::fs::read_dir(&dir) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            let path = entry.path();
+            let Ok(ty) = entry.file_type() else {
+                continue;
+            };
+            if ty.is_dir() {
+                stack.push(path);
+            } else if ty.is_file() {
+                if let Ok(rel) = path.strip_prefix(root) {
+                    out.push((rel.to_string_lossy().replace('\\', "/"), path));
+                }
+            }
+        }
+    }
+    out.sort();
+    out
+}
+
+fn read_backup(path: &Path) -> Option<ConfigBackup> {
+    let id = path.file_stem()?.to_str()?.to_string();
+    let created_ms: u64 = id.parse().ok()?;
+    let size_bytes = std::fs::metadata(path).ok()?.len();
+    let archive = zip::ZipArchive::new(std::fs::File::open(path).ok()?).ok()?;
+    Some(ConfigBackup {
+        id,
+        created_at: created_ms / 1000,
+        reason: String::from_utf8_lossy(archive.comment()).to_string(),
+        files: archive.len(),
+        size_bytes,
+    })
+}
+
+/// Zip the shared config dir into a new snapshot. `None` when there is nothing to save.
+pub fn snapshot(app: &tauri::AppHandle, reason: &str) -> Result<Option<ConfigBackup>, String> {
+    let shared = storage::shared_config_dir(app)?;
+    let files = config_files(&shared);
+    if files.is_empty() {
+        return Ok(None);
+    }
+    let dir = storage::config_backups_dir(app)?;
+    std::fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+
+    let mut id = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0);
+    while dir.join(format!("{id}.zip")).exists() {
+        id += 1;
+    }
+    let path = dir.join(format!("{id}.zip"));
+    let tmp = dir.join(format!("{id}.zip.tmp"));
+
+    let write = || -> Result<(), String> {
+        let file = std::fs::File::create(&tmp).map_err(|e| e.to_string())?;
+        let mut zip = zip::ZipWriter::new(file);
+        zip.set_comment(reason);
+        for (rel, src) in &files {
+            // Skip files that vanished or can't be read instead of failing the sync.
+            let Ok(bytes) = std::fs::read(src) else {
+                log::warn!("Config backup skipped unreadable {}", src.display());
+                continue;
+            };
+            zip.start_file(rel.as_str(), zip::write::SimpleFileOptions::default())
+                .map_err(|e| e.to_string())?;
+            zip.write_all(&bytes).map_err(|e| e.to_string())?;
+        }
+        zip.finish().map_err(|e| e.to_string())?;
+        std::fs::rename(&tmp, &path).map_err(|e| e.to_string())
+    };
+    if let Err(e) = write() {
+        let _ = std::fs::remove_file(&tmp);
+        return Err(format!("config backup failed: {e}"));
+    }
+    log::info!(
+        "Backed up {} config file(s) to {} ({reason})",
+        files.len(),
+        path.display()
+    );
+    let backup = read_backup(&path);
+    if let Err(e) = retention::run_for(app, retention::Category::ConfigBackups) {
+        log::warn!("Failed to apply config backup retention: {e}");
+    }
+    Ok(backup)
+}
+
+/// Snapshots, newest first.
+#[tauri::command]
+pub fn list_config_backups(app: tauri::AppHandle) -> Result<Vec<ConfigBackup>, String> {
+    let dir = storage::config_backups_dir(&app)?;
+    let Ok(entries) = std::fs::read_dir(&dir) else {
+        return Ok(vec![]);
+    };
+    let mut out: Vec<ConfigBackup> = entries
+        .flatten()
+        .map(|e| e.path())
+        .filter(|p| p.extension().is_some_and(|e| e == "zip"))
+        .filter_map(|p| read_backup(&p))
+        .collect();
+    out.sort_by_key(|b| std::cmp::Reverse(b.id.parse::<u64>().unwrap_or(0)));
+    Ok(out)
+}
+
+/// Put the shared config dir back to snapshot `id`: its files are restored and files it
+/// doesn't contain are removed. The current state is snapshotted first, so a restore can
+/// itself be undone.
+#[tauri::command]
+pub fn restore_config_backup(app: tauri::AppHandle, id: String) -> Result<ConfigBackup, String> {
+    let path = backup_path(&app, &id)?;
+    let backup = read_backup(&path).ok_or_else(|| format!("config backup {id} not found"))?;
+    if crate::is_game_running(&app) {
+        return Err("stop the game before restoring its config".to_string());
+    }
+
+    let file = std::fs::File::open(&path).map_err(|e| e.to_string())?;
+    let mut archive = zip::ZipArchive::new(file).map_err(|e| e.to_string())?;
+    let mut restored: Vec<(PathBuf, Vec<u8>)> = Vec::with_capacity(archive.len());
+    for i in 0..archive.len() {
+        let mut entry = archive.by_index(i).map_err(|e| e.to_string())?;
+        if entry.is_dir() {
+            continue;
+        }
+        let Some(rel) = zip_utils::safe_entry_path(entry.name(), entry.unix_mode()) else {
+            log::warn!("Skipped unsafe backup entry: {}", entry.name());
+            continue;
+        };
+        let mut bytes = Vec::with_capacity(entry.size() as usize);
+        entry.read_to_end(&mut bytes).map_err(|e| e.to_string())?;
+        restored.push((rel, bytes));
+    }
+
+    snapshot(&app, &format!("before restoring backup {id}"))?;
+
+    let shared = storage::shared_config_dir(&app)?;
+    let keep: std::collections::HashSet<&Path> =
+        restored.iter().map(|(rel, _)| rel.as_path()).collect();
+    for (rel, current) in config_files(&shared) {
+        if !keep.contains(Path::new(&rel)) {
+            std::fs::remove_file(&current).map_err(|e| format!("{}: {e}", current.display()))?;
+        }
+    }
+    for (rel, bytes) in &restored {
+        let dest = shared.join(rel);
+        if let Some(parent) = dest.parent() {
+            std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+        }
+        zip_utils::write_atomic(&dest, bytes)?;
+    }
+    log::info!("Restored {} config file(s) from backup {id}", restored.len());
+    Ok(backup)
+}
@@ -0,0 +1,166 @@
+//! What a config update does with local config files that differ from the new defaults.
+//!
+//! New files are always added. For the others, `Settings::config_sync_strategy` picks:
+//!
+//! - `add_only`: keep every local file.
+//! - `overwrite`: replace every local file with the new default.
+//! - `prompt` (default): three-way compare against the previously applied defaults. A file
+//!   only the player edited is kept, one only upstream changed is updated, and files both
+//!   changed are sent as a `config-conflict` event with all three versions before the
+//!   player is asked whether to keep theirs. To merge by hand, the UI writes the merged
+//!   text with `write_config_file` and answers `keep`. Without a recorded base (the first
+//!   update after upgrading) an edit can't be told from an old default, so differing
+//!   files are kept, as the add-only sync always did.
+//!
+//! Files the manifest lists in `config_overwrite` are replaced under every strategy.
+
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{decisions, progress, settings, zip_utils};
+
+/// Larger (or binary) files are listed in a conflict without their contents.
+const MAX_DIFF_BYTES: usize = 256 * 1024;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ConfigSyncStrategy {
+    AddOnly,
+    Overwrite,
+    #[default]
+    Prompt,
+}
+
+/// One config file changed both locally and upstream. Contents are `None` when the
+/// version doesn't exist, isn't text or is too large to diff.
+#[derive(Debug, Clone, Serialize)]
+pub struct ConfigConflict {
+    /// Relative to the shared config dir.
+    pub path: String,
+    /// The default applied by the previous config update.
+    pub base: Option<String>,
+    pub local: Option<String>,
+    /// The new default.
+    pub remote: Option<String>,
+}
+
+/// Event name: `config-conflict`
+#[derive(Debug, Clone, Serialize)]
+pub struct ConfigConflictPayload {
+    pub conflicts: Vec<ConfigConflict>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Action {
+    Keep,
+    Overwrite,
+    Conflict,
+}
+
+fn action(
+    local: &[u8],
+    remote: &[u8],
+    base: Option<&[u8]>,
+    forced: bool,
+    strategy: ConfigSyncStrategy,
+) -> Action {
+    if forced {
+        return Action::Overwrite;
+    }
+    match strategy {
+        ConfigSyncStrategy::AddOnly => Action::Keep,
+        ConfigSyncStrategy::Overwrite => Action::Overwrite,
+        ConfigSyncStrategy::Prompt => match base {
+            _ if local == remote => Action::Keep,
+            // Unknown history: not a conflict we can show, keep the local file.
+            None => Action::Keep,
+            // Upstream didn't touch it: the local edit stands.
+            Some(base) if base == remote => Action::Keep,
+            // Never edited locally: take the new default.
+            Some(base) if base == local => Action::Overwrite,
+            Some(_) => Action::Conflict,
+        },
+    }
+}
+
+fn text(bytes: Option<&[u8]>) -> Option<String> {
+    let bytes = bytes.filter(|b| b.len() <= MAX_DIFF_BYTES)?;
+    std::str::from_utf8(bytes).ok().map(str::to_string)
+}
+
+fn is_forced(forced: &[String], path: &str) -> bool {
+    forced
+        .iter()
+        .any(|f| f.trim().replace('\\', "/").eq_ignore_ascii_case(path))
+}
+
+/// Which of `changed` (local files that differ from the new defaults in `new_zip`, see
+/// `zip_utils::changed_config_files`) a config update should overwrite. `base` holds the
+/// files of the previously applied defaults, if known; `forced` is the manifest's
+/// `config_overwrite`.
+pub async fn files_to_overwrite(
+    app: &tauri::AppHandle,
+    changed: &[String],
+    config_dir: &Path,
+    new_zip: &Path,
+    base: Option<&BTreeMap<String, Vec<u8>>>,
+    forced: &[String],
+) -> Result<Vec<String>, String> {
+    if changed.is_empty() {
+        return Ok(vec![]);
+    }
+    let strategy = settings::load(app).config_sync_strategy;
+    let new = zip_utils::config_zip_files(new_zip)?;
+
+    let mut overwrite = vec![];
+    let mut conflicts = vec![];
+    for path in changed {
+        let local = std::fs::read(config_dir.join(path)).unwrap_or_default();
+        let remote = new.get(path).map(Vec::as_slice).unwrap_or_default();
+        let base_file = base.and_then(|b| b.get(path)).map(Vec::as_slice);
+        match action(&local, remote, base_file, is_forced(forced, path), strategy) {
+            Action::Keep => {}
+            Action::Overwrite => overwrite.push(path.clone()),
+            Action::Conflict => conflicts.push(ConfigConflict {
+                path: path.clone(),
+                base: text(base_file),
+                local: text(Some(&local)),
+                remote: text(Some(remote)),
+            }),
+        }
+    }
+    log::info!(
+        "Config update ({strategy:?}): {} changed file(s), {} to overwrite, {} in conflict",
+        changed.len(),
+        overwrite.len(),
+        conflicts.len()
+    );
+    if conflicts.is_empty() {
+        return Ok(overwrite);
+    }
+
+    let paths: Vec<String> = conflicts.iter().map(|c| c.path.clone()).collect();
+    progress::emit(app, "config-conflict", ConfigConflictPayload { conflicts });
+    let choice = decisions::ask(
+        app,
+        None,
+        "overwrite_config",
+        format!(
+            "{} config file(s) were changed by you and by the new defaults. Keep your versions?",
+            paths.len()
+        ),
+        paths.clone(),
+        &[
+            ("keep", "Keep my files"),
+            ("overwrite", "Use the new defaults"),
+        ],
+        "keep",
+    )
+    .await;
+    if choice == "overwrite" {
+        overwrite.extend(paths);
+    }
+    Ok(overwrite)
+}
@@ -0,0 +1,78 @@
+use std::collections::HashMap;
+use std::hash::{BuildHasher, Hasher};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use tauri::Manager;
+
+/// How long a confirmation nonce stays valid.
+const NONCE_TTL: Duration = Duration::from_secs(120);
+
+/// Confirmation nonces for destructive commands.
+///
+/// A destructive command is split in two calls: the first one (dry run) describes what
+/// would happen and returns a nonce bound to the exact action, the second one must echo
+/// that nonce. Nonces are single-use, expire after `NONCE_TTL`, and there is deliberately
+/// no command that hands out a nonce for an arbitrary action.
+#[derive(Default)]
+pub struct ConfirmState {
+    seq: AtomicU64,
+    pending: Mutex<HashMap<String, Pending>>,
+}
+
+struct Pending {
+    action: String,
+    issued_at: Instant,
+}
+
+fn new_nonce(seq: u64) -> String {
+    let nanos = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_nanos())
+        .unwrap_or(0);
+    // RandomState is seeded per process from the OS RNG; good enough for a UI nonce.
+    let mut h = std::collections::hash_map::RandomState::new().build_hasher();
+    h.write_u128(nanos);
+    h.write_u64(seq);
+    format!("{:016x}", h.finish())
+}
+
+/// Issue a nonce for `action` (e.g. `reset_launcher:all`, `uninstall:v73`).
+pub fn issue(app: &tauri::AppHandle, action: &str) -> Result<String, String> {
+    let state = app
+        .try_state::<ConfirmState>()
+        .ok_or_else(|| "confirmation state not initialized".to_string())?;
+    let nonce = new_nonce(state.seq.fetch_add(1, Ordering::Relaxed));
+    let mut pending = state
+        .pending
+        .lock()
+        .map_err(|_| "confirmation state lock poisoned".to_string())?;
+    pending.retain(|_, p| p.issued_at.elapsed() < NONCE_TTL);
+    pending.insert(
+        nonce.clone(),
+        Pending {
+            action: action.to_string(),
+            issued_at: Instant::now(),
+        },
+    );
+    Ok(nonce)
+}
+
+/// Consume `nonce`; fails unless it was issued for exactly `action` and hasn't expired.
+pub fn consume(app: &tauri::AppHandle, action: &str, nonce: &str) -> Result<(), String> {
+    let state = app
+        .try_state::<ConfirmState>()
+        .ok_or_else(|| "confirmation state not initialized".to_string())?;
+    let mut pending = state
+        .pending
+        .lock()
+        .map_err(|_| "confirmation state lock poisoned".to_string())?;
+    match pending.remove(nonce) {
+        Some(p) if p.action == action && p.issued_at.elapsed() < NONCE_TTL => Ok(()),
+        _ => {
+            log::warn!("Rejected confirmation for {action}: invalid or expired nonce");
+            Err("invalid or expired confirmation token".to_string())
+        }
+    }
+}
@@ -0,0 +1,283 @@
+//! Crash-loop detection and safe-mode launches.
+//!
+//! Every launch is watched until the game exits. `CRASH_LOOP_THRESHOLD` exits in a row
+//! within `QUICK_EXIT` emit `crash-loop`, and the UI can offer `launch_game_safe_mode`:
+//! every mod except the manifest's `required` ones (and what they depend on) is moved to
+//! `BepInEx/plugins_safe_mode/` for one session and put back when the game exits.
+
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+use tauri::Manager;
+
+use crate::bepinex_cfg::read_manifest;
+use crate::progress::{self, CrashLoopPayload};
+use crate::{installer, mod_config, mods};
+
+/// A session shorter than this counts as a crash.
+const QUICK_EXIT: Duration = Duration::from_secs(20);
+/// Quick exits in a row before `crash-loop` is emitted.
+const CRASH_LOOP_THRESHOLD: u32 = 3;
+/// How often the supervisor checks whether the game is still running.
+const POLL: Duration = Duration::from_secs(1);
+
+#[derive(Default)]
+pub struct CrashGuard {
+    state: Mutex<GuardState>,
+}
+
+#[derive(Default)]
+struct GuardState {
+    /// Consecutive quick exits per game version.
+    quick_exits: HashMap<u32, u32>,
+    /// Set by `stop_game` so a deliberate stop isn't counted as a crash.
+    user_stopped: bool,
+}
+
+/// `{game_root}/.hq-launcher/safe_mode.json`: plugin folders moved aside for safe mode.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct SafeModeRecord {
+    moved: Vec<String>,
+}
+
+fn safe_mode_dir(game_root: &Path) -> PathBuf {
+    game_root.join("BepInEx").join("plugins_safe_mode")
+}
+
+fn record_path(game_root: &Path) -> PathBuf {
+    game_root.join(".hq-launcher").join("safe_mode.json")
+}
+
+fn read_record(game_root: &Path) -> Option<SafeModeRecord> {
+    let text = std::fs::read_to_string(record_path(game_root)).ok()?;
+    serde_json::from_str(&text).ok()
+}
+
+/// Mark the running game as stopped on purpose.
+pub fn note_user_stop(app: &tauri::AppHandle) {
+    if let Ok(mut state) = app.state::<CrashGuard>().state.lock() {
+        state.user_stopped = true;
+    }
+}
+
+/// Watch the game launched for `version` as `pid` until it exits.
+pub fn supervise(app: &tauri::AppHandle, version: u32, pid: u32) {
+    if let Ok(mut state) = app.state::<CrashGuard>().state.lock() {
+        state.user_stopped = false;
+    }
+    let app = app.clone();
+    std::thread::spawn(move || {
+        let started = Instant::now();
+        while crate::running_game_pid(&app) == Some(pid) {
+            std::thread::sleep(POLL);
+        }
+        let ran = started.elapsed();
+        crate::overlay::finish(&app, version);
+
+        let game_root = crate::version_dir(&app, version);
+        let safe_mode = game_root.as_ref().is_ok_and(|r| record_path(r).exists());
+        if let Ok(root) = &game_root {
+            if safe_mode {
+                if let Err(e) = restore(root) {
+                    log::warn!("Failed to restore mods after safe mode on v{version}: {e}");
+                }
+            }
+        }
+
+        let quick_exits = {
+            let guard = app.state::<CrashGuard>();
+            let Ok(mut state) = guard.state.lock() else {
+                return;
+            };
+            let deliberate = std::mem::take(&mut state.user_stopped);
+            let count = state.quick_exits.entry(version).or_insert(0);
+            if ran < QUICK_EXIT && !deliberate {
+                *count += 1;
+            } else {
+                *count = 0;
+            }
+            *count
+        };
+        log::info!(
+            "Game v{version} exited after {}s ({quick_exits} quick exit(s) in a row)",
+            ran.as_secs()
+        );
+        if quick_exits >= CRASH_LOOP_THRESHOLD {
+            log::warn!("Crash loop detected for v{version}");
+            progress::emit_crash_loop(
+                &app,
+                CrashLoopPayload {
+                    version,
+                    quick_exits,
+                    last_run_secs: ran.as_secs(),
+                    safe_mode_active: safe_mode,
+                },
+            );
+        }
+    });
+}
+
+/// `dev-name` (lowercase) of the mods a plugin folder's manifest depends on.
+fn dependencies_of(folder: &Path) -> Vec<String> {
+    let Ok(manifest) = read_manifest(&folder.join("manifest.json")) else {
+        return vec![];
+    };
+    manifest
+        .dependencies
+        .iter()
+        .filter_map(|dep| dep.rsplit_once('-').map(|(id, _version)| id.to_lowercase()))
+        .collect()
+}
+
+/// Plugin folder ids (`dev-name`, lowercase) of `seeds` plus everything they depend on,
+/// following the `manifest.json` of each installed folder.
+pub(crate) fn with_dependencies(plugins: &Path, seeds: BTreeSet<String>) -> BTreeSet<String> {
+    let folders: BTreeMap<String, PathBuf> = std::fs::read_dir(plugins)
+        .map(|rd| {
+            rd.flatten()
+                .filter(|e| e.path().is_dir())
+                .map(|e| (e.file_name().to_string_lossy().to_lowercase(), e.path()))
+                .collect()
+        })
+        .unwrap_or_default();
+
+    let mut out = seeds;
+    let mut queue: Vec<String> = out.iter().cloned().collect();
+    while let Some(id) = queue.pop() {
+        let Some(path) = folders.get(&id) else {
+            continue;
+        };
+        for dep in dependencies_of(path) {
+            if out.insert(dep.clone()) {
+                queue.push(dep);
+            }
+        }
+    }
+    out
+}
+
+/// Plugin folders that always stay: required mods and everything they depend on.
+pub(crate) fn folders_to_keep(plugins: &Path) -> BTreeSet<String> {
+    let required: BTreeSet<String> = mod_config::cached_remote_manifest()
+        .map(|m| {
+            m.mods
+                .iter()
+                .filter(|m| m.is_required())
+                .map(|m| format!("{}-{}", m.dev, m.name).to_lowercase())
+                .collect()
+        })
+        .unwrap_or_default();
+    with_dependencies(plugins, required)
+}
+
+/// Move every non-required plugin folder of `game_root` aside. Returns the moved folders.
+fn enter(game_root: &Path) -> Result<Vec<String>, String> {
+    if record_path(game_root).exists() {
+        restore(game_root)?;
+    }
+    let plugins = mods::plugins_dir(game_root);
+    let keep = folders_to_keep(&plugins);
+    let aside = safe_mode_dir(game_root);
+
+    let mut moved: Vec<String> = vec![];
+    let Ok(rd) = std::fs::read_dir(&plugins) else {
+        return Ok(moved);
+    };
+    for e in rd.flatten() {
+        let folder = e.file_name().to_string_lossy().to_string();
+        if !e.path().is_dir() || keep.contains(&folder.to_lowercase()) {
+            continue;
+        }
+        std::fs::create_dir_all(&aside).map_err(|e| e.to_string())?;
+        std::fs::rename(e.path(), aside.join(&folder)).map_err(|e| e.to_string())?;
+        moved.push(folder);
+    }
+
+    let path = record_path(game_root);
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    let json = serde_json::to_string_pretty(&SafeModeRecord {
+        moved: moved.clone(),
+    })
+    .map_err(|e| e.to_string())?;
+    crate::zip_utils::write_atomic(&path, json)?;
+    Ok(moved)
+}
+
+/// Put folders moved aside by safe mode back into `BepInEx/plugins`.
+///
+/// A folder reinstalled in the meantime (e.g. by a sync) wins over the moved copy.
+fn restore(game_root: &Path) -> Result<(), String> {
+    let record = read_record(game_root).unwrap_or_default();
+    let plugins = mods::plugins_dir(game_root);
+    let aside = safe_mode_dir(game_root);
+    for folder in &record.moved {
+        let from = aside.join(folder);
+        if !from.exists() {
+            continue;
+        }
+        let to = plugins.join(folder);
+        if to.exists() {
+            std::fs::remove_dir_all(&from).map_err(|e| e.to_string())?;
+        } else {
+            std::fs::rename(&from, &to).map_err(|e| e.to_string())?;
+        }
+    }
+    let _ = std::fs::remove_dir(&aside);
+    let _ = std::fs::remove_file(record_path(game_root));
+    log::info!("Restored {} mod(s) after safe mode", record.moved.len());
+    Ok(())
+}
+
+/// Undo safe-mode sessions the launcher didn't get to finish (crash, forced quit).
+pub fn restore_leftovers(app: &tauri::AppHandle) -> Result<(), String> {
+    for (version, root) in installer::installed_version_dirs(app)? {
+        if record_path(&root).exists() {
+            log::info!("Restoring mods left in safe mode for v{version}");
+            restore(&root)?;
+        }
+    }
+    Ok(())
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SafeModeLaunch {
+    pub pid: u32,
+    /// Plugin folders moved aside for this session.
+    pub disabled: Vec<String>,
+}
+
+/// Launch `version` with only required mods; everything else comes back on exit.
+#[tauri::command]
+pub fn launch_game_safe_mode(
+    app: tauri::AppHandle,
+    version: Option<u32>,
+    state: tauri::State<'_, crate::GameState>,
+) -> Result<SafeModeLaunch, String> {
+    let version = crate::resolve_version(&app, version)?;
+    if crate::is_game_running(&app) {
+        return Err("game is already running".to_string());
+    }
+    let game_root = crate::version_dir(&app, version)?;
+    if crate::bisect::is_active(&game_root) {
+        return Err("finish or cancel the mod bisect first".to_string());
+    }
+    let disabled = enter(&game_root)?;
+    log::info!(
+        "Safe mode for v{version}: moved {} mod(s) aside",
+        disabled.len()
+    );
+    match crate::launch_game(app, Some(version), state) {
+        Ok(pid) => Ok(SafeModeLaunch { pid, disabled }),
+        Err(e) => {
+            if let Err(re) = restore(&game_root) {
+                log::warn!("Failed to restore mods after failed safe-mode launch: {re}");
+            }
+            Err(e)
+        }
+    }
+}
@@ -0,0 +1,178 @@
+//! Questions a running task puts to the player instead of guessing, e.g. whether to
+//! overwrite config files they edited.
+//!
+//! `ask` emits `task-decision-required` and waits until the frontend answers through
+//! `resolve_decision`. A task has at most one open question, so the task id is the key.
+//! Unanswered questions fall back to their default after `DECISION_TIMEOUT`. Until the
+//! frontend's dialog registers through `set_decision_handler`, nobody can answer, so
+//! `ask` returns the default straight away instead of stalling the task.
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
+
+use serde::Serialize;
+use tauri::Manager;
+use tokio::sync::oneshot;
+
+use crate::progress;
+use crate::tasks;
+
+const DECISION_TIMEOUT: Duration = Duration::from_secs(10 * 60);
+
+/// A frontend listens for `task-decision-required`.
+static HANDLER_ACTIVE: AtomicBool = AtomicBool::new(false);
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DecisionOption {
+    pub id: String,
+    pub label: String,
+}
+
+/// A task is paused until the player picks one of `options`.
+///
+/// Event name: `task-decision-required`
+#[derive(Debug, Clone, Serialize)]
+pub struct DecisionRequiredPayload {
+    /// `None` for questions about shared data (the config dir) asked outside a task.
+    pub version: Option<u32>,
+    /// Pass back to `resolve_decision`. The running task's id, or a standalone id when
+    /// no task is running for `version`.
+    pub task_id: String,
+    /// `overwrite_config` or `remove_orphaned_mods`.
+    pub kind: String,
+    pub message: String,
+    /// What the decision applies to (file paths, mod names).
+    pub details: Vec<String>,
+    pub options: Vec<DecisionOption>,
+    /// Chosen when nobody answers within `timeout_secs`.
+    pub default: String,
+    pub timeout_secs: u64,
+}
+
+struct Pending {
+    options: Vec<String>,
+    tx: oneshot::Sender<String>,
+}
+
+#[derive(Default)]
+pub struct DecisionState {
+    pending: Mutex<HashMap<String, Pending>>,
+}
+
+/// Ask the player to choose one of `options` (`(id, label)` pairs) and wait for the answer.
+///
+/// Returns the chosen option id, or `default` on timeout or when nothing can answer.
+pub async fn ask(
+    app: &tauri::AppHandle,
+    version: Option<u32>,
+    kind: &str,
+    message: String,
+    details: Vec<String>,
+    options: &[(&str, &str)],
+    default: &str,
+) -> String {
+    let Some(state) = app.try_state::<DecisionState>() else {
+        return default.to_string();
+    };
+    if !HANDLER_ACTIVE.load(Ordering::Relaxed) {
+        log::info!("No decision handler for {kind} ({message}); using {default}");
+        return default.to_string();
+    }
+    let task_id = version
+        .and_then(|v| tasks::task_id_for(app, v))
+        .unwrap_or_else(|| {
+            let ts = std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .map(|d| d.as_millis())
+                .unwrap_or(0);
+            format!("decision-{kind}-{ts}")
+        });
+    let (tx, rx) = oneshot::channel::<String>();
+    {
+        let Ok(mut pending) = state.pending.lock() else {
+            return default.to_string();
+        };
+        pending.insert(
+            task_id.clone(),
+            Pending {
+                options: options.iter().map(|(id, _)| id.to_string()).collect(),
+                tx,
+            },
+        );
+    }
+
+    log::info!("Task {task_id} waiting for a decision ({kind}): {message}");
+    if let Some(v) = version {
+        tasks::set_waiting(app, v, true);
+    }
+    progress::emit(
+        app,
+        "task-decision-required",
+        DecisionRequiredPayload {
+            version,
+            task_id: task_id.clone(),
+            kind: kind.to_string(),
+            message,
+            details,
+            options: options
+                .iter()
+                .map(|(id, label)| DecisionOption {
+                    id: id.to_string(),
+                    label: label.to_string(),
+                })
+                .collect(),
+            default: default.to_string(),
+            timeout_secs: DECISION_TIMEOUT.as_secs(),
+        },
+    );
+
+    let choice = match tokio::time::timeout(DECISION_TIMEOUT, rx).await {
+        Ok(Ok(choice)) => choice,
+        _ => {
+            log::warn!("No answer for decision {kind} of task {task_id}; using {default}");
+            if let Ok(mut pending) = state.pending.lock() {
+                pending.remove(&task_id);
+            }
+            default.to_string()
+        }
+    };
+    if let Some(v) = version {
+        tasks::set_waiting(app, v, false);
+    }
+    log::info!("Task {task_id} resumed with decision {kind}={choice}");
+    choice
+}
+
+/// Answer the open question of `task_id` with one of its option ids.
+#[tauri::command]
+pub fn resolve_decision(
+    state: tauri::State<'_, DecisionState>,
+    task_id: String,
+    choice: String,
+) -> Result<(), String> {
+    let mut pending = state
+        .pending
+        .lock()
+        .map_err(|_| "decision state lock poisoned".to_string())?;
+    let Some(open) = pending.get(&task_id) else {
+        return Err(format!("task {task_id} is not waiting for a decision"));
+    };
+    if !open.options.contains(&choice) {
+        return Err(format!(
+            "invalid choice {choice}; expected one of {}",
+            open.options.join(", ")
+        ));
+    }
+    if let Some(open) = pending.remove(&task_id) {
+        let _ = open.tx.send(choice);
+    }
+    Ok(())
+}
+
+/// Called by the frontend's decision dialog when it starts (`true`) or stops listening.
+#[tauri::command]
+pub fn set_decision_handler(active: bool) {
+    HANDLER_ACTIVE.store(active, Ordering::Relaxed);
+}
@@ -0,0 +1,432 @@
+//! Diagnostics bundles and their upload to the maintainers.
+//!
+//! `create_diagnostics` zips the launcher log, launcher state and each install's lockfile
+//! into `diagnostics/{report_id}.zip`. `submit_diagnostics` uploads one bundle to the
+//! manifest's `diagnostics_url`, only after the player confirmed (two-phase, see `confirm`).
+//!
+//! Upload protocol, chunked so a dropped connection resumes where it stopped:
+//! - `POST {url}/uploads` with `{report_id, size, sha256, app_version}` → `{upload_id}`
+//! - `GET {url}/uploads/{upload_id}` → `{received, ticket_id?}`
+//! - `PUT {url}/uploads/{upload_id}` with one chunk and `Content-Range` →
+//!   `{received, ticket_id?}`; `ticket_id` is set once the last chunk arrived.
+
+use std::collections::BTreeMap;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+use tauri::Manager;
+
+use crate::{installer, integrity, mod_config, storage};
+
+const CHUNK_BYTES: usize = 1024 * 1024;
+/// Minimum time between two new submissions; resuming an upload isn't limited.
+const SUBMIT_INTERVAL: Duration = Duration::from_secs(10 * 60);
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(60);
+/// Launcher state files included in a bundle.
+const STATE_FILES: [&str; 4] = [
+    "settings.json",
+    "manifest_state.json",
+    "disablemod.json",
+    "migrations.json",
+];
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DiagnosticsReport {
+    pub report_id: String,
+    pub path: String,
+    pub size: u64,
+    /// Entries of the bundle.
+    pub files: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SubmitResult {
+    pub report_id: String,
+    pub endpoint: String,
+    pub size: u64,
+    /// Set on the dry run; pass it back to consent to the upload.
+    pub confirm_token: Option<String>,
+    /// Reference to quote when asking for help, once uploaded.
+    pub ticket_id: Option<String>,
+}
+
+/// `state/diagnostics_uploads.json`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct UploadsState {
+    #[serde(default)]
+    last_submitted_at: u64,
+    #[serde(default)]
+    uploads: BTreeMap<String, Upload>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct Upload {
+    upload_id: String,
+    #[serde(default)]
+    ticket_id: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct StartResponse {
+    upload_id: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct UploadStatus {
+    received: u64,
+    #[serde(default)]
+    ticket_id: Option<String>,
+}
+
+fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+fn bundle_path(app: &tauri::AppHandle, report_id: &str) -> Result<PathBuf, String> {
+    let valid = !report_id.is_empty()
+        && report_id
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-');
+    if !valid {
+        return Err(format!("invalid report id: {report_id}"));
+    }
+    Ok(storage::diagnostics_dir(app)?.join(format!("{report_id}.zip")))
+}
+
+fn uploads_path(app: &tauri::AppHandle) -> Result<PathBuf, String> {
+    Ok(storage::state_dir(app)?.join("diagnostics_uploads.json"))
+}
+
+fn read_uploads(app: &tauri::AppHandle) -> UploadsState {
+    uploads_path(app)
+        .ok()
+        .and_then(|p| std::fs::read_to_string(p).ok())
+        .and_then(|text| serde_json::from_str(&text).ok())
+        .unwrap_or_default()
+}
+
+fn write_uploads(app: &tauri::AppHandle, state: &UploadsState) -> Result<(), String> {
+    let path = uploads_path(app)?;
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    let json = serde_json::to_string_pretty(state).map_err(|e| e.to_string())?;
+    crate::zip_utils::write_atomic(&path, json)
+}
+
+fn add_file<W: Write + std::io::Seek>(
+    zip: &mut zip::ZipWriter<W>,
+    name: &str,
+    path: &Path,
+    files: &mut Vec<String>,
+) -> Result<(), String> {
+    let Ok(bytes) = std::fs::read(path) else {
+        return Ok(());
+    };
+    zip.start_file(name, zip::write::SimpleFileOptions::default())
+        .map_err(|e| e.to_string())?;
+    zip.write_all(&bytes).map_err(|e| e.to_string())?;
+    files.push(name.to_string());
+    Ok(())
+}
+
+fn write_bundle(app: &tauri::AppHandle, path: &Path) -> Result<Vec<String>, String> {
+    let file = std::fs::File::create(path).map_err(|e| e.to_string())?;
+    let mut zip = zip::ZipWriter::new(file);
+    let mut files = vec![];
+
+    let versions = installer::installed_version_dirs(app)?;
+    let summary = serde_json::json!({
+        "app_version": app.package_info().version.to_string(),
+        "os": std::env::consts::OS,
+        "arch": std::env::consts::ARCH,
+        "created_at": now_secs(),
+        "installed_versions": versions.iter().map(|(v, _)| v).collect::<Vec<_>>(),
+    });
+    zip.start_file("report.json", zip::write::SimpleFileOptions::default())
+        .map_err(|e| e.to_string())?;
+    zip.write_all(summary.to_string().as_bytes())
+        .map_err(|e| e.to_string())?;
+    files.push("report.json".to_string());
+
+    let logs = storage::logs_dir(app)?;
+    add_file(
+        &mut zip,
+        "logs/hq-launcher.log",
+        &logs.join("hq-launcher.log"),
+        &mut files,
+    )?;
+    add_file(
+        &mut zip,
+        "logs/hq-launcher.0.log",
+        &logs.join("hq-launcher.0.log"),
+        &mut files,
+    )?;
+    let state = storage::state_dir(app)?;
+    for name in STATE_FILES {
+        add_file(
+            &mut zip,
+            &format!("state/{name}"),
+            &state.join(name),
+            &mut files,
+        )?;
+    }
+    for (version, root) in &versions {
+        let lock = integrity::read_lockfile(root);
+        let json = serde_json::to_string_pretty(&lock).map_err(|e| e.to_string())?;
+        let name = format!("versions/v{version}/lock.json");
+        zip.start_file(name.as_str(), zip::write::SimpleFileOptions::default())
+            .map_err(|e| e.to_string())?;
+        zip.write_all(json.as_bytes()).map_err(|e| e.to_string())?;
+        files.push(name);
+    }
+
+    zip.finish().map_err(|e| e.to_string())?;
+    Ok(files)
+}
+
+/// Bundle logs and launcher state for a bug report.
+#[tauri::command]
+pub async fn create_diagnostics(app: tauri::AppHandle) -> Result<DiagnosticsReport, String> {
+    let report_id = format!("diag-{}", now_secs());
+    let path = bundle_path(&app, &report_id)?;
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    let (app2, path2) = (app.clone(), path.clone());
+    let files = tauri::async_runtime::spawn_blocking(move || write_bundle(&app2, &path2))
+        .await
+        .map_err(|e| e.to_string())??;
+    let size = std::fs::metadata(&path).map_err(|e| e.to_string())?.len();
+    log::info!("Created diagnostics bundle {report_id} ({size} bytes)");
+    Ok(DiagnosticsReport {
+        report_id,
+        path: path.to_string_lossy().to_string(),
+        size,
+        files,
+    })
+}
+
+async fn upload_status(
+    client: &reqwest::Client,
+    endpoint: &str,
+    upload_id: &str,
+) -> Result<UploadStatus, String> {
+    client
+        .get(format!("{endpoint}/uploads/{upload_id}"))
+        .timeout(REQUEST_TIMEOUT)
+        .send()
+        .await
+        .map_err(|e| e.to_string())?
+        .error_for_status()
+        .map_err(|e| e.to_string())?
+        .json()
+        .await
+        .map_err(|e| e.to_string())
+}
+
+async fn start_upload(
+    app: &tauri::AppHandle,
+    client: &reqwest::Client,
+    endpoint: &str,
+    report_id: &str,
+    bytes: &[u8],
+) -> Result<String, String> {
+    let body = serde_json::json!({
+        "report_id": report_id,
+        "size": bytes.len(),
+        "sha256": integrity::sha256_hex(bytes),
+        "app_version": app.package_info().version.to_string(),
+    });
+    let started: StartResponse = client
+        .post(format!("{endpoint}/uploads"))
+        .timeout(REQUEST_TIMEOUT)
+        .json(&body)
+        .send()
+        .await
+        .map_err(|e| e.to_string())?
+        .error_for_status()
+        .map_err(|e| e.to_string())?
+        .json()
+        .await
+        .map_err(|e| e.to_string())?;
+    Ok(started.upload_id)
+}
+
+/// Send `bytes` from `offset` on, chunk by chunk. Returns the ticket id.
+async fn send_chunks(
+    client: &reqwest::Client,
+    endpoint: &str,
+    upload_id: &str,
+    bytes: &[u8],
+    mut offset: usize,
+) -> Result<String, String> {
+    let total = bytes.len();
+    loop {
+        let end = (offset + CHUNK_BYTES).min(total);
+        let status: UploadStatus = client
+            .put(format!("{endpoint}/uploads/{upload_id}"))
+            .timeout(REQUEST_TIMEOUT)
+            .header(
+                "Content-Range",
+                format!("bytes {offset}-{}/{total}", end.saturating_sub(1)),
+            )
+            .body(bytes[offset..end].to_vec())
+            .send()
+            .await
+            .map_err(|e| e.to_string())?
+            .error_for_status()
+            .map_err(|e| e.to_string())?
+            .json()
+            .await
+            .map_err(|e| e.to_string())?;
+        if let Some(ticket) = status.ticket_id {
+            return Ok(ticket);
+        }
+        let received = status.received as usize;
+        if received <= offset || received > total {
+            return Err(format!(
+                "upload stalled at {offset} of {total} bytes (server has {received})"
+            ));
+        }
+        offset = received;
+    }
+}
+
+/// Upload diagnostics bundle `report_id` to the maintainers.
+///
+/// Called without `confirm_token` it only says what would be sent where; the upload needs
+/// the token back (the player's consent). An interrupted upload resumes on the next call.
+#[tauri::command]
+pub async fn submit_diagnostics(
+    app: tauri::AppHandle,
+    report_id: String,
+    confirm_token: Option<String>,
+) -> Result<SubmitResult, String> {
+    let path = bundle_path(&app, &report_id)?;
+    if !path.is_file() {
+        return Err(format!("no diagnostics bundle {report_id}"));
+    }
+    let size = std::fs::metadata(&path).map_err(|e| e.to_string())?.len();
+    let client = reqwest::Client::new();
+    let endpoint = mod_config::fetch_remote_manifest(&client)
+        .await
+        .ok()
+        .or_else(mod_config::cached_remote_manifest)
+        .and_then(|m| m.diagnostics_url)
+        .map(|u| u.trim().trim_end_matches('/').to_string())
+        .filter(|u| !u.is_empty())
+        .ok_or_else(|| "diagnostics upload is not configured".to_string())?;
+
+    let mut state = read_uploads(&app);
+    let previous = state.uploads.get(&report_id).cloned();
+    if let Some(ticket_id) = previous.as_ref().and_then(|u| u.ticket_id.clone()) {
+        return Ok(SubmitResult {
+            report_id,
+            endpoint,
+            size,
+            confirm_token: None,
+            ticket_id: Some(ticket_id),
+        });
+    }
+
+    let action = format!("submit_diagnostics:{report_id}");
+    let Some(token) = confirm_token else {
+        return Ok(SubmitResult {
+            report_id,
+            endpoint,
+            size,
+            confirm_token: Some(crate::confirm::issue(&app, &action)?),
+            ticket_id: None,
+        });
+    };
+    crate::confirm::consume(&app, &action, &token)?;
+
+    let bytes = std::fs::read(&path).map_err(|e| e.to_string())?;
+    // Resume where the server stopped; an unknown upload id starts over.
+    let resumed = match &previous {
+        Some(upload) => match upload_status(&client, &endpoint, &upload.upload_id).await {
+            Ok(status) => Some((upload.upload_id.clone(), status)),
+            Err(e) => {
+                log::warn!("Can't resume diagnostics upload {}: {e}", upload.upload_id);
+                None
+            }
+        },
+        None => None,
+    };
+    let (upload_id, offset) = match resumed {
+        Some((
+            _,
+            UploadStatus {
+                ticket_id: Some(ticket_id),
+                ..
+            },
+        )) => {
+            state.uploads.insert(
+                report_id.clone(),
+                Upload {
+                    upload_id: previous.map(|u| u.upload_id).unwrap_or_default(),
+                    ticket_id: Some(ticket_id.clone()),
+                },
+            );
+            write_uploads(&app, &state)?;
+            return Ok(SubmitResult {
+                report_id,
+                endpoint,
+                size,
+                confirm_token: None,
+                ticket_id: Some(ticket_id),
+            });
+        }
+        Some((upload_id, status)) => (upload_id, (status.received as usize).min(bytes.len())),
+        None => {
+            let wait =
+                (state.last_submitted_at + SUBMIT_INTERVAL.as_secs()).saturating_sub(now_secs());
+            if wait > 0 {
+                return Err(format!(
+                    "a report was submitted recently; try again in {} min",
+                    wait.div_ceil(60)
+                ));
+            }
+            let upload_id = start_upload(&app, &client, &endpoint, &report_id, &bytes).await?;
+            state.last_submitted_at = now_secs();
+            state.uploads.insert(
+                report_id.clone(),
+                Upload {
+                    upload_id: upload_id.clone(),
+                    ticket_id: None,
+                },
+            );
+            write_uploads(&app, &state)?;
+            (upload_id, 0)
+        }
+    };
+
+    log::info!(
+        "Uploading diagnostics {report_id} from byte {offset} of {}",
+        bytes.len()
+    );
+    let ticket_id = send_chunks(&client, &endpoint, &upload_id, &bytes, offset).await?;
+    state.uploads.insert(
+        report_id.clone(),
+        Upload {
+            upload_id,
+            ticket_id: Some(ticket_id.clone()),
+        },
+    );
+    write_uploads(&app, &state)?;
+    log::info!("Diagnostics {report_id} submitted as ticket {ticket_id}");
+
+    Ok(SubmitResult {
+        report_id,
+        endpoint,
+        size,
+        confirm_token: None,
+        ticket_id: Some(ticket_id),
+    })
+}
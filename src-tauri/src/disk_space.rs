@@ -0,0 +1,116 @@
+//! Free disk space checks before large writes.
+//!
+//! An install that runs out of space fails deep inside DepotDownloader or an extraction
+//! with an IO error that says nothing about the cause, and leaves a half-written version
+//! dir behind. Checking up front against the manifest's size estimate turns that into a
+//! clear, early error.
+
+use std::path::{Path, PathBuf};
+
+use crate::error::{Error, Result};
+
+/// Installed size assumed when the manifest doesn't publish `install_size` for a version
+/// (game, BepInEx and the manifest mods, rounded up).
+pub const DEFAULT_INSTALL_SIZE_BYTES: u64 = 2 * 1024 * 1024 * 1024;
+
+/// Headroom on top of the estimate for temp files and logs written during the install.
+const MARGIN_BYTES: u64 = 256 * 1024 * 1024;
+
+/// `path` itself, or its nearest ancestor that exists (the target of an install may not).
+fn existing_ancestor(path: &Path) -> Option<PathBuf> {
+    path.ancestors().find(|p| p.exists()).map(Path::to_path_buf)
+}
+
+/// Space on the volume holding a path.
+#[derive(Debug, Clone, Copy)]
+pub struct VolumeSpace {
+    /// Bytes available to this user (excludes root-reserved blocks on unix).
+    pub available: u64,
+    pub total: u64,
+}
+
+/// Bytes available to this user on the filesystem holding `path`.
+pub fn available_bytes(path: &Path) -> Result<u64> {
+    volume_space(path).map(|v| v.available)
+}
+
+/// Available and total bytes of the filesystem holding `path`.
+#[cfg(unix)]
+pub fn volume_space(path: &Path) -> Result<VolumeSpace> {
+    use std::os::unix::ffi::OsStrExt;
+
+    let dir = existing_ancestor(path)
+        .ok_or_else(|| Error::Other(format!("{} not found", path.display())))?;
+    let c_path = std::ffi::CString::new(dir.as_os_str().as_bytes())
+        .map_err(|e| Error::Other(e.to_string()))?;
+    let mut st: libc::statvfs = unsafe { std::mem::zeroed() };
+    // SAFETY: `c_path` is NUL-terminated and `st` is a valid, writable statvfs.
+    if unsafe { libc::statvfs(c_path.as_ptr(), &mut st) } != 0 {
+        return Err(Error::io(&dir, std::io::Error::last_os_error()).in_step("statvfs"));
+    }
+    let block = st.f_frsize as u64;
+    Ok(VolumeSpace {
+        available: (st.f_bavail as u64).saturating_mul(block),
+        total: (st.f_blocks as u64).saturating_mul(block),
+    })
+}
+
+#[cfg(windows)]
+#[link(name = "kernel32")]
+extern "system" {
+    fn GetDiskFreeSpaceExW(
+        directory: *const u16,
+        free_available: *mut u64,
+        total: *mut u64,
+        total_free: *mut u64,
+    ) -> i32;
+}
+
+/// Available and total bytes of the volume holding `path`.
+#[cfg(windows)]
+pub fn volume_space(path: &Path) -> Result<VolumeSpace> {
+    use std::os::windows::ffi::OsStrExt;
+
+    let dir = existing_ancestor(path)
+        .ok_or_else(|| Error::Other(format!("{} not found", path.display())))?;
+    let wide: Vec<u16> = dir.as_os_str().encode_wide().chain([0]).collect();
+    let (mut free, mut total) = (0u64, 0u64);
+    // SAFETY: `wide` is NUL-terminated; the last out-param may be null.
+    let ok = unsafe {
+        GetDiskFreeSpaceExW(
+            wide.as_ptr(),
+            &mut free,
+            &mut total,
+            std::ptr::null_mut(),
+        )
+    };
+    if ok == 0 {
+        return Err(Error::io(&dir, std::io::Error::last_os_error()).in_step("GetDiskFreeSpaceExW"));
+    }
+    Ok(VolumeSpace {
+        available: free,
+        total,
+    })
+}
+
+#[cfg(not(any(unix, windows)))]
+pub fn volume_space(_path: &Path) -> Result<VolumeSpace> {
+    Err("free disk space is not available on this platform".into())
+}
+
+/// Bytes an install writes, given the manifest's estimate (if any).
+pub fn required_bytes(estimate: Option<u64>) -> u64 {
+    estimate
+        .unwrap_or(DEFAULT_INSTALL_SIZE_BYTES)
+        .saturating_add(MARGIN_BYTES)
+}
+
+pub fn format_bytes(bytes: u64) -> String {
+    const GIB: f64 = 1024.0 * 1024.0 * 1024.0;
+    const MIB: f64 = 1024.0 * 1024.0;
+    if bytes as f64 >= GIB {
+        format!("{:.1} GiB", bytes as f64 / GIB)
+    } else {
+        format!("{:.0} MiB", bytes as f64 / MIB)
+    }
+}
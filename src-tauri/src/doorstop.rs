@@ -0,0 +1,338 @@
+//! Typed editor for the loader's own settings, so nobody has to hand-edit INI files inside
+//! a versioned folder:
+//!
+//! - `doorstop_config.ini` in the game root (per version): whether BepInEx loads at all,
+//!   the assembly doorstop hands control to, and output log redirection.
+//! - `BepInEx/config/BepInEx.cfg` (shared by every version through the config link):
+//!   console window and log levels.
+//!
+//! Edits are line-based, so comments, ordering and keys this module doesn't know survive.
+
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{storage, zip_utils};
+
+/// BepInEx log levels, as spelled in `LogLevels` (a comma-separated set).
+pub const LOG_LEVELS: [&str; 8] = [
+    "None", "Fatal", "Error", "Warning", "Message", "Info", "Debug", "All",
+];
+
+/// BepInEx's default `LogLevels` for both the console and the disk log.
+const DEFAULT_LOG_LEVELS: &str = "Fatal, Error, Warning, Message, Info";
+
+/// Value of `key` in `[section]` (both case-insensitive), without surrounding whitespace.
+pub fn get_ini_value(text: &str, section: &str, key: &str) -> Option<String> {
+    let header = format!("[{section}]");
+    let mut in_section = false;
+    for line in text.lines() {
+        let trimmed = line.trim();
+        if trimmed.starts_with('[') {
+            in_section = trimmed.eq_ignore_ascii_case(&header);
+        } else if in_section {
+            if let Some((k, v)) = trimmed.split_once('=') {
+                if k.trim().eq_ignore_ascii_case(key) {
+                    return Some(v.trim().to_string());
+                }
+            }
+        }
+    }
+    None
+}
+
+/// `text` with `key = value` set in `[section]`, replacing the key if present and adding
+/// the key (and section) otherwise.
+pub fn set_ini_value(text: &str, section: &str, key: &str, value: &str) -> String {
+    let header = format!("[{section}]");
+    let line_for = || format!("{key} = {value}");
+    let mut out: Vec<String> = Vec::new();
+    let mut in_section = false;
+    let mut done = false;
+    for line in text.lines() {
+        let trimmed = line.trim();
+        if trimmed.starts_with('[') {
+            if in_section && !done {
+                // Append after the section's last line, before the blank lines separating
+                // it from the next one.
+                let at = out
+                    .iter()
+                    .rposition(|l| !l.trim().is_empty())
+                    .map_or(0, |i| i + 1);
+                out.insert(at, line_for());
+                done = true;
+            }
+            in_section = trimmed.eq_ignore_ascii_case(&header);
+        } else if in_section
+            && !done
+            && trimmed
+                .split_once('=')
+                .is_some_and(|(k, _)| k.trim().eq_ignore_ascii_case(key))
+        {
+            out.push(line_for());
+            done = true;
+            continue;
+        }
+        out.push(line.to_string());
+    }
+    if !done {
+        if !in_section {
+            if out.last().is_some_and(|l| !l.trim().is_empty()) {
+                out.push(String::new());
+            }
+            out.push(header);
+        }
+        out.push(line_for());
+    }
+    let mut text = out.join("\n");
+    text.push('\n');
+    text
+}
+
+/// Section and key names of the doorstop generation that wrote `ini`: doorstop 3 (older
+/// BepInExPacks) uses `[UnityDoorstop]` with camelCase keys, doorstop 4 `[General]`.
+struct DoorstopKeys {
+    section: &'static str,
+    enabled: &'static str,
+    target_assembly: &'static str,
+    redirect_output_log: &'static str,
+}
+
+fn doorstop_keys(ini: &str) -> DoorstopKeys {
+    if ini.contains("[UnityDoorstop]") {
+        DoorstopKeys {
+            section: "UnityDoorstop",
+            enabled: "enabled",
+            target_assembly: "targetAssembly",
+            redirect_output_log: "redirectOutputLog",
+        }
+    } else {
+        DoorstopKeys {
+            section: "General",
+            enabled: "enabled",
+            target_assembly: "target_assembly",
+            redirect_output_log: "redirect_output_log",
+        }
+    }
+}
+
+/// Doorstop generation of `ini` (3 or 4).
+fn doorstop_version(ini: &str) -> u8 {
+    if doorstop_keys(ini).section == "UnityDoorstop" {
+        3
+    } else {
+        4
+    }
+}
+
+fn parse_bool(value: Option<String>, default: bool) -> bool {
+    match value.map(|v| v.to_ascii_lowercase()) {
+        Some(v) if v == "true" || v == "1" => true,
+        Some(v) if v == "false" || v == "0" => false,
+        _ => default,
+    }
+}
+
+fn parse_levels(value: Option<String>) -> Vec<String> {
+    value
+        .as_deref()
+        .unwrap_or(DEFAULT_LOG_LEVELS)
+        .split(',')
+        .map(str::trim)
+        .filter(|l| !l.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+/// `levels` in BepInEx's spelling, or an error naming the first unknown one.
+fn normalize_levels(levels: &[String]) -> Result<String, String> {
+    let mut out = vec![];
+    for level in levels {
+        let known = LOG_LEVELS
+            .iter()
+            .find(|l| l.eq_ignore_ascii_case(level.trim()))
+            .ok_or_else(|| {
+                format!(
+                    "unknown log level `{level}` (expected one of {})",
+                    LOG_LEVELS.join(", ")
+                )
+            })?;
+        out.push(*known);
+    }
+    if out.is_empty() {
+        out.push("None");
+    }
+    Ok(out.join(", "))
+}
+
+/// `target` as a doorstop path (`\`-separated), if it names a file under `game_root`.
+fn checked_target(game_root: &Path, target: &str) -> Result<String, String> {
+    let parts: Vec<&str> = target
+        .split(['/', '\\'])
+        .filter(|p| !p.is_empty() && *p != ".")
+        .collect();
+    if parts.is_empty() || parts.iter().any(|p| *p == ".." || p.contains(':')) {
+        return Err(format!(
+            "target assembly must be a path inside the game folder: {target}"
+        ));
+    }
+    let path: PathBuf = parts.iter().collect();
+    if !game_root.join(&path).is_file() {
+        return Err(format!("{} not found in the game folder", path.display()));
+    }
+    Ok(parts.join("\\"))
+}
+
+fn doorstop_path(game_root: &Path) -> PathBuf {
+    game_root.join("doorstop_config.ini")
+}
+
+fn bepinex_cfg_path(game_root: &Path) -> PathBuf {
+    game_root.join("BepInEx").join("config").join("BepInEx.cfg")
+}
+
+/// A file that may not exist yet (BepInEx writes `BepInEx.cfg` on first launch).
+fn read_optional(path: &Path) -> Result<String, String> {
+    match std::fs::read_to_string(path) {
+        Ok(t) => Ok(t),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(String::new()),
+        Err(e) => Err(format!("{}: {e}", path.display())),
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct LoaderConfig {
+    pub version: u32,
+    /// Doorstop generation (3 or 4); decides the key names written back.
+    pub doorstop_version: u8,
+    /// `false` starts the game without BepInEx (and without any mods).
+    pub doorstop_enabled: bool,
+    /// Relative to the game root, `\`-separated.
+    pub target_assembly: String,
+    /// Unity's `output_log.txt` goes next to the game instead of the user profile.
+    pub redirect_output_log: bool,
+    /// BepInEx console window.
+    pub console_enabled: bool,
+    pub console_log_levels: Vec<String>,
+    /// `BepInEx/LogOutput.log`.
+    pub disk_log_enabled: bool,
+    pub disk_log_levels: Vec<String>,
+}
+
+/// Fields to change; `None` leaves a setting as it is.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct LoaderConfigPatch {
+    pub doorstop_enabled: Option<bool>,
+    pub target_assembly: Option<String>,
+    pub redirect_output_log: Option<bool>,
+    pub console_enabled: Option<bool>,
+    pub console_log_levels: Option<Vec<String>>,
+    pub disk_log_enabled: Option<bool>,
+    pub disk_log_levels: Option<Vec<String>>,
+}
+
+fn read_config(game_root: &Path, version: u32) -> Result<LoaderConfig, String> {
+    let doorstop = std::fs::read_to_string(doorstop_path(game_root))
+        .map_err(|e| format!("doorstop_config.ini of v{version}: {e}"))?;
+    let keys = doorstop_keys(&doorstop);
+    let cfg = read_optional(&bepinex_cfg_path(game_root))?;
+    Ok(LoaderConfig {
+        version,
+        doorstop_version: doorstop_version(&doorstop),
+        doorstop_enabled: parse_bool(get_ini_value(&doorstop, keys.section, keys.enabled), true),
+        target_assembly: get_ini_value(&doorstop, keys.section, keys.target_assembly)
+            .unwrap_or_default(),
+        redirect_output_log: parse_bool(
+            get_ini_value(&doorstop, keys.section, keys.redirect_output_log),
+            false,
+        ),
+        console_enabled: parse_bool(get_ini_value(&cfg, "Logging.Console", "Enabled"), false),
+        console_log_levels: parse_levels(get_ini_value(&cfg, "Logging.Console", "LogLevels")),
+        disk_log_enabled: parse_bool(get_ini_value(&cfg, "Logging.Disk", "Enabled"), true),
+        disk_log_levels: parse_levels(get_ini_value(&cfg, "Logging.Disk", "LogLevels")),
+    })
+}
+
+fn game_root(app: &tauri::AppHandle, version: Option<u32>) -> Result<(u32, PathBuf), String> {
+    let version = crate::resolve_version(app, version)?;
+    Ok((version, storage::installed_version_dir(app, version)?))
+}
+
+/// Doorstop and BepInEx settings of `version` (default: active version).
+#[tauri::command]
+pub fn get_loader_config(
+    app: tauri::AppHandle,
+    version: Option<u32>,
+) -> Result<LoaderConfig, String> {
+    let (version, game_root) = game_root(&app, version)?;
+    read_config(&game_root, version)
+}
+
+/// Apply `patch` to `version` (default: active version) and return the result. Everything
+/// is validated before either file is written. BepInEx.cfg is shared, so its settings
+/// change for every installed version; changes apply on the next launch.
+#[tauri::command]
+pub fn set_loader_config(
+    app: tauri::AppHandle,
+    version: Option<u32>,
+    patch: LoaderConfigPatch,
+) -> Result<LoaderConfig, String> {
+    let (version, game_root) = game_root(&app, version)?;
+
+    let doorstop_file = doorstop_path(&game_root);
+    let mut doorstop = std::fs::read_to_string(&doorstop_file)
+        .map_err(|e| format!("doorstop_config.ini of v{version}: {e}"))?;
+    let keys = doorstop_keys(&doorstop);
+    let flag = |b: bool| if b { "true" } else { "false" };
+    if let Some(target) = &patch.target_assembly {
+        let target = checked_target(&game_root, target)?;
+        doorstop = set_ini_value(&doorstop, keys.section, keys.target_assembly, &target);
+    }
+    if let Some(enabled) = patch.doorstop_enabled {
+        doorstop = set_ini_value(&doorstop, keys.section, keys.enabled, flag(enabled));
+    }
+    if let Some(redirect) = patch.redirect_output_log {
+        doorstop = set_ini_value(
+            &doorstop,
+            keys.section,
+            keys.redirect_output_log,
+            flag(redirect),
+        );
+    }
+
+    let cfg_file = bepinex_cfg_path(&game_root);
+    let mut cfg = read_optional(&cfg_file)?;
+    let cfg_before = cfg.clone();
+    if let Some(enabled) = patch.console_enabled {
+        cfg = set_ini_value(&cfg, "Logging.Console", "Enabled", flag(enabled));
+    }
+    if let Some(levels) = &patch.console_log_levels {
+        cfg = set_ini_value(
+            &cfg,
+            "Logging.Console",
+            "LogLevels",
+            &normalize_levels(levels)?,
+        );
+    }
+    if let Some(enabled) = patch.disk_log_enabled {
+        cfg = set_ini_value(&cfg, "Logging.Disk", "Enabled", flag(enabled));
+    }
+    if let Some(levels) = &patch.disk_log_levels {
+        cfg = set_ini_value(
+            &cfg,
+            "Logging.Disk",
+            "LogLevels",
+            &normalize_levels(levels)?,
+        );
+    }
+
+    log::info!("Updating loader config of v{version}: {patch:?}");
+    zip_utils::write_atomic(&doorstop_file, doorstop)?;
+    if cfg != cfg_before {
+        if let Some(parent) = cfg_file.parent() {
+            std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+        }
+        zip_utils::write_atomic(&cfg_file, cfg)?;
+    }
+    read_config(&game_root, version)
+}
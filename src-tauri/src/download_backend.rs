@@ -0,0 +1,541 @@
+//! Backends for HTTP file downloads (BepInEx, mod zips, tool archives).
+//!
+//! reqwest is built in and the default. Users whose TLS interception or proxy setup
+//! defeats it can switch to the system `curl`, and `aria2c` adds segmented downloads with
+//! its own resume. The external tools are looked up on `PATH` at download time; a
+//! configured backend that isn't installed falls back to reqwest with a warning.
+//!
+//! Every backend downloads into `{dest}.part` and renames it into place on success, and
+//! honours cancellation and `Settings::bandwidth_limit`. The external tools keep their
+//! `.part` (and aria2c its `.aria2` control file) when a download fails or is cancelled,
+//! so the next attempt resumes it.
+
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
+
+use futures_util::future::BoxFuture;
+use serde::{Deserialize, Serialize};
+use tokio::io::{AsyncBufReadExt, BufReader};
+use tokio::process::Command;
+use tokio::sync::mpsc;
+
+use crate::downloader::{self, HttpDownloadError};
+use crate::fs_utils::find_on_path;
+
+/// How often progress is forwarded and cancellation checked.
+const POLL_INTERVAL: Duration = Duration::from_millis(200);
+
+/// Connections aria2c opens per file.
+const ARIA2_SEGMENTS: u32 = 8;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DownloadBackendKind {
+    #[default]
+    Reqwest,
+    Aria2c,
+    Curl,
+}
+
+impl DownloadBackendKind {
+    const ALL: [DownloadBackendKind; 3] = [Self::Reqwest, Self::Aria2c, Self::Curl];
+
+    /// Executable looked up on `PATH`; `None` for the built-in backend.
+    fn program(self) -> Option<&'static str> {
+        match self {
+            Self::Reqwest => None,
+            Self::Aria2c => Some("aria2c"),
+            Self::Curl => Some("curl"),
+        }
+    }
+}
+
+static PREFERRED: Mutex<DownloadBackendKind> = Mutex::new(DownloadBackendKind::Reqwest);
+
+/// Backend new downloads use (`Settings::download_backend`).
+pub(crate) fn set_preferred(kind: DownloadBackendKind) {
+    if let Ok(mut preferred) = PREFERRED.lock() {
+        *preferred = kind;
+    }
+}
+
+fn preferred() -> DownloadBackendKind {
+    PREFERRED.lock().map(|p| *p).unwrap_or_default()
+}
+
+/// Most downloads the launcher runs at once (`Settings::mod_download_concurrency`).
+static DOWNLOAD_SLOTS: AtomicUsize = AtomicUsize::new(1);
+
+pub(crate) fn set_download_slots(slots: usize) {
+    DOWNLOAD_SLOTS.store(slots.max(1), Ordering::Relaxed);
+}
+
+/// Rate cap for one external tool run. The tools can't share the built-in pacing, so
+/// each gets an equal slice of `bandwidth_limit`, sized for the most downloads that can
+/// run at once; together they stay under the global cap.
+fn tool_rate_limit() -> u64 {
+    let limit = downloader::bandwidth_limit();
+    if limit == 0 {
+        return 0;
+    }
+    (limit / DOWNLOAD_SLOTS.load(Ordering::Relaxed).max(1) as u64).max(1)
+}
+
+fn control_path(part: &Path) -> PathBuf {
+    let mut name = part.as_os_str().to_os_string();
+    name.push(".aria2");
+    PathBuf::from(name)
+}
+
+/// Drop a partial download that can't be resumed.
+fn discard_partial(part: &Path) {
+    let _ = std::fs::remove_file(part);
+    let _ = std::fs::remove_file(control_path(part));
+}
+/// Bytes received so far and the total (`0` while unknown), written by a backend and
+/// forwarded to the caller's callback by `download`.
+#[derive(Debug, Default)]
+pub(crate) struct Progress {
+    received: AtomicU64,
+    total: AtomicU64,
+}
+
+impl Progress {
+    fn set(&self, received: u64, total: Option<u64>) {
+        self.received.store(received, Ordering::Relaxed);
+        self.total.store(total.unwrap_or(0), Ordering::Relaxed);
+    }
+
+    fn get(&self) -> (u64, Option<u64>) {
+        let total = self.total.load(Ordering::Relaxed);
+        (
+            self.received.load(Ordering::Relaxed),
+            (total > 0).then_some(total),
+        )
+    }
+}
+
+/// Downloads one URL into a file.
+pub(crate) trait DownloadBackend: Send + Sync {
+    fn kind(&self) -> DownloadBackendKind;
+
+    /// Download `url` into `dest`; returns the bytes written.
+    fn download<'a>(
+        &'a self,
+        url: &'a str,
+        dest: &'a Path,
+        cancel: Option<&'a AtomicBool>,
+        progress: &'a Progress,
+    ) -> BoxFuture<'a, Result<u64, HttpDownloadError>>;
+}
+
+/// Built-in downloader with range resume (`downloader::download_resumable`).
+struct ReqwestBackend {
+    client: reqwest::Client,
+}
+
+impl DownloadBackend for ReqwestBackend {
+    fn kind(&self) -> DownloadBackendKind {
+        DownloadBackendKind::Reqwest
+    }
+
+    fn download<'a>(
+        &'a self,
+        url: &'a str,
+        dest: &'a Path,
+        cancel: Option<&'a AtomicBool>,
+        progress: &'a Progress,
+    ) -> BoxFuture<'a, Result<u64, HttpDownloadError>> {
+        Box::pin(downloader::download_resumable(
+            &self.client,
+            url,
+            dest,
+            cancel,
+            |received, total| progress.set(received, total),
+        ))
+    }
+}
+
+struct Aria2Backend {
+    executable: PathBuf,
+}
+
+impl DownloadBackend for Aria2Backend {
+    fn kind(&self) -> DownloadBackendKind {
+        DownloadBackendKind::Aria2c
+    }
+
+    fn download<'a>(
+        &'a self,
+        url: &'a str,
+        dest: &'a Path,
+        cancel: Option<&'a AtomicBool>,
+        progress: &'a Progress,
+    ) -> BoxFuture<'a, Result<u64, HttpDownloadError>> {
+        Box::pin(async move {
+            let part = downloader::part_path(dest);
+            let (dir, name) = split_dest(&part)?;
+            let mut cmd = Command::new(&self.executable);
+            cmd.arg("--continue=true")
+                .arg("--allow-overwrite=true")
+                .arg("--auto-file-renaming=false")
+                .arg("--file-allocation=none")
+                .arg(format!("--split={ARIA2_SEGMENTS}"))
+                .arg(format!("--max-connection-per-server={ARIA2_SEGMENTS}"))
+                .arg("--min-split-size=1M")
+                .arg("--max-tries=5")
+                .arg("--retry-wait=1")
+                .arg("--console-log-level=error")
+                .arg("--show-console-readout=false")
+                .arg("--summary-interval=1")
+                .arg("--download-result=hide")
+                .arg("--dir")
+                .arg(dir)
+                .arg("--out")
+                .arg(name);
+            let limit = tool_rate_limit();
+            if limit > 0 {
+                cmd.arg(format!("--max-overall-download-limit={limit}"));
+            }
+            cmd.arg(url);
+
+            // A `.part` without its control file can't be resumed safely; aria2c would
+            // treat it as complete.
+            if part.exists() && !control_path(&part).exists() {
+                discard_partial(&part);
+            }
+            let run = run_tool(cmd, &part, cancel, |line| {
+                if let Some((received, total)) = parse_aria2_readout(line) {
+                    progress.set(received, total);
+                }
+            })
+            .await?;
+            match run {
+                ToolExit { code: 0, .. } => {
+                    let _ = std::fs::remove_file(control_path(&part));
+                    finish(&part, dest)
+                }
+                // "Resource was not found"
+                ToolExit { code: 3, stderr, .. } => {
+                    discard_partial(&part);
+                    Err(HttpDownloadError::NotFound(format!("{url}: {stderr}")))
+                }
+                // Kept for the next attempt to resume.
+                ToolExit { code, stderr, .. } => Err(HttpDownloadError::Interrupted(format!(
+                    "aria2c exited with {code}: {stderr}"
+                ))),
+            }
+        })
+    }
+}
+
+struct CurlBackend {
+    executable: PathBuf,
+}
+
+impl DownloadBackend for CurlBackend {
+    fn kind(&self) -> DownloadBackendKind {
+        DownloadBackendKind::Curl
+    }
+
+    fn download<'a>(
+        &'a self,
+        url: &'a str,
+        dest: &'a Path,
+        cancel: Option<&'a AtomicBool>,
+        progress: &'a Progress,
+    ) -> BoxFuture<'a, Result<u64, HttpDownloadError>> {
+        Box::pin(async move {
+            let part = downloader::part_path(dest);
+            let mut cmd = Command::new(&self.executable);
+            cmd.args(["--fail", "--location", "--silent", "--show-error"])
+                .args(["--retry", "5", "--retry-delay", "1", "--continue-at", "-"])
+                .args(["--write-out", "%{http_code}\\n"])
+                .arg("--output")
+                .arg(&part);
+            let limit = tool_rate_limit();
+            if limit > 0 {
+                cmd.arg("--limit-rate").arg(limit.to_string());
+            }
+            cmd.arg(url);
+
+            // curl writes sequentially, so the .part size is the progress.
+            let run = run_tool(cmd, &part, cancel, |_| {
+                let received = std::fs::metadata(&part).map(|m| m.len()).unwrap_or(0);
+                progress.set(received, None);
+            })
+            .await?;
+            match run {
+                ToolExit { code: 0, .. } => finish(&part, dest),
+                ToolExit { stdout, .. } if stdout.trim() == "404" => {
+                    discard_partial(&part);
+                    Err(HttpDownloadError::NotFound(format!("{url}: 404 Not Found")))
+                }
+                // 33: the server can't resume ranges, so the `.part` is useless.
+                ToolExit { code: 33, stderr, .. } => {
+                    discard_partial(&part);
+                    Err(HttpDownloadError::Failed(format!(
+                        "curl exited with 33: {stderr}"
+                    )))
+                }
+                // Kept for the next attempt to resume.
+                ToolExit { code, stderr, .. } => Err(HttpDownloadError::Interrupted(format!(
+                    "curl exited with {code}: {stderr}"
+                ))),
+            }
+        })
+    }
+}
+
+fn split_dest(part: &Path) -> Result<(&Path, &std::ffi::OsStr), HttpDownloadError> {
+    match (part.parent(), part.file_name()) {
+        (Some(dir), Some(name)) => Ok((dir, name)),
+        _ => Err(HttpDownloadError::Failed(format!(
+            "invalid download target {}",
+            part.display()
+        ))),
+    }
+}
+
+fn finish(part: &Path, dest: &Path) -> Result<u64, HttpDownloadError> {
+    let len = std::fs::metadata(part)
+        .map_err(|e| HttpDownloadError::Failed(format!("{}: {e}", part.display())))?
+        .len();
+    std::fs::rename(part, dest).map_err(|e| HttpDownloadError::Failed(e.to_string()))?;
+    Ok(len)
+}
+
+struct ToolExit {
+    code: i32,
+    /// Last stdout line.
+    stdout: String,
+    /// Last stderr line.
+    stderr: String,
+}
+
+/// Run a download tool to completion. `on_output` gets each stdout line, and is also
+/// called with an empty line every `POLL_INTERVAL`. Cancelling kills the tool; `part` is
+/// left for a later resume.
+async fn run_tool<F>(
+    mut cmd: Command,
+    part: &Path,
+    cancel: Option<&AtomicBool>,
+    mut on_output: F,
+) -> Result<ToolExit, HttpDownloadError>
+where
+    F: FnMut(&str),
+{
+    if let Some(parent) = part.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| HttpDownloadError::Failed(e.to_string()))?;
+    }
+    let mut child = cmd
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true)
+        .spawn()
+        .map_err(|e| HttpDownloadError::Failed(format!("failed to start downloader: {e}")))?;
+
+    let (tx, mut rx) = mpsc::unbounded_channel::<(bool, String)>();
+    if let Some(stdout) = child.stdout.take() {
+        let tx = tx.clone();
+        tokio::spawn(async move {
+            let mut r = BufReader::new(stdout).lines();
+            while let Ok(Some(line)) = r.next_line().await {
+                let _ = tx.send((false, line));
+            }
+        });
+    }
+    if let Some(stderr) = child.stderr.take() {
+        let tx = tx.clone();
+        tokio::spawn(async move {
+            let mut r = BufReader::new(stderr).lines();
+            while let Ok(Some(line)) = r.next_line().await {
+                let _ = tx.send((true, line));
+            }
+        });
+    }
+    drop(tx);
+
+    let (mut stdout, mut stderr) = (String::new(), String::new());
+    let mut ticks = tokio::time::interval(POLL_INTERVAL);
+    let status = loop {
+        tokio::select! {
+            s = child.wait() => {
+                break s.map_err(|e| HttpDownloadError::Failed(e.to_string()))?;
+            }
+            Some((is_err, line)) = rx.recv() => {
+                if line.trim().is_empty() {
+                    continue;
+                }
+                if is_err {
+                    stderr = line;
+                } else {
+                    on_output(&line);
+                    stdout = line;
+                }
+            }
+            _ = ticks.tick() => {
+                if cancel.is_some_and(|c| c.load(Ordering::Relaxed)) {
+                    let _ = child.kill().await;
+                    let _ = child.wait().await;
+                    return Err(HttpDownloadError::Cancelled);
+                }
+                on_output("");
+            }
+        }
+    };
+    // Output still queued when the tool exited.
+    while let Ok((is_err, line)) = rx.try_recv() {
+        if line.trim().is_empty() {
+            continue;
+        }
+        if is_err {
+            stderr = line;
+        } else {
+            stdout = line;
+        }
+    }
+    Ok(ToolExit {
+        code: status.code().unwrap_or(-1),
+        stdout,
+        stderr,
+    })
+}
+
+/// `12MiB` / `1.5GiB` / `512B` as bytes.
+fn parse_aria2_size(s: &str) -> Option<u64> {
+    let split = s.find(|c: char| c.is_ascii_alphabetic())?;
+    let (number, unit) = s.split_at(split);
+    let factor: f64 = match unit {
+        "B" => 1.0,
+        "KiB" => 1024.0,
+        "MiB" => 1024.0 * 1024.0,
+        "GiB" => 1024.0 * 1024.0 * 1024.0,
+        _ => return None,
+    };
+    Some((number.parse::<f64>().ok()? * factor) as u64)
+}
+
+/// Received and total bytes from an aria2c summary line such as
+/// `[#2089b0 12MiB/40MiB(30%) CN:8 DL:3.1MiB ETA:8s]`.
+fn parse_aria2_readout(line: &str) -> Option<(u64, Option<u64>)> {
+    let rest = &line[line.find("[#")?..];
+    let sizes = rest.split_whitespace().nth(1)?;
+    let (received, total) = sizes.split_once('/')?;
+    let total = total.split('(').next()?;
+    let total = parse_aria2_size(total).filter(|t| *t > 0);
+    Some((parse_aria2_size(received)?, total))
+}
+
+/// Backend for the next download: the configured one if its tool is installed,
+/// otherwise reqwest.
+pub(crate) fn select(client: &reqwest::Client) -> Box<dyn DownloadBackend> {
+    let kind = preferred();
+    let external = kind.program().map(|program| (program, find_on_path(program)));
+    match (kind, external) {
+        (DownloadBackendKind::Aria2c, Some((_, Some(executable)))) => {
+            Box::new(Aria2Backend { executable })
+        }
+        (DownloadBackendKind::Curl, Some((_, Some(executable)))) => {
+            Box::new(CurlBackend { executable })
+        }
+        (_, Some((program, None))) => {
+            log::warn!("{program} not found on PATH; downloading with the built-in client");
+            Box::new(ReqwestBackend {
+                client: client.clone(),
+            })
+        }
+        _ => Box::new(ReqwestBackend {
+            client: client.clone(),
+        }),
+    }
+}
+
+/// Download `url` into `dest` with the selected backend. `on_progress(received, total)`
+/// is called as bytes arrive (at most every `POLL_INTERVAL`).
+pub(crate) async fn download<F>(
+    client: &reqwest::Client,
+    url: &str,
+    dest: &Path,
+    cancel: Option<&AtomicBool>,
+    mut on_progress: F,
+) -> Result<u64, HttpDownloadError>
+where
+    F: FnMut(u64, Option<u64>),
+{
+    let backend = select(client);
+    if backend.kind() != DownloadBackendKind::Reqwest {
+        log::info!("Downloading {url} with {:?}", backend.kind());
+    }
+    let progress = Progress::default();
+    let fut = backend.download(url, dest, cancel, &progress);
+    tokio::pin!(fut);
+    let mut ticks = tokio::time::interval(POLL_INTERVAL);
+    let mut reported = None;
+    let mut report = |on_progress: &mut F| {
+        let now = progress.get();
+        if reported != Some(now) {
+            reported = Some(now);
+            on_progress(now.0, now.1);
+        }
+    };
+    let result = loop {
+        tokio::select! {
+            res = &mut fut => break res,
+            _ = ticks.tick() => report(&mut on_progress),
+        }
+    };
+    report(&mut on_progress);
+    result
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DownloadBackendInfo {
+    pub kind: DownloadBackendKind,
+    /// Installed (always true for reqwest).
+    pub available: bool,
+    pub path: Option<String>,
+    /// First line of `--version`.
+    pub version: Option<String>,
+    /// Chosen in the settings; an unavailable choice falls back to reqwest.
+    pub selected: bool,
+}
+
+async fn tool_version(executable: &Path) -> Option<String> {
+    let output = Command::new(executable)
+        .arg("--version")
+        .stdin(Stdio::null())
+        .output()
+        .await
+        .ok()?;
+    String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .next()
+        .map(|l| l.trim().to_string())
+}
+
+/// Every backend with whether it can be used on this machine.
+#[tauri::command]
+pub async fn get_download_backends() -> Vec<DownloadBackendInfo> {
+    let selected = preferred();
+    let mut out = vec![];
+    for kind in DownloadBackendKind::ALL {
+        let executable = kind.program().and_then(find_on_path);
+        let version = match &executable {
+            Some(path) => tool_version(path).await,
+            None => None,
+        };
+        out.push(DownloadBackendInfo {
+            kind,
+            available: kind == DownloadBackendKind::Reqwest || executable.is_some(),
+            path: executable.map(|p| p.to_string_lossy().to_string()),
+            version,
+            selected: kind == selected,
+        });
+    }
+    out
+}
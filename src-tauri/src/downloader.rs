@@ -1,21 +1,23 @@
 use expectrl::{ControlCode, Regex, Session};
+use futures_util::future::BoxFuture;
 use log::info;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::process::Command as StdCommand;
 use std::process::Stdio;
-use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
 use std::sync::Arc;
 use std::sync::Mutex;
 use std::time::{Duration, Instant};
-use tauri::Emitter;
 use tauri::Manager;
 use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
 use tokio::process::Command;
 use tokio::sync::mpsc;
 
+use crate::fake_downloader::FakeDownloader;
 use crate::progress::{self, TaskProgressPayload};
+use crate::{settings, storage};
 
 fn strip_ansi(s: &str) -> String {
     // Minimal ANSI stripper for log display.
@@ -105,6 +107,41 @@ fn looks_like_twofactor_needed(text: &str) -> bool {
         || (l.contains("sent") && l.contains("code"))
 }
 
+/// Stop an interactive login: Ctrl+C first, then force the process down so an abandoned
+/// session doesn't linger until DepotDownloader gives up on its own.
+fn kill_login_process(p: &mut Session) {
+    let _ = p.send(ControlCode::EndOfText);
+    #[cfg(unix)]
+    {
+        let _ = p.get_process_mut().exit(true);
+    }
+    #[cfg(windows)]
+    {
+        let _ = p.get_process_mut().exit(1);
+    }
+}
+
+/// DepotDownloader release `install_downloader` fetches.
+const DEPOT_DOWNLOADER_VERSION: &str = "3.4.0";
+/// Written next to the binary by `install_downloader`: the release it came from. Installs
+/// from before the marker existed are the release above.
+const DEPOT_DOWNLOADER_VERSION_MARKER: &str = ".hq_launcher_version";
+
+/// First DepotDownloader release with `-validate` (checksum files already on disk). Older
+/// releases re-download pre-seeded files instead. The other flags the launcher passes are
+/// the same in every release it installs.
+const VALIDATE_SINCE: semver::Version = semver::Version::new(2, 4, 0);
+
+/// Release of the DepotDownloader installed in `downloader_dir`.
+fn installed_downloader_version(downloader_dir: &Path) -> semver::Version {
+    let recorded = std::fs::read_to_string(downloader_dir.join(DEPOT_DOWNLOADER_VERSION_MARKER))
+        .ok()
+        .and_then(|v| semver::Version::parse(v.trim()).ok());
+    recorded.unwrap_or_else(|| {
+        semver::Version::parse(DEPOT_DOWNLOADER_VERSION).expect("valid DepotDownloader version")
+    })
+}
+
 #[cfg(all(target_os = "windows", target_arch = "x86_64"))]
 const DEPOT_DOWNLOADER_NAME: &str = "DepotDownloader-windows-x64";
 
@@ -164,16 +201,276 @@ pub struct DownloadTaskContext {
     pub step_name: String,
 }
 
-fn overall_from_step(step: u32, step_progress: f64, steps_total: u32) -> f64 {
+pub(crate) fn overall_from_step(step: u32, step_progress: f64, steps_total: u32) -> f64 {
     let s = step.max(1).min(steps_total) as f64;
     let sp = step_progress.clamp(0.0, 1.0);
     (((s - 1.0) + sp) / (steps_total as f64)) * 100.0
 }
 
+/// Attempts per `download_resumable` call; each retry resumes where the last one stopped.
+const RESUME_ATTEMPTS: u32 = 5;
+
+/// Bytes per second shared by every `download_resumable` transfer; `0` = unlimited.
+static BANDWIDTH_LIMIT: AtomicU64 = AtomicU64::new(0);
+/// When the bandwidth budget is next free (pacing across concurrent downloads).
+static NEXT_SLOT: Mutex<Option<Instant>> = Mutex::new(None);
+
+/// Cap HTTP downloads (BepInEx, mods, configs) at `bytes_per_sec`; DepotDownloader
+/// isn't affected.
+pub(crate) fn set_bandwidth_limit(bytes_per_sec: u64) {
+    BANDWIDTH_LIMIT.store(bytes_per_sec, Ordering::Relaxed);
+}
+
+/// Current cap in bytes per second; `0` = unlimited. External download backends split
+/// it between them (`download_backend::tool_rate_limit`).
+pub(crate) fn bandwidth_limit() -> u64 {
+    BANDWIDTH_LIMIT.load(Ordering::Relaxed)
+}
+
+/// Wait until `bytes` more fit into the bandwidth limit.
+async fn throttle(bytes: u64) {
+    let limit = BANDWIDTH_LIMIT.load(Ordering::Relaxed);
+    if limit == 0 {
+        return;
+    }
+    let wait = {
+        let Ok(mut next) = NEXT_SLOT.lock() else {
+            return;
+        };
+        let now = Instant::now();
+        // Idle time isn't banked: a pause doesn't allow a burst afterwards.
+        let start = next.filter(|t| *t > now).unwrap_or(now);
+        let slot = start + Duration::from_secs_f64(bytes as f64 / limit as f64);
+        *next = Some(slot);
+        slot - now
+    };
+    tokio::time::sleep(wait).await;
+}
+
+#[derive(Debug)]
+pub(crate) enum HttpDownloadError {
+    /// The server answered 404 (e.g. a yanked package version).
+    NotFound(String),
+    /// Connection dropped or 5xx; worth resuming.
+    Interrupted(String),
+    Failed(String),
+    Cancelled,
+}
+
+impl From<HttpDownloadError> for String {
+    fn from(e: HttpDownloadError) -> Self {
+        match e {
+            HttpDownloadError::NotFound(e)
+            | HttpDownloadError::Interrupted(e)
+            | HttpDownloadError::Failed(e) => e,
+            HttpDownloadError::Cancelled => "Cancelled".to_string(),
+        }
+    }
+}
+
+/// `.part` file next to `dest`.
+pub(crate) fn part_path(dest: &Path) -> PathBuf {
+    let mut name = dest.file_name().unwrap_or_default().to_os_string();
+    name.push(".part");
+    dest.with_file_name(name)
+}
+
+/// Total size from `Content-Range: bytes 100-199/200`.
+fn content_range_total(response: &reqwest::Response) -> Option<u64> {
+    response
+        .headers()
+        .get(reqwest::header::CONTENT_RANGE)?
+        .to_str()
+        .ok()?
+        .rsplit('/')
+        .next()?
+        .parse()
+        .ok()
+}
+
+/// One request, appending to `part` from `*received`. `validator` is the ETag or
+/// Last-Modified of the first response, so a resumed range can't splice two files.
+async fn fetch_range<F>(
+    client: &reqwest::Client,
+    url: &str,
+    part: &Path,
+    received: &mut u64,
+    validator: &mut Option<String>,
+    cancel: Option<&AtomicBool>,
+    on_progress: &mut F,
+) -> Result<(), HttpDownloadError>
+where
+    F: FnMut(u64, Option<u64>),
+{
+    use futures_util::StreamExt;
+    use std::io::Write;
+    use HttpDownloadError::*;
+
+    let mut request = client.get(url);
+    if *received > 0 {
+        request = request.header(reqwest::header::RANGE, format!("bytes={received}-"));
+        if let Some(v) = validator.as_deref() {
+            request = request.header(reqwest::header::IF_RANGE, v);
+        }
+    }
+    let response = request
+        .send()
+        .await
+        .map_err(|e| Interrupted(e.to_string()))?;
+    let status = response.status();
+    if status == reqwest::StatusCode::NOT_FOUND {
+        return Err(NotFound(format!("{url}: 404 Not Found")));
+    }
+    if status == reqwest::StatusCode::RANGE_NOT_SATISFIABLE {
+        // The .part doesn't fit the file anymore; start over.
+        *received = 0;
+        return Err(Interrupted(format!("{url}: range not satisfiable")));
+    }
+    if status.is_server_error() {
+        return Err(Interrupted(format!("{url}: {status}")));
+    }
+    let response = response
+        .error_for_status()
+        .map_err(|e| Failed(e.to_string()))?;
+
+    let resumed = *received > 0 && status == reqwest::StatusCode::PARTIAL_CONTENT;
+    if !resumed {
+        // Fresh start, or the server ignored `Range`/`If-Range`.
+        *received = 0;
+        let headers = response.headers();
+        *validator = headers
+            .get(reqwest::header::ETAG)
+            .or_else(|| headers.get(reqwest::header::LAST_MODIFIED))
+            .and_then(|v| v.to_str().ok())
+            .map(str::to_string);
+    }
+    let total = if resumed {
+        content_range_total(&response)
+    } else {
+        response.content_length()
+    };
+
+    let mut file = std::fs::OpenOptions::new()
+        .create(true)
+        .write(true)
+        .append(resumed)
+        .truncate(!resumed)
+        .open(part)
+        .map_err(|e| Failed(e.to_string()))?;
+    let mut stream = response.bytes_stream();
+    while let Some(chunk) = stream.next().await {
+        if cancel.is_some_and(|c| c.load(Ordering::Relaxed)) {
+            return Err(Cancelled);
+        }
+        let chunk = chunk.map_err(|e| Interrupted(e.to_string()))?;
+        file.write_all(&chunk).map_err(|e| Failed(e.to_string()))?;
+        *received = received.saturating_add(chunk.len() as u64);
+        on_progress(*received, total);
+        throttle(chunk.len() as u64).await;
+    }
+    if total.is_some_and(|t| *received < t) {
+        return Err(Interrupted(format!(
+            "{url}: connection closed at {received} of {} bytes",
+            total.unwrap_or_default()
+        )));
+    }
+    Ok(())
+}
+
+/// Download `url` into `dest` via `{dest}.part`. A dropped connection is retried with a
+/// `Range` request from the bytes already written (when the server supports it), so a
+/// hiccup doesn't restart a large download. `on_progress(received, total)`.
+pub(crate) async fn download_resumable<F>(
+    client: &reqwest::Client,
+    url: &str,
+    dest: &Path,
+    cancel: Option<&AtomicBool>,
+    mut on_progress: F,
+) -> Result<u64, HttpDownloadError>
+where
+    F: FnMut(u64, Option<u64>),
+{
+    let part = part_path(dest);
+    let mut received: u64 = 0;
+    let mut validator: Option<String> = None;
+    let mut attempt = 1;
+    let result = loop {
+        match fetch_range(
+            client,
+            url,
+            &part,
+            &mut received,
+            &mut validator,
+            cancel,
+            &mut on_progress,
+        )
+        .await
+        {
+            Err(HttpDownloadError::Interrupted(e)) if attempt < RESUME_ATTEMPTS => {
+                log::warn!("Download of {url} interrupted ({e}); resuming at {received} bytes");
+                tokio::time::sleep(Duration::from_secs(u64::from(attempt))).await;
+                attempt += 1;
+            }
+            other => break other,
+        }
+    };
+    match result {
+        Ok(()) => {
+            std::fs::rename(&part, dest).map_err(|e| HttpDownloadError::Failed(e.to_string()))?;
+            Ok(received)
+        }
+        Err(e) => {
+            let _ = std::fs::remove_file(&part);
+            Err(e)
+        }
+    }
+}
+
+/// Set to `1`/`true` to install from `FakeDownloader` instead of Steam (demo mode, CI).
+pub const FAKE_DOWNLOADER_ENV: &str = "HQ_LAUNCHER_FAKE_DOWNLOADER";
+
+/// Source of game files for `download_and_setup`.
+pub trait GameDownloader: Send + Sync {
+    fn login_state(&self) -> LoginState;
+
+    fn download_game<'a>(
+        &'a self,
+        manifest_id: Option<String>,
+        output_dir: PathBuf,
+        validate: bool,
+        task: Option<DownloadTaskContext>,
+        cancel: Option<Arc<std::sync::atomic::AtomicBool>>,
+    ) -> BoxFuture<'a, Result<(), String>>;
+}
+
+fn use_fake_downloader(app: &tauri::AppHandle) -> bool {
+    let from_env = std::env::var(FAKE_DOWNLOADER_ENV)
+        .map(|v| matches!(v.trim(), "1" | "true"))
+        .unwrap_or(false);
+    from_env || settings::load(app).fake_downloader
+}
+
+/// Pick the game downloader: the fake one in demo mode, otherwise DepotDownloader
+/// (installed on demand).
+pub async fn select_game_downloader(
+    app: &tauri::AppHandle,
+) -> Result<Box<dyn GameDownloader>, String> {
+    if use_fake_downloader(app) {
+        log::info!("Using fake game downloader (demo mode)");
+        return Ok(Box::new(FakeDownloader::new(app)));
+    }
+    if let Err(e) = install_downloader(app).await {
+        return Err(format!("Failed to install DepotDownloader: {e}"));
+    }
+    Ok(Box::new(DepotDownloader::new(app)?))
+}
+
 #[derive(Default)]
 pub struct DepotLoginState {
     next_id: AtomicU64,
     sessions: Mutex<HashMap<u64, mpsc::UnboundedSender<String>>>,
+    /// Session of the `steam_login` flow, which `steam_submit_guard_code` feeds.
+    steam_session: Mutex<Option<u64>>,
 }
 
 pub struct DepotDownloader {
@@ -181,21 +478,19 @@ pub struct DepotDownloader {
     executable_path: PathBuf,
     config_dir: PathBuf,
     ipc_mode: bool,
+    /// The installed release supports `-validate` (see `VALIDATE_SINCE`).
+    validate_supported: bool,
 }
 
 impl DepotDownloader {
-    const APP_ID: &'static str = "1966720";
-    const DEPOT_ID: &'static str = "1966721";
+    const APP_ID: &'static str = crate::game::current().steam_app_id;
+    const DEPOT_ID: &'static str = crate::game::current().steam_depot_id;
     const PATCH_MARKER: &'static str = ".hq_launcher_ipc";
 
     pub fn new(app: &tauri::AppHandle) -> Result<Self, String> {
-        let app_data = app
-            .path()
-            .app_data_dir()
-            .map_err(|e| format!("failed to resolve app data dir: {e}"))?;
-
-        let downloader_dir = app_data.join("downloader");
+        let downloader_dir = storage::downloader_dir(app)?;
         let ipc_mode = downloader_dir.join(Self::PATCH_MARKER).exists();
+        let validate_supported = installed_downloader_version(&downloader_dir) >= VALIDATE_SINCE;
 
         #[cfg(target_os = "windows")]
         let executable_path = downloader_dir.join("DepotDownloader.exe");
@@ -215,6 +510,7 @@ impl DepotDownloader {
             executable_path,
             config_dir,
             ipc_mode,
+            validate_supported,
         })
     }
 
@@ -570,15 +866,25 @@ impl DepotDownloader {
         let mut requested_2fa = false;
         let mut saw_mobile_confirm = false;
         loop {
-            // Drain submitted codes.
-            while let Ok(code) = rx_code.try_recv() {
-                let t = code.trim().to_string();
-                if !t.is_empty() {
-                    self.emit_event(DepotDownloaderEvent::Output(format!(
-                        "Steam Guard code received (len={}).",
-                        t.len()
-                    )));
-                    pending_code = Some(t);
+            // Drain submitted codes. A closed channel means the session was abandoned
+            // (logout or a newer login), so stop the process instead of waiting it out.
+            loop {
+                match rx_code.try_recv() {
+                    Ok(code) => {
+                        let t = code.trim().to_string();
+                        if !t.is_empty() {
+                            self.emit_event(DepotDownloaderEvent::Output(format!(
+                                "Steam Guard code received (len={}).",
+                                t.len()
+                            )));
+                            pending_code = Some(t);
+                        }
+                    }
+                    Err(mpsc::error::TryRecvError::Empty) => break,
+                    Err(mpsc::error::TryRecvError::Disconnected) => {
+                        kill_login_process(&mut p);
+                        return Err("Login cancelled.".to_string());
+                    }
                 }
             }
 
@@ -673,7 +979,7 @@ impl DepotDownloader {
 
             // Hard timeout
             if start.elapsed() > Duration::from_secs(180) {
-                let _ = p.send(ControlCode::EndOfText);
+                kill_login_process(&mut p);
                 return Err("Login timed out.".to_string());
             }
 
@@ -727,6 +1033,7 @@ impl DepotDownloader {
         &self,
         manifest_id: Option<String>,
         output_dir: PathBuf,
+        validate: bool,
         task: Option<DownloadTaskContext>,
         cancel: Option<Arc<std::sync::atomic::AtomicBool>>,
     ) -> Result<(), String> {
@@ -760,6 +1067,15 @@ impl DepotDownloader {
             args.push("-manifest".to_string());
             args.push(manifest);
         }
+        // Checksum files already on disk and only fetch chunks that differ
+        // (used when the output dir was pre-seeded from another version).
+        match (validate, self.validate_supported) {
+            (true, true) => args.push("-validate".to_string()),
+            (true, false) => {
+                log::warn!("DepotDownloader has no -validate; pre-seeded files are re-fetched")
+            }
+            (false, _) => {}
+        }
 
         let mut child = Command::new(&self.executable_path)
             .args(&args)
@@ -1071,6 +1387,91 @@ impl DepotDownloader {
         }
     }
 
+    /// Current depot manifest of `branch`, fetched with the remembered login and
+    /// `-manifest-only` (nothing but the manifest listing is downloaded).
+    pub async fn current_manifest(&self, branch: &str) -> Result<DiscoveredManifest, String> {
+        let login_state = self.get_login_state();
+        if !login_state.is_logged_in {
+            return Err("Not logged in. Please login first.".to_string());
+        }
+        let username = login_state.username.clone().ok_or_else(|| {
+            "Missing username for remembered login. Please login again.".to_string()
+        })?;
+
+        let out_dir = self.config_dir.join("_manifest_discovery");
+        let _ = std::fs::remove_dir_all(&out_dir);
+        std::fs::create_dir_all(&out_dir).map_err(|e| e.to_string())?;
+
+        let mut args = vec![
+            "-app".to_string(),
+            Self::APP_ID.to_string(),
+            "-depot".to_string(),
+            Self::DEPOT_ID.to_string(),
+            "-branch".to_string(),
+            branch.to_string(),
+            "-manifest-only".to_string(),
+            "-dir".to_string(),
+            out_dir.to_string_lossy().to_string(),
+            "-username".to_string(),
+            username,
+            "-remember-password".to_string(),
+        ];
+        if self.ipc_mode {
+            args.insert(0, "-ipc".to_string());
+        }
+
+        log::info!("Discovering depot manifest of branch {branch}");
+        // No stdin: a Steam Guard prompt ends the run instead of hanging it.
+        let child = Command::new(&self.executable_path)
+            .args(&args)
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .current_dir(&self.config_dir)
+            .kill_on_drop(true)
+            .spawn()
+            .map_err(|e| format!("Failed to spawn DepotDownloader: {e}"))?;
+        let output = tokio::time::timeout(Duration::from_secs(120), child.wait_with_output())
+            .await
+            .map_err(|_| format!("Manifest listing for branch {branch} timed out"))?
+            .map_err(|e| e.to_string())?;
+
+        let listing = std::fs::read_dir(&out_dir)
+            .ok()
+            .into_iter()
+            .flatten()
+            .flatten()
+            .find_map(|e| {
+                let name = e.file_name().to_string_lossy().to_string();
+                let prefix = format!("manifest_{}_", Self::DEPOT_ID);
+                let id = name.strip_prefix(&prefix)?.strip_suffix(".txt")?.to_string();
+                Some((id, std::fs::read_to_string(e.path()).unwrap_or_default()))
+            });
+        let _ = std::fs::remove_dir_all(&out_dir);
+
+        let Some((manifest_id, text)) = listing else {
+            let stdout = strip_ansi(&String::from_utf8_lossy(&output.stdout));
+            let stderr = strip_ansi(&String::from_utf8_lossy(&output.stderr));
+            for line in stdout.lines().chain(stderr.lines()) {
+                log::info!("DepotDownloader: {line}");
+            }
+            return Err(if looks_like_twofactor_needed(&format!("{stdout}\n{stderr}")) {
+                "Steam Guard / login required. Please login and try again.".to_string()
+            } else {
+                format!(
+                    "DepotDownloader listed no manifest for branch {branch} ({})",
+                    output.status
+                )
+            });
+        };
+        Ok(DiscoveredManifest {
+            branch: branch.to_string(),
+            manifest_id,
+            date: parse_manifest_listing_date(&text),
+            listed_as: None,
+        })
+    }
+
     /// 로그아웃
     pub fn logout(&self) -> Result<(), String> {
         let state = LoginState {
@@ -1145,20 +1546,193 @@ impl DepotDownloader {
             }
             _ => {}
         }
-        let _ = self.app.emit("depot-downloader", event);
+        let login_status = match &event {
+            DepotDownloaderEvent::NeedsTwoFactor {
+                session_id,
+                message,
+            } => Some((Some(*session_id), "guard_code_required", message.clone())),
+            DepotDownloaderEvent::NeedsMobileConfirmation { session_id } => {
+                Some((Some(*session_id), "mobile_confirmation_required", None))
+            }
+            DepotDownloaderEvent::LoginSuccess => Some((None, "succeeded", None)),
+            DepotDownloaderEvent::LoginFailed(e) => Some((None, "failed", Some(e.clone()))),
+            _ => None,
+        };
+        if let Some((session_id, status, message)) = login_status {
+            progress::emit_steam_login(
+                &self.app,
+                progress::SteamLoginPayload {
+                    session_id,
+                    status,
+                    message,
+                },
+            );
+        }
+        crate::progress::emit(&self.app, "depot-downloader", event);
+    }
+}
+
+impl GameDownloader for DepotDownloader {
+    fn login_state(&self) -> LoginState {
+        self.get_login_state()
+    }
+
+    fn download_game<'a>(
+        &'a self,
+        manifest_id: Option<String>,
+        output_dir: PathBuf,
+        validate: bool,
+        task: Option<DownloadTaskContext>,
+        cancel: Option<Arc<std::sync::atomic::AtomicBool>>,
+    ) -> BoxFuture<'a, Result<(), String>> {
+        Box::pin(self.download_depot(manifest_id, output_dir, validate, task, cancel))
     }
 }
 
 fn depot_config_dir(app: &tauri::AppHandle) -> Result<PathBuf, String> {
-    let app_data = app
-        .path()
-        .app_data_dir()
-        .map_err(|e| format!("failed to resolve app data dir: {e}"))?;
-    let config_dir = app_data.join("depot_config");
+    let config_dir = storage::depot_config_dir(app)?;
     std::fs::create_dir_all(&config_dir).map_err(|e| e.to_string())?;
     Ok(config_dir)
 }
 
+/// Persistent DepotDownloader workspace shared by every game version.
+///
+/// DepotDownloader remembers the last manifest it installed under `<dir>/.DepotDownloader`
+/// and, when pointed at the same dir again, only fetches chunks that changed between
+/// manifests. Downloading each version through this dir turns a v62 -> v73 upgrade into a
+/// delta download; the result is then cloned (or, without copy-on-write, copied) into
+/// `versions/v{N}`.
+pub fn depot_cache_dir(app: &tauri::AppHandle) -> Result<PathBuf, String> {
+    let cache_dir = storage::depot_cache_dir(app)?;
+    std::fs::create_dir_all(&cache_dir).map_err(|e| e.to_string())?;
+    Ok(cache_dir)
+}
+
+pub(crate) fn dir_size_bytes(path: &std::path::Path) -> u64 {
+    let Ok(rd) = std::fs::read_dir(path) else {
+        return 0;
+    };
+    let mut total = 0u64;
+    for entry in rd.flatten() {
+        let Ok(ty) = entry.file_type() else {
+            continue;
+        };
+        if ty.is_dir() {
+            total = total.saturating_add(dir_size_bytes(&entry.path()));
+        } else if ty.is_file() {
+            total = total.saturating_add(entry.metadata().map(|m| m.len()).unwrap_or(0));
+        }
+    }
+    total
+}
+
+fn collect_cache_files(dir: &Path, out: &mut Vec<(std::time::SystemTime, u64, PathBuf)>) {
+    let Ok(rd) = std::fs::read_dir(dir) else {
+        return;
+    };
+    for entry in rd.flatten() {
+        let Ok(meta) = entry.metadata() else {
+            continue;
+        };
+        if meta.is_dir() {
+            collect_cache_files(&entry.path(), out);
+        } else if meta.is_file() {
+            let used = meta.modified().unwrap_or(std::time::SystemTime::UNIX_EPOCH);
+            out.push((used, meta.len(), entry.path()));
+        }
+    }
+}
+
+/// Trim the depot cache down to `max_bytes`, least recently written files first.
+///
+/// DepotDownloader re-fetches files missing from its workspace, so a trimmed cache still
+/// works; it just saves less on the next download. Its `.DepotDownloader` state is kept.
+pub fn enforce_depot_cache_limit(app: &tauri::AppHandle, max_bytes: u64) -> Result<(), String> {
+    let cache_dir = depot_cache_dir(app)?;
+    let mut total = dir_size_bytes(&cache_dir);
+    if total <= max_bytes {
+        return Ok(());
+    }
+    let mut files = vec![];
+    let Ok(rd) = std::fs::read_dir(&cache_dir) else {
+        return Ok(());
+    };
+    for entry in rd.flatten() {
+        if entry.file_name() == ".DepotDownloader" {
+            continue;
+        }
+        let path = entry.path();
+        if path.is_dir() {
+            collect_cache_files(&path, &mut files);
+        } else if let Ok(meta) = entry.metadata() {
+            let used = meta.modified().unwrap_or(std::time::SystemTime::UNIX_EPOCH);
+            files.push((used, meta.len(), path));
+        }
+    }
+    files.sort();
+
+    let (mut evicted, mut freed) = (0usize, 0u64);
+    for (_, len, path) in files {
+        if total <= max_bytes {
+            break;
+        }
+        match std::fs::remove_file(&path) {
+            Ok(()) => {
+                total = total.saturating_sub(len);
+                evicted += 1;
+                freed = freed.saturating_add(len);
+            }
+            Err(e) => {
+                log::warn!("Failed to evict {} from the depot cache: {e}", path.display())
+            }
+        }
+    }
+    log::info!(
+        "Trimmed {evicted} file(s), {freed} bytes from the depot cache (limit {max_bytes})"
+    );
+    Ok(())
+}
+
+/// Depot manifest id last downloaded through the depot cache, next to DepotDownloader's own
+/// state (so `copy_depot_tree` leaves it out of the version dir).
+fn depot_cache_manifest_path(cache_dir: &std::path::Path) -> PathBuf {
+    cache_dir.join(".DepotDownloader").join("hq-launcher-manifest")
+}
+
+/// Remember which depot manifest the cache at `cache_dir` now holds.
+pub fn record_depot_cache_manifest(cache_dir: &std::path::Path, manifest_id: &str) {
+    let path = depot_cache_manifest_path(cache_dir);
+    if let Err(e) = std::fs::write(&path, manifest_id) {
+        log::warn!("Failed to record depot cache manifest in {}: {e}", path.display());
+    }
+}
+
+/// Drop the depot cache when the manifest it holds is no longer in the remote manifest.
+///
+/// Delta reuse only pays off when a later install is close to the cached build; one the
+/// manifest no longer lists is just disk usage. A cache without a recorded manifest
+/// (from before it was tracked) is left to `enforce_depot_cache_limit`.
+/// Returns whether the cache was cleared.
+pub fn evict_stale_depot_cache<'a>(
+    app: &tauri::AppHandle,
+    manifest_ids: impl IntoIterator<Item = &'a String>,
+) -> Result<bool, String> {
+    let cache_dir = depot_cache_dir(app)?;
+    let Ok(cached) = std::fs::read_to_string(depot_cache_manifest_path(&cache_dir)) else {
+        return Ok(false);
+    };
+    let cached = cached.trim();
+    if manifest_ids.into_iter().any(|id| id == cached) {
+        return Ok(false);
+    }
+    log::info!(
+        "Depot cache holds manifest {cached}, which the remote manifest no longer lists; clearing {}",
+        cache_dir.display()
+    );
+    std::fs::remove_dir_all(&cache_dir).map_err(|e| e.to_string())?;
+    Ok(true)
+}
+
 fn depot_login_state_path(app: &tauri::AppHandle) -> Result<PathBuf, String> {
     Ok(depot_config_dir(app)?.join("login_state.json"))
 }
@@ -1184,13 +1758,9 @@ fn write_saved_login_state(app: &tauri::AppHandle, state: &LoginState) -> Result
 }
 
 pub async fn install_downloader(app: &tauri::AppHandle) -> Result<bool, String> {
-    let download_url = format!("https://github.com/SteamRE/DepotDownloader/releases/download/DepotDownloader_3.4.0/{DEPOT_DOWNLOADER_NAME}.zip");
+    let download_url = format!("https://github.com/SteamRE/DepotDownloader/releases/download/DepotDownloader_{DEPOT_DOWNLOADER_VERSION}/{DEPOT_DOWNLOADER_NAME}.zip");
 
-    let install_path = app
-        .path()
-        .app_data_dir()
-        .map_err(|e| format!("failed to resolve app data dir: {e}"))?
-        .join("downloader");
+    let install_path = storage::downloader_dir(app)?;
     let marker_path = install_path.join(DepotDownloader::PATCH_MARKER);
 
     // If patched build already installed, skip.
@@ -1298,7 +1868,7 @@ pub async fn install_downloader(app: &tauri::AppHandle) -> Result<bool, String>
 
         for i in 0..archive.len() {
             let mut file = archive.by_index(i).map_err(|e| e.to_string())?;
-            let outpath = match file.enclosed_name() {
+            let outpath = match crate::zip_utils::safe_entry_path(file.name(), file.unix_mode()) {
                 Some(path) => install_path_clone.join(path),
                 None => continue,
             };
@@ -1313,16 +1883,10 @@ pub async fn install_downloader(app: &tauri::AppHandle) -> Result<bool, String>
                 }
                 let mut outfile = std::fs::File::create(&outpath).map_err(|e| e.to_string())?;
                 std::io::copy(&mut file, &mut outfile).map_err(|e| e.to_string())?;
-            }
+                drop(outfile);
 
-            // Unix 실행 권한 설정
-            #[cfg(unix)]
-            {
-                use std::os::unix::fs::PermissionsExt;
-                if let Some(mode) = file.unix_mode() {
-                    std::fs::set_permissions(&outpath, std::fs::Permissions::from_mode(mode))
-                        .map_err(|e| e.to_string())?;
-                }
+                // Unix 실행 권한 설정
+                crate::zip_utils::restore_unix_permissions(&outpath, file.unix_mode())?;
             }
         }
 
@@ -1334,7 +1898,12 @@ pub async fn install_downloader(app: &tauri::AppHandle) -> Result<bool, String>
     .await
     .map_err(|e| e.to_string())??;
 
-    info!("DepotDownloader installed successfully");
+    std::fs::write(
+        install_path.join(DEPOT_DOWNLOADER_VERSION_MARKER),
+        DEPOT_DOWNLOADER_VERSION,
+    )
+    .map_err(|e| e.to_string())?;
+    info!("DepotDownloader {DEPOT_DOWNLOADER_VERSION} installed successfully");
 
     Ok(true)
 }
@@ -1393,6 +1962,50 @@ pub async fn depot_login(
     res
 }
 
+/// Register a new login session and return its id with the receiver for submitted codes.
+/// Dropping the session's sender (see `close_login_session`) stops its DepotDownloader process.
+fn open_login_session(
+    login_state: &DepotLoginState,
+) -> Result<(u64, mpsc::UnboundedReceiver<String>), String> {
+    let session_id = login_state.next_id.fetch_add(1, Ordering::Relaxed) + 1;
+    let (tx, rx) = mpsc::unbounded_channel::<String>();
+    let mut map = login_state
+        .sessions
+        .lock()
+        .map_err(|_| "login state lock poisoned".to_string())?;
+    map.insert(session_id, tx);
+    Ok((session_id, rx))
+}
+
+fn close_login_session(login_state: &DepotLoginState, session_id: u64) {
+    if let Ok(mut map) = login_state.sessions.lock() {
+        map.remove(&session_id);
+    }
+}
+
+/// Run `login_interactive` for an open session in the background, then drop the session
+/// and hand the result to `on_exit`.
+fn spawn_login_session(
+    app: &tauri::AppHandle,
+    downloader: DepotDownloader,
+    session_id: u64,
+    mut rx: mpsc::UnboundedReceiver<String>,
+    credentials: LoginCredentials,
+    on_exit: impl FnOnce(&tauri::AppHandle, &DepotDownloader, Result<(), String>) + Send + 'static,
+) {
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        let res = downloader
+            .login_interactive(session_id, credentials, None, &mut rx)
+            .await;
+
+        // IMPORTANT: don't capture `tauri::State<'_ , _>` into the spawned task (not 'static).
+        // Re-acquire state from the AppHandle instead.
+        close_login_session(&app.state::<DepotLoginState>(), session_id);
+        on_exit(&app, &downloader, res);
+    });
+}
+
 /// Start an interactive login session and return session_id immediately.
 /// The running process will emit `LoginSuccess`/`Error` events, and accept codes via `depot_login_submit_code`.
 #[tauri::command]
@@ -1405,15 +2018,7 @@ pub async fn depot_login_start(
     let downloader = DepotDownloader::new(&app)?;
 
     // NOTE: Never log passwords or 2FA codes.
-    let session_id = login_state.next_id.fetch_add(1, Ordering::Relaxed) + 1;
-    let (tx, mut rx) = mpsc::unbounded_channel::<String>();
-    {
-        let mut map = login_state
-            .sessions
-            .lock()
-            .map_err(|_| "login state lock poisoned".to_string())?;
-        map.insert(session_id, tx);
-    }
+    let (session_id, rx) = open_login_session(&login_state)?;
 
     // Prompt UI immediately (no reliance on log detection).
     downloader.emit_event(DepotDownloaderEvent::NeedsTwoFactor {
@@ -1424,39 +2029,18 @@ pub async fn depot_login_start(
         "로그인 시도 시작됨. Steam Guard 코드가 오면 입력 후 Submit code를 눌러주세요.".to_string(),
     ));
 
-    let app2 = app.clone();
-    tauri::async_runtime::spawn(async move {
-        let downloader = match DepotDownloader::new(&app2) {
-            Ok(d) => d,
-            Err(e) => {
-                let _ = app2.emit("depot-downloader", DepotDownloaderEvent::Error(e));
-                return;
+    spawn_login_session(
+        &app,
+        downloader,
+        session_id,
+        rx,
+        LoginCredentials { username, password },
+        |_, downloader, res| {
+            if let Err(err) = res {
+                downloader.emit_event(DepotDownloaderEvent::Error(err));
             }
-        };
-
-        let res = downloader
-            .login_interactive(
-                session_id,
-                LoginCredentials { username, password },
-                None,
-                &mut rx,
-            )
-            .await;
-
-        // Cleanup session sender.
-        // IMPORTANT: don't capture `tauri::State<'_ , _>` into the spawned task (not 'static).
-        // Re-acquire state from the AppHandle instead.
-        {
-            let state = app2.state::<DepotLoginState>();
-            if let Ok(mut map) = state.sessions.lock() {
-                map.remove(&session_id);
-            };
-        }
-
-        if let Err(err) = res {
-            downloader.emit_event(DepotDownloaderEvent::Error(err));
-        }
-    });
+        },
+    );
 
     Ok(session_id)
 }
@@ -1487,6 +2071,114 @@ pub fn depot_login_submit_code(
         .map_err(|_| "failed to send code to login session".to_string())?;
     Ok(true)
 }
+/// Start the login flow for the account: DepotDownloader runs in the background and
+/// `steam-login` events report when Steam Guard wants a code (`steam_submit_guard_code`)
+/// or a confirmation in the mobile app, then whether the login succeeded.
+#[tauri::command]
+pub async fn steam_login(
+    app: tauri::AppHandle,
+    login_state: tauri::State<'_, DepotLoginState>,
+    username: String,
+    password: String,
+) -> Result<u64, String> {
+    let username = username.trim().to_string();
+    if username.is_empty() || password.is_empty() {
+        return Err("username and password are required".to_string());
+    }
+    let downloader = DepotDownloader::new(&app)?;
+
+    // NOTE: Never log passwords or 2FA codes.
+    let (session_id, rx) = open_login_session(&login_state)?;
+    if let Ok(mut current) = login_state.steam_session.lock() {
+        // A newer attempt replaces an abandoned one; closing it stops the old process.
+        if let Some(previous) = current.replace(session_id) {
+            close_login_session(&login_state, previous);
+        }
+    }
+    progress::emit_steam_login(
+        &app,
+        progress::SteamLoginPayload {
+            session_id: Some(session_id),
+            status: "started",
+            message: None,
+        },
+    );
+
+    spawn_login_session(
+        &app,
+        downloader,
+        session_id,
+        rx,
+        LoginCredentials { username, password },
+        move |app, downloader, res| {
+            let state = app.state::<DepotLoginState>();
+            let was_current = match state.steam_session.lock() {
+                Ok(mut current) if *current == Some(session_id) => {
+                    *current = None;
+                    true
+                }
+                _ => false,
+            };
+            // A session closed by logout or a newer login isn't a failed login.
+            if let Err(err) = res {
+                if was_current {
+                    downloader.emit_event(DepotDownloaderEvent::LoginFailed(err));
+                }
+            }
+        },
+    );
+
+    Ok(session_id)
+}
+
+/// Pass a Steam Guard code (email or authenticator) to the running `steam_login`.
+#[tauri::command]
+pub fn steam_submit_guard_code(
+    app: tauri::AppHandle,
+    login_state: tauri::State<'_, DepotLoginState>,
+    code: String,
+) -> Result<bool, String> {
+    let session_id = login_state
+        .steam_session
+        .lock()
+        .map_err(|_| "login state lock poisoned".to_string())?
+        .ok_or_else(|| "no Steam login in progress".to_string())?;
+    depot_login_submit_code(login_state, session_id, code)?;
+    progress::emit_steam_login(
+        &app,
+        progress::SteamLoginPayload {
+            session_id: Some(session_id),
+            status: "code_submitted",
+            message: None,
+        },
+    );
+    Ok(true)
+}
+
+/// Forget the saved Steam login (and stop a login in progress).
+#[tauri::command]
+pub fn steam_logout(
+    app: tauri::AppHandle,
+    login_state: tauri::State<'_, DepotLoginState>,
+) -> Result<(), String> {
+    if let Ok(mut current) = login_state.steam_session.lock() {
+        // Closing the session stops its DepotDownloader process.
+        if let Some(session_id) = current.take() {
+            close_login_session(&login_state, session_id);
+        }
+    }
+    depot_logout(app.clone())?;
+    progress::emit_steam_login(
+        &app,
+        progress::SteamLoginPayload {
+            session_id: None,
+            status: "logged_out",
+            message: None,
+        },
+    );
+    Ok(())
+}
+
 #[tauri::command]
 pub async fn depot_download(
     app: tauri::AppHandle,
@@ -1495,7 +2187,7 @@ pub async fn depot_download(
 ) -> Result<(), String> {
     let downloader = DepotDownloader::new(&app)?;
     downloader
-        .download_depot(manifest_id, PathBuf::from(output_dir), None, None)
+        .download_depot(manifest_id, PathBuf::from(output_dir), false, None, None)
         .await
 }
 
@@ -1551,3 +2243,127 @@ pub async fn depot_download_files(
         .download_files(files, PathBuf::from(output_dir))
         .await
 }
+
+/// A branch's current depot manifest, as found by `discover_depot_manifests`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DiscoveredManifest {
+    pub branch: String,
+    pub manifest_id: String,
+    /// Creation date from the manifest listing, `YYYY-MM-DD` when it parses.
+    pub date: Option<String>,
+    /// Game version the cached remote manifest already maps to this id.
+    pub listed_as: Option<u32>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ManifestDiscovery {
+    pub manifests: Vec<DiscoveredManifest>,
+    /// JSON `manifests` object with the ids not listed yet, to merge into the remote
+    /// manifest after replacing each `<version…>` key with the game version.
+    pub snippet: String,
+}
+
+/// Date of a `-manifest-only` listing (`Manifest ID / date : 123 / 11/08/2023 22:11:04`).
+fn parse_manifest_listing_date(listing: &str) -> Option<String> {
+    let line = listing
+        .lines()
+        .find(|l| l.trim_start().starts_with("Manifest ID / date"))?;
+    let (_, value) = line.split_once(':')?;
+    let (_, date) = value.split_once('/')?;
+    let date = date.split_whitespace().next()?;
+    let parts: Vec<&str> = date.split('/').collect();
+    match parts.as_slice() {
+        [month, day, year] if year.len() == 4 => Some(format!(
+            "{year}-{:0>2}-{:0>2}",
+            month.trim(),
+            day.trim()
+        )),
+        _ => Some(date.to_string()),
+    }
+}
+
+/// `{"manifests": {...}}` with the manifests not listed yet, keyed `<version>` (one) or
+/// `<version of {branch}>` (several) for the maintainer to fill in. Empty when all are listed.
+fn manifest_snippet(found: &[DiscoveredManifest]) -> String {
+    let unlisted: Vec<&DiscoveredManifest> =
+        found.iter().filter(|m| m.listed_as.is_none()).collect();
+    if unlisted.is_empty() {
+        return String::new();
+    }
+    let mut entries = serde_json::Map::new();
+    for m in &unlisted {
+        let mut fields = serde_json::Map::new();
+        fields.insert("manifest_id".to_string(), m.manifest_id.clone().into());
+        if let Some(date) = &m.date {
+            fields.insert("release_date".to_string(), date.clone().into());
+        }
+        if m.branch != "public" {
+            fields.insert("display_name".to_string(), m.branch.clone().into());
+        }
+        let key = if unlisted.len() == 1 {
+            "<version>".to_string()
+        } else {
+            format!("<version of {}>", m.branch)
+        };
+        entries.insert(key, fields.into());
+    }
+    serde_json::to_string_pretty(&serde_json::json!({ "manifests": entries }))
+        .unwrap_or_default()
+}
+
+/// Maintainer tool: the current depot manifest id of each of `branches` (default
+/// `public`), read with the logged-in account, plus the `manifests` snippet for the ones
+/// the remote manifest doesn't list yet. Replaces looking the ids up on SteamDB.
+///
+/// DepotDownloader only resolves a branch's current manifest, not its history; run this
+/// when a game update ships. Requires `Settings::maintainer_mode`.
+#[tauri::command]
+pub async fn discover_depot_manifests(
+    app: tauri::AppHandle,
+    branches: Option<Vec<String>>,
+) -> Result<ManifestDiscovery, String> {
+    if !settings::load(&app).maintainer_mode {
+        return Err("Manifest discovery requires maintainer mode.".to_string());
+    }
+    let branches: Vec<String> = branches
+        .filter(|b| !b.is_empty())
+        .unwrap_or_else(|| vec!["public".to_string()])
+        .iter()
+        .map(|b| b.trim().to_string())
+        .filter(|b| !b.is_empty())
+        .collect();
+    // Passed to DepotDownloader as `-branch`; Steam branch names fit this set.
+    if let Some(bad) = branches.iter().find(|b| !settings::is_safe_name(b)) {
+        return Err(format!(
+            "Invalid branch name {bad:?}: use letters, digits, '-', '_' and '.' only."
+        ));
+    }
+    let downloader = DepotDownloader::new(&app)?;
+    let listed = crate::mod_config::cached_remote_manifest()
+        .map(|m| m.manifest_ids())
+        .unwrap_or_default();
+
+    let mut manifests: Vec<DiscoveredManifest> = vec![];
+    for branch in &branches {
+        let mut found = downloader.current_manifest(branch).await?;
+        if manifests.iter().any(|m| m.manifest_id == found.manifest_id) {
+            continue;
+        }
+        found.listed_as = listed
+            .iter()
+            .find(|(_, id)| **id == found.manifest_id)
+            .map(|(version, _)| *version);
+        log::info!(
+            "Branch {} is on depot manifest {} ({})",
+            found.branch,
+            found.manifest_id,
+            found
+                .listed_as
+                .map(|v| format!("listed as v{v}"))
+                .unwrap_or_else(|| "not listed".to_string())
+        );
+        manifests.push(found);
+    }
+    let snippet = manifest_snippet(&manifests);
+    Ok(ManifestDiscovery { manifests, snippet })
+}
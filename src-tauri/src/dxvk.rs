@@ -0,0 +1,361 @@
+//! DXVK builds for Proton launches.
+//!
+//! Release tarballs from GitHub are unpacked to `proton_env/dxvk/dxvk-{version}/`. The
+//! build selected in a version's launch options is copied next to the game executable at
+//! launch (Proton re-copies its own DXVK into the prefix on every run, so the game dir is
+//! the only place a custom build sticks) and enabled through `WINEDLLOVERRIDES`.
+
+use std::collections::BTreeSet;
+use std::fs::File;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::progress::{self, DxvkProgressPayload};
+use crate::{http_cache, installer, launch_options, storage, temp_files};
+
+const RELEASES_URL: &str = "https://api.github.com/repos/doitsujin/dxvk/releases";
+
+/// DLLs Lethal Company (D3D11) loads from a DXVK build.
+pub const DXVK_DLLS: &[&str] = &["d3d11", "dxgi", "d3d10core"];
+
+#[derive(Debug, Clone, Deserialize)]
+struct GitHubRelease {
+    tag_name: String,
+    published_at: Option<String>,
+    #[serde(default)]
+    prerelease: bool,
+    assets: Vec<GitHubAsset>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct GitHubAsset {
+    name: String,
+    browser_download_url: String,
+    size: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DxvkRelease {
+    pub version: String,
+    pub published_at: Option<String>,
+    pub download_url: String,
+    pub size: u64,
+    pub installed: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DxvkStatus {
+    /// Builds present under `proton_env/dxvk`, newest first.
+    pub installed: Vec<String>,
+    /// Stable GitHub releases, newest first (empty when offline).
+    pub available: Vec<DxvkRelease>,
+}
+
+/// Files the launcher copied into a game dir, so they can be removed again.
+///
+/// `{game_root}/.hq-launcher/dxvk.json`
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct DeployedDxvk {
+    version: String,
+    exe_dir: PathBuf,
+    files: Vec<String>,
+}
+
+fn dxvk_root(app: &tauri::AppHandle) -> Result<PathBuf, String> {
+    Ok(storage::proton_env_dir(app)?.join("dxvk"))
+}
+
+fn build_dir(app: &tauri::AppHandle, version: &str) -> Result<PathBuf, String> {
+    Ok(dxvk_root(app)?.join(format!("dxvk-{version}")))
+}
+
+fn deployed_path(game_root: &Path) -> PathBuf {
+    game_root.join(".hq-launcher").join("dxvk.json")
+}
+
+fn version_key(v: &str) -> Vec<u64> {
+    v.split('.').map(|p| p.parse().unwrap_or(0)).collect()
+}
+
+fn installed_builds(app: &tauri::AppHandle) -> Result<Vec<String>, String> {
+    let Ok(rd) = std::fs::read_dir(dxvk_root(app)?) else {
+        return Ok(vec![]);
+    };
+    let mut out: Vec<String> = rd
+        .flatten()
+        .filter(|e| e.path().join("x64").is_dir())
+        .filter_map(|e| {
+            e.file_name()
+                .to_str()
+                .and_then(|n| n.strip_prefix("dxvk-"))
+                .map(str::to_string)
+        })
+        .collect();
+    out.sort_by_key(|v| std::cmp::Reverse(version_key(v)));
+    Ok(out)
+}
+
+async fn fetch_releases(client: &reqwest::Client) -> Result<Vec<(String, GitHubRelease)>, String> {
+    let releases: Vec<GitHubRelease> = http_cache::get_json(client, RELEASES_URL)
+        .await
+        .map_err(|e| format!("Failed to fetch DXVK releases: {e}"))?;
+    Ok(releases
+        .into_iter()
+        .filter(|r| !r.prerelease)
+        .map(|r| (r.tag_name.trim_start_matches('v').to_string(), r))
+        .collect())
+}
+
+fn tarball(version: &str, release: &GitHubRelease) -> Option<GitHubAsset> {
+    let name = format!("dxvk-{version}.tar.gz");
+    release.assets.iter().find(|a| a.name == name).cloned()
+}
+
+#[tauri::command]
+pub async fn list_dxvk(app: tauri::AppHandle) -> Result<DxvkStatus, String> {
+    let installed = installed_builds(&app)?;
+    let client = reqwest::Client::new();
+    let available = match fetch_releases(&client).await {
+        Ok(releases) => releases
+            .iter()
+            .filter_map(|(version, r)| {
+                let asset = tarball(version, r)?;
+                Some(DxvkRelease {
+                    version: version.clone(),
+                    published_at: r.published_at.clone(),
+                    download_url: asset.browser_download_url,
+                    size: asset.size,
+                    installed: installed.contains(version),
+                })
+            })
+            .collect(),
+        Err(e) => {
+            log::warn!("{e}");
+            vec![]
+        }
+    };
+    Ok(DxvkStatus {
+        installed,
+        available,
+    })
+}
+
+fn unpack_tarball(tar_path: &Path, dest_root: &Path, version: &str) -> Result<(), String> {
+    use flate2::read::GzDecoder;
+    use tar::Archive;
+
+    let ts = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_millis())
+        .unwrap_or(0);
+    let extract_tmp = dest_root.join(format!(".tmp_extract_dxvk-{version}_{ts}"));
+    std::fs::create_dir_all(&extract_tmp).map_err(|e| e.to_string())?;
+
+    let res = (|| -> Result<(), String> {
+        let f = File::open(tar_path).map_err(|e| e.to_string())?;
+        let mut archive = Archive::new(GzDecoder::new(f));
+        for entry in archive.entries().map_err(|e| e.to_string())? {
+            let mut entry = entry.map_err(|e| e.to_string())?;
+            let raw_path = entry.path().map_err(|e| e.to_string())?.to_path_buf();
+            let Some(rel) = installer::sanitize_tar_rel_path(&raw_path) else {
+                log::warn!("Skipped unsafe tar path: {}", raw_path.to_string_lossy());
+                continue;
+            };
+            let out_path = extract_tmp.join(&rel);
+            if let Some(parent) = out_path.parent() {
+                std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+            }
+            entry.unpack(&out_path).map_err(|e| e.to_string())?;
+        }
+
+        let extracted = extract_tmp.join(format!("dxvk-{version}"));
+        if !extracted.join("x64").is_dir() {
+            return Err(format!("DXVK archive did not contain dxvk-{version}/x64"));
+        }
+        let final_dir = dest_root.join(format!("dxvk-{version}"));
+        if final_dir.exists() {
+            std::fs::remove_dir_all(&final_dir).map_err(|e| e.to_string())?;
+        }
+        std::fs::rename(&extracted, &final_dir).map_err(|e| e.to_string())
+    })();
+
+    let _ = std::fs::remove_dir_all(&extract_tmp);
+    res
+}
+
+/// Download and unpack DXVK `version` (`None` = latest stable). Returns the version.
+///
+/// Progress is reported as `dxvk://progress`.
+#[tauri::command]
+pub async fn install_dxvk(
+    app: tauri::AppHandle,
+    version: Option<String>,
+) -> Result<String, String> {
+    let client = reqwest::Client::new();
+    let releases = fetch_releases(&client).await?;
+    let (version, release) = match version {
+        Some(v) => {
+            let v = v.trim_start_matches('v').to_string();
+            releases
+                .into_iter()
+                .find(|(rv, _)| rv == &v)
+                .ok_or_else(|| format!("DXVK {v} not found in GitHub releases"))?
+        }
+        None => releases
+            .into_iter()
+            .next()
+            .ok_or_else(|| "no DXVK releases found".to_string())?,
+    };
+
+    if installed_builds(&app)?.contains(&version) {
+        log::info!("DXVK {version} already installed");
+        return Ok(version);
+    }
+    let asset = tarball(&version, &release)
+        .ok_or_else(|| format!("DXVK {version} has no dxvk-{version}.tar.gz asset"))?;
+
+    let tar_path = temp_files::temp_path(&app, temp_files::LAUNCHER_OWNER, &asset.name)?;
+    log::info!(
+        "Downloading DXVK {version} from {}",
+        asset.browser_download_url
+    );
+    let emit = |downloaded: u64, detail: &str| {
+        progress::emit_dxvk_progress(
+            &app,
+            DxvkProgressPayload {
+                version: version.clone(),
+                downloaded_bytes: downloaded,
+                total_bytes: (asset.size > 0).then_some(asset.size),
+                detail: Some(detail.to_string()),
+            },
+        )
+    };
+    let received = crate::mods::download_with_progress(
+        &client,
+        &asset.browser_download_url,
+        &tar_path,
+        |received| emit(received, "Downloading"),
+    )
+    .await
+    .inspect_err(|_| {
+        let _ = std::fs::remove_file(&tar_path);
+    })?;
+    emit(received, "Extracting");
+
+    let root = dxvk_root(&app)?;
+    std::fs::create_dir_all(&root).map_err(|e| e.to_string())?;
+    let (tar, v) = (tar_path.clone(), version.clone());
+    let res = tauri::async_runtime::spawn_blocking(move || unpack_tarball(&tar, &root, &v))
+        .await
+        .map_err(|e| e.to_string())
+        .and_then(|r| r);
+    let _ = std::fs::remove_file(&tar_path);
+    res?;
+
+    emit(received, "Installed");
+    log::info!("DXVK {version} installed");
+    Ok(version)
+}
+
+/// Install the latest DXVK and move every game version that uses an older build to it.
+///
+/// Returns the latest version.
+#[tauri::command]
+pub async fn update_dxvk(app: tauri::AppHandle) -> Result<String, String> {
+    let latest = install_dxvk(app.clone(), None).await?;
+    for version in launch_options::versions_using_dxvk(&app)? {
+        launch_options::set_dxvk_version(&app, version, Some(latest.clone()))?;
+    }
+    Ok(latest)
+}
+
+/// Remove an installed DXVK build. Versions still using it fall back to Proton's DXVK.
+#[tauri::command]
+pub fn remove_dxvk(app: tauri::AppHandle, version: String) -> Result<bool, String> {
+    let dir = build_dir(&app, &version)?;
+    if !dir.exists() {
+        return Ok(false);
+    }
+    std::fs::remove_dir_all(&dir).map_err(|e| e.to_string())?;
+    for v in launch_options::versions_using_dxvk(&app)? {
+        if launch_options::load(&app, v).dxvk_version.as_deref() == Some(version.as_str()) {
+            launch_options::set_dxvk_version(&app, v, None)?;
+        }
+    }
+    log::info!("Removed DXVK {version}");
+    Ok(true)
+}
+
+fn remove_deployed(game_root: &Path, deployed: &DeployedDxvk) {
+    for name in &deployed.files {
+        let _ = std::fs::remove_file(deployed.exe_dir.join(name));
+    }
+    let _ = std::fs::remove_file(deployed_path(game_root));
+}
+
+pub fn is_installed(app: &tauri::AppHandle, version: &str) -> bool {
+    build_dir(app, version).is_ok_and(|d| d.join("x64").is_dir())
+}
+
+/// Put the DXVK build selected for `version` next to the executable, or remove a
+/// previously deployed one.
+pub fn deploy(
+    app: &tauri::AppHandle,
+    version: u32,
+    game_root: &Path,
+    exe_dir: &Path,
+) -> Result<(), String> {
+    let previous: Option<DeployedDxvk> = std::fs::read_to_string(deployed_path(game_root))
+        .ok()
+        .and_then(|text| serde_json::from_str(&text).ok());
+    let wanted = launch_options::load(app, version).dxvk_version;
+
+    let Some(wanted) = wanted else {
+        if let Some(prev) = previous {
+            log::info!("Removing DXVK {} from v{version}", prev.version);
+            remove_deployed(game_root, &prev);
+        }
+        return Ok(());
+    };
+    let src = build_dir(app, &wanted)?.join("x64");
+    if !src.is_dir() {
+        log::warn!("DXVK {wanted} selected for v{version} is not installed; using Proton's");
+        if let Some(prev) = previous {
+            remove_deployed(game_root, &prev);
+        }
+        return Ok(());
+    }
+    if previous
+        .as_ref()
+        .is_some_and(|p| p.version == wanted && p.exe_dir == exe_dir)
+    {
+        return Ok(());
+    }
+    if let Some(prev) = previous {
+        remove_deployed(game_root, &prev);
+    }
+
+    let mut files = BTreeSet::new();
+    for dll in DXVK_DLLS {
+        let name = format!("{dll}.dll");
+        let from = src.join(&name);
+        if from.exists() {
+            std::fs::copy(&from, exe_dir.join(&name)).map_err(|e| e.to_string())?;
+            files.insert(name);
+        }
+    }
+    let record = DeployedDxvk {
+        version: wanted.clone(),
+        exe_dir: exe_dir.to_path_buf(),
+        files: files.into_iter().collect(),
+    };
+    let path = deployed_path(game_root);
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    let json = serde_json::to_string_pretty(&record).map_err(|e| e.to_string())?;
+    crate::zip_utils::write_atomic(&path, json)?;
+    log::info!("Deployed DXVK {wanted} for v{version}");
+    Ok(())
+}
@@ -0,0 +1,178 @@
+//! Remote URL bases.
+//!
+//! Every remote the launcher talks to (besides Steam/GitHub) is derived from one of the
+//! bases below, so tests and self-hosted mirrors can point the launcher elsewhere via env.
+
+use std::sync::RwLock;
+
+const DEFAULT_LAUNCHER_BASE: &str = "https://f.asta.rs/hq-launcher";
+const DEFAULT_THUNDERSTORE_BASE: &str = "https://thunderstore.io";
+
+/// Overrides the base serving `manifest.json` and `default_config.zip`.
+pub const LAUNCHER_BASE_ENV: &str = "HQ_LAUNCHER_BASE_URL";
+/// Overrides the Thunderstore base (package list + package downloads).
+pub const THUNDERSTORE_BASE_ENV: &str = "HQ_THUNDERSTORE_BASE_URL";
+
+/// Which manifest the launcher follows (`Settings::manifest_channel`).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ManifestChannel {
+    /// `manifest.json`, what every player gets.
+    Stable,
+    /// `manifest-beta.json`: upcoming mod lists, for testers.
+    Beta,
+    /// `manifest-{name}.json`, for other channels published next to the stable one.
+    Named(String),
+    /// A manifest anywhere else (e.g. a tester's local server). Mirrors don't apply.
+    Custom(String),
+}
+
+impl ManifestChannel {
+    /// Parse the setting: unset or `stable`, `beta`, a channel name, or an `http(s)://` URL.
+    pub fn parse(setting: Option<&str>) -> Result<Self, String> {
+        let Some(s) = setting.map(str::trim).filter(|s| !s.is_empty()) else {
+            return Ok(ManifestChannel::Stable);
+        };
+        if s.starts_with("https://") || s.starts_with("http://") {
+            return match reqwest::Url::parse(s) {
+                Ok(_) => Ok(ManifestChannel::Custom(s.to_string())),
+                Err(e) => Err(format!("invalid manifest URL {s}: {e}")),
+            };
+        }
+        match s {
+            "stable" => Ok(ManifestChannel::Stable),
+            "beta" => Ok(ManifestChannel::Beta),
+            _ if crate::settings::is_safe_name(s) => Ok(ManifestChannel::Named(s.to_string())),
+            _ => Err(format!("invalid manifest channel: {s}")),
+        }
+    }
+}
+
+static MANIFEST_CHANNEL: RwLock<ManifestChannel> = RwLock::new(ManifestChannel::Stable);
+/// `Settings::mirrors`, for code paths without an app handle.
+static MIRRORS: RwLock<Vec<String>> = RwLock::new(Vec::new());
+
+pub fn set_manifest_channel(channel: ManifestChannel) {
+    if let Ok(mut current) = MANIFEST_CHANNEL.write() {
+        *current = channel;
+    }
+}
+
+pub fn manifest_channel() -> ManifestChannel {
+    MANIFEST_CHANNEL
+        .read()
+        .map(|c| c.clone())
+        .unwrap_or(ManifestChannel::Stable)
+}
+
+pub fn set_mirrors(mirrors: Vec<String>) {
+    if let Ok(mut current) = MIRRORS.write() {
+        *current = mirrors;
+    }
+}
+
+/// Mirror bases from the settings (see `set_mirrors`).
+pub fn configured_mirrors() -> Vec<String> {
+    MIRRORS.read().map(|m| m.clone()).unwrap_or_default()
+}
+
+fn base_from_env(var: &str, default: &str) -> String {
+    std::env::var(var)
+        .ok()
+        .map(|v| v.trim().trim_end_matches('/').to_string())
+        .filter(|v| !v.is_empty())
+        .unwrap_or_else(|| default.to_string())
+}
+
+pub fn launcher_base() -> String {
+    base_from_env(LAUNCHER_BASE_ENV, DEFAULT_LAUNCHER_BASE)
+}
+
+pub fn thunderstore_base() -> String {
+    base_from_env(THUNDERSTORE_BASE_ENV, DEFAULT_THUNDERSTORE_BASE)
+}
+
+/// Launcher base followed by `mirrors` (trailing `/` trimmed, duplicates dropped).
+pub fn launcher_bases(mirrors: &[String]) -> Vec<String> {
+    let mut out = vec![launcher_base()];
+    for m in mirrors {
+        let m = m.trim().trim_end_matches('/').to_string();
+        if !m.is_empty() && !out.contains(&m) {
+            out.push(m);
+        }
+    }
+    out
+}
+
+/// Remote mod/depot manifest of the current channel on the primary base.
+pub fn manifest_url() -> String {
+    manifest_url_for(&launcher_base())
+}
+
+/// The manifest of the current channel on a specific base (primary or mirror); a custom
+/// channel's URL whatever the base.
+pub fn manifest_url_for(base: &str) -> String {
+    match manifest_channel() {
+        ManifestChannel::Stable => format!("{base}/manifest.json"),
+        ManifestChannel::Beta => format!("{base}/manifest-beta.json"),
+        ManifestChannel::Named(name) => format!("{base}/manifest-{name}.json"),
+        ManifestChannel::Custom(url) => url,
+    }
+}
+
+/// `manifest_url_for` each of `bases`, in order, without duplicates (a custom channel
+/// yields a single URL).
+pub fn manifest_urls(bases: &[String]) -> Vec<String> {
+    let mut out: Vec<String> = vec![];
+    for url in bases.iter().map(|b| manifest_url_for(b)) {
+        if !out.contains(&url) {
+            out.push(url);
+        }
+    }
+    out
+}
+
+/// Default BepInEx config pack extracted into the shared config dir.
+pub fn default_config_url() -> String {
+    default_config_url_for(&launcher_base())
+}
+
+/// `default_config.zip` on a specific base (primary or mirror).
+pub fn default_config_url_for(base: &str) -> String {
+    format!("{base}/default_config.zip")
+}
+
+/// Thunderstore package list of `community`.
+pub fn thunderstore_package_list_url(community: &str) -> String {
+    format!("{}/c/{community}/api/v1/package/", thunderstore_base())
+}
+
+/// Direct download endpoint (zip) for one package version.
+pub fn thunderstore_download_url(dev: &str, name: &str, version: &str) -> String {
+    format!(
+        "{}/package/download/{}/{}/{}/",
+        thunderstore_base(),
+        dev,
+        name,
+        version
+    )
+}
+
+/// README (raw markdown, wrapped in JSON) for one package version.
+pub fn thunderstore_readme_url(dev: &str, name: &str, version: &str) -> String {
+    format!(
+        "{}/api/experimental/package/{}/{}/{}/readme/",
+        thunderstore_base(),
+        dev,
+        name,
+        version
+    )
+}
+
+/// r2modman profile share code (`#r2modman` + base64 zip).
+pub fn thunderstore_profile_url(code: &str) -> String {
+    format!(
+        "{}/api/experimental/legacyprofile/get/{}/",
+        thunderstore_base(),
+        code
+    )
+}
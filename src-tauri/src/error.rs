@@ -0,0 +1,177 @@
+//! Crate-wide error type.
+//!
+//! Only part of the crate uses it so far: the path and storage layer (`storage`,
+//! `storage_stats`, `disk_space`), the remote fetches (`http_cache`, `mod_config`) and
+//! the install, cancel and active-version commands in `lib.rs`. The other modules and
+//! commands still return `Result<_, String>`. Both directions convert with `?`
+//! (`From<String>` and `From<Error> for String`), so the two styles mix freely.
+//!
+//! Commands that return `Error` reach the frontend as `{ kind, message, url, path, step,
+//! version }`; `message` is the string a `String` error would have carried, and the other
+//! fields are set when known.
+
+use std::path::{Path, PathBuf};
+
+use serde::ser::SerializeStruct;
+use serde::{Serialize, Serializer};
+
+pub type Result<T, E = Error> = std::result::Result<T, E>;
+
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    /// Request failed to send, or the server answered with an error status.
+    #[error("{url}: {source}")]
+    Http {
+        url: String,
+        #[source]
+        source: reqwest::Error,
+    },
+    #[error("{}: {source}", path.display())]
+    Io {
+        path: PathBuf,
+        #[source]
+        source: std::io::Error,
+    },
+    /// A document (`what`: URL or file) didn't parse.
+    #[error("invalid JSON from {what}: {source}")]
+    Json {
+        what: String,
+        #[source]
+        source: serde_json::Error,
+    },
+    /// A version argument that is out of range, not installed or not in the manifest.
+    #[error("unknown game version v{version}: {reason}")]
+    UnknownVersion { version: u32, reason: &'static str },
+    /// `source` failed while running `step` (e.g. an install task step).
+    #[error("{step}: {source}")]
+    Step {
+        step: String,
+        #[source]
+        source: Box<Error>,
+    },
+    /// Not (yet) classified; also what converted `String` errors become.
+    #[error("{0}")]
+    Other(String),
+}
+
+impl Error {
+    pub fn http(url: impl Into<String>, source: reqwest::Error) -> Self {
+        Error::Http {
+            url: url.into(),
+            source,
+        }
+    }
+
+    pub fn io(path: impl AsRef<Path>, source: std::io::Error) -> Self {
+        Error::Io {
+            path: path.as_ref().to_path_buf(),
+            source,
+        }
+    }
+
+    pub fn json(what: impl Into<String>, source: serde_json::Error) -> Self {
+        Error::Json {
+            what: what.into(),
+            source,
+        }
+    }
+
+    /// Wrap `self` as having happened during `step`.
+    pub fn in_step(self, step: impl Into<String>) -> Self {
+        Error::Step {
+            step: step.into(),
+            source: Box::new(self),
+        }
+    }
+
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Error::Http { .. } => "http",
+            Error::Io { .. } => "io",
+            Error::Json { .. } => "json",
+            Error::UnknownVersion { .. } => "unknown_version",
+            Error::Step { source, .. } => source.kind(),
+            Error::Other(_) => "other",
+        }
+    }
+
+    /// The error under any `Step` wrappers.
+    fn root(&self) -> &Error {
+        match self {
+            Error::Step { source, .. } => source.root(),
+            other => other,
+        }
+    }
+
+    pub fn url(&self) -> Option<&str> {
+        match self.root() {
+            Error::Http { url, .. } => Some(url),
+            _ => None,
+        }
+    }
+
+    pub fn path(&self) -> Option<&Path> {
+        match self.root() {
+            Error::Io { path, .. } => Some(path),
+            _ => None,
+        }
+    }
+
+    /// Game version an `UnknownVersion` error is about.
+    pub fn version(&self) -> Option<u32> {
+        match self.root() {
+            Error::UnknownVersion { version, .. } => Some(*version),
+            _ => None,
+        }
+    }
+
+    /// Outermost step, if any.
+    pub fn step(&self) -> Option<&str> {
+        match self {
+            Error::Step { step, .. } => Some(step),
+            _ => None,
+        }
+    }
+}
+
+impl From<String> for Error {
+    fn from(message: String) -> Self {
+        Error::Other(message)
+    }
+}
+
+impl From<&str> for Error {
+    fn from(message: &str) -> Self {
+        Error::Other(message.to_string())
+    }
+}
+
+impl From<Error> for String {
+    fn from(error: Error) -> Self {
+        error.to_string()
+    }
+}
+
+impl Serialize for Error {
+    fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        let mut s = serializer.serialize_struct("Error", 6)?;
+        s.serialize_field("kind", self.kind())?;
+        s.serialize_field("message", &self.to_string())?;
+        s.serialize_field("url", &self.url())?;
+        s.serialize_field("path", &self.path())?;
+        s.serialize_field("step", &self.step())?;
+        s.serialize_field("version", &self.version())?;
+        s.end()
+    }
+}
+
+/// `.step("...")` on any result whose error converts into `Error`.
+pub trait ResultExt<T> {
+    fn step(self, step: &str) -> Result<T>;
+}
+
+impl<T, E: Into<Error>> ResultExt<T> for std::result::Result<T, E> {
+    fn step(self, step: &str) -> Result<T> {
+        self.map_err(|e| e.into().in_step(step))
+    }
+}
@@ -0,0 +1,108 @@
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use futures_util::future::BoxFuture;
+
+use crate::downloader::{overall_from_step, DownloadTaskContext, GameDownloader, LoginState};
+use crate::progress::{self, TaskProgressPayload};
+
+/// Tiny stand-in for the Lethal Company depot: enough for the launcher to detect an
+/// install, lay BepInEx on top and "launch" it.
+const FIXTURE_FILES: [(&str, &[u8]); 5] = [
+    ("Lethal Company.exe", b"MZ demo build"),
+    ("UnityPlayer.dll", b"MZ demo build"),
+    ("steam_appid.txt", b"1966720"),
+    ("Lethal Company_Data/Managed/Assembly-CSharp.dll", b"MZ demo build"),
+    ("Lethal Company_Data/globalgamemanagers", b"demo"),
+];
+
+/// Deterministic `GameDownloader` that never talks to Steam.
+///
+/// Used for offline demo mode (UI development) and CI runs of the install pipeline.
+pub struct FakeDownloader {
+    app: tauri::AppHandle,
+}
+
+impl FakeDownloader {
+    pub fn new(app: &tauri::AppHandle) -> Self {
+        Self { app: app.clone() }
+    }
+
+    async fn materialize(
+        &self,
+        manifest_id: Option<String>,
+        output_dir: PathBuf,
+        task: Option<DownloadTaskContext>,
+        cancel: Option<Arc<AtomicBool>>,
+    ) -> Result<(), String> {
+        log::info!(
+            "Fake downloader: materializing fixture game tree (manifest={}) into {}",
+            manifest_id.as_deref().unwrap_or("latest"),
+            output_dir.display()
+        );
+
+        let total = FIXTURE_FILES.len() as u64;
+        for (i, (rel, contents)) in FIXTURE_FILES.iter().enumerate() {
+            if cancel.as_ref().is_some_and(|c| c.load(Ordering::Relaxed)) {
+                return Err("Cancelled".to_string());
+            }
+
+            let path = output_dir.join(rel);
+            if let Some(parent) = path.parent() {
+                std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+            }
+            std::fs::write(&path, contents).map_err(|e| e.to_string())?;
+
+            if let Some(task) = task.as_ref() {
+                let done = i as u64 + 1;
+                let step_progress = done as f64 / total as f64;
+                progress::emit_progress(
+                    &self.app,
+                    TaskProgressPayload {
+                        version: task.version,
+                        steps_total: task.steps_total,
+                        step: task.step,
+                        step_name: task.step_name.clone(),
+                        step_progress,
+                        overall_percent: overall_from_step(
+                            task.step,
+                            step_progress,
+                            task.steps_total,
+                        ),
+                        detail: Some(rel.to_string()),
+                        downloaded_bytes: None,
+                        total_bytes: None,
+                        extracted_files: Some(done),
+                        total_files: Some(total),
+                    },
+                );
+            }
+
+            // Keep the progress bar visible in demo mode.
+            tokio::time::sleep(Duration::from_millis(150)).await;
+        }
+        Ok(())
+    }
+}
+
+impl GameDownloader for FakeDownloader {
+    fn login_state(&self) -> LoginState {
+        LoginState {
+            is_logged_in: true,
+            username: Some("demo".to_string()),
+        }
+    }
+
+    fn download_game<'a>(
+        &'a self,
+        manifest_id: Option<String>,
+        output_dir: PathBuf,
+        _validate: bool,
+        task: Option<DownloadTaskContext>,
+        cancel: Option<Arc<AtomicBool>>,
+    ) -> BoxFuture<'a, Result<(), String>> {
+        Box::pin(self.materialize(manifest_id, output_dir, task, cancel))
+    }
+}
@@ -0,0 +1,232 @@
+//! Rough memory footprint of a mod set, from the Unity asset bundles its mods ship.
+//!
+//! Cosmetic and moon mods load bundles that dwarf their DLLs, and a profile with dozens of
+//! them can run a low-memory machine out of RAM or VRAM mid-session. The estimate is
+//! deliberately simple: a vanilla baseline plus the bundle sizes scaled by how much they
+//! grow once loaded. It only has to tell "fine" from "this will not fit".
+
+use std::collections::BTreeSet;
+use std::path::Path;
+
+use serde::Serialize;
+
+use crate::mod_config::ModEntry;
+use crate::{gpu, profiles};
+
+/// Vanilla game with BepInEx, before any mod assets.
+const BASE_RAM_BYTES: u64 = 3 * 1024 * 1024 * 1024;
+const BASE_VRAM_BYTES: u64 = 1536 * 1024 * 1024;
+/// Bundles are mostly LZ4-compressed; loaded assets take about this much more RAM.
+const ASSET_RAM_FACTOR: f64 = 2.0;
+/// Share of loaded asset data that ends up on the GPU (textures, meshes).
+const ASSET_VRAM_FACTOR: f64 = 1.0;
+/// Warn once the RAM estimate passes this share of physical memory (the OS needs some).
+const RAM_WARN_SHARE: f64 = 0.85;
+
+/// Signatures of Unity asset bundles (current and legacy formats).
+const BUNDLE_MAGICS: [&[u8]; 3] = [b"UnityFS", b"UnityWeb", b"UnityRaw"];
+/// Unity serialized files and streamed resources, which have no signature.
+const ASSET_EXTENSIONS: [&str; 3] = ["assets", "ress", "resource"];
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ModFootprint {
+    /// Plugin folder, `dev-name`.
+    pub id: String,
+    pub asset_bytes: u64,
+    pub asset_files: u32,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ProfileFootprint {
+    pub version: u32,
+    /// `None`: the mods currently enabled for `version`.
+    pub profile: Option<String>,
+    /// Largest first.
+    pub mods: Vec<ModFootprint>,
+    /// Mods of the profile without a plugin folder yet (not counted).
+    pub not_installed: Vec<String>,
+    pub asset_bytes: u64,
+    pub est_ram_bytes: u64,
+    pub est_vram_bytes: u64,
+    pub system_ram_bytes: Option<u64>,
+    pub vram_bytes: Option<u64>,
+    pub warnings: Vec<String>,
+}
+
+fn is_asset_file(path: &Path) -> bool {
+    if path
+        .extension()
+        .and_then(|e| e.to_str())
+        .is_some_and(|e| ASSET_EXTENSIONS.contains(&e.to_ascii_lowercase().as_str()))
+    {
+        return true;
+    }
+    let mut head = [0u8; 8];
+    let Ok(mut f) = std::fs::File::open(path) else {
+        return false;
+    };
+    let n = std::io::Read::read(&mut f, &mut head).unwrap_or(0);
+    BUNDLE_MAGICS.iter().any(|m| head[..n].starts_with(m))
+}
+
+fn scan_dir(dir: &Path, out: &mut ModFootprint) {
+    let Ok(rd) = std::fs::read_dir(dir) else {
+        return;
+    };
+    for e in rd.flatten() {
+        let path = e.path();
+        let Ok(md) = e.metadata() else { continue };
+        if md.is_dir() {
+            scan_dir(&path, out);
+        } else if is_asset_file(&path) {
+            out.asset_bytes = out.asset_bytes.saturating_add(md.len());
+            out.asset_files += 1;
+        }
+    }
+}
+
+/// Asset bundles under one plugin folder.
+pub fn scan_mod(dir: &Path) -> ModFootprint {
+    let mut out = ModFootprint {
+        id: dir
+            .file_name()
+            .unwrap_or_default()
+            .to_string_lossy()
+            .to_string(),
+        asset_bytes: 0,
+        asset_files: 0,
+    };
+    scan_dir(dir, &mut out);
+    out
+}
+
+#[cfg(target_os = "linux")]
+fn system_ram() -> Option<u64> {
+    let meminfo = std::fs::read_to_string("/proc/meminfo").ok()?;
+    let kib: u64 = meminfo
+        .lines()
+        .find_map(|l| l.strip_prefix("MemTotal:"))?
+        .trim()
+        .trim_end_matches("kB")
+        .trim()
+        .parse()
+        .ok()?;
+    Some(kib * 1024)
+}
+
+#[cfg(not(target_os = "linux"))]
+fn system_ram() -> Option<u64> {
+    None
+}
+
+fn gib(bytes: u64) -> String {
+    format!("{:.1} GiB", bytes as f64 / (1024.0 * 1024.0 * 1024.0))
+}
+
+fn estimate(
+    version: u32,
+    profile: Option<String>,
+    mut mods: Vec<ModFootprint>,
+    not_installed: Vec<String>,
+) -> ProfileFootprint {
+    mods.sort_by(|a, b| b.asset_bytes.cmp(&a.asset_bytes).then(a.id.cmp(&b.id)));
+    let asset_bytes: u64 = mods.iter().map(|m| m.asset_bytes).sum();
+    let est_ram_bytes = BASE_RAM_BYTES + (asset_bytes as f64 * ASSET_RAM_FACTOR) as u64;
+    let est_vram_bytes = BASE_VRAM_BYTES + (asset_bytes as f64 * ASSET_VRAM_FACTOR) as u64;
+    let system_ram_bytes = system_ram();
+    let vram_bytes = gpu::vram_total();
+
+    let mut warnings = vec![];
+    if let Some(ram) = system_ram_bytes {
+        if est_ram_bytes as f64 > ram as f64 * RAM_WARN_SHARE {
+            warnings.push(format!(
+                "Mods may need about {} of RAM; this machine has {}",
+                gib(est_ram_bytes),
+                gib(ram)
+            ));
+        }
+    }
+    if let Some(vram) = vram_bytes {
+        if est_vram_bytes > vram {
+            warnings.push(format!(
+                "Mods may need about {} of VRAM; the GPU has {}",
+                gib(est_vram_bytes),
+                gib(vram)
+            ));
+        }
+    }
+    for w in &warnings {
+        log::warn!("v{version} footprint: {w}");
+    }
+
+    ProfileFootprint {
+        version,
+        profile,
+        mods,
+        not_installed,
+        asset_bytes,
+        est_ram_bytes,
+        est_vram_bytes,
+        system_ram_bytes,
+        vram_bytes,
+        warnings,
+    }
+}
+
+/// Asset footprint of profile `name` on `version` (default: active version), or of the
+/// mods currently enabled there when `name` is `None`.
+#[tauri::command]
+pub async fn get_profile_footprint(
+    app: tauri::AppHandle,
+    name: Option<String>,
+    version: Option<u32>,
+) -> Result<ProfileFootprint, String> {
+    let version = crate::resolve_version(&app, version)?;
+    let plugins = crate::plugins_dir(&app, version)?;
+
+    let (profile, wanted): (Option<String>, Option<Vec<ModEntry>>) = match name {
+        Some(name) => {
+            let profile = profiles::find_profile(&app, &name)?;
+            let manifest_mods = profiles::manifest_mods(&profile).await;
+            let wanted = profiles::profile_mods(&profile, &manifest_mods);
+            (Some(profile.name), Some(wanted))
+        }
+        None => (None, None),
+    };
+
+    tauri::async_runtime::spawn_blocking(move || -> Result<ProfileFootprint, String> {
+        let mut mods = vec![];
+        let mut not_installed = vec![];
+        match wanted {
+            Some(wanted) => {
+                let mut seen = BTreeSet::new();
+                for m in wanted {
+                    let id = format!("{}-{}", m.dev, m.name);
+                    if !seen.insert(id.to_lowercase()) {
+                        continue;
+                    }
+                    match crate::mod_dir_for(&plugins, &m.dev, &m.name) {
+                        Some(dir) => mods.push(scan_mod(&dir)),
+                        None => not_installed.push(id),
+                    }
+                }
+            }
+            None => {
+                let disabled = crate::read_disablemod(&app)?;
+                let rd = std::fs::read_dir(&plugins).map_err(|e| e.to_string())?;
+                for e in rd.flatten().filter(|e| e.path().is_dir()) {
+                    let folder = e.file_name().to_string_lossy().to_string();
+                    let off = folder.split_once('-').is_some_and(|(dev, name)| {
+                        disabled.mods.contains(&crate::normalize_mod_id(dev, name))
+                    });
+                    if !off {
+                        mods.push(scan_mod(&e.path()));
+                    }
+                }
+            }
+        }
+        Ok(estimate(version, profile, mods, not_installed))
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
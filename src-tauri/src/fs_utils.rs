@@ -0,0 +1,134 @@
+//! Filesystem helpers shared by the installer, the depot cache and the tool lookups.
+
+use std::path::{Path, PathBuf};
+
+/// `program` on `PATH` (`program.exe` on Windows), if any.
+pub fn find_on_path(program: &str) -> Option<PathBuf> {
+    let file = if cfg!(windows) {
+        format!("{program}.exe")
+    } else {
+        program.to_string()
+    };
+    std::env::var_os("PATH").and_then(|paths| {
+        std::env::split_paths(&paths)
+            .map(|dir| dir.join(&file))
+            .find(|p| p.is_file())
+    })
+}
+
+/// Clone `from` to `to` where the filesystem supports it (btrfs, XFS, bcachefs), else copy.
+///
+/// Never a hard link: DepotDownloader's `-validate` rewrites damaged files in place, and
+/// a linked file would carry that write back into the tree it was seeded from. A clone
+/// shares blocks only until one side is written.
+pub fn clone_or_copy_file(from: &Path, to: &Path) -> Result<(), String> {
+    #[cfg(target_os = "linux")]
+    if reflink(from, to).is_ok() {
+        return Ok(());
+    }
+    // macOS `fs::copy` already clones on APFS.
+    std::fs::copy(from, to)
+        .map(|_| ())
+        .map_err(|e| format!("{} -> {}: {e}", from.display(), to.display()))
+}
+
+#[cfg(target_os = "linux")]
+fn reflink(from: &Path, to: &Path) -> std::io::Result<()> {
+    use std::os::unix::io::AsRawFd;
+
+    /// `_IOW(0x94, 9, int)`
+    const FICLONE: libc::c_ulong = 0x4004_9409;
+
+    let src = std::fs::File::open(from)?;
+    let perms = src.metadata()?.permissions();
+    let dst = std::fs::File::create(to)?;
+    // SAFETY: both descriptors are open for the duration of the call.
+    let rc = unsafe { libc::ioctl(dst.as_raw_fd(), FICLONE as _, src.as_raw_fd()) };
+    if rc != 0 {
+        let err = std::io::Error::last_os_error();
+        drop(dst);
+        let _ = std::fs::remove_file(to);
+        return Err(err);
+    }
+    dst.set_permissions(perms)
+}
+
+/// Copy the tree at `src` into `dst` with `clone_or_copy_file`. Top-level entries named
+/// in `skip` are left out; existing files in `dst` are replaced only with `overwrite`.
+pub fn copy_tree(src: &Path, dst: &Path, skip: &[&str], overwrite: bool) -> Result<(), String> {
+    fn walk(src: &Path, dst: &Path, skip: &[&str], overwrite: bool) -> Result<(), String> {
+        std::fs::create_dir_all(dst).map_err(|e| e.to_string())?;
+        for entry in std::fs::read_dir(src).map_err(|e| e.to_string())? {
+            let entry = entry.map_err(|e| e.to_string())?;
+            let name = entry.file_name();
+            if skip.iter().any(|s| name == *s) {
+                continue;
+            }
+            let from = entry.path();
+            let to = dst.join(&name);
+            let ty = entry.file_type().map_err(|e| e.to_string())?;
+            if ty.is_dir() {
+                walk(&from, &to, &[], overwrite)?;
+            } else if ty.is_file() {
+                if to.exists() {
+                    if !overwrite {
+                        continue;
+                    }
+                    // Replace rather than write through: `to` may itself share data.
+                    std::fs::remove_file(&to).map_err(|e| e.to_string())?;
+                }
+                clone_or_copy_file(&from, &to)?;
+            }
+        }
+        Ok(())
+    }
+    walk(src, dst, skip, overwrite)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::TempDir;
+
+    #[test]
+    fn seeded_install_leaves_the_source_version_unchanged() {
+        use std::io::Write;
+
+        let tmp = TempDir::new("seed-copy");
+        let source = tmp.path().join("v72");
+        let target = tmp.path().join("v73");
+        std::fs::create_dir_all(source.join("Lethal Company_Data")).unwrap();
+        std::fs::create_dir_all(source.join("BepInEx").join("plugins")).unwrap();
+        std::fs::write(source.join("Lethal Company.exe"), "exe v72").unwrap();
+        std::fs::write(source.join("Lethal Company_Data").join("level0"), "level v72").unwrap();
+        std::fs::write(source.join("BepInEx").join("plugins").join("Mod.dll"), "mod").unwrap();
+
+        copy_tree(&source, &target, &["BepInEx"], false).expect("seed");
+        assert!(!target.join("BepInEx").exists());
+
+        // `-validate` patches damaged files in place.
+        let mut patched = std::fs::OpenOptions::new()
+            .write(true)
+            .truncate(true)
+            .open(target.join("Lethal Company_Data").join("level0"))
+            .unwrap();
+        patched.write_all(b"level v73").unwrap();
+        drop(patched);
+
+        assert_eq!(
+            std::fs::read_to_string(source.join("Lethal Company_Data").join("level0")).unwrap(),
+            "level v72"
+        );
+        assert_eq!(
+            std::fs::read_to_string(target.join("Lethal Company.exe")).unwrap(),
+            "exe v72"
+        );
+
+        // Without `overwrite`, files already in the target are kept.
+        copy_tree(&source, &target, &["BepInEx"], false).expect("reseed");
+        assert_eq!(
+            std::fs::read_to_string(target.join("Lethal Company_Data").join("level0")).unwrap(),
+            "level v73"
+        );
+    }
+}
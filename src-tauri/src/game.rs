@@ -0,0 +1,29 @@
+//! The game the launcher manages.
+//!
+//! Everything game-specific that the install pipeline needs (Thunderstore community,
+//! Steam depot, executable) lives here, so adopting another Thunderstore-supported game
+//! means adding a definition rather than hunting for literals.
+
+#[derive(Debug, Clone, Copy)]
+pub struct GameDefinition {
+    pub name: &'static str,
+    /// Slug in `thunderstore.io/c/{community}/`; mods are only installed from it.
+    pub thunderstore_community: &'static str,
+    pub steam_app_id: &'static str,
+    pub steam_depot_id: &'static str,
+    /// Executable at the root of the depot.
+    pub executable: &'static str,
+}
+
+pub const LETHAL_COMPANY: GameDefinition = GameDefinition {
+    name: "Lethal Company",
+    thunderstore_community: "lethal-company",
+    steam_app_id: "1966720",
+    steam_depot_id: "1966721",
+    executable: "Lethal Company.exe",
+};
+
+/// The game this build manages.
+pub const fn current() -> &'static GameDefinition {
+    &LETHAL_COMPANY
+}
@@ -0,0 +1,167 @@
+//! GPU detection and render offload for hybrid-graphics machines.
+//!
+//! GPUs are read from `/sys/class/drm/card*/device`. On laptops the iGPU drives the
+//! display (`boot_vga = 1`), so without offload variables the game renders on it.
+
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use serde::Serialize;
+
+const SYS_DRM: &str = "/sys/class/drm";
+const SYS_PCI_DEVICES: &str = "/sys/bus/pci/devices";
+
+/// `LaunchOptions::gpu` value that picks the first GPU not driving the display.
+pub const DISCRETE: &str = "discrete";
+
+#[derive(Debug, Clone, Serialize)]
+pub struct GpuInfo {
+    /// PCI slot, e.g. `0000:01:00.0`; what `LaunchOptions::gpu` stores.
+    pub pci_slot: String,
+    pub vendor: String,
+    pub vendor_id: String,
+    pub device_id: String,
+    pub driver: Option<String>,
+    /// Drives the display at boot (the iGPU on hybrid laptops).
+    pub boot_vga: bool,
+}
+
+impl GpuInfo {
+    fn is_nvidia_proprietary(&self) -> bool {
+        self.driver.as_deref() == Some("nvidia")
+    }
+}
+
+fn vendor_name(vendor_id: &str) -> &'static str {
+    match vendor_id {
+        "0x10de" => "NVIDIA",
+        "0x1002" => "AMD",
+        "0x8086" => "Intel",
+        _ => "Unknown",
+    }
+}
+
+fn read_trimmed(path: &Path) -> Option<String> {
+    std::fs::read_to_string(path)
+        .ok()
+        .map(|s| s.trim().to_string())
+}
+
+fn detect() -> Vec<GpuInfo> {
+    let Ok(rd) = std::fs::read_dir(SYS_DRM) else {
+        return vec![];
+    };
+    let mut out: Vec<GpuInfo> = vec![];
+    for e in rd.flatten() {
+        let name = e.file_name().to_string_lossy().to_string();
+        // `card0`, not connectors like `card0-HDMI-A-1`.
+        let Some(index) = name.strip_prefix("card") else {
+            continue;
+        };
+        if index.parse::<u32>().is_err() {
+            continue;
+        }
+        let device = e.path().join("device");
+        let Some(pci_slot) = std::fs::canonicalize(&device)
+            .ok()
+            .and_then(|p| p.file_name().map(|n| n.to_string_lossy().to_string()))
+        else {
+            continue;
+        };
+        if out.iter().any(|g| g.pci_slot == pci_slot) {
+            continue;
+        }
+        let vendor_id = read_trimmed(&device.join("vendor")).unwrap_or_default();
+        out.push(GpuInfo {
+            vendor: vendor_name(&vendor_id).to_string(),
+            device_id: read_trimmed(&device.join("device")).unwrap_or_default(),
+            driver: std::fs::read_link(device.join("driver"))
+                .ok()
+                .and_then(|p| p.file_name().map(|n| n.to_string_lossy().to_string())),
+            boot_vga: read_trimmed(&device.join("boot_vga")).as_deref() == Some("1"),
+            vendor_id,
+            pci_slot,
+        });
+    }
+    out.sort_by(|a, b| a.pci_slot.cmp(&b.pci_slot));
+    out
+}
+
+#[tauri::command]
+pub fn list_gpus() -> Vec<GpuInfo> {
+    detect()
+}
+
+/// Largest dedicated VRAM among the GPUs, in bytes. amdgpu exposes it in sysfs; the
+/// NVIDIA proprietary driver doesn't, so `nvidia-smi` is asked instead. `None` if unknown.
+pub fn vram_total() -> Option<u64> {
+    let sysfs = detect()
+        .iter()
+        .filter_map(|g| {
+            let path = Path::new(SYS_PCI_DEVICES)
+                .join(&g.pci_slot)
+                .join("mem_info_vram_total");
+            read_trimmed(&path)?.parse::<u64>().ok()
+        })
+        .max();
+    sysfs.or_else(nvidia_smi_vram)
+}
+
+fn nvidia_smi_vram() -> Option<u64> {
+    let out = std::process::Command::new("nvidia-smi")
+        .args(["--query-gpu=memory.total", "--format=csv,noheader,nounits"])
+        .output()
+        .ok()?;
+    if !out.status.success() {
+        return None;
+    }
+    String::from_utf8_lossy(&out.stdout)
+        .lines()
+        .filter_map(|l| l.trim().parse::<u64>().ok())
+        .max()
+        .map(|mib| mib * 1024 * 1024)
+}
+
+/// Resolve a `LaunchOptions::gpu` value (`discrete` or a PCI slot) to a detected GPU.
+///
+/// `discrete` finds nothing on single-GPU machines: there is nothing to offload to.
+fn resolve(selection: &str) -> Option<GpuInfo> {
+    let gpus = detect();
+    if selection == DISCRETE {
+        return gpus.into_iter().find(|g| !g.boot_vga);
+    }
+    gpus.into_iter().find(|g| g.pci_slot == selection)
+}
+
+/// Offload variables that make GL/Vulkan render on the selected GPU.
+pub fn offload_env(selection: &str) -> BTreeMap<String, String> {
+    let mut env = BTreeMap::new();
+    let Some(gpu) = resolve(selection) else {
+        if selection != DISCRETE {
+            log::warn!("GPU {selection:?} not found; using the default GPU");
+        }
+        return env;
+    };
+    if gpu.boot_vga {
+        return env;
+    }
+    if gpu.is_nvidia_proprietary() {
+        env.insert("__NV_PRIME_RENDER_OFFLOAD".into(), "1".into());
+        env.insert("__GLX_VENDOR_LIBRARY_NAME".into(), "nvidia".into());
+        env.insert("__VK_LAYER_NV_optimus".into(), "NVIDIA_only".into());
+    } else {
+        // Mesa: GL and the Vulkan device-select layer both honor DRI_PRIME.
+        let slot = gpu.pci_slot.replace([':', '.'], "_");
+        env.insert("DRI_PRIME".into(), format!("pci-{slot}"));
+        env.insert(
+            "MESA_VK_DEVICE_SELECT".into(),
+            format!(
+                "{}:{}",
+                gpu.vendor_id.trim_start_matches("0x"),
+                gpu.device_id.trim_start_matches("0x")
+            ),
+        );
+    }
+    log::info!("Launching on {} GPU {}", gpu.vendor, gpu.pci_slot);
+    env
+}
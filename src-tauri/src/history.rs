@@ -0,0 +1,121 @@
+//! Per-version timeline of what the launcher changed: installs, syncs (with the manifest
+//! version they applied), mod updates, manual mod toggles, repairs and uninstalls. Meant
+//! for "it worked last week": the entries show what changed since then.
+//!
+//! Stored as `state/history/v{N}.jsonl`, one entry per line, outside the version dir so a
+//! reinstall or uninstall doesn't erase what came before it. Recording never fails the
+//! operation it describes; a write error is only logged.
+
+use std::path::PathBuf;
+
+use serde::{Deserialize, Serialize};
+
+use crate::progress::ModChange;
+use crate::storage;
+
+/// Oldest entries are dropped past this many per version.
+const MAX_ENTRIES: usize = 500;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HistoryKind {
+    Install,
+    Sync,
+    ModUpdate,
+    ModToggle,
+    Repair,
+    Uninstall,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HistoryEntry {
+    /// Unix seconds.
+    pub at: u64,
+    pub kind: HistoryKind,
+    /// Remote manifest revision the change applied, when it came from the manifest.
+    pub manifest_version: Option<u32>,
+    /// Task that made the change; its log is available via `get_task_log`.
+    pub task_id: Option<String>,
+    pub summary: String,
+    /// Mods added or updated.
+    #[serde(default)]
+    pub changes: Vec<ModChange>,
+}
+
+fn history_path(app: &tauri::AppHandle, version: u32) -> Result<PathBuf, String> {
+    let version = storage::check_version(version)?;
+    Ok(storage::state_dir(app)?
+        .join("history")
+        .join(format!("v{version}.jsonl")))
+}
+
+/// Entries of `version`, oldest first. Unreadable lines are skipped.
+fn read(app: &tauri::AppHandle, version: u32) -> Result<Vec<HistoryEntry>, String> {
+    let path = history_path(app, version)?;
+    let text = match std::fs::read_to_string(&path) {
+        Ok(t) => t,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(vec![]),
+        Err(e) => return Err(format!("{}: {e}", path.display())),
+    };
+    Ok(text
+        .lines()
+        .filter_map(|l| serde_json::from_str(l).ok())
+        .collect())
+}
+
+fn append(app: &tauri::AppHandle, version: u32, entry: HistoryEntry) -> Result<(), String> {
+    let path = history_path(app, version)?;
+    let mut entries = read(app, version)?;
+    entries.push(entry);
+    let skip = entries.len().saturating_sub(MAX_ENTRIES);
+    let mut text = String::new();
+    for e in &entries[skip..] {
+        text.push_str(&serde_json::to_string(e).map_err(|e| e.to_string())?);
+        text.push('\n');
+    }
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    crate::zip_utils::write_atomic(&path, text)
+}
+
+/// Add an entry to `version`'s timeline, attributed to its running task (if any).
+pub fn record(
+    app: &tauri::AppHandle,
+    version: u32,
+    kind: HistoryKind,
+    manifest_version: Option<u32>,
+    summary: impl Into<String>,
+    changes: Vec<ModChange>,
+) {
+    let entry = HistoryEntry {
+        at: std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0),
+        kind,
+        manifest_version,
+        task_id: crate::task_log::latest_task(version),
+        summary: summary.into(),
+        changes,
+    };
+    if let Err(e) = append(app, version, entry) {
+        log::warn!("Failed to record history for v{version}: {e}");
+    }
+}
+
+/// `version`'s timeline, newest first; `limit` caps the number of entries returned.
+/// Versions that are no longer installed keep their history.
+#[tauri::command]
+pub fn get_install_history(
+    app: tauri::AppHandle,
+    version: u32,
+    limit: Option<usize>,
+) -> Result<Vec<HistoryEntry>, String> {
+    let mut entries = read(&app, version)?;
+    entries.reverse();
+    if let Some(limit) = limit {
+        entries.truncate(limit);
+    }
+    Ok(entries)
+}
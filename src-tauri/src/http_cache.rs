@@ -0,0 +1,227 @@
+//! Tiny ETag cache for small JSON endpoints (manifest, release metadata).
+//!
+//! Each entry stores `url -> (etag, last_modified, body, fetched_at)` under
+//! `cache/http/`; refetches send `If-None-Match`/`If-Modified-Since` and reuse the stored
+//! body on `304 Not Modified`. Bodies without validators are stored too, so the last good
+//! response is still there when the network is not. Until `init` runs (e.g. in integration
+//! tests) requests pass straight through.
+//!
+//! The Thunderstore index is too large to duplicate here; it keeps its own cache file and
+//! only uses `conditional_get`.
+
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use reqwest::header::{ETAG, IF_MODIFIED_SINCE, IF_NONE_MATCH, LAST_MODIFIED};
+use reqwest::StatusCode;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+
+use crate::error::{Error, Result};
+use crate::integrity::Fnv1a;
+
+const USER_AGENT: &str = "hq-launcher/0.1 (tauri)";
+
+static CACHE_DIR: OnceLock<PathBuf> = OnceLock::new();
+
+/// Enable the on-disk cache (called once from app setup).
+pub fn init(dir: PathBuf) {
+    let _ = CACHE_DIR.set(dir);
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct Entry {
+    url: String,
+    etag: Option<String>,
+    last_modified: Option<String>,
+    fetched_at: u64,
+    body: String,
+}
+
+/// Validators of a previous response, sent back as conditional request headers.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Validators {
+    #[serde(default)]
+    pub etag: Option<String>,
+    #[serde(default)]
+    pub last_modified: Option<String>,
+}
+
+/// Where a cached body came from, for callers that fall back to it offline.
+#[derive(Debug, Clone, Serialize)]
+pub struct CacheInfo {
+    pub url: String,
+    pub etag: Option<String>,
+    /// Unix seconds of the last response (200 or 304) for `url`.
+    pub fetched_at: u64,
+}
+
+/// Result of a conditional GET.
+pub enum Conditional {
+    NotModified,
+    Fresh {
+        body: String,
+        validators: Validators,
+    },
+}
+
+fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+/// FNV-1a; stable across builds so cache file names survive upgrades.
+fn url_key(url: &str) -> String {
+    let mut h = Fnv1a::new();
+    h.update(url.as_bytes());
+    format!("{:016x}", h.finish())
+}
+
+fn entry_path(dir: &Path, url: &str) -> PathBuf {
+    dir.join(format!("{}.json", url_key(url)))
+}
+
+fn read_entry(dir: &Path, url: &str) -> Option<Entry> {
+    let text = std::fs::read_to_string(entry_path(dir, url)).ok()?;
+    serde_json::from_str::<Entry>(&text)
+        .ok()
+        .filter(|e| e.url == url)
+}
+
+fn write_entry(dir: &Path, entry: &Entry) {
+    let res = std::fs::create_dir_all(dir)
+        .map_err(|e| e.to_string())
+        .and_then(|_| serde_json::to_string(entry).map_err(|e| e.to_string()))
+        .and_then(|json| crate::zip_utils::write_atomic(&entry_path(dir, &entry.url), json));
+    if let Err(e) = res {
+        log::warn!("Failed to write HTTP cache entry for {}: {e}", entry.url);
+    }
+}
+
+/// GET `url`, sending `validators` as `If-None-Match`/`If-Modified-Since`.
+pub async fn conditional_get(
+    client: &reqwest::Client,
+    url: &str,
+    validators: &Validators,
+) -> Result<Conditional> {
+    let mut req = client.get(url).header("User-Agent", USER_AGENT);
+    if let Some(etag) = &validators.etag {
+        req = req.header(IF_NONE_MATCH, etag);
+    }
+    if let Some(lm) = &validators.last_modified {
+        req = req.header(IF_MODIFIED_SINCE, lm);
+    }
+
+    let response = req.send().await.map_err(|e| Error::http(url, e))?;
+    if response.status() == StatusCode::NOT_MODIFIED {
+        return Ok(Conditional::NotModified);
+    }
+    let response = response.error_for_status().map_err(|e| Error::http(url, e))?;
+    let header = |name| {
+        response
+            .headers()
+            .get(name)
+            .and_then(|v| v.to_str().ok())
+            .map(str::to_string)
+    };
+    let validators = Validators {
+        etag: header(ETAG),
+        last_modified: header(LAST_MODIFIED),
+    };
+    let body = response.text().await.map_err(|e| Error::http(url, e))?;
+    Ok(Conditional::Fresh { body, validators })
+}
+
+/// GET `url` as text through the cache.
+pub async fn get_text(client: &reqwest::Client, url: &str) -> Result<String> {
+    fetch_text(client, url, true).await
+}
+
+/// GET `url` as text without sending the stored validators, replacing the cached body.
+pub async fn refresh_text(client: &reqwest::Client, url: &str) -> Result<String> {
+    fetch_text(client, url, false).await
+}
+
+async fn fetch_text(
+    client: &reqwest::Client,
+    url: &str,
+    revalidate: bool,
+) -> Result<String> {
+    let Some(dir) = CACHE_DIR.get() else {
+        return match conditional_get(client, url, &Validators::default()).await? {
+            Conditional::Fresh { body, .. } => Ok(body),
+            Conditional::NotModified => Err(Error::Other(format!("unexpected 304 from {url}"))),
+        };
+    };
+
+    let cached = read_entry(dir, url).filter(|_| revalidate);
+    let validators = cached
+        .as_ref()
+        .map(|e| Validators {
+            etag: e.etag.clone(),
+            last_modified: e.last_modified.clone(),
+        })
+        .unwrap_or_default();
+
+    match conditional_get(client, url, &validators).await? {
+        Conditional::NotModified => {
+            let Some(mut entry) = cached else {
+                return Err(Error::Other(format!("unexpected 304 from {url}")));
+            };
+            log::info!("HTTP cache hit (304) for {url}");
+            entry.fetched_at = now_secs();
+            write_entry(dir, &entry);
+            Ok(entry.body)
+        }
+        Conditional::Fresh { body, validators } => {
+            write_entry(
+                dir,
+                &Entry {
+                    url: url.to_string(),
+                    etag: validators.etag,
+                    last_modified: validators.last_modified,
+                    fetched_at: now_secs(),
+                    body: body.clone(),
+                },
+            );
+            Ok(body)
+        }
+    }
+}
+
+/// GET `url` and decode it as JSON through the cache.
+pub async fn get_json<T: DeserializeOwned>(
+    client: &reqwest::Client,
+    url: &str,
+) -> Result<T> {
+    let body = get_text(client, url).await?;
+    serde_json::from_str(&body).map_err(|e| Error::json(url, e))
+}
+
+/// Like `get_json`, but always downloads the full body (no `If-None-Match`).
+pub async fn refresh_json<T: DeserializeOwned>(
+    client: &reqwest::Client,
+    url: &str,
+) -> Result<T> {
+    let body = refresh_text(client, url).await?;
+    serde_json::from_str(&body).map_err(|e| Error::json(url, e))
+}
+
+/// Body last stored for `url`, decoded as JSON; never touches the network.
+pub fn cached_json<T: DeserializeOwned>(url: &str) -> Option<T> {
+    let entry = read_entry(CACHE_DIR.get()?, url)?;
+    serde_json::from_str(&entry.body).ok()
+}
+
+/// ETag and fetch time of the body stored for `url`; never touches the network.
+pub fn cache_info(url: &str) -> Option<CacheInfo> {
+    let entry = read_entry(CACHE_DIR.get()?, url)?;
+    Some(CacheInfo {
+        url: entry.url,
+        etag: entry.etag,
+        fetched_at: entry.fetched_at,
+    })
+}
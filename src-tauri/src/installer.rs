@@ -1,3 +1,4 @@
+use std::collections::BTreeSet;
 use std::fs::File;
 use std::io::Write;
 use std::path::{Path, PathBuf};
@@ -6,30 +7,76 @@ use std::sync::Arc;
 
 use futures_util::StreamExt;
 use serde::{Deserialize, Serialize};
-use tauri::Manager;
 
+use crate::allowlist;
+use crate::cache;
+use crate::config_backup;
+use crate::config_sync;
+use crate::decisions;
+use crate::disk_space;
+use crate::download_backend;
+use crate::doorstop;
 use crate::downloader;
-use crate::mod_config::ModsConfig;
+use crate::endpoints;
+use crate::fs_utils;
+use crate::history::{self, HistoryKind};
+use crate::integrity;
+use crate::mirrors;
+use crate::mod_config::{self, BepInExLoader, ModsConfig};
 use crate::mods;
-use crate::progress::{self, TaskErrorPayload, TaskFinishedPayload, TaskProgressPayload};
+use crate::motd;
+use crate::progress::{
+    self, CancelReason, InstallSummary, TaskCancelledPayload, TaskErrorPayload,
+    TaskFinishedPayload, TaskProgressPayload, TaskStep,
+};
+use crate::settings;
+use crate::storage;
+use crate::tasks::{self, TaskKind};
+use crate::temp_files;
 use crate::zip_utils;
 use progress::{emit_error, emit_finished, emit_progress};
 
-// BepInEx installation via Thunderstore BepInExPack (Mono, preconfigured).
-// We download the Thunderstore package zip and extract the contents of the `BepInExPack/` folder
-// into the game root (versions/v{version}).
-//
-// Reference: https://thunderstore.io/c/lethal-company/p/BepInEx/BepInExPack/
-const BEPINEXPACK_VERSION: &str = "5.4.2304";
-const BEPINEXPACK_URL: &str =
-    "https://thunderstore.io/package/download/BepInEx/BepInExPack/5.4.2304/";
-
 // Proton-GE (Linux): download and extract into AppData/proton_env/proton/.
+// `Settings::proton_version` picks another release tag.
 #[cfg(target_os = "linux")]
 const PROTON_GE_VERSION: &str = "GE-Proton10-28";
+
+/// Proton-GE release to install and prefer: the settings override, else `PROTON_GE_VERSION`.
+#[cfg(target_os = "linux")]
+fn proton_ge_version(app: &tauri::AppHandle) -> String {
+    settings::load(app)
+        .proton_version
+        .unwrap_or_else(|| PROTON_GE_VERSION.to_string())
+}
+
 #[cfg(target_os = "linux")]
-const PROTON_GE_URL: &str =
-    "https://github.com/GloriousEggroll/proton-ge-custom/releases/download/GE-Proton10-28/GE-Proton10-28.tar.gz";
+fn proton_ge_url(version: &str) -> String {
+    format!(
+        "https://github.com/GloriousEggroll/proton-ge-custom/releases/download/{version}/{version}.tar.gz"
+    )
+}
+
+/// Steps of `download_and_setup`, announced via `task-plan` before the first step runs.
+/// Weights approximate each step's share of a typical install (the depot download dominates).
+const INSTALL_STEPS: [TaskStep; 5] = [
+    TaskStep { id: "login_check", name: "Login Check", weight: 1.0 },
+    TaskStep { id: "download_game", name: "Download Game", weight: 70.0 },
+    TaskStep { id: "install_bepinex", name: "Install BepInEx", weight: 5.0 },
+    TaskStep { id: "install_config", name: "Install Config", weight: 2.0 },
+    TaskStep { id: "install_mods", name: "Install Mods", weight: 22.0 },
+];
+
+/// Steps of `uninstall_version`.
+const UNINSTALL_STEPS: [TaskStep; 2] = [
+    TaskStep { id: "remove_game", name: "Remove Game Files", weight: 9.0 },
+    TaskStep { id: "cleanup", name: "Clean Up", weight: 1.0 },
+];
+
+/// Steps of `move_install_root`, run once per installed version.
+const MOVE_STEPS: [TaskStep; 1] = [TaskStep { id: "move_game", name: "Move Game Files", weight: 1.0 }];
+
+/// Steps of `sync_latest_install_from_manifest`.
+const SYNC_STEPS: [TaskStep; 1] = [TaskStep { id: "sync_mods", name: "Sync Mods", weight: 1.0 }];
 
 fn overall_from_step(step: u32, step_progress: f64, steps_total: u32) -> f64 {
     let s = step.max(1).min(steps_total) as f64;
@@ -37,8 +84,7 @@ fn overall_from_step(step: u32, step_progress: f64, steps_total: u32) -> f64 {
     (((s - 1.0) + sp) / (steps_total as f64)) * 100.0
 }
 
-#[cfg(target_os = "linux")]
-fn sanitize_tar_rel_path(p: &Path) -> Option<PathBuf> {
+pub(crate) fn sanitize_tar_rel_path(p: &Path) -> Option<PathBuf> {
     use std::path::Component;
     // Accept only relative, "normal" components; strip any leading "./".
     // Reject absolute paths, prefixes, and any ".." traversal.
@@ -63,7 +109,7 @@ fn dir_has_any_entries(path: &Path) -> bool {
 }
 
 #[cfg(target_os = "linux")]
-fn list_other_proton_ge_dirs(proton_root: &Path) -> Vec<PathBuf> {
+fn list_other_proton_ge_dirs(proton_root: &Path, keep: &str) -> Vec<PathBuf> {
     let mut out = vec![];
     let Ok(rd) = std::fs::read_dir(proton_root) else {
         return out;
@@ -77,7 +123,7 @@ fn list_other_proton_ge_dirs(proton_root: &Path) -> Vec<PathBuf> {
         let Some(name) = path.file_name().and_then(|s| s.to_str()) else {
             continue;
         };
-        if name.starts_with("GE-Proton") && name != PROTON_GE_VERSION {
+        if name.starts_with("GE-Proton") && name != keep {
             out.push(path);
         }
     }
@@ -85,12 +131,7 @@ fn list_other_proton_ge_dirs(proton_root: &Path) -> Vec<PathBuf> {
 }
 
 pub fn proton_root_dir(app: &tauri::AppHandle) -> Result<PathBuf, String> {
-    Ok(app
-        .path()
-        .app_data_dir()
-        .map_err(|e| format!("failed to resolve app data dir: {e}"))?
-        .join("proton_env")
-        .join("proton"))
+    Ok(storage::proton_env_dir(app)?.join("proton"))
 }
 
 #[cfg(not(target_os = "linux"))]
@@ -98,15 +139,6 @@ fn get_current_proton_dir_impl(_app: &tauri::AppHandle) -> Result<Option<PathBuf
     Ok(None)
 }
 
-#[cfg(target_os = "linux")]
-pub fn proton_env_dir(app: &tauri::AppHandle) -> Result<PathBuf, String> {
-    Ok(app
-        .path()
-        .app_data_dir()
-        .map_err(|e| format!("failed to resolve app data dir: {e}"))?
-        .join("proton_env"))
-}
-
 #[cfg(target_os = "linux")]
 pub fn get_current_proton_dir_impl(app: &tauri::AppHandle) -> Result<Option<PathBuf>, String> {
     let proton_root = proton_root_dir(app)?;
@@ -115,7 +147,7 @@ pub fn get_current_proton_dir_impl(app: &tauri::AppHandle) -> Result<Option<Path
     }
 
     // Prefer the desired version if present and non-empty.
-    let preferred = proton_root.join(PROTON_GE_VERSION);
+    let preferred = proton_root.join(proton_ge_version(app));
     if preferred.exists() && preferred.is_dir() && dir_has_any_entries(&preferred) {
         return Ok(Some(preferred));
     }
@@ -159,9 +191,9 @@ pub fn get_current_proton_dir_impl(app: &tauri::AppHandle) -> Result<Option<Path
 
 /// Install Proton-GE under `AppDataDir/proton_env/proton/` (Linux only).
 ///
-/// Behavior:
-/// - If `.../proton/GE-Proton10-28/` already exists, do nothing.
-/// - Otherwise download `GE-Proton10-28.tar.gz`, extract safely, then move into place.
+/// Behavior (`{version}` from `proton_ge_version`):
+/// - If `.../proton/{version}/` already exists, do nothing.
+/// - Otherwise download `{version}.tar.gz`, extract safely, then move into place.
 pub async fn install_proton_ge_impl(app: &tauri::AppHandle) -> Result<bool, String> {
     #[cfg(not(target_os = "linux"))]
     {
@@ -177,15 +209,12 @@ pub async fn install_proton_ge_impl(app: &tauri::AppHandle) -> Result<bool, Stri
 
         log::info!("Installing Proton-GE");
 
-        let app_data = app
-            .path()
-            .app_data_dir()
-            .map_err(|e| format!("failed to resolve app data dir: {e}"))?;
-
-        let proton_root = app_data.join("proton_env").join("proton");
+        let proton_root = proton_root_dir(app)?;
         std::fs::create_dir_all(&proton_root).map_err(|e| e.to_string())?;
 
-        let final_dir = proton_root.join(PROTON_GE_VERSION);
+        let proton_version = proton_ge_version(app);
+        let proton_url = proton_ge_url(&proton_version);
+        let final_dir = proton_root.join(&proton_version);
         if final_dir.exists() && dir_has_any_entries(&final_dir) {
             // Desired version already present.
             log::info!(
@@ -205,12 +234,12 @@ pub async fn install_proton_ge_impl(app: &tauri::AppHandle) -> Result<bool, Stri
         }
 
         // If another GE-Proton version is installed, remove it and install the desired version.
-        let other_ge_dirs = list_other_proton_ge_dirs(&proton_root);
+        let other_ge_dirs = list_other_proton_ge_dirs(&proton_root, &proton_version);
         if !other_ge_dirs.is_empty() {
             log::info!(
                 "Found {} other GE-Proton version(s); replacing with {}",
                 other_ge_dirs.len(),
-                PROTON_GE_VERSION
+                proton_version
             );
             for d in other_ge_dirs {
                 match std::fs::remove_dir_all(&d) {
@@ -223,20 +252,21 @@ pub async fn install_proton_ge_impl(app: &tauri::AppHandle) -> Result<bool, Stri
             }
         }
 
-        let temp_dir = app_data.join("temp");
-        std::fs::create_dir_all(&temp_dir).map_err(|e| e.to_string())?;
-
-        let tar_path = temp_dir.join(format!("{PROTON_GE_VERSION}.tar.gz"));
+        let tar_path = temp_files::temp_path(
+            app,
+            temp_files::LAUNCHER_OWNER,
+            &format!("{proton_version}.tar.gz"),
+        )?;
         log::info!(
             "Downloading Proton-GE from {} to {}",
-            PROTON_GE_URL,
+            proton_url,
             tar_path.to_string_lossy()
         );
 
         // Stream download into file (avoid holding whole tarball in memory).
         let client = reqwest::Client::new();
         let response = client
-            .get(PROTON_GE_URL)
+            .get(&proton_url)
             .header("User-Agent", "hq-launcher/0.1 (tauri)")
             .send()
             .await
@@ -278,7 +308,7 @@ pub async fn install_proton_ge_impl(app: &tauri::AppHandle) -> Result<bool, Stri
             .duration_since(std::time::UNIX_EPOCH)
             .map(|d| d.as_millis())
             .unwrap_or(0);
-        let extract_tmp = proton_root.join(format!(".tmp_extract_{PROTON_GE_VERSION}_{ts}"));
+        let extract_tmp = proton_root.join(format!(".tmp_extract_{proton_version}_{ts}"));
         if extract_tmp.exists() {
             let _ = std::fs::remove_dir_all(&extract_tmp);
         }
@@ -312,14 +342,14 @@ pub async fn install_proton_ge_impl(app: &tauri::AppHandle) -> Result<bool, Stri
         .await
         .map_err(|e| e.to_string())??;
 
-        // Expect the tarball to contain a top-level folder named exactly PROTON_GE_VERSION.
-        let extracted_dir = extract_tmp.join(PROTON_GE_VERSION);
+        // Expect the tarball to contain a top-level folder named exactly after the release.
+        let extracted_dir = extract_tmp.join(&proton_version);
         if !extracted_dir.exists() {
             let _ = std::fs::remove_file(&tar_path);
             let _ = std::fs::remove_dir_all(&extract_tmp);
             return Err(format!(
                 "Proton-GE archive did not contain expected top-level folder `{}`",
-                PROTON_GE_VERSION
+                proton_version
             ));
         }
 
@@ -361,15 +391,13 @@ pub fn get_current_proton_dir(app: tauri::AppHandle) -> Result<Option<String>, S
 #[derive(Debug, Clone, Serialize, Deserialize)]
 struct ManifestState {
     manifest_version: u32,
+    /// Manifest `config_version` whose default config was last applied.
+    #[serde(default)]
+    config_version: Option<u32>,
 }
 
 fn manifest_state_path(app: &tauri::AppHandle) -> Result<std::path::PathBuf, String> {
-    Ok(app
-        .path()
-        .app_data_dir()
-        .map_err(|e| format!("failed to resolve app data dir: {e}"))?
-        .join("config")
-        .join("manifest_state.json"))
+    Ok(storage::state_dir(app)?.join("manifest_state.json"))
 }
 
 fn read_manifest_state(app: &tauri::AppHandle) -> Result<ManifestState, String> {
@@ -377,6 +405,7 @@ fn read_manifest_state(app: &tauri::AppHandle) -> Result<ManifestState, String>
     if !path.exists() {
         return Ok(ManifestState {
             manifest_version: 0,
+            config_version: None,
         });
     }
     let text = std::fs::read_to_string(&path).map_err(|e| e.to_string())?;
@@ -392,14 +421,12 @@ fn write_manifest_state(app: &tauri::AppHandle, state: &ManifestState) -> Result
     std::fs::write(&path, json).map_err(|e| e.to_string())
 }
 
+/// Highest installed version, skipping `excluded` ones (retired versions).
 fn latest_installed_version_dir(
     app: &tauri::AppHandle,
+    excluded: &[u32],
 ) -> Result<Option<(u32, std::path::PathBuf)>, String> {
-    let dir = app
-        .path()
-        .app_data_dir()
-        .map_err(|e| format!("failed to resolve app data dir: {e}"))?
-        .join("versions");
+    let dir = storage::versions_dir(app)?;
 
     let Ok(rd) = std::fs::read_dir(&dir) else {
         return Ok(None);
@@ -421,6 +448,9 @@ fn latest_installed_version_dir(
         let Ok(v) = num.parse::<u32>() else {
             continue;
         };
+        if excluded.contains(&v) {
+            continue;
+        }
         if best.as_ref().map(|(bv, _)| v > *bv).unwrap_or(true) {
             best = Some((v, path));
         }
@@ -429,12 +459,25 @@ fn latest_installed_version_dir(
     Ok(best)
 }
 
-fn installed_version_dirs(app: &tauri::AppHandle) -> Result<Vec<(u32, std::path::PathBuf)>, String> {
-    let dir = app
-        .path()
-        .app_data_dir()
-        .map_err(|e| format!("failed to resolve app data dir: {e}"))?
-        .join("versions");
+/// Version to use when the caller doesn't name one: the persisted active version if it is
+/// still installed (and not in `excluded`), otherwise the highest installed one.
+pub fn active_version_dir(
+    app: &tauri::AppHandle,
+    excluded: &[u32],
+) -> Result<Option<(u32, PathBuf)>, String> {
+    if let Some(v) = settings::load(app).active_version {
+        if !excluded.contains(&v) {
+            if let Some(found) = installed_version_dirs(app)?.into_iter().find(|(iv, _)| *iv == v) {
+                return Ok(Some(found));
+            }
+            log::warn!("Active version v{v} is not installed; falling back to latest");
+        }
+    }
+    latest_installed_version_dir(app, excluded)
+}
+
+pub fn installed_version_dirs(app: &tauri::AppHandle) -> Result<Vec<(u32, std::path::PathBuf)>, String> {
+    let dir = storage::versions_dir(app)?;
 
     let Ok(rd) = std::fs::read_dir(&dir) else {
         return Ok(vec![]);
@@ -465,12 +508,43 @@ fn installed_version_dirs(app: &tauri::AppHandle) -> Result<Vec<(u32, std::path:
 }
 
 fn shared_config_dir(app: &tauri::AppHandle) -> Result<PathBuf, String> {
-    Ok(app
-        .path()
-        .app_data_dir()
-        .map_err(|e| format!("failed to resolve app data dir: {e}"))?
-        .join("config")
-        .join("shared"))
+    Ok(storage::shared_config_dir(app)?)
+}
+
+/// Relative paths (`/`-separated) of every file under the shared config dir.
+///
+/// Taken before and after a task to list the config files it added; best-effort.
+pub(crate) fn config_file_snapshot(app: &tauri::AppHandle) -> BTreeSet<String> {
+    fn walk(root: &Path, dir: &Path, out: &mut BTreeSet<String>) {
+        let Ok(entries) = std::fs::read_dir(dir) else {
+            return;
+        };
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if path.is_dir() {
+                walk(root, &path, out);
+            } else if let Ok(rel) = path.strip_prefix(root) {
+                out.insert(rel.to_string_lossy().replace('\\', "/"));
+            }
+        }
+    }
+
+    let mut out = BTreeSet::new();
+    if let Ok(shared) = shared_config_dir(app) {
+        walk(&shared, &shared, &mut out);
+    }
+    out
+}
+
+/// Config files present now that weren't in `before`.
+pub(crate) fn config_files_added_since(
+    app: &tauri::AppHandle,
+    before: &BTreeSet<String>,
+) -> Vec<String> {
+    config_file_snapshot(app)
+        .into_iter()
+        .filter(|f| !before.contains(f))
+        .collect()
 }
 
 fn plugins_dir_for_version_root(version_root: &Path) -> PathBuf {
@@ -618,23 +692,133 @@ fn copy_dir_add_only(src: &Path, dst: &Path) -> Result<(), String> {
     Ok(())
 }
 
+/// Top-level entries of a version dir that are not part of the game depot
+/// (BepInEx pack, launcher state) and must not be carried over when pre-seeding.
+const NON_DEPOT_ENTRIES: [&str; 8] = [
+    "BepInEx",
+    ".hq-launcher",
+    ".DepotDownloader",
+    "winhttp.dll",
+    "doorstop_config.ini",
+    ".doorstop_version",
+    "changelog.txt",
+    // .NET runtime of BepInEx 6 IL2CPP builds.
+    "dotnet",
+];
+
+/// Assembly doorstop hands control to, relative to the game root.
+fn loader_entry_assembly(loader: BepInExLoader) -> &'static str {
+    match loader {
+        BepInExLoader::Mono => r"BepInEx\core\BepInEx.Preloader.dll",
+        BepInExLoader::Il2cpp => r"BepInEx\core\BepInEx.Unity.IL2CPP.dll",
+    }
+}
+
+/// `(section, key, value)` a loader needs in `doorstop_config.ini`.
+///
+/// Doorstop 3 (older BepInExPacks) keeps its settings under `[UnityDoorstop]` with
+/// camelCase keys; doorstop 4 uses `[General]`, plus `[Il2Cpp]` for the CoreCLR runtime
+/// that BepInEx 6 IL2CPP ships under `dotnet/`.
+fn doorstop_settings(loader: BepInExLoader, ini: &str) -> Vec<(&'static str, &'static str, &'static str)> {
+    let target = loader_entry_assembly(loader);
+    if loader == BepInExLoader::Mono && ini.contains("[UnityDoorstop]") {
+        return vec![
+            ("UnityDoorstop", "enabled", "true"),
+            ("UnityDoorstop", "targetAssembly", target),
+        ];
+    }
+    let mut out = vec![("General", "enabled", "true"), ("General", "target_assembly", target)];
+    if loader == BepInExLoader::Il2cpp {
+        out.push(("Il2Cpp", "coreclr_path", r"dotnet\coreclr.dll"));
+        out.push(("Il2Cpp", "corlib_dir", "dotnet"));
+    }
+    out
+}
+
+/// Check that the extracted loader is the one the manifest declared, and point doorstop at
+/// its entry assembly (a pack built for the other runtime, or a doorstop config left over
+/// from a previous loader, would otherwise fail silently at launch).
+fn configure_loader(game_root: &Path, pkg: &mod_config::BepInExPackage) -> Result<(), String> {
+    let entry: PathBuf = loader_entry_assembly(pkg.loader).split('\\').collect();
+    if !game_root.join(&entry).is_file() {
+        return Err(format!(
+            "{} has no {} ({:?} loader); check the manifest's `loader` for this game version",
+            pkg.label(),
+            entry.display(),
+            pkg.loader
+        ));
+    }
+    if pkg.loader == BepInExLoader::Il2cpp && !game_root.join("dotnet").is_dir() {
+        return Err(format!("{} has no dotnet/ runtime for IL2CPP", pkg.label()));
+    }
+    let ini_path = game_root.join("doorstop_config.ini");
+    let mut ini = std::fs::read_to_string(&ini_path)
+        .map_err(|e| format!("{} has no doorstop_config.ini: {e}", pkg.label()))?;
+    for (section, key, value) in doorstop_settings(pkg.loader, &ini) {
+        ini = doorstop::set_ini_value(&ini, section, key, value);
+    }
+    zip_utils::write_atomic(&ini_path, ini)
+}
+
+/// Installed version to pre-seed from: the closest one below `version` (or above, if none
+/// is older). Never `version` itself: a reinstall stages next to the live install, which
+/// must stay untouched if the reinstall fails.
+fn seed_source_version(
+    app: &tauri::AppHandle,
+    version: u32,
+) -> Result<Option<(u32, PathBuf)>, String> {
+    let others: Vec<(u32, PathBuf)> = installed_version_dirs(app)?
+        .into_iter()
+        .filter(|(v, _)| *v != version)
+        .collect();
+    let older = others.iter().rev().find(|(v, _)| *v < version).cloned();
+    Ok(older.or_else(|| others.into_iter().find(|(v, _)| *v > version)))
+}
+
+/// Copy the game files of `src` into `dst` for `-validate` to patch up. Files are cloned
+/// or copied, never hard-linked: validation rewrites files in place, which would reach
+/// back into the source version through a link.
+fn seed_game_files(src: &Path, dst: &Path) -> Result<(), String> {
+    fs_utils::copy_tree(src, dst, &NON_DEPOT_ENTRIES, false)
+}
+
+/// Clone a depot download into `dst`, overwriting files and skipping DepotDownloader's
+/// own `.DepotDownloader` state dir. Never hard links: the next download rewrites cache
+/// files in place.
+fn copy_depot_tree(src: &Path, dst: &Path) -> Result<(), String> {
+    fs_utils::copy_tree(src, dst, &[".DepotDownloader"], true)
+}
+
 #[cfg(windows)]
-fn is_reparse_point(path: &Path) -> Result<bool, String> {
+pub(crate) fn is_reparse_point(path: &Path) -> Result<bool, String> {
     use std::os::windows::fs::MetadataExt;
     let md = std::fs::symlink_metadata(path).map_err(|e| e.to_string())?;
     Ok((md.file_attributes() & 0x400) != 0) // FILE_ATTRIBUTE_REPARSE_POINT
 }
 
 #[cfg(not(windows))]
-fn is_reparse_point(path: &Path) -> Result<bool, String> {
+pub(crate) fn is_reparse_point(path: &Path) -> Result<bool, String> {
     // On Unix, treat symlinks as "reparse-point-like" so we don't recurse into the target
     // when cleaning up the old config path.
     let md = std::fs::symlink_metadata(path).map_err(|e| e.to_string())?;
     Ok(md.file_type().is_symlink())
 }
 
+/// Recreate the link at `src` (a symlink or junction) at `dst`.
+pub(crate) fn copy_link(src: &Path, dst: &Path) -> Result<(), String> {
+    let target = std::fs::read_link(src).map_err(|e| e.to_string())?;
+    #[cfg(unix)]
+    {
+        std::os::unix::fs::symlink(&target, dst).map_err(|e| e.to_string())
+    }
+    #[cfg(not(unix))]
+    {
+        create_dir_junction(dst, &target)
+    }
+}
+
 #[cfg(windows)]
-fn create_dir_junction(link: &Path, target: &Path) -> Result<(), String> {
+pub(crate) fn create_dir_junction(link: &Path, target: &Path) -> Result<(), String> {
     let link_s = link.to_string_lossy().to_string();
     let target_s = target.to_string_lossy().to_string();
 
@@ -652,21 +836,19 @@ fn create_dir_junction(link: &Path, target: &Path) -> Result<(), String> {
 }
 
 #[cfg(not(windows))]
-fn create_dir_junction(link: &Path, target: &Path) -> Result<(), String> {
-    // Prefer a directory symlink so the game config path points to the shared config dir.
+pub(crate) fn create_dir_junction(link: &Path, target: &Path) -> Result<(), String> {
+    // A directory symlink so the game config path points to the shared config dir.
     // On Linux, a bind mount would require elevated privileges; symlink is the best userland option.
+    // No plain-directory fallback: that would silently give this version its own config.
     #[cfg(unix)]
     {
-        if let Err(e) = std::os::unix::fs::symlink(target, link) {
-            log::warn!(
-                "Failed to create config symlink {} -> {} ({}); falling back to directory",
+        std::os::unix::fs::symlink(target, link).map_err(|e| {
+            format!(
+                "Failed to create config symlink {} -> {}: {e}",
                 link.display(),
-                target.display(),
-                e
-            );
-            std::fs::create_dir_all(link).map_err(|e| e.to_string())?;
-        }
-        Ok(())
+                target.display()
+            )
+        })
     }
 
     #[cfg(not(unix))]
@@ -695,38 +877,143 @@ fn remove_dir_link(path: &Path) -> Result<(), String> {
 /// - If an old config dir exists, copy files into shared (skip existing), then replace with junction.
 fn ensure_config_junction(app: &tauri::AppHandle, game_root: &Path) -> Result<PathBuf, String> {
     let shared = shared_config_dir(app)?;
-    std::fs::create_dir_all(&shared).map_err(|e| e.to_string())?;
-
     let bepinex_dir = game_root.join("BepInEx");
     std::fs::create_dir_all(&bepinex_dir).map_err(|e| e.to_string())?;
-    let link = bepinex_dir.join("config");
+    link_to_shared(&bepinex_dir.join("config"), &shared)?;
+    Ok(shared)
+}
+
+/// Make `link` a junction to `shared`, moving whatever `link` held into `shared` (add-only).
+fn link_to_shared(link: &Path, shared: &Path) -> Result<(), String> {
+    std::fs::create_dir_all(shared).map_err(|e| e.to_string())?;
 
     // If it's already pointing to shared, do nothing.
     // Use symlink_metadata so broken symlinks are still detected and cleaned up.
-    if std::fs::symlink_metadata(&link).is_ok() {
-        if let (Ok(a), Ok(b)) = (std::fs::canonicalize(&link), std::fs::canonicalize(&shared)) {
+    if std::fs::symlink_metadata(link).is_ok() {
+        if let (Ok(a), Ok(b)) = (std::fs::canonicalize(link), std::fs::canonicalize(shared)) {
             if a == b {
-                return Ok(shared);
+                return Ok(());
             }
         }
 
         if link.is_dir() {
             // If it's a junction/symlink already, remove only the link itself.
-            if is_reparse_point(&link)? {
-                remove_dir_link(&link)?;
+            if is_reparse_point(link)? {
+                // Pointing somewhere else (e.g. a pre-shared config dir): keep its files.
+                let _ = copy_dir_add_only(link, shared);
+                remove_dir_link(link)?;
             } else {
                 // Regular directory: copy into shared (add-only) then remove.
-                let _ = copy_dir_add_only(&link, &shared);
-                std::fs::remove_dir_all(&link).map_err(|e| e.to_string())?;
+                let _ = copy_dir_add_only(link, shared);
+                std::fs::remove_dir_all(link).map_err(|e| e.to_string())?;
             }
         } else {
-            // Unexpected file at the config path.
-            std::fs::remove_file(&link).map_err(|e| e.to_string())?;
+            // Dangling symlink or unexpected file at the link path.
+            std::fs::remove_file(link).map_err(|e| e.to_string())?;
         }
     }
 
-    create_dir_junction(&link, &shared)?;
-    Ok(shared)
+    if let Some(parent) = link.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    create_dir_junction(link, shared)
+}
+
+/// Folders a manifest may share (themselves or any folder inside them): where mods keep
+/// persistent data. Anything else under the game root is game files or managed by the
+/// launcher (`BepInEx/plugins`, `BepInEx/core`, ...) and must stay per version.
+const SHAREABLE_ROOTS: [&str; 1] = ["bepinex/data"];
+
+/// `entry` as a path under the game root, or `None` if it's absolute, escapes the root or
+/// isn't inside one of `SHAREABLE_ROOTS`.
+fn shared_folder_rel(entry: &str) -> Option<PathBuf> {
+    let normalized = entry.trim().replace('\\', "/");
+    let rel = PathBuf::from(normalized.trim_matches('/'));
+    let normal = rel
+        .components()
+        .all(|c| matches!(c, std::path::Component::Normal(_)));
+    let key = normalized.trim_matches('/').to_lowercase();
+    let allowed = SHAREABLE_ROOTS.iter().any(|root| {
+        key == *root || key.strip_prefix(root).is_some_and(|r| r.starts_with('/'))
+    });
+    (normal && allowed).then_some(rel)
+}
+
+/// Link each manifest `shared_folders` entry of `game_root` to
+/// `game_config/shared_data/{entry}`, so mod data (e.g. suit unlocks) is the same in every
+/// installed version. Entries failing to link are logged and skipped.
+pub fn ensure_shared_folders(app: &tauri::AppHandle, game_root: &Path, folders: &[String]) {
+    let Ok(shared_root) = storage::shared_data_dir(app) else {
+        return;
+    };
+    for entry in folders {
+        let Some(rel) = shared_folder_rel(entry) else {
+            log::warn!("Ignoring shared folder {entry:?}: only folders under BepInEx/data can be shared");
+            continue;
+        };
+        if let Err(e) = link_to_shared(&game_root.join(&rel), &shared_root.join(&rel)) {
+            log::warn!("Failed to share {entry} for {}: {e}", game_root.display());
+        }
+    }
+}
+
+/// State files that used to live directly under `config/`.
+const LEGACY_STATE_FILES: [&str; 3] = ["manifest_state.json", "disablemod.json", "settings.json"];
+
+/// One-time move of the old `config/` tree into `state/` + `game_config/shared`.
+///
+/// Version `BepInEx/config` links that pointed at the old shared dir are re-pointed,
+/// since the move leaves them dangling.
+pub fn migrate_legacy_config_layout(app: &tauri::AppHandle) -> Result<(), String> {
+    let legacy = storage::legacy_config_dir(app)?;
+    if !legacy.is_dir() {
+        return Ok(());
+    }
+
+    let state = storage::state_dir(app)?;
+    std::fs::create_dir_all(&state).map_err(|e| e.to_string())?;
+    for name in LEGACY_STATE_FILES {
+        let from = legacy.join(name);
+        let to = state.join(name);
+        if from.is_file() && !to.exists() {
+            std::fs::rename(&from, &to).map_err(|e| e.to_string())?;
+            log::info!("Migrated {} -> {}", from.display(), to.display());
+        }
+    }
+
+    let old_shared = legacy.join("shared");
+    let new_shared = shared_config_dir(app)?;
+    if old_shared.is_dir() && !new_shared.exists() {
+        // Remember which versions link to the old location before it moves.
+        let old_canon = std::fs::canonicalize(&old_shared).unwrap_or(old_shared.clone());
+        let linked: Vec<PathBuf> = installed_version_dirs(app)?
+            .into_iter()
+            .map(|(_, root)| bepinex_config_dir_for_version_root(&root))
+            .filter(|cfg| is_config_linked_to_shared(cfg, &old_canon))
+            .collect();
+
+        if let Some(parent) = new_shared.parent() {
+            std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+        }
+        if std::fs::rename(&old_shared, &new_shared).is_err() {
+            copy_dir_add_only(&old_shared, &new_shared)?;
+            std::fs::remove_dir_all(&old_shared).map_err(|e| e.to_string())?;
+        }
+        log::info!(
+            "Migrated {} -> {}",
+            old_shared.display(),
+            new_shared.display()
+        );
+
+        for cfg in linked {
+            remove_dir_link(&cfg)?;
+            create_dir_junction(&cfg, &new_shared)?;
+        }
+    }
+
+    // Only drops the dir when nothing unexpected is left in it.
+    let _ = std::fs::remove_dir(&legacy);
+    Ok(())
 }
 
 #[derive(Debug, Clone, Serialize)]
@@ -789,12 +1076,7 @@ pub struct VersionConfigLinkState {
 }
 
 fn version_root_dir(app: &tauri::AppHandle, version: u32) -> Result<PathBuf, String> {
-    Ok(app
-        .path()
-        .app_data_dir()
-        .map_err(|e| format!("failed to resolve app data dir: {e}"))?
-        .join("versions")
-        .join(format!("v{version}")))
+    Ok(storage::version_dir(app, version)?)
 }
 
 pub fn get_config_link_state_for_version(
@@ -823,19 +1105,13 @@ pub fn get_config_link_state_for_version(
 }
 
 pub fn link_config_for_version(app: &tauri::AppHandle, version: u32) -> Result<VersionConfigLinkState, String> {
-    let root = version_root_dir(app, version)?;
-    if !root.exists() {
-        return Err(format!("version folder not found: {}", root.to_string_lossy()));
-    }
+    let root = storage::installed_version_dir(app, version)?;
     let _ = ensure_config_junction(app, &root)?;
     get_config_link_state_for_version(app, version)
 }
 
 pub fn unlink_config_for_version(app: &tauri::AppHandle, version: u32) -> Result<VersionConfigLinkState, String> {
-    let root = version_root_dir(app, version)?;
-    if !root.exists() {
-        return Err(format!("version folder not found: {}", root.to_string_lossy()));
-    }
+    let root = storage::installed_version_dir(app, version)?;
 
     let shared = shared_config_dir(app)?;
     std::fs::create_dir_all(&shared).map_err(|e| e.to_string())?;
@@ -911,9 +1187,78 @@ pub fn unlink_config_for_all_versions(app: &tauri::AppHandle) -> Result<u32, Str
     Ok(changed)
 }
 
-/// Download default config if shared config directory is empty or missing.
-/// This is called on app startup to ensure config files exist.
-pub async fn ensure_default_config(app: tauri::AppHandle) -> Result<(), String> {
+fn default_config_cache_path(app: &tauri::AppHandle) -> Result<PathBuf, String> {
+    Ok(storage::cache_dir(app)?.join("default_config.zip"))
+}
+
+/// A link (symlink/junction) whose target no longer exists.
+fn is_dangling_link(path: &Path) -> bool {
+    std::fs::symlink_metadata(path).is_ok() && std::fs::metadata(path).is_err()
+}
+
+/// Recover from the shared config dir being deleted behind our back.
+///
+/// Recreates `game_config/shared` (reseeding it from the cached default config zip) and
+/// re-points every version whose `BepInEx/config` link dangles; otherwise the game would
+/// write its configs into a dead link. Returns the number of repaired versions.
+pub fn repair_config_links(app: &tauri::AppHandle) -> Result<u32, String> {
+    let shared = shared_config_dir(app)?;
+    if !shared.is_dir() {
+        log::warn!("Shared config dir {} is missing; recreating", shared.display());
+        std::fs::create_dir_all(&shared).map_err(|e| e.to_string())?;
+        let cached_zip = default_config_cache_path(app)?;
+        if cached_zip.is_file() {
+            zip_utils::extract_config_zip_into_bepinex_config_with_progress(
+                &cached_zip,
+                &shared,
+                |_done, _total, _name| {},
+            )?;
+            log::info!("Reseeded shared config from {}", cached_zip.display());
+        }
+    }
+
+    let mut repaired: u32 = 0;
+    for (version, root) in installed_version_dirs(app)? {
+        let cfg = bepinex_config_dir_for_version_root(&root);
+        if !is_dangling_link(&cfg) {
+            continue;
+        }
+        log::warn!("v{version}: BepInEx/config link is dangling; re-linking to shared config");
+        remove_dir_link(&cfg)?;
+        create_dir_junction(&cfg, &shared)?;
+        repaired = repaired.saturating_add(1);
+    }
+    Ok(repaired)
+}
+
+/// Longest `wait_for_game_exit` waits before giving up.
+const GAME_EXIT_WAIT: std::time::Duration = std::time::Duration::from_secs(30 * 60);
+
+/// Wait until no launcher-started game session is running, at most `GAME_EXIT_WAIT`.
+async fn wait_for_game_exit(app: &tauri::AppHandle) -> Result<(), String> {
+    if !crate::is_game_running(app) {
+        return Ok(());
+    }
+    log::info!("Game is running; delaying config extraction until it exits");
+    let started = std::time::Instant::now();
+    while crate::is_game_running(app) {
+        if started.elapsed() >= GAME_EXIT_WAIT {
+            return Err(format!(
+                "the game is still running after {} minutes; config extraction skipped until the next sync",
+                GAME_EXIT_WAIT.as_secs() / 60
+            ));
+        }
+        tokio::time::sleep(std::time::Duration::from_secs(5)).await;
+    }
+    log::info!("Game exited; resuming config extraction");
+    Ok(())
+}
+
+/// Download default config if shared config directory is empty or missing, or when the
+/// manifest's `config_version` changed (extraction is add-only either way).
+/// This is called on app startup to ensure config files exist; `force` re-downloads
+/// regardless (restoring deleted default files).
+pub async fn ensure_default_config(app: tauri::AppHandle, force: bool) -> Result<(), String> {
     let shared_config = shared_config_dir(&app)?;
 
     // Check if config directory exists and has files (other than BepInEx.cfg which is auto-generated)
@@ -938,54 +1283,90 @@ pub async fn ensure_default_config(app: tauri::AppHandle) -> Result<(), String>
         !has_other_files
     };
 
-    if !needs_download {
+    let client = reqwest::Client::new();
+    // Expected version/digest/size; an unreachable manifest falls back to the cached one.
+    let expected = match mod_config::fetch_remote_manifest(&client).await {
+        Ok(m) => Some(m),
+        Err(e) => {
+            log::warn!("Manifest unavailable for config check: {e}");
+            mod_config::cached_remote_manifest()
+        }
+    };
+    let remote_config_version = expected.as_ref().and_then(|m| m.config_version);
+    let local_config_version = read_manifest_state(&app)?.config_version;
+    let outdated = remote_config_version.is_some_and(|v| local_config_version != Some(v));
+
+    if !needs_download && !outdated && !force {
         log::info!("Config directory already has files, skipping download");
         return Ok(());
     }
 
-    log::info!("Config directory is empty or missing, downloading default config");
-
-    let client = reqwest::Client::new();
-    let config_zip_url = "https://f.asta.rs/hq-launcher/default_config.zip";
-    log::info!("Downloading config from {}", config_zip_url);
-
-    let response = client
-        .get(config_zip_url)
-        .header("User-Agent", "hq-launcher/0.1 (tauri)")
-        .send()
-        .await
-        .map_err(|e| format!("Failed to download config: {e}"))?;
-
-    if !response.status().is_success() {
-        let status = response.status();
-        let body = response.text().await.unwrap_or_default();
-        return Err(format!(
-            "Config download failed with status {}: {}",
-            status, body
-        ));
+    if force {
+        log::info!("Re-syncing default config");
+    } else if needs_download {
+        log::info!("Config directory is empty or missing, downloading default config");
+    } else {
+        log::info!(
+            "Config version changed: local={:?} remote={:?} -> adding new default config files",
+            local_config_version,
+            remote_config_version
+        );
     }
 
-    let cfg_bytes = response
-        .bytes()
-        .await
-        .map_err(|e| format!("Failed to read config response: {e}"))?;
+    // Fastest mirror first when the speedtest is enabled; the rest are fallbacks. Each gets
+    // the `config_download` budget.
+    let bases = mirrors::ordered_bases(&app).await;
+    let timeout = tasks::step_timeout("config_download").unwrap_or(std::time::Duration::MAX);
+    let cfg_bytes = mirrors::first_success(&bases, timeout, |base| {
+        let (app, client, expected) = (&app, &client, expected.as_ref());
+        async move {
+            let config_zip_url = endpoints::default_config_url_for(&base);
+            log::info!("Downloading config from {}", config_zip_url);
+            let bytes = download_config_zip(client, &config_zip_url).await?;
+            verify_config_zip(app, &config_zip_url, &bytes, expected)?;
+            Ok(bytes)
+        }
+    })
+    .await?;
 
     log::info!("Downloaded {} bytes of config", cfg_bytes.len());
 
-    // Create temporary directory for extraction
-    let temp_dir = app
-        .path()
-        .app_data_dir()
-        .map_err(|e| format!("failed to resolve app data dir: {e}"))?
-        .join("temp");
-    std::fs::create_dir_all(&temp_dir).map_err(|e| e.to_string())?;
-
-    let cfg_zip_path = temp_dir.join("default_config.zip");
+    // Keep the zip around: `repair_config_links` reseeds from it if the shared dir is deleted.
+    let cfg_zip_path = default_config_cache_path(&app)?;
+    if let Some(parent) = cfg_zip_path.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    // The defaults applied last time: the base for telling local edits from upstream ones.
+    let base_files = zip_utils::config_zip_files(&cfg_zip_path).ok();
     std::fs::write(&cfg_zip_path, &cfg_bytes).map_err(|e| e.to_string())?;
 
     // Ensure shared config directory exists
     std::fs::create_dir_all(&shared_config).map_err(|e| e.to_string())?;
 
+    // Don't extract into the shared config while the game may be writing to it.
+    wait_for_game_exit(&app).await?;
+
+    // Existing files that differ from the new defaults are handled per the config sync
+    // strategy (the player may have edited them).
+    let (zip2, dir2) = (cfg_zip_path.clone(), shared_config.clone());
+    let changed =
+        tauri::async_runtime::spawn_blocking(move || zip_utils::changed_config_files(&zip2, &dir2))
+            .await
+            .map_err(|e| e.to_string())??;
+    let forced = expected
+        .as_ref()
+        .map(|m| m.config_overwrite.clone())
+        .unwrap_or_default();
+    let overwrite = config_sync::files_to_overwrite(
+        &app,
+        &changed,
+        &shared_config,
+        &cfg_zip_path,
+        base_files.as_ref(),
+        &forced,
+    )
+    .await?;
+
     // Extract config (add-only, won't overwrite existing files)
     let cfg_zip_path2 = cfg_zip_path.clone();
     let config_dir2 = shared_config.clone();
@@ -996,43 +1377,264 @@ pub async fn ensure_default_config(app: tauri::AppHandle) -> Result<(), String>
             &config_dir2,
             |_done, _total, _name| {}, // No progress reporting for background download
         )?;
-        let _ = std::fs::remove_file(&cfg_zip_path2);
         Ok(())
     })
     .await
     .map_err(|e| e.to_string())??;
 
     log::info!("Default config extracted successfully");
+
+    if !overwrite.is_empty() {
+        zip_utils::overwrite_config_files(&cfg_zip_path, &shared_config, &overwrite)?;
+        log::info!(
+            "Overwrote {} config file(s) with new defaults",
+            overwrite.len()
+        );
+    }
+
+    if let Some(v) = remote_config_version {
+        let mut state = read_manifest_state(&app)?;
+        state.config_version = Some(v);
+        write_manifest_state(&app, &state)?;
+    }
     Ok(())
 }
 
-/// On app startup: compare local applied manifest version with remote manifest version.
-/// If different, apply updates **additively** to the latest installed version (no overwrites).
-/// Note: Config is no longer synced here - use ensure_default_config() on app startup instead.
-pub async fn sync_latest_install_from_manifest(app: tauri::AppHandle) -> Result<(), String> {
-    let Some((game_version, game_root)) = latest_installed_version_dir(&app)? else {
+/// Check a downloaded config zip against the manifest's `config_sha256`/`config_size`.
+///
+/// Emits `config://integrity-error` on mismatch so the zip is never extracted.
+fn verify_config_zip(
+    app: &tauri::AppHandle,
+    url: &str,
+    bytes: &[u8],
+    manifest: Option<&mod_config::RemoteManifest>,
+) -> Result<(), String> {
+    let Some(manifest) = manifest else {
         return Ok(());
     };
+    let expected_sha256 = manifest
+        .config_sha256
+        .as_ref()
+        .map(|h| h.trim().to_lowercase());
+    let actual_size = bytes.len() as u64;
+    let size_ok = manifest.config_size.is_none_or(|s| s == actual_size);
+    let actual_sha256 = integrity::sha256_hex(bytes);
+    let hash_ok = expected_sha256.as_ref().is_none_or(|h| *h == actual_sha256);
+    if size_ok && hash_ok {
+        return Ok(());
+    }
+
+    progress::emit_config_integrity_error(
+        app,
+        progress::ConfigIntegrityErrorPayload {
+            url: url.to_string(),
+            expected_sha256,
+            actual_sha256,
+            expected_size: manifest.config_size,
+            actual_size,
+        },
+    );
+    Err(format!(
+        "Config download from {url} failed verification (size {actual_size}, expected {:?}); not extracting",
+        manifest.config_size
+    ))
+}
+
+async fn download_config_zip(client: &reqwest::Client, url: &str) -> Result<Vec<u8>, String> {
+    let response = client
+        .get(url)
+        .header("User-Agent", "hq-launcher/0.1 (tauri)")
+        .send()
+        .await
+        .map_err(|e| format!("Failed to download config: {e}"))?;
+
+    if !response.status().is_success() {
+        let status = response.status();
+        let body = response.text().await.unwrap_or_default();
+        return Err(format!(
+            "Config download failed with status {}: {}",
+            status, body
+        ));
+    }
+
+    response
+        .bytes()
+        .await
+        .map(|b| b.to_vec())
+        .map_err(|e| format!("Failed to read config response: {e}"))
+}
+
+/// Steps run by `sync_latest_install_from_manifest`.
+#[derive(Debug, Clone, Copy)]
+pub struct SyncSteps {
+    pub config: bool,
+    pub mods: bool,
+    /// Run the selected steps even when the local state says they're up to date.
+    pub force: bool,
+}
+
+impl Default for SyncSteps {
+    fn default() -> Self {
+        Self {
+            config: true,
+            mods: true,
+            force: false,
+        }
+    }
+}
+
+/// Plugin folders the launcher installed (they're in the lockfile) that the manifest no
+/// longer lists.
+fn orphaned_mods(game_root: &Path, cfg: &ModsConfig) -> Vec<String> {
+    let listed: BTreeSet<String> = cfg
+        .mods
+        .iter()
+        .map(|m| format!("{}-{}", m.dev, m.name).to_lowercase())
+        .collect();
+    let plugins = plugins_dir_for_version_root(game_root);
+    let folders: BTreeSet<String> = integrity::read_lockfile(game_root)
+        .files
+        .keys()
+        .filter_map(|k| integrity::plugin_folder(k))
+        .map(|f| f.to_string())
+        .collect();
+    folders
+        .into_iter()
+        .filter(|f| !listed.contains(&f.to_lowercase()) && plugins.join(f).is_dir())
+        .collect()
+}
+
+/// Ask whether to remove orphaned mods (default: keep them, as before) and remove them if
+/// the player agrees. Returns the removed folders relative to `game_root`.
+async fn remove_orphaned_mods(
+    app: &tauri::AppHandle,
+    version: u32,
+    game_root: &Path,
+    cfg: &ModsConfig,
+) -> Result<Vec<PathBuf>, String> {
+    let orphans = orphaned_mods(game_root, cfg);
+    if orphans.is_empty() {
+        return Ok(vec![]);
+    }
+    let choice = decisions::ask(
+        app,
+        Some(version),
+        "remove_orphaned_mods",
+        format!(
+            "{} mod(s) installed by the launcher are no longer in the manifest. Remove them?",
+            orphans.len()
+        ),
+        orphans.clone(),
+        &[("keep", "Keep them"), ("remove", "Remove them")],
+        "keep",
+    )
+    .await;
+    if choice != "remove" {
+        return Ok(vec![]);
+    }
+
+    let plugins = plugins_dir_for_version_root(game_root);
+    let mut removed = vec![];
+    for folder in orphans {
+        std::fs::remove_dir_all(plugins.join(&folder)).map_err(|e| format!("{folder}: {e}"))?;
+        log::info!("Removed orphaned mod {folder} from v{version}");
+        removed.push(Path::new("BepInEx").join("plugins").join(folder));
+    }
+    Ok(removed)
+}
+
+/// On app startup: compare local applied manifest version with remote manifest version.
+/// If different, apply updates **additively** to the active version (no overwrites).
+///
+/// Config is versioned separately (`config_version`): a config-only bump runs
+/// `ensure_default_config` without a mods pass, and a mods-only bump leaves the config alone.
+/// `steps` limits the sync to one part, e.g. to re-sync just the config.
+pub async fn sync_latest_install_from_manifest(
+    app: tauri::AppHandle,
+    steps: SyncSteps,
+) -> Result<(), String> {
+    if installed_version_dirs(&app)?.is_empty() {
+        return Ok(());
+    }
 
     let client = reqwest::Client::new();
-    let remote = ModsConfig::fetch_manifest(&client).await?;
-    let (remote_manifest_version, mods_cfg, _chain_config, _manifests) = remote;
+    let (remote, origin) = mod_config::fetch_remote_manifest_or_cached(&client, false).await?;
+    motd::notify_new(&app, &remote);
+    if origin.offline {
+        // Nothing can be downloaded; keep the install usable with what the cache knows.
+        progress::emit_manifest_offline(
+            &app,
+            progress::ManifestOfflinePayload {
+                manifest_version: remote.version,
+                fetched_at: origin.fetched_at,
+                error: origin.error,
+            },
+        );
+        for (_, root) in installed_version_dirs(&app)? {
+            ensure_shared_folders(&app, &root, &remote.shared_folders);
+        }
+        log::info!("Offline; skipping manifest sync (cached manifest {})", remote.version);
+        return Ok(());
+    }
+    let retired = remote.retired_versions();
+    let Some((game_version, game_root)) = active_version_dir(&app, &retired)? else {
+        log::info!("Only retired game versions are installed; skipping manifest sync");
+        return Ok(());
+    };
+    let remote_manifest_version = remote.version;
+    let remote_config_version = remote.config_version;
+    let corrections = remote.corrections;
+    progress::emit_manifest_corrections(
+        &app,
+        progress::ManifestCorrectionsPayload {
+            version: game_version,
+            manifest_version: remote_manifest_version,
+            corrections: corrections.clone(),
+        },
+    );
+    // Cheap and idempotent, so every sync picks up newly declared folders.
+    for (_, root) in installed_version_dirs(&app)? {
+        ensure_shared_folders(&app, &root, &remote.shared_folders);
+    }
+    let manifest_ids = remote.manifest_ids();
+    let mods_cfg = ModsConfig { mods: remote.mods };
 
     let local_state = read_manifest_state(&app)?;
-    if local_state.manifest_version == remote_manifest_version {
+    let config_outdated =
+        remote_config_version.is_some_and(|v| local_state.config_version != Some(v));
+    if steps.config && (config_outdated || steps.force) {
+        if let Err(e) = config_backup::snapshot(&app, "before config sync") {
+            log::warn!("{e}");
+        }
+        match ensure_default_config(app.clone(), steps.force).await {
+            Ok(()) => {}
+            // An explicit re-sync should report its failure.
+            Err(e) if steps.force => return Err(e),
+            Err(e) => log::warn!("Config update failed: {e}"),
+        }
+    }
+    if !steps.mods {
+        return Ok(());
+    }
+    if local_state.manifest_version == remote_manifest_version && !steps.force {
         log::info!("Manifest up-to-date: {}", remote_manifest_version);
         return Ok(());
     }
 
     log::info!(
-        "Manifest changed: local={} remote={} -> applying additive updates",
+        "Manifest sync: local={} remote={} force={} -> applying additive updates",
         local_state.manifest_version,
-        remote_manifest_version
+        remote_manifest_version,
+        steps.force
     );
 
-    // One-step sync: mods only (config is handled separately on app startup).
-    const STEPS_TOTAL: u32 = 1;
-    let sync_res: Result<(), String> = async {
+    // One-step sync: mods only (config follows `config_version`, see above).
+    const STEPS_TOTAL: u32 = SYNC_STEPS.len() as u32;
+    let task_id = tasks::begin(&app, game_version, TaskKind::Sync);
+    let cancel = tasks::cancel_flag(&app, game_version);
+    progress::emit_plan(&app, game_version, &SYNC_STEPS);
+    let config_before = config_file_snapshot(&app);
+    let sync_res: Result<(InstallSummary, Vec<String>), String> = async {
         // Step 1: mods
         progress::emit_progress(
             &app,
@@ -1051,18 +1653,14 @@ pub async fn sync_latest_install_from_manifest(app: tauri::AppHandle) -> Result<
             },
         );
 
-        mods::install_mods_with_progress(
+        let changes = mods::install_mods_with_progress(
             &app,
             &game_root,
             game_version,
             &mods_cfg,
-            |done, total, detail| {
-                let step_progress = if total == 0 {
-                    1.0
-                } else {
-                    (done as f64 / total as f64).clamp(0.0, 1.0)
-                };
-
+            Some(cancel.as_ref()),
+            |p| {
+                let step_progress = p.fraction();
                 progress::emit_progress(
                     &app,
                     TaskProgressPayload {
@@ -1072,17 +1670,40 @@ pub async fn sync_latest_install_from_manifest(app: tauri::AppHandle) -> Result<
                         step_name: "Sync Mods".to_string(),
                         step_progress,
                         overall_percent: overall_from_step(1, step_progress, STEPS_TOTAL),
-                        detail,
-                        downloaded_bytes: None,
-                        total_bytes: None,
-                        extracted_files: Some(done),
-                        total_files: Some(total),
+                        detail: p.detail,
+                        downloaded_bytes: (p.total_bytes > 0).then_some(p.done_bytes),
+                        total_bytes: (p.total_bytes > 0).then_some(p.total_bytes),
+                        extracted_files: Some(p.done_mods),
+                        total_files: Some(p.total_mods),
                     },
                 );
             },
         )
         .await?;
 
+        // A manifest change can narrow a mod's range; don't leave it loaded out of range.
+        let moved = mods::disable_incompatible_mods(&game_root, game_version, &mods_cfg)?;
+        if !moved.is_empty() {
+            log::info!(
+                "Moved {} incompatible mod(s) to plugins_disabled for v{}",
+                moved.len(),
+                game_version
+            );
+        }
+
+        // Lock what this sync wrote so the background sweep can spot later damage.
+        let mut touched: Vec<PathBuf> = changes
+            .iter()
+            .map(|c| {
+                Path::new("BepInEx")
+                    .join("plugins")
+                    .join(format!("{}-{}", c.dev, c.name))
+            })
+            .collect();
+        // Re-recording a removed folder drops its lockfile entries.
+        touched.extend(remove_orphaned_mods(&app, game_version, &game_root, &mods_cfg).await?);
+        let locked = integrity::record(&game_root, &touched)?;
+
         // Mark sync as complete for the UI.
         progress::emit_progress(
             &app,
@@ -1101,24 +1722,56 @@ pub async fn sync_latest_install_from_manifest(app: tauri::AppHandle) -> Result<
             },
         );
 
-        write_manifest_state(
-            &app,
-            &ManifestState {
-                manifest_version: remote_manifest_version,
-            },
-        )?;
+        // Mods held back by strict mode are retried on the next sync (after the
+        // allowlist is updated), so don't mark this manifest version as applied.
+        let (_, held_back) = allowlist::enforce(&app, &mods_cfg)?;
+        if held_back.is_empty() {
+            write_manifest_state(
+                &app,
+                &ManifestState {
+                    manifest_version: remote_manifest_version,
+                    config_version: read_manifest_state(&app)?.config_version,
+                },
+            )?;
+            integrity::mark_manifest_applied(&game_root, remote_manifest_version, &corrections)?;
+        } else {
+            log::warn!(
+                "Manifest {remote_manifest_version} not marked applied: {} mod(s) await allowlist approval",
+                held_back.len()
+            );
+        }
 
-        Ok(())
+        let summary = InstallSummary {
+            config_files_added: config_files_added_since(&app, &config_before),
+            ..InstallSummary::from_changes(changes)
+        };
+        Ok((summary, locked))
     }
     .await;
+    tasks::end(&app, game_version, &task_id);
 
     match sync_res {
-        Ok(()) => {
+        Ok((summary, locked)) => {
+            history::record(
+                &app,
+                game_version,
+                HistoryKind::Sync,
+                Some(remote_manifest_version),
+                format!(
+                    "Synced to manifest {remote_manifest_version}: {} added, {} updated",
+                    summary.mods_added.len(),
+                    summary.mods_updated.len()
+                ),
+                [summary.mods_added.clone(), summary.mods_updated.clone()].concat(),
+            );
+            integrity::schedule_verify(&app, game_version, game_root.clone(), locked);
+            evict_unused_caches(&app, &mods_cfg, &remote.bepinex, manifest_ids.values());
             progress::emit_finished(
                 &app,
                 progress::TaskFinishedPayload {
                     version: game_version,
                     path: game_root.to_string_lossy().to_string(),
+                    summary: Some(summary),
                 },
             );
             Ok(())
@@ -1129,6 +1782,7 @@ pub async fn sync_latest_install_from_manifest(app: tauri::AppHandle) -> Result<
                 progress::TaskErrorPayload {
                     version: game_version,
                     message: e.clone(),
+                    ..Default::default()
                 },
             );
             Err(e)
@@ -1136,32 +1790,205 @@ pub async fn sync_latest_install_from_manifest(app: tauri::AppHandle) -> Result<
     }
 }
 
+/// Trim the mod zip cache and the depot cache of what neither the installed versions nor
+/// the current manifest use. Runs after a successful sync; failures only get logged.
+fn evict_unused_caches<'a>(
+    app: &tauri::AppHandle,
+    mods_cfg: &ModsConfig,
+    bepinex: &[mod_config::BepInExPackage],
+    manifest_ids: impl IntoIterator<Item = &'a String>,
+) {
+    let max_bytes = settings::load(app).package_cache_max_bytes;
+    if let Err(e) = cache::evict_unreferenced(app, &mods_cfg.mods, bepinex, max_bytes) {
+        log::warn!("Failed to trim package cache: {e}");
+    }
+    if let Err(e) = downloader::evict_stale_depot_cache(app, manifest_ids) {
+        log::warn!("Failed to trim depot cache: {e}");
+    }
+}
+
+/// Refuse the install of `version` into `extract_dir` up front when the disk can't hold it.
+/// Leftovers in `extract_dir` (a staging dir) count as free, since the install replaces
+/// them; the working install stays until the new one is complete, so it doesn't.
+fn check_disk_space(
+    app: &tauri::AppHandle,
+    version: u32,
+    extract_dir: &Path,
+    estimate: Option<u64>,
+) -> Result<(), String> {
+    let available = match disk_space::available_bytes(extract_dir) {
+        Ok(bytes) => bytes,
+        Err(e) => {
+            log::warn!("Skipping disk space check for v{version}: {e}");
+            return Ok(());
+        }
+    };
+    let reclaimed = if extract_dir.exists() {
+        downloader::dir_size_bytes(extract_dir)
+    } else {
+        0
+    };
+    let required = disk_space::required_bytes(estimate);
+    let usable = available.saturating_add(reclaimed);
+    if usable >= required {
+        return Ok(());
+    }
+    progress::emit_insufficient_space(
+        app,
+        progress::InsufficientSpacePayload {
+            version,
+            path: extract_dir.to_string_lossy().to_string(),
+            required_bytes: required,
+            available_bytes: usable,
+            estimated_from_manifest: estimate.is_some(),
+        },
+    );
+    Err(format!(
+        "Not enough disk space to install v{version}: needs about {}, {} available at {}",
+        disk_space::format_bytes(required),
+        disk_space::format_bytes(usable),
+        extract_dir.display()
+    ))
+}
+
+/// `versions/.staging/v{version}`: where an install is assembled before it replaces
+/// `versions/v{version}`. Same parent as the version dirs, so the final rename is atomic.
+fn staging_dir(app: &tauri::AppHandle, version: u32) -> Result<PathBuf, String> {
+    let version = storage::check_version(version)?;
+    Ok(storage::versions_dir(app)?
+        .join(".staging")
+        .join(format!("v{version}")))
+}
+
+/// Delete a staging dir left by this or an interrupted install, links first so the shared
+/// config and data they point to are never reached.
+fn discard_staging(staging: &Path) -> Result<(), String> {
+    if std::fs::symlink_metadata(staging).is_err() {
+        return Ok(());
+    }
+    remove_links(staging)?;
+    std::fs::remove_dir_all(staging)
+        .map_err(|e| format!("failed to remove {}: {e}", staging.display()))
+}
+
+/// Where DepotDownloader puts the game files of the install staged in `staging`: the
+/// shared depot cache when it's enabled, else the staging dir itself.
+fn depot_download_dir(app: &tauri::AppHandle, staging: &Path) -> Result<PathBuf, String> {
+    if settings::load(app).depot_cache_max_bytes > 0 {
+        downloader::depot_cache_dir(app)
+    } else {
+        Ok(staging.to_path_buf())
+    }
+}
+
+/// DepotDownloader state left where the staged install downloads to: the game download
+/// picks up where it stopped.
+fn can_resume(app: &tauri::AppHandle, staging: &Path) -> bool {
+    depot_download_dir(app, staging).is_ok_and(|dir| dir.join(".DepotDownloader").is_dir())
+}
+
+/// Clean up after the install of `version` was cancelled: its temp downloads always go,
+/// the partial install only when the user cancelled or there's nothing to resume.
+pub(crate) fn clean_up_cancelled_install(
+    app: &tauri::AppHandle,
+    version: u32,
+    reason: CancelReason,
+) -> TaskCancelledPayload {
+    let task_id = tasks::task_id_for(app, version);
+    let temp_deleted = task_id
+        .as_deref()
+        .is_none_or(|id| temp_files::remove_task_entries(app, id));
+
+    let staging = staging_dir(app, version).ok();
+    // A timed-out step was most likely a wedged connection; retrying should resume too.
+    let staging_kept = matches!(
+        reason,
+        CancelReason::Shutdown | CancelReason::Timeout | CancelReason::Retry
+    ) && staging.as_deref().is_some_and(|s| can_resume(app, s));
+    if !staging_kept {
+        if let Some(staging) = &staging {
+            if let Err(e) = discard_staging(staging) {
+                log::warn!("Failed to remove {}: {e}", staging.display());
+            }
+        }
+    }
+    TaskCancelledPayload {
+        version,
+        task_id,
+        reason,
+        cleaned_up: staging.is_some_and(|s| std::fs::symlink_metadata(s).is_err()),
+        temp_deleted,
+        staging_kept,
+    }
+}
+
+/// Swap the completed install in `staging` into `final_dir`. The previous install is moved
+/// aside first and put back if the swap fails, so there's always a working one.
+fn promote_staging(staging: &Path, final_dir: &Path) -> Result<(), String> {
+    let previous = staging.with_extension("old");
+    discard_staging(&previous)?;
+    let had_previous = final_dir.exists();
+    if had_previous {
+        std::fs::rename(final_dir, &previous).map_err(|e| {
+            format!(
+                "failed to move the previous install {} aside (is the game running?): {e}",
+                final_dir.display()
+            )
+        })?;
+    }
+    if let Err(e) = std::fs::rename(staging, final_dir) {
+        if had_previous {
+            if let Err(restore) = std::fs::rename(&previous, final_dir) {
+                log::error!(
+                    "Failed to restore {} from {}: {restore}",
+                    final_dir.display(),
+                    previous.display()
+                );
+            }
+        }
+        return Err(format!("failed to move the new install into place: {e}"));
+    }
+    if let Err(e) = discard_staging(&previous) {
+        log::warn!("Failed to remove the previous install {}: {e}", previous.display());
+    }
+    if let Some(parent) = staging.parent() {
+        // Only succeeds when no other install is staging.
+        let _ = std::fs::remove_dir(parent);
+    }
+    Ok(())
+}
+
+/// Install `version`. `manifest` replaces the remote manifest (e.g. a local file, see
+/// `install_from_manifest_file`); `None` fetches it.
 pub async fn download_and_setup(
     app: tauri::AppHandle,
     version: u32,
+    acknowledge_eol: bool,
     cancel: Arc<AtomicBool>,
+    manifest: Option<mod_config::RemoteManifest>,
 ) -> Result<bool, String> {
-    let dir = app
-        .path()
-        .app_data_dir()
-        .map_err(|e| format!("failed to resolve app data dir: {e}"))?
-        .join("versions");
-    std::fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
-    let extract_dir = dir.join(format!("v{version}"));
+    let final_dir = storage::version_dir(&app, version)?;
+    std::fs::create_dir_all(storage::versions_dir(&app)?).map_err(|e| e.to_string())?;
+    // Every step writes to the staging dir; the working install (if any) is only replaced
+    // once all of them succeeded.
+    let extract_dir = staging_dir(&app, version)?;
 
+    let config_before = config_file_snapshot(&app);
     let res: Result<bool, String> = async {
-        // DepotDownloader 설치 확인
-        if let Err(e) = downloader::install_downloader(&app).await {
-            return Err(format!("Failed to install DepotDownloader: {e}"));
-        }
+        // DepotDownloader 설치 확인 (or the fake downloader in demo mode)
+        let downloader = downloader::select_game_downloader(&app).await?;
 
-        let client = reqwest::Client::new();
+        let client = reqwest::Client::builder()
+            .user_agent("hq-launcher/0.1 (tauri)")
+            .build()
+            .map_err(|e| e.to_string())?;
         if cancel.load(Ordering::Relaxed) {
             return Err("Cancelled".to_string());
         }
 
-        // Download -> Extract Game -> Install BepInEx -> Install Config -> Install Mods
-        const STEPS_TOTAL: u32 = 5;
+        // Login Check -> Download Game -> Install BepInEx -> Install Config -> Install Mods
+        const STEPS_TOTAL: u32 = INSTALL_STEPS.len() as u32;
+        progress::emit_plan(&app, version, &INSTALL_STEPS);
 
         // Step 1: Steam 로그인 확인
         emit_progress(
@@ -1181,10 +2008,17 @@ pub async fn download_and_setup(
             },
         );
 
-        let downloader = downloader::DepotDownloader::new(&app)?;
-        let login_state = downloader.get_login_state();
+        let login_state = downloader.login_state();
 
         if !login_state.is_logged_in {
+            progress::emit_steam_login(
+                &app,
+                progress::SteamLoginPayload {
+                    session_id: None,
+                    status: "login_required",
+                    message: Some(format!("Log in to Steam to install v{version}")),
+                },
+            );
             return Err("Not logged in to Steam. Please login first.".to_string());
         }
 
@@ -1209,8 +2043,42 @@ pub async fn download_and_setup(
         );
 
         // Fetch remote manifest data (mods + per-game-version depots manifest ids).
-        let (_remote_manifest_version, mods_cfg, _chain_config, manifests) =
-            ModsConfig::fetch_manifest(&client).await?;
+        let remote = match manifest {
+            Some(manifest) => manifest,
+            None => mod_config::fetch_remote_manifest(&client).await?,
+        };
+        let info = remote.game_version(version)?;
+        if info.is_retired() {
+            let note = info.status_note.clone().unwrap_or_default();
+            if !acknowledge_eol {
+                return Err(format!(
+                    "Game version v{version} is marked {:?} by the manifest. {note}",
+                    info.status
+                )
+                .trim_end()
+                .to_string());
+            }
+            log::warn!("Installing retired game version v{version} ({:?}) {note}", info.status);
+        }
+        let install_size = info.install_size;
+        check_disk_space(&app, version, &extract_dir, install_size)?;
+        let manifests = remote.manifest_ids();
+        let remote_manifest_version = remote.version;
+        let corrections = remote.corrections;
+        progress::emit_manifest_corrections(
+            &app,
+            progress::ManifestCorrectionsPayload {
+                version,
+                manifest_version: remote_manifest_version,
+                corrections: corrections.clone(),
+            },
+        );
+        // BepInEx installation via a Thunderstore BepInExPack-style package (Mono,
+        // preconfigured): the contents of its top-level folder go into the game root.
+        // The manifest picks the package per game version (`RemoteManifest::bepinex`).
+        let bepinex = remote.bepinex_for(version);
+        let shared_folders = remote.shared_folders;
+        let mods_cfg = ModsConfig { mods: remote.mods };
 
         // Step 2: Lethal Company 다운로드
         emit_progress(
@@ -1234,22 +2102,68 @@ pub async fn download_and_setup(
             return Err("Cancelled".to_string());
         }
 
-        if extract_dir.exists() {
-            std::fs::remove_dir_all(&extract_dir).map_err(|e| e.to_string())?;
+        if can_resume(&app, &extract_dir) {
+            log::info!("Resuming the interrupted install of v{version}");
+        } else {
+            discard_staging(&extract_dir)?;
         }
         std::fs::create_dir_all(&extract_dir).map_err(|e| e.to_string())?;
 
-        log::info!("Downloading Lethal Company to {}", extract_dir.display());
+        log::info!(
+            "Downloading {} to {}",
+            crate::game::current().name,
+            extract_dir.display()
+        );
 
         let manifest_id = manifests.get(&version).cloned().ok_or_else(|| {
             format!("No depot manifest id for game version {version} in remote manifest.")
         })?;
 
         // 게임 다운로드
+        // With the depot cache enabled, download into the shared cache dir so chunks
+        // unchanged since the previously downloaded version are reused, then copy out.
+        let cache_max_bytes = settings::load(&app).depot_cache_max_bytes;
+        let depot_dir = depot_download_dir(&app, &extract_dir)?;
+
+        // Without prior DepotDownloader state in the target dir, pre-seed it from the closest
+        // installed version and let `-validate` replace only the files that changed.
+        let mut validate = false;
+        if !depot_dir.join(".DepotDownloader").exists() {
+            if let Some((seed_version, seed_dir)) = seed_source_version(&app, version)? {
+                log::info!(
+                    "Pre-seeding {} from v{} ({})",
+                    depot_dir.display(),
+                    seed_version,
+                    seed_dir.display()
+                );
+                emit_progress(
+                    &app,
+                    TaskProgressPayload {
+                        version,
+                        steps_total: STEPS_TOTAL,
+                        step: 2,
+                        step_name: "Download Game".to_string(),
+                        step_progress: 0.0,
+                        overall_percent: overall_from_step(2, 0.0, STEPS_TOTAL),
+                        detail: Some(format!("Reusing files from v{seed_version}...")),
+                        downloaded_bytes: Some(0),
+                        total_bytes: None,
+                        extracted_files: None,
+                        total_files: None,
+                    },
+                );
+                match seed_game_files(&seed_dir, &depot_dir) {
+                    Ok(()) => validate = true,
+                    Err(e) => log::warn!("Pre-seeding from v{seed_version} failed: {e}"),
+                }
+            }
+        }
+
         downloader
-            .download_depot(
-                Some(manifest_id),
-                extract_dir.clone(),
+            .download_game(
+                Some(manifest_id.clone()),
+                depot_dir.clone(),
+                validate,
                 Some(downloader::DownloadTaskContext {
                     version,
                     steps_total: STEPS_TOTAL,
@@ -1260,6 +2174,30 @@ pub async fn download_and_setup(
             )
             .await?;
 
+        if depot_dir != extract_dir {
+            emit_progress(
+                &app,
+                TaskProgressPayload {
+                    version,
+                    steps_total: STEPS_TOTAL,
+                    step: 2,
+                    step_name: "Download Game".to_string(),
+                    step_progress: 1.0,
+                    overall_percent: overall_from_step(2, 1.0, STEPS_TOTAL),
+                    detail: Some("Copying game files from depot cache...".to_string()),
+                    downloaded_bytes: None,
+                    total_bytes: None,
+                    extracted_files: None,
+                    total_files: None,
+                },
+            );
+            downloader::record_depot_cache_manifest(&depot_dir, &manifest_id);
+            copy_depot_tree(&depot_dir, &extract_dir)?;
+            if let Err(e) = downloader::enforce_depot_cache_limit(&app, cache_max_bytes) {
+                log::warn!("Failed to trim depot cache: {e}");
+            }
+        }
+
         emit_progress(
             &app,
             TaskProgressPayload {
@@ -1295,133 +2233,120 @@ pub async fn download_and_setup(
             },
         );
 
-        log::info!(
-            "Downloading BepInExPack {} from {}",
-            BEPINEXPACK_VERSION,
-            BEPINEXPACK_URL
+        let bepinex_label = bepinex.label();
+        let bepinexpack_url = bepinex.download_url();
+        let zip_path = cache::package_path(
+            &cache::packages_dir(&app)?,
+            &bepinex.namespace,
+            &bepinex.name,
+            &bepinex.version,
         );
+        if cache::is_cached(&zip_path) {
+            log::info!("Using cached {bepinex_label}");
+            cache::touch(&zip_path);
+        } else {
+            log::info!("Downloading {} from {}", bepinex_label, bepinexpack_url);
+            download_backend::download(
+                &client,
+                &bepinexpack_url,
+                &zip_path,
+                Some(cancel.as_ref()),
+                |downloaded, total| {
+                    let step_progress = total
+                        .map(|t| {
+                            if t == 0 {
+                                0.0
+                            } else {
+                                (downloaded as f64 / t as f64).clamp(0.0, 1.0)
+                            }
+                        })
+                        .unwrap_or(0.0);
 
-        let response = client
-            .get(BEPINEXPACK_URL)
-            .header("User-Agent", "hq-launcher/0.1 (tauri)")
-            .send()
-            .await
-            .map_err(|e| e.to_string())?
-            .error_for_status()
-            .map_err(|e| e.to_string())?;
-
-        let total = response.content_length();
-        let temp_dir = app
-            .path()
-            .app_data_dir()
-            .map_err(|e| format!("Failed to resolve app data dir: {e}"))?
-            .join("temp");
-        std::fs::create_dir_all(&temp_dir).map_err(|e| e.to_string())?;
-
-        let zip_path = temp_dir.join(format!("bepinexpack_{BEPINEXPACK_VERSION}.zip"));
-        let mut file = File::create(&zip_path).map_err(|e| e.to_string())?;
-
-        let mut downloaded: u64 = 0;
-        let mut stream = response.bytes_stream();
-        while let Some(chunk) = stream.next().await {
-            if cancel.load(Ordering::Relaxed) {
-                let _ = std::fs::remove_file(&zip_path);
-                return Err("Cancelled".to_string());
-            }
-            let chunk = chunk.map_err(|e| e.to_string())?;
-            file.write_all(&chunk).map_err(|e| e.to_string())?;
-            downloaded = downloaded.saturating_add(chunk.len() as u64);
-
-            let step_progress = total
-                .map(|t| {
-                    if t == 0 {
-                        0.0
-                    } else {
-                        (downloaded as f64 / t as f64).clamp(0.0, 1.0)
-                    }
-                })
-                .unwrap_or(0.0);
-
-            emit_progress(
-                &app,
-                TaskProgressPayload {
-                    version,
-                    steps_total: STEPS_TOTAL,
-                    step: 3,
-                    step_name: "Install BepInEx".to_string(),
-                    step_progress: step_progress * 0.5, // download = 0~50%
-                    overall_percent: overall_from_step(3, step_progress * 0.5, STEPS_TOTAL),
-                    detail: Some(format!(
-                        "Downloading BepInExPack... {} MB",
-                        downloaded / 1024 / 1024
-                    )),
-                    downloaded_bytes: Some(downloaded),
-                    total_bytes: total,
-                    extracted_files: None,
-                    total_files: None,
+                    emit_progress(
+                        &app,
+                        TaskProgressPayload {
+                            version,
+                            steps_total: STEPS_TOTAL,
+                            step: 3,
+                            step_name: "Install BepInEx".to_string(),
+                            step_progress: step_progress * 0.5, // download = 0~50%
+                            overall_percent: overall_from_step(3, step_progress * 0.5, STEPS_TOTAL),
+                            detail: Some(format!(
+                                "Downloading {}... {} MB",
+                                bepinex.name,
+                                downloaded / 1024 / 1024
+                            )),
+                            downloaded_bytes: Some(downloaded),
+                            total_bytes: total,
+                            extracted_files: None,
+                            total_files: None,
+                        },
+                    );
                 },
-            );
+            )
+            .await
+            .map_err(String::from)?;
         }
-        drop(file);
 
         // Basic sanity check: ZIP files start with "PK". If not, we likely downloaded an HTML error page.
-        {
-            use std::io::Read as _;
-            let mut f = std::fs::File::open(&zip_path).map_err(|e| e.to_string())?;
-            let mut header = [0u8; 4];
-            let n = f.read(&mut header).map_err(|e| e.to_string())?;
-            if n < 2 || header[0] != b'P' || header[1] != b'K' {
-                let _ = std::fs::remove_file(&zip_path);
-                return Err(
-                    "BepInExPack download is not a valid zip (got non-zip response). Please retry."
-                        .to_string(),
-                );
-            }
+        if !cache::is_cached(&zip_path) {
+            let _ = std::fs::remove_file(&zip_path);
+            return Err(format!(
+                "{bepinex_label} download is not a valid zip (got non-zip response). Please retry."
+            ));
         }
+        cache::verify_sha256(&zip_path, &bepinex_label, bepinex.sha256.as_deref())?;
 
-        // Extract Thunderstore package into the game root.
+        // Extract the loader into the game root.
         // Thunderstore zips contain top-level files (manifest.json, icon.png) and a top-level folder (BepInExPack/).
-        // This extractor strips the top-level dir and ignores the top-level files, resulting in:
+        // That extractor strips the top-level dir and ignores the top-level files, resulting in:
         // - winhttp.dll, doorstop_config.ini, BepInEx/**, etc directly under versions/v{version}.
+        // BepInEx 6 builds (builds.bepinex.dev) already have that layout at the zip root.
         let zip_path_clone = zip_path.clone();
         let extract_dir_clone = extract_dir.clone();
         let app_clone = app.clone();
         let cancel_clone = cancel.clone();
+        let bepinex_name = bepinex.name.clone();
         tauri::async_runtime::spawn_blocking(move || -> Result<(), String> {
-            zip_utils::extract_thunderstore_package_with_progress(
-                &zip_path_clone,
-                &extract_dir_clone,
-                |done, total, detail| {
-                    if cancel_clone.load(Ordering::Relaxed) {
-                        // Stop extraction early (best-effort) when cancelled.
-                        return;
-                    }
-                    let step_progress = if total == 0 {
-                        1.0
-                    } else {
-                        (done as f64 / total as f64).clamp(0.0, 1.0)
-                    };
-                    let step_progress = 0.5 + (step_progress * 0.5); // extract = 50~100%
-                    emit_progress(
-                        &app_clone,
-                        TaskProgressPayload {
-                            version,
-                            steps_total: STEPS_TOTAL,
-                            step: 3,
-                            step_name: "Install BepInEx".to_string(),
-                            step_progress,
-                            overall_percent: overall_from_step(3, step_progress, STEPS_TOTAL),
-                            detail: detail.map(|d| format!("Extracting BepInExPack... {d}")),
-                            downloaded_bytes: None,
-                            total_bytes: None,
-                            extracted_files: Some(done),
-                            total_files: Some(total),
-                        },
-                    );
-                },
-            )?;
-            let _ = std::fs::remove_file(&zip_path_clone);
-            Ok(())
+            let on_progress = |done: u64, total: u64, detail: Option<String>| {
+                if cancel_clone.load(Ordering::Relaxed) {
+                    // Stop extraction early (best-effort) when cancelled.
+                    return;
+                }
+                let step_progress = if total == 0 {
+                    1.0
+                } else {
+                    (done as f64 / total as f64).clamp(0.0, 1.0)
+                };
+                let step_progress = 0.5 + (step_progress * 0.5); // extract = 50~100%
+                emit_progress(
+                    &app_clone,
+                    TaskProgressPayload {
+                        version,
+                        steps_total: STEPS_TOTAL,
+                        step: 3,
+                        step_name: "Install BepInEx".to_string(),
+                        step_progress,
+                        overall_percent: overall_from_step(3, step_progress, STEPS_TOTAL),
+                        detail: detail.map(|d| format!("Extracting {bepinex_name}... {d}")),
+                        downloaded_bytes: None,
+                        total_bytes: None,
+                        extracted_files: Some(done),
+                        total_files: Some(total),
+                    },
+                );
+            };
+            let flat = zip_utils::has_root_file(&zip_path_clone, "doorstop_config.ini")?;
+            if flat {
+                zip_utils::extract_zip_with_progress(&zip_path_clone, &extract_dir_clone, on_progress)
+            } else {
+                zip_utils::extract_thunderstore_package_with_progress(
+                    &zip_path_clone,
+                    &extract_dir_clone,
+                    on_progress,
+                )
+            }
+            .inspect_err(|_| cache::evict(&zip_path_clone))
         })
         .await
         .map_err(|e| e.to_string())??;
@@ -1429,6 +2354,7 @@ pub async fn download_and_setup(
         if cancel.load(Ordering::Relaxed) {
             return Err("Cancelled".to_string());
         }
+        configure_loader(&extract_dir, &bepinex)?;
 
         emit_progress(
             &app,
@@ -1439,7 +2365,7 @@ pub async fn download_and_setup(
                 step_name: "Install BepInEx".to_string(),
                 step_progress: 1.0,
                 overall_percent: overall_from_step(3, 1.0, STEPS_TOTAL),
-                detail: Some(format!("BepInExPack {} installed", BEPINEXPACK_VERSION)),
+                detail: Some(format!("{bepinex_label} installed")),
                 downloaded_bytes: None,
                 total_bytes: None,
                 extracted_files: None,
@@ -1465,9 +2391,10 @@ pub async fn download_and_setup(
             },
         );
 
-        // Config directory is a junction to AppData/config/shared.
+        // Config directory is a junction to AppData/game_config/shared.
         // Config files are downloaded separately on app startup if needed.
         let _shared = ensure_config_junction(&app, &extract_dir)?;
+        ensure_shared_folders(&app, &extract_dir, &shared_folders);
 
         emit_progress(
             &app,
@@ -1511,17 +2438,14 @@ pub async fn download_and_setup(
             return Err("Cancelled".to_string());
         }
 
-        mods::install_mods_with_progress(
+        let changes = mods::install_mods_with_progress(
             &app,
             &extract_dir,
             version,
             &mods_cfg,
-            |done, total, detail| {
-                let step_progress = if total == 0 {
-                    1.0
-                } else {
-                    (done as f64 / total as f64).clamp(0.0, 1.0)
-                };
+            Some(cancel.as_ref()),
+            |p| {
+                let step_progress = p.fraction();
                 emit_progress(
                     &app,
                     TaskProgressPayload {
@@ -1531,11 +2455,11 @@ pub async fn download_and_setup(
                         step_name: "Install Mods".to_string(),
                         step_progress,
                         overall_percent: overall_from_step(5, step_progress, STEPS_TOTAL),
-                        detail,
-                        downloaded_bytes: None,
-                        total_bytes: None,
-                        extracted_files: Some(done),
-                        total_files: Some(total),
+                        detail: p.detail,
+                        downloaded_bytes: (p.total_bytes > 0).then_some(p.done_bytes),
+                        total_bytes: (p.total_bytes > 0).then_some(p.total_bytes),
+                        extracted_files: Some(p.done_mods),
+                        total_files: Some(p.total_mods),
                     },
                 );
             },
@@ -1559,11 +2483,40 @@ pub async fn download_and_setup(
             },
         );
 
+        if allowlist::enforce(&app, &mods_cfg)?.1.is_empty() {
+            integrity::mark_manifest_applied(
+                &extract_dir,
+                remote_manifest_version,
+                &corrections,
+            )?;
+        }
+
+        promote_staging(&extract_dir, &final_dir)?;
+
+        let summary = InstallSummary {
+            config_files_added: config_files_added_since(&app, &config_before),
+            bepinex_version: Some(bepinex.version.clone()),
+            ..InstallSummary::from_changes(changes)
+        };
+        history::record(
+            &app,
+            version,
+            HistoryKind::Install,
+            Some(remote_manifest_version),
+            format!(
+                "Installed with {} and {} mod(s)",
+                bepinex.label(),
+                summary.mods_added.len() + summary.mods_updated.len()
+            ),
+            [summary.mods_added.clone(), summary.mods_updated.clone()].concat(),
+        );
+
         emit_finished(
             &app,
             TaskFinishedPayload {
                 version,
-                path: extract_dir.to_string_lossy().to_string(),
+                path: final_dir.to_string_lossy().to_string(),
+                summary: Some(summary),
             },
         );
 
@@ -1574,16 +2527,479 @@ pub async fn download_and_setup(
 
     if let Err(message) = &res {
         if message == "Cancelled" {
-            let _ = std::fs::remove_dir_all(&extract_dir);
+            let reason = tasks::cancel_reason(&app, version).unwrap_or(CancelReason::User);
+            log::info!("Install of v{version} cancelled ({reason:?})");
+            progress::emit_cancelled(&app, clean_up_cancelled_install(&app, version, reason));
+            return res;
+        }
+        // The previous install of `version` (if any) was never touched.
+        if let Err(e) = discard_staging(&extract_dir) {
+            log::warn!("Failed to remove {}: {e}", extract_dir.display());
         }
         emit_error(
             &app,
             TaskErrorPayload {
                 version,
                 message: message.clone(),
+                ..Default::default()
             },
         );
     }
 
     res
 }
+
+#[derive(Debug, Clone, Serialize)]
+pub struct UninstallResult {
+    pub version: u32,
+    /// Paths that will be (or were) removed.
+    pub paths: Vec<String>,
+    pub purge_config: bool,
+    /// Set on the dry run; pass it back to actually uninstall.
+    pub confirm_token: Option<String>,
+    pub done: bool,
+}
+
+/// Remove junctions/symlinks under `dir` (the links only), so deleting the version can
+/// never reach the shared config or shared data they point to.
+fn remove_links(dir: &Path) -> Result<(), String> {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return Ok(());
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        let Ok(md) = std::fs::symlink_metadata(&path) else {
+            continue;
+        };
+        if md.file_type().is_symlink() || is_reparse_point(&path)? {
+            remove_dir_link(&path)
+                .or_else(|_| std::fs::remove_file(&path).map_err(|e| e.to_string()))?;
+        } else if md.is_dir() {
+            remove_links(&path)?;
+        }
+    }
+    Ok(())
+}
+
+fn uninstall_targets(
+    app: &tauri::AppHandle,
+    version: u32,
+    purge_config: bool,
+) -> Result<Vec<PathBuf>, String> {
+    let mut targets = vec![version_root_dir(app, version)?];
+    targets.push(storage::shader_cache_dir(app)?.join(format!("v{version}")));
+    targets.extend(temp_files::entries_for_version(app, version)?);
+    if purge_config {
+        targets.push(shared_config_dir(app)?);
+        targets.push(storage::shared_data_dir(app)?);
+    }
+    Ok(targets.into_iter().filter(|p| p.exists()).collect())
+}
+
+fn emit_uninstall_progress(
+    app: &tauri::AppHandle,
+    version: u32,
+    step: u32,
+    step_progress: f64,
+    detail: String,
+) {
+    const STEPS_TOTAL: u32 = UNINSTALL_STEPS.len() as u32;
+    emit_progress(
+        app,
+        TaskProgressPayload {
+            version,
+            steps_total: STEPS_TOTAL,
+            step,
+            step_name: UNINSTALL_STEPS[step as usize - 1].name.to_string(),
+            step_progress,
+            overall_percent: overall_from_step(step, step_progress, STEPS_TOTAL),
+            detail: Some(detail),
+            downloaded_bytes: None,
+            total_bytes: None,
+            extracted_files: None,
+            total_files: None,
+        },
+    );
+}
+
+/// Delete `version` and `targets`, top-level entry by entry. Setting `cancel` stops before
+/// the next entry; what was removed by then stays removed.
+fn remove_version_files(
+    app: &tauri::AppHandle,
+    version: u32,
+    targets: &[PathBuf],
+    cancel: &AtomicBool,
+) -> Result<(), String> {
+    let root = version_root_dir(app, version)?;
+    remove_links(&root)?;
+
+    // Top-level entries one by one, for progress on large installs.
+    let entries: Vec<PathBuf> = std::fs::read_dir(&root)
+        .map(|rd| rd.flatten().map(|e| e.path()).collect())
+        .unwrap_or_default();
+    let total = entries.len().max(1) as f64;
+    for (i, entry) in entries.iter().enumerate() {
+        if cancel.load(Ordering::Relaxed) {
+            return Err("Cancelled".to_string());
+        }
+        let name = entry
+            .file_name()
+            .unwrap_or_default()
+            .to_string_lossy()
+            .to_string();
+        emit_uninstall_progress(
+            app,
+            version,
+            1,
+            i as f64 / total,
+            format!("Removing {name}"),
+        );
+        let res = if entry.is_dir() {
+            std::fs::remove_dir_all(entry)
+        } else {
+            std::fs::remove_file(entry)
+        };
+        res.map_err(|e| format!("{}: {e}", entry.display()))?;
+    }
+    let _ = std::fs::remove_dir(&root);
+    emit_uninstall_progress(app, version, 1, 1.0, "Game files removed".to_string());
+
+    let rest: Vec<&PathBuf> = targets.iter().filter(|p| **p != root).collect();
+    for (i, path) in rest.iter().enumerate() {
+        if cancel.load(Ordering::Relaxed) {
+            return Err("Cancelled".to_string());
+        }
+        emit_uninstall_progress(
+            app,
+            version,
+            2,
+            i as f64 / rest.len() as f64,
+            format!("Removing {}", path.display()),
+        );
+        let res = if path.is_dir() {
+            std::fs::remove_dir_all(path)
+        } else {
+            std::fs::remove_file(path)
+        };
+        if let Err(e) = res {
+            log::warn!("Failed to remove {}: {e}", path.display());
+        }
+    }
+    emit_uninstall_progress(app, version, 2, 1.0, format!("v{version} uninstalled"));
+    Ok(())
+}
+
+/// Two-phase uninstall of `version`: the game folder, its shader cache and temp files.
+///
+/// `purge_config` also deletes the shared config and shared mod data, which is only allowed
+/// when no other version is installed (they'd lose their config). Called without
+/// `confirm_token` it only reports what would be removed; see `confirm`.
+#[tauri::command]
+pub async fn uninstall_version(
+    app: tauri::AppHandle,
+    version: u32,
+    purge_config: Option<bool>,
+    confirm_token: Option<String>,
+) -> Result<UninstallResult, String> {
+    let purge_config = purge_config.unwrap_or(false);
+    storage::installed_version_dir(&app, version)?;
+    if purge_config
+        && installed_version_dirs(&app)?
+            .iter()
+            .any(|(v, _)| *v != version)
+    {
+        return Err("other versions still use the shared config; uninstall them first".to_string());
+    }
+
+    let targets = uninstall_targets(&app, version, purge_config)?;
+    let paths: Vec<String> = targets
+        .iter()
+        .map(|p| p.to_string_lossy().to_string())
+        .collect();
+    let action = format!("uninstall:v{version}:{purge_config}");
+
+    let Some(token) = confirm_token else {
+        return Ok(UninstallResult {
+            version,
+            paths,
+            purge_config,
+            confirm_token: Some(crate::confirm::issue(&app, &action)?),
+            done: false,
+        });
+    };
+    crate::confirm::consume(&app, &action, &token)?;
+
+    if crate::is_game_running(&app) {
+        return Err("stop the game before uninstalling".to_string());
+    }
+    if tasks::task_id_for(&app, version).is_some() {
+        return Err(format!("a task is running for v{version}; cancel it first"));
+    }
+
+    log::warn!("Uninstalling v{version} (purge_config={purge_config}): {paths:?}");
+    let task_id = tasks::begin(&app, version, TaskKind::Uninstall);
+    let cancel = tasks::cancel_flag(&app, version);
+    progress::emit_plan(&app, version, &UNINSTALL_STEPS);
+    let app2 = app.clone();
+    let res = tauri::async_runtime::spawn_blocking(move || {
+        remove_version_files(&app2, version, &targets, &cancel)
+    })
+    .await
+    .map_err(|e| e.to_string())
+    .and_then(|r| r);
+    tasks::end(&app, version, &task_id);
+
+    if let Err(e) = &res {
+        emit_error(
+            &app,
+            TaskErrorPayload {
+                version,
+                message: e.clone(),
+                ..Default::default()
+            },
+        );
+        return Err(e.clone());
+    }
+    history::record(
+        &app,
+        version,
+        HistoryKind::Uninstall,
+        None,
+        if purge_config {
+            "Uninstalled, shared config removed"
+        } else {
+            "Uninstalled"
+        },
+        vec![],
+    );
+
+    let mut settings = settings::load(&app);
+    if settings.active_version == Some(version) {
+        settings.active_version = None;
+        settings::save(&app, &settings)?;
+    }
+
+    Ok(UninstallResult {
+        version,
+        paths,
+        purge_config,
+        confirm_token: None,
+        done: true,
+    })
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct MoveInstallRootResult {
+    pub from: String,
+    pub to: String,
+    /// Versions now living under `to`.
+    pub moved: Vec<u32>,
+}
+
+fn emit_move_progress(app: &tauri::AppHandle, version: u32, step_progress: f64, detail: String) {
+    emit_progress(
+        app,
+        TaskProgressPayload {
+            version,
+            steps_total: MOVE_STEPS.len() as u32,
+            step: 1,
+            step_name: MOVE_STEPS[0].name.to_string(),
+            step_progress,
+            overall_percent: overall_from_step(1, step_progress, MOVE_STEPS.len() as u32),
+            detail: Some(detail),
+            downloaded_bytes: None,
+            total_bytes: None,
+            extracted_files: None,
+            total_files: None,
+        },
+    );
+}
+
+fn count_files(dir: &Path) -> u64 {
+    let Ok(rd) = std::fs::read_dir(dir) else {
+        return 0;
+    };
+    rd.flatten()
+        .map(|e| match e.file_type() {
+            Ok(t) if t.is_dir() => count_files(&e.path()),
+            _ => 1,
+        })
+        .sum()
+}
+
+/// Copy `src` into `dst`, recreating links (config junctions) instead of following them.
+fn copy_tree_keep_links<F>(src: &Path, dst: &Path, on_file: &mut F) -> Result<(), String>
+where
+    F: FnMut(&Path) -> Result<(), String>,
+{
+    std::fs::create_dir_all(dst).map_err(|e| e.to_string())?;
+    for entry in std::fs::read_dir(src).map_err(|e| e.to_string())? {
+        let entry = entry.map_err(|e| e.to_string())?;
+        let from = entry.path();
+        let to = dst.join(entry.file_name());
+        if is_reparse_point(&from)? {
+            copy_link(&from, &to)?;
+            on_file(&from)?;
+        } else if entry.file_type().map_err(|e| e.to_string())?.is_dir() {
+            copy_tree_keep_links(&from, &to, on_file)?;
+        } else {
+            std::fs::copy(&from, &to).map_err(|e| format!("{}: {e}", from.display()))?;
+            on_file(&from)?;
+        }
+    }
+    Ok(())
+}
+
+/// Move one version dir: a rename on the same filesystem, otherwise copy into a staging
+/// dir next to `dst`, rename that into place and only then delete `src`. Setting `cancel`
+/// abandons the copy and leaves `src` as it was.
+fn move_version_dir(
+    app: &tauri::AppHandle,
+    version: u32,
+    src: &Path,
+    dst: &Path,
+    cancel: Option<&AtomicBool>,
+) -> Result<(), String> {
+    emit_move_progress(app, version, 0.0, format!("Moving v{version}"));
+    if std::fs::rename(src, dst).is_ok() {
+        emit_move_progress(app, version, 1.0, format!("v{version} moved"));
+        return Ok(());
+    }
+
+    let staging = dst.with_file_name(format!(".v{version}.moving"));
+    if staging.exists() {
+        std::fs::remove_dir_all(&staging).map_err(|e| e.to_string())?;
+    }
+    let total = count_files(src).max(1) as f64;
+    let mut copied = 0u64;
+    let res = copy_tree_keep_links(src, &staging, &mut |from| {
+        if cancel.is_some_and(|c| c.load(Ordering::Relaxed)) {
+            return Err("Cancelled".to_string());
+        }
+        copied += 1;
+        let name = from.file_name().unwrap_or_default().to_string_lossy();
+        emit_move_progress(
+            app,
+            version,
+            copied as f64 / total,
+            format!("Copying {name}"),
+        );
+        Ok(())
+    })
+    .and_then(|()| std::fs::rename(&staging, dst).map_err(|e| e.to_string()));
+    if let Err(e) = res {
+        let _ = std::fs::remove_dir_all(&staging);
+        return Err(format!(
+            "failed to copy v{version} to {}: {e}",
+            dst.display()
+        ));
+    }
+
+    // The copy is complete; never let the delete reach through the config links.
+    remove_links(src)?;
+    if let Err(e) = std::fs::remove_dir_all(src) {
+        log::warn!("Failed to remove old v{version} at {}: {e}", src.display());
+    }
+    emit_move_progress(app, version, 1.0, format!("v{version} moved"));
+    Ok(())
+}
+
+/// Move every installed version under `new_root` and make it the install root (`None`
+/// moves them back to the app data dir). Versions are moved one at a time with progress
+/// keyed by version; if one fails, the ones already moved are moved back.
+#[tauri::command]
+pub async fn move_install_root(
+    app: tauri::AppHandle,
+    new_root: Option<String>,
+) -> Result<MoveInstallRootResult, String> {
+    let from = storage::versions_dir(&app)?;
+    let to = match new_root.as_deref().map(str::trim) {
+        Some(root) if !root.is_empty() => {
+            let root = PathBuf::from(root);
+            if !root.is_absolute() {
+                return Err(format!(
+                    "install root must be an absolute path: {}",
+                    root.display()
+                ));
+            }
+            root
+        }
+        _ => storage::default_versions_dir(&app)?,
+    };
+    let result = |moved| MoveInstallRootResult {
+        from: from.to_string_lossy().to_string(),
+        to: to.to_string_lossy().to_string(),
+        moved,
+    };
+    if to == from {
+        return Ok(result(vec![]));
+    }
+    if to.starts_with(&from) {
+        return Err("the new install root can't be inside the current one".to_string());
+    }
+    if crate::is_game_running(&app) {
+        return Err("stop the game before moving installs".to_string());
+    }
+
+    let versions = installed_version_dirs(&app)?;
+    for (v, _) in &versions {
+        if tasks::task_id_for(&app, *v).is_some() {
+            return Err(format!("a task is running for v{v}; wait for it first"));
+        }
+        if to.join(format!("v{v}")).exists() {
+            return Err(format!("{} already has a v{v} folder", to.display()));
+        }
+    }
+    std::fs::create_dir_all(&to).map_err(|e| e.to_string())?;
+
+    log::info!(
+        "Moving install root from {} to {}",
+        from.display(),
+        to.display()
+    );
+    let mut moved: Vec<(u32, PathBuf)> = vec![];
+    for (v, src) in versions {
+        let dst = to.join(format!("v{v}"));
+        let task_id = tasks::begin(&app, v, TaskKind::Move);
+        let cancel = tasks::cancel_flag(&app, v);
+        progress::emit_plan(&app, v, &MOVE_STEPS);
+        let (app2, src2, dst2) = (app.clone(), src.clone(), dst.clone());
+        let res = tauri::async_runtime::spawn_blocking(move || {
+            move_version_dir(&app2, v, &src2, &dst2, Some(cancel.as_ref()))
+        })
+        .await
+        .map_err(|e| e.to_string())
+        .and_then(|r| r);
+        tasks::end(&app, v, &task_id);
+
+        if let Err(e) = res {
+            emit_error(
+                &app,
+                TaskErrorPayload {
+                    version: v,
+                    message: e.clone(),
+                    ..Default::default()
+                },
+            );
+            for (mv, old) in moved.into_iter().rev() {
+                let back = move_version_dir(&app, mv, &to.join(format!("v{mv}")), &old, None);
+                if let Err(e) = back {
+                    log::error!("Failed to move v{mv} back to {}: {e}", old.display());
+                }
+            }
+            return Err(e);
+        }
+        moved.push((v, src));
+    }
+
+    let mut settings = settings::load(&app);
+    settings.install_root = new_root
+        .filter(|r| !r.trim().is_empty())
+        .map(|_| to.to_string_lossy().to_string());
+    settings::save(&app, &settings)?;
+    if let Err(e) = repair_config_links(&app) {
+        log::warn!("Failed to repair config links after moving installs: {e}");
+    }
+
+    Ok(result(moved.into_iter().map(|(v, _)| v).collect()))
+}
@@ -0,0 +1,309 @@
+use std::collections::BTreeMap;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+use crate::mod_config::ManifestCorrection;
+use crate::progress::{self, IntegrityWarningPayload};
+
+/// Give antivirus scanners time to act on freshly written files before verifying.
+const VERIFY_DELAY: Duration = Duration::from_secs(30);
+/// Pause between files so the sweep never competes with the game for disk.
+const VERIFY_PAUSE: Duration = Duration::from_millis(5);
+
+/// 64-bit FNV-1a. Not cryptographic: catches deleted/truncated/corrupted files, which is
+/// all the lockfile is for.
+pub(crate) struct Fnv1a(u64);
+
+impl Fnv1a {
+    pub(crate) fn new() -> Self {
+        Self(0xcbf2_9ce4_8422_2325)
+    }
+
+    pub(crate) fn update(&mut self, bytes: &[u8]) {
+        for b in bytes {
+            self.0 ^= u64::from(*b);
+            self.0 = self.0.wrapping_mul(0x0100_0000_01b3);
+        }
+    }
+
+    pub(crate) fn finish(&self) -> u64 {
+        self.0
+    }
+}
+
+/// Lowercase hex SHA-256 of `bytes`, for digests published in the remote manifest.
+pub fn sha256_hex(bytes: &[u8]) -> String {
+    use sha2::{Digest, Sha256};
+    Sha256::digest(bytes)
+        .iter()
+        .map(|b| format!("{b:02x}"))
+        .collect()
+}
+
+/// Lowercase hex SHA-256 of the file at `path`, read in chunks.
+pub fn sha256_file(path: &Path) -> Result<String, String> {
+    use sha2::{Digest, Sha256};
+    let mut file = std::fs::File::open(path).map_err(|e| e.to_string())?;
+    let mut hasher = Sha256::new();
+    let mut buf = [0u8; 64 * 1024];
+    loop {
+        let n = file.read(&mut buf).map_err(|e| e.to_string())?;
+        if n == 0 {
+            break;
+        }
+        hasher.update(&buf[..n]);
+    }
+    Ok(hasher
+        .finalize()
+        .iter()
+        .map(|b| format!("{b:02x}"))
+        .collect())
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LockedFile {
+    pub size: u64,
+    pub hash: String,
+}
+
+/// `{game_root}/.hq-launcher/lock.json`: expected size/hash of files written by syncs,
+/// keyed by `/`-separated path relative to the game root.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Lockfile {
+    /// Remote manifest `version` last applied to this install.
+    #[serde(default)]
+    pub manifest_version: Option<u32>,
+    /// Unix seconds of that apply.
+    #[serde(default)]
+    pub applied_at: Option<u64>,
+    #[serde(default)]
+    pub files: BTreeMap<String, LockedFile>,
+    /// Mods installed at an older version because the pinned one was yanked, keyed by
+    /// plugin folder (`dev-name`).
+    #[serde(default)]
+    pub version_fallbacks: BTreeMap<String, VersionFallback>,
+    /// Entries of the applied manifest the launcher rewrote before installing.
+    #[serde(default)]
+    pub manifest_corrections: Vec<ManifestCorrection>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct VersionFallback {
+    pub requested: String,
+    pub installed: String,
+    pub reason: String,
+    pub at: u64,
+}
+
+fn lockfile_path(game_root: &Path) -> PathBuf {
+    game_root.join(".hq-launcher").join("lock.json")
+}
+
+pub fn read_lockfile(game_root: &Path) -> Lockfile {
+    std::fs::read_to_string(lockfile_path(game_root))
+        .ok()
+        .and_then(|text| serde_json::from_str(&text).ok())
+        .unwrap_or_default()
+}
+
+pub(crate) fn hash_file(path: &Path) -> Result<LockedFile, String> {
+    let mut file = std::fs::File::open(path).map_err(|e| e.to_string())?;
+    let mut hasher = Fnv1a::new();
+    let mut size: u64 = 0;
+    let mut buf = [0u8; 64 * 1024];
+    loop {
+        let n = file.read(&mut buf).map_err(|e| e.to_string())?;
+        if n == 0 {
+            break;
+        }
+        hasher.update(&buf[..n]);
+        size += n as u64;
+    }
+    Ok(LockedFile {
+        size,
+        hash: format!("{:016x}", hasher.finish()),
+    })
+}
+
+fn rel_key(game_root: &Path, path: &Path) -> Option<String> {
+    path.strip_prefix(game_root)
+        .ok()
+        .map(|rel| rel.to_string_lossy().replace('\\', "/"))
+}
+
+fn collect_files(dir: &Path, out: &mut Vec<PathBuf>) {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path.is_dir() {
+            collect_files(&path, out);
+        } else {
+            out.push(path);
+        }
+    }
+}
+
+/// Hash every file under `dirs` (relative to `game_root`) into the lockfile, replacing
+/// previous entries below those dirs. Returns the recorded keys.
+pub fn record(game_root: &Path, dirs: &[PathBuf]) -> Result<Vec<String>, String> {
+    let mut lock = read_lockfile(game_root);
+    let mut recorded = Vec::new();
+    for dir in dirs {
+        let abs = game_root.join(dir);
+        if let Some(prefix) = rel_key(game_root, &abs) {
+            let prefix = format!("{prefix}/");
+            lock.files.retain(|k, _| !k.starts_with(&prefix));
+        }
+        let mut files = Vec::new();
+        collect_files(&abs, &mut files);
+        for path in files {
+            let Some(key) = rel_key(game_root, &path) else {
+                continue;
+            };
+            lock.files.insert(key.clone(), hash_file(&path)?);
+            recorded.push(key);
+        }
+    }
+
+    write_lockfile(game_root, &lock)?;
+    Ok(recorded)
+}
+
+fn write_lockfile(game_root: &Path, lock: &Lockfile) -> Result<(), String> {
+    let path = lockfile_path(game_root);
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    let json = serde_json::to_string_pretty(lock).map_err(|e| e.to_string())?;
+    crate::zip_utils::write_atomic(&path, json)
+}
+
+/// Remember that `manifest_version` is now fully applied to this install, along with the
+/// fix-ups that were applied to it.
+pub fn mark_manifest_applied(
+    game_root: &Path,
+    manifest_version: u32,
+    corrections: &[ManifestCorrection],
+) -> Result<(), String> {
+    let mut lock = read_lockfile(game_root);
+    lock.manifest_version = Some(manifest_version);
+    lock.manifest_corrections = corrections.to_vec();
+    lock.applied_at = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .ok()
+        .map(|d| d.as_secs());
+    write_lockfile(game_root, &lock)
+}
+
+/// Note that `folder` got `installed` in place of the yanked `requested` version.
+pub fn note_version_fallback(
+    game_root: &Path,
+    folder: &str,
+    requested: &str,
+    installed: &str,
+    reason: &str,
+) -> Result<(), String> {
+    let mut lock = read_lockfile(game_root);
+    lock.version_fallbacks.insert(
+        folder.to_string(),
+        VersionFallback {
+            requested: requested.to_string(),
+            installed: installed.to_string(),
+            reason: reason.to_string(),
+            at: std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .map(|d| d.as_secs())
+                .unwrap_or(0),
+        },
+    );
+    write_lockfile(game_root, &lock)
+}
+
+/// Drop the fallback note of `folder` (it got the version it asked for).
+pub fn clear_version_fallback(game_root: &Path, folder: &str) -> Result<(), String> {
+    let mut lock = read_lockfile(game_root);
+    if lock.version_fallbacks.remove(folder).is_none() {
+        return Ok(());
+    }
+    write_lockfile(game_root, &lock)
+}
+
+/// Whether `installed` is a noted stand-in for the yanked `requested` version of `folder`.
+pub fn is_known_fallback(game_root: &Path, folder: &str, requested: &str, installed: &str) -> bool {
+    read_lockfile(game_root)
+        .version_fallbacks
+        .get(folder)
+        .is_some_and(|f| f.requested == requested && f.installed == installed)
+}
+
+/// Plugin folder (`dev-name`) a lockfile key belongs to.
+pub fn plugin_folder(key: &str) -> Option<&str> {
+    let rest = key.strip_prefix("BepInEx/plugins/")?;
+    let (folder, _) = rest.split_once('/')?;
+    Some(folder)
+}
+
+/// Compare `keys` against the lockfile. Returns `(missing, mismatched)`.
+pub fn verify(game_root: &Path, keys: &[String]) -> (Vec<String>, Vec<String>) {
+    let lock = read_lockfile(game_root);
+    let mut missing = Vec::new();
+    let mut mismatched = Vec::new();
+    for key in keys {
+        let Some(expected) = lock.files.get(key) else {
+            continue;
+        };
+        let path = game_root.join(key);
+        if !path.exists() {
+            missing.push(key.clone());
+        } else if hash_file(&path).ok().as_ref() != Some(expected) {
+            mismatched.push(key.clone());
+        }
+        std::thread::sleep(VERIFY_PAUSE);
+    }
+    (missing, mismatched)
+}
+
+/// Verify `keys` in the background after `VERIFY_DELAY`, emitting `integrity-warning`
+/// if anything went missing or changed.
+pub fn schedule_verify(
+    app: &tauri::AppHandle,
+    version: u32,
+    game_root: PathBuf,
+    keys: Vec<String>,
+) {
+    if keys.is_empty() {
+        return;
+    }
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        tokio::time::sleep(VERIFY_DELAY).await;
+        let root = game_root.clone();
+        let res = tauri::async_runtime::spawn_blocking(move || verify(&root, &keys)).await;
+        let Ok((missing, mismatched)) = res else {
+            return;
+        };
+        if missing.is_empty() && mismatched.is_empty() {
+            log::info!("Integrity sweep for v{version}: all files intact");
+            return;
+        }
+        log::warn!(
+            "Integrity sweep for v{version}: {} missing, {} modified (antivirus or disk error?)",
+            missing.len(),
+            mismatched.len()
+        );
+        progress::emit_integrity_warning(
+            &app,
+            IntegrityWarningPayload {
+                version,
+                path: game_root.to_string_lossy().to_string(),
+                missing,
+                mismatched,
+            },
+        );
+    });
+}
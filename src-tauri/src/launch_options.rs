@@ -0,0 +1,260 @@
+//! Per-version launch options, persisted as `state/launch_options.json`.
+
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
+use serde::{Deserialize, Serialize};
+
+#[cfg(target_os = "linux")]
+use crate::dxvk;
+use crate::{gpu, mod_config, storage};
+
+/// BepInEx is loaded through doorstop's `winhttp.dll` proxy, so it always comes first.
+const BASE_DLL_OVERRIDES: &[&str] = &["winhttp=n,b"];
+
+/// Load orders Wine understands (`""` disables the DLL).
+const DLL_MODES: &[&str] = &["n", "b", "n,b", "b,n", ""];
+
+/// Latency used by the audio fix when neither the user nor the manifest sets one.
+const DEFAULT_PULSE_LATENCY_MSEC: u32 = 60;
+/// Largest audio buffer accepted; more only adds audible lag.
+const MAX_PULSE_LATENCY_MSEC: u32 = 1000;
+/// Sample rate `PIPEWIRE_LATENCY` is expressed in.
+const PIPEWIRE_RATE: u32 = 48_000;
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct LaunchOptions {
+    /// Extra Wine DLL overrides as `dll=mode`, applied after the manifest defaults.
+    #[serde(default)]
+    pub dll_overrides: Vec<String>,
+    /// DXVK build (see `dxvk`) to use instead of the one bundled with Proton.
+    #[serde(default)]
+    pub dxvk_version: Option<String>,
+    /// Raise the PulseAudio/PipeWire buffer to stop crackling audio.
+    /// `None` follows the manifest's recommendation.
+    #[serde(default)]
+    pub audio_latency_fix: Option<bool>,
+    /// Buffer size for the audio fix; defaults to the manifest's value.
+    #[serde(default)]
+    pub pulse_latency_msec: Option<u32>,
+    /// Render on this GPU: `discrete`, or a PCI slot from `list_gpus`.
+    /// `None` leaves the choice to the driver.
+    #[serde(default)]
+    pub gpu: Option<String>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct LaunchOptionsFile {
+    #[serde(default)]
+    versions: BTreeMap<u32, LaunchOptions>,
+}
+
+fn options_path(app: &tauri::AppHandle) -> Result<PathBuf, String> {
+    Ok(storage::state_dir(app)?.join("launch_options.json"))
+}
+
+fn read_file(app: &tauri::AppHandle) -> Result<LaunchOptionsFile, String> {
+    let path = options_path(app)?;
+    let Ok(text) = std::fs::read_to_string(&path) else {
+        return Ok(LaunchOptionsFile::default());
+    };
+    serde_json::from_str(&text).map_err(|e| format!("invalid {}: {e}", path.display()))
+}
+
+fn write_file(app: &tauri::AppHandle, file: &LaunchOptionsFile) -> Result<(), String> {
+    let path = options_path(app)?;
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    let json = serde_json::to_string_pretty(file).map_err(|e| e.to_string())?;
+    crate::zip_utils::write_atomic(&path, json)
+}
+
+/// Options for `version` (defaults when none were saved).
+pub fn load(app: &tauri::AppHandle, version: u32) -> LaunchOptions {
+    match read_file(app) {
+        Ok(mut file) => file.versions.remove(&version).unwrap_or_default(),
+        Err(e) => {
+            log::warn!("{e}; using default launch options");
+            LaunchOptions::default()
+        }
+    }
+}
+
+/// Split `dll=mode` into its parts, rejecting anything Wine would misparse.
+fn parse_dll_override(entry: &str) -> Result<(String, String), String> {
+    let (dll, mode) = entry
+        .split_once('=')
+        .ok_or_else(|| format!("invalid DLL override {entry:?} (expected dll=mode)"))?;
+    let dll = dll.trim().trim_end_matches(".dll").to_lowercase();
+    let mode = mode.trim().replace(' ', "");
+    if dll.is_empty() || dll.contains([';', ',', '=']) {
+        return Err(format!("invalid DLL name in override {entry:?}"));
+    }
+    if !DLL_MODES.contains(&mode.as_str()) {
+        return Err(format!(
+            "invalid mode {mode:?} in override {entry:?} (expected n, b, n,b or b,n)"
+        ));
+    }
+    Ok((dll, mode))
+}
+
+/// `WINEDLLOVERRIDES` for `version`: built-in, then the selected DXVK build, then manifest
+/// defaults, then the user's list, later entries replacing earlier ones for the same DLL.
+pub fn wine_dll_overrides(app: &tauri::AppHandle, version: u32) -> String {
+    let manifest_defaults = mod_config::cached_remote_manifest()
+        .map(|m| m.dll_overrides)
+        .unwrap_or_default();
+    let options = load(app, version);
+    #[cfg(target_os = "linux")]
+    let dxvk_native: Vec<String> = match &options.dxvk_version {
+        Some(v) if dxvk::is_installed(app, v) => dxvk::DXVK_DLLS
+            .iter()
+            .map(|dll| format!("{dll}=n"))
+            .collect(),
+        _ => vec![],
+    };
+    // DXVK builds are only managed (and Wine only used) on Linux.
+    #[cfg(not(target_os = "linux"))]
+    let dxvk_native: Vec<String> = vec![];
+
+    let mut merged: Vec<(String, String)> = Vec::new();
+    let entries = BASE_DLL_OVERRIDES
+        .iter()
+        .map(|s| s.to_string())
+        .chain(dxvk_native)
+        .chain(manifest_defaults)
+        .chain(options.dll_overrides);
+    for entry in entries {
+        match parse_dll_override(&entry) {
+            Ok((dll, mode)) => {
+                merged.retain(|(d, _)| d != &dll);
+                merged.push((dll, mode));
+            }
+            Err(e) => log::warn!("Ignoring {e}"),
+        }
+    }
+    merged
+        .iter()
+        .map(|(dll, mode)| format!("{dll}={mode}"))
+        .collect::<Vec<_>>()
+        .join(";")
+}
+
+/// Game versions with a DXVK build selected.
+#[cfg(target_os = "linux")]
+pub fn versions_using_dxvk(app: &tauri::AppHandle) -> Result<Vec<u32>, String> {
+    Ok(read_file(app)?
+        .versions
+        .into_iter()
+        .filter(|(_, o)| o.dxvk_version.is_some())
+        .map(|(v, _)| v)
+        .collect())
+}
+
+#[cfg(target_os = "linux")]
+pub fn set_dxvk_version(
+    app: &tauri::AppHandle,
+    version: u32,
+    dxvk_version: Option<String>,
+) -> Result<(), String> {
+    let mut file = read_file(app)?;
+    file.versions.entry(version).or_default().dxvk_version = dxvk_version;
+    write_file(app, &file)
+}
+
+/// Extra environment for launching `version` (only meaningful under Proton).
+pub fn launch_env(app: &tauri::AppHandle, version: u32) -> BTreeMap<String, String> {
+    let options = load(app, version);
+    let recommended = mod_config::cached_remote_manifest().and_then(|m| m.pulse_latency_msec);
+    let mut env = BTreeMap::new();
+
+    if options.audio_latency_fix.unwrap_or(recommended.is_some()) {
+        let msec = options
+            .pulse_latency_msec
+            .or(recommended)
+            .unwrap_or(DEFAULT_PULSE_LATENCY_MSEC)
+            .clamp(1, MAX_PULSE_LATENCY_MSEC);
+        let frames = u64::from(msec) * u64::from(PIPEWIRE_RATE) / 1000;
+        env.insert("PULSE_LATENCY_MSEC".to_string(), msec.to_string());
+        env.insert(
+            "PIPEWIRE_LATENCY".to_string(),
+            format!("{frames}/{PIPEWIRE_RATE}"),
+        );
+    }
+    if let Some(selection) = &options.gpu {
+        env.extend(gpu::offload_env(selection));
+    }
+    env
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct LaunchOptionsView {
+    pub version: u32,
+    pub options: LaunchOptions,
+    /// DLL overrides the manifest applies to every install.
+    pub manifest_dll_overrides: Vec<String>,
+    /// The `WINEDLLOVERRIDES` value the next launch will use.
+    pub effective_dll_overrides: String,
+    /// Other environment variables the next launch will set.
+    pub effective_env: BTreeMap<String, String>,
+}
+
+#[tauri::command]
+pub fn get_launch_options(
+    app: tauri::AppHandle,
+    version: Option<u32>,
+) -> Result<LaunchOptionsView, String> {
+    let version = crate::resolve_version(&app, version)?;
+    Ok(LaunchOptionsView {
+        version,
+        options: load(&app, version),
+        manifest_dll_overrides: mod_config::cached_remote_manifest()
+            .map(|m| m.dll_overrides)
+            .unwrap_or_default(),
+        effective_dll_overrides: wine_dll_overrides(&app, version),
+        effective_env: launch_env(&app, version),
+    })
+}
+
+#[tauri::command]
+pub fn set_launch_options(
+    app: tauri::AppHandle,
+    version: Option<u32>,
+    mut options: LaunchOptions,
+) -> Result<LaunchOptionsView, String> {
+    let version = crate::resolve_version(&app, version)?;
+    options.dll_overrides = options
+        .dll_overrides
+        .iter()
+        .map(|entry| parse_dll_override(entry).map(|(dll, mode)| format!("{dll}={mode}")))
+        .collect::<Result<_, _>>()?;
+    if let Some(selection) = &options.gpu {
+        let known =
+            selection == gpu::DISCRETE || gpu::list_gpus().iter().any(|g| &g.pci_slot == selection);
+        if !known {
+            return Err(format!("unknown GPU {selection:?}"));
+        }
+    }
+    if let Some(msec) = options.pulse_latency_msec {
+        if !(1..=MAX_PULSE_LATENCY_MSEC).contains(&msec) {
+            return Err(format!(
+                "audio latency must be 1-{MAX_PULSE_LATENCY_MSEC} ms, got {msec}"
+            ));
+        }
+    }
+    if let Some(v) = &options.dxvk_version {
+        #[cfg(target_os = "linux")]
+        if !dxvk::is_installed(&app, v) {
+            return Err(format!("DXVK {v} is not installed"));
+        }
+        #[cfg(not(target_os = "linux"))]
+        return Err(format!("DXVK {v} is only supported on Linux"));
+    }
+
+    let mut file = read_file(&app)?;
+    file.versions.insert(version, options);
+    write_file(&app, &file)?;
+    log::info!("Saved launch options for v{version}");
+    get_launch_options(app, Some(version))
+}
@@ -1,13 +1,67 @@
+mod allowlist;
+mod announce;
 mod bepinex_cfg;
+mod bisect;
+mod cache;
+mod cli;
+mod config_backup;
+mod config_sync;
+mod confirm;
+mod crash_guard;
+mod decisions;
+mod diagnostics;
+mod doorstop;
+mod disk_space;
+mod download_backend;
 mod downloader;
+#[cfg(target_os = "linux")]
+mod dxvk;
+pub mod endpoints;
+pub mod error;
+mod fake_downloader;
+mod footprint;
+mod fs_utils;
+mod game;
+mod gpu;
+mod history;
+mod http_cache;
 mod installer;
+mod integrity;
+mod launch_options;
 mod logger;
-mod mod_config;
+mod migrations;
+mod mirrors;
+pub mod mod_config;
 mod mods;
+mod motd;
+mod overlay;
+mod pe_meta;
+mod plugin_scan;
+mod prefix_tweaks;
+mod profiles;
 mod progress;
-mod thunderstore;
-mod zip_utils;
+mod r2modman;
+mod reset;
+mod retention;
+mod session;
+mod settings;
+mod shader_cache;
+mod single_instance;
+mod storage;
+mod storage_stats;
+mod task_log;
+mod taskbar;
+mod tasks;
+mod temp_files;
+#[cfg(test)]
+mod test_support;
+pub mod thunderstore;
+pub mod zip_utils;
+#[cfg(target_os = "linux")]
+mod umu;
 mod variable;
+mod verify;
+mod vulkan;
 
 use serde::{Deserialize, Serialize};
 use std::collections::BTreeMap;
@@ -17,10 +71,10 @@ use std::sync::Mutex;
 use tauri::{Manager, State};
 
 use crate::bepinex_cfg::read_manifest;
-use crate::progress::{TaskErrorPayload, TaskProgressPayload};
+use crate::progress::{TaskErrorPayload, TaskProgressPayload, TaskStep};
 use crate::{
     mod_config::ModsConfig,
-    progress::{TaskFinishedPayload, TaskUpdatableProgressPayload},
+    progress::{InstallSummary, ModChange, TaskFinishedPayload, TaskUpdatableProgressPayload},
 };
 
 fn overall_from_step(step: u32, step_progress: f64, steps_total: u32) -> f64 {
@@ -32,21 +86,36 @@ fn overall_from_step(step: u32, step_progress: f64, steps_total: u32) -> f64 {
     (((step0 + step_progress.clamp(0.0, 1.0)) / steps) * 100.0).clamp(0.0, 100.0)
 }
 
+const PRACTICE_STEPS: [TaskStep; 1] = [TaskStep {
+    id: "practice_mods",
+    name: "Practice Mods",
+    weight: 1.0,
+}];
+
+const UPDATE_STEPS: [TaskStep; 2] = [
+    TaskStep {
+        id: "check_updates",
+        name: "Check Updates",
+        weight: 1.0,
+    },
+    TaskStep {
+        id: "update_mods",
+        name: "Update Mods",
+        weight: 4.0,
+    },
+];
+
 #[derive(Debug, Clone, Serialize)]
 struct ManifestDto {
     version: u32,
     chain_config: Vec<Vec<String>>,
     mods: Vec<mod_config::ModEntry>,
     manifests: BTreeMap<u32, String>,
+    origin: mod_config::ManifestOrigin,
 }
 
 fn shared_config_dir(app: &tauri::AppHandle) -> Result<std::path::PathBuf, String> {
-    Ok(app
-        .path()
-        .app_data_dir()
-        .map_err(|e| format!("failed to resolve app data dir: {e}"))?
-        .join("config")
-        .join("shared"))
+    Ok(storage::shared_config_dir(app)?)
 }
 
 fn is_safe_rel_path(rel: &std::path::Path) -> bool {
@@ -57,13 +126,46 @@ fn is_safe_rel_path(rel: &std::path::Path) -> bool {
     })
 }
 
+/// `rel_path` under the config dir `base`, for reading or writing a config file.
+///
+/// Besides the lexical check, no existing part of the path below `base` may be a link
+/// (symlink or junction, dangling or not), and the deepest existing part has to resolve
+/// inside `base`, so nothing under the config dir can point the editor at files elsewhere
+/// on disk.
+fn config_file_path(
+    base: &std::path::Path,
+    rel_path: &str,
+) -> Result<std::path::PathBuf, String> {
+    let rel = std::path::Path::new(rel_path);
+    if rel_path.is_empty() || !is_safe_rel_path(rel) {
+        return Err("invalid path".to_string());
+    }
+    let path = base.join(rel);
+    let base_canon = std::fs::canonicalize(base)
+        .map_err(|e| format!("config folder {} not found: {e}", base.display()))?;
+    let mut current = base.to_path_buf();
+    for part in rel.components() {
+        current.push(part);
+        if std::fs::symlink_metadata(&current).is_err() {
+            break;
+        }
+        if installer::is_reparse_point(&current)? {
+            return Err(format!("{rel_path} goes through a link"));
+        }
+    }
+    let existing = path.ancestors().find(|p| p.exists()).unwrap_or(base);
+    let canon = std::fs::canonicalize(existing).map_err(|e| e.to_string())?;
+    if !canon.starts_with(&base_canon) {
+        return Err(format!("{rel_path} is outside the config folder"));
+    }
+    if path.is_dir() {
+        return Err(format!("{rel_path} is a folder"));
+    }
+    Ok(path)
+}
+
 fn version_dir(app: &tauri::AppHandle, version: u32) -> Result<std::path::PathBuf, String> {
-    Ok(app
-        .path()
-        .app_data_dir()
-        .map_err(|e| format!("failed to resolve app data dir: {e}"))?
-        .join("versions")
-        .join(format!("v{version}")))
+    Ok(storage::version_dir(app, version)?)
 }
 
 fn version_config_dir(app: &tauri::AppHandle, version: u32) -> Result<std::path::PathBuf, String> {
@@ -159,6 +261,9 @@ struct InstalledModVersion {
     dev: String,
     name: String,
     version: String,
+    /// From the cached Thunderstore package list, when available.
+    downloads: Option<u64>,
+    rating_score: Option<i64>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -168,21 +273,15 @@ struct DisableModFile {
 }
 
 fn disablemod_path(app: &tauri::AppHandle) -> Result<std::path::PathBuf, String> {
-    Ok(app
-        .path()
-        .app_data_dir()
-        .map_err(|e| format!("failed to resolve app data dir: {e}"))?
-        .join("config")
-        .join("disablemod.json"))
+    Ok(storage::state_dir(app)?.join("disablemod.json"))
 }
 
 pub(crate) fn thunderstore_cache_path(app: &tauri::AppHandle) -> Result<std::path::PathBuf, String> {
-    Ok(app
-        .path()
-        .app_data_dir()
-        .map_err(|e| format!("failed to resolve app data dir: {e}"))?
-        .join("cache")
-        .join("thunderstore.json"))
+    Ok(storage::cache_dir(app)?.join("thunderstore.json"))
+}
+
+fn readme_cache_dir(app: &tauri::AppHandle) -> Result<std::path::PathBuf, String> {
+    Ok(storage::cache_dir(app)?.join("readme"))
 }
 
 fn read_disablemod(app: &tauri::AppHandle) -> Result<DisableModFile, String> {
@@ -357,13 +456,7 @@ fn ensure_practice_mods_disabled_for_version(app: &tauri::AppHandle, version: u3
 }
 
 async fn prepare_practice_mods_for_version(app: &tauri::AppHandle, version: u32) -> Result<(), String> {
-    let game_root = version_dir(app, version)?;
-    if !game_root.exists() {
-        return Err(format!(
-            "version folder not found: {}",
-            game_root.to_string_lossy()
-        ));
-    }
+    let game_root = storage::installed_version_dir(app, version)?;
 
     let practice_all = variable::get_practice_mod_list();
     let practice_enabled: Vec<mod_config::ModEntry> = practice_all
@@ -373,7 +466,10 @@ async fn prepare_practice_mods_for_version(app: &tauri::AppHandle, version: u32)
         .collect();
 
     // Emit progress so the UI can show work (practice installs can be slow).
-    const STEPS_TOTAL: u32 = 1;
+    const STEPS_TOTAL: u32 = PRACTICE_STEPS.len() as u32;
+    let task_id = tasks::begin(app, version, tasks::TaskKind::PracticeMods);
+    let cancel = tasks::cancel_flag(app, version);
+    progress::emit_plan(app, version, &PRACTICE_STEPS);
     progress::emit_progress(
         app,
         TaskProgressPayload {
@@ -396,17 +492,14 @@ async fn prepare_practice_mods_for_version(app: &tauri::AppHandle, version: u32)
         mods: practice_enabled.clone(),
     };
 
-    let install_res: Result<(), String> = mods::install_mods_with_progress(
+    let install_res: Result<Vec<ModChange>, String> = mods::install_mods_with_progress(
         app,
         &game_root,
         version,
         &cfg,
-        |done, total, detail| {
-            let step_progress = if total == 0 {
-                1.0
-            } else {
-                (done as f64 / total as f64).clamp(0.0, 1.0)
-            };
+        Some(cancel.as_ref()),
+        |p| {
+            let step_progress = p.fraction();
             progress::emit_progress(
                 app,
                 TaskProgressPayload {
@@ -416,16 +509,17 @@ async fn prepare_practice_mods_for_version(app: &tauri::AppHandle, version: u32)
                     step_name: "Practice Mods".to_string(),
                     step_progress,
                     overall_percent: overall_from_step(1, step_progress, STEPS_TOTAL),
-                    detail,
-                    downloaded_bytes: None,
-                    total_bytes: None,
-                    extracted_files: Some(done),
-                    total_files: Some(total),
+                    detail: p.detail,
+                    downloaded_bytes: (p.total_bytes > 0).then_some(p.done_bytes),
+                    total_bytes: (p.total_bytes > 0).then_some(p.total_bytes),
+                    extracted_files: Some(p.done_mods),
+                    total_files: Some(p.total_mods),
                 },
             );
         },
     )
     .await;
+    tasks::end(app, version, &task_id);
 
     if let Err(e) = &install_res {
         progress::emit_error(
@@ -433,6 +527,7 @@ async fn prepare_practice_mods_for_version(app: &tauri::AppHandle, version: u32)
             TaskErrorPayload {
                 version,
                 message: e.clone(),
+                ..Default::default()
             },
         );
         return Err(e.clone());
@@ -491,6 +586,7 @@ async fn prepare_practice_mods_for_version(app: &tauri::AppHandle, version: u32)
         TaskFinishedPayload {
             version,
             path: game_root.to_string_lossy().to_string(),
+            summary: install_res.ok().map(InstallSummary::from_changes),
         },
     );
 
@@ -502,6 +598,25 @@ struct GameState {
     child: Mutex<Option<std::process::Child>>,
 }
 
+/// Whether the game process launched by this launcher is still running.
+pub(crate) fn is_game_running(app: &tauri::AppHandle) -> bool {
+    running_game_pid(app).is_some()
+}
+
+/// Pid of the game process launched by this launcher, while it runs.
+pub(crate) fn running_game_pid(app: &tauri::AppHandle) -> Option<u32> {
+    let state = app.try_state::<GameState>()?;
+    let mut guard = state.child.lock().ok()?;
+    match guard.as_mut().map(|c| c.try_wait()) {
+        Some(Ok(None)) => guard.as_ref().map(|c| c.id()),
+        Some(_) => {
+            *guard = None;
+            None
+        }
+        None => None,
+    }
+}
+
 #[derive(Default)]
 struct DownloadState {
     active: Mutex<Option<ActiveDownload>>,
@@ -518,14 +633,71 @@ struct GameStatus {
     pid: Option<u32>,
 }
 
+/// Install `version`. With `launch` (default: `Settings::auto_launch_after_install`) the
+/// game starts as soon as the install succeeds; see `auto-launch` events.
 #[tauri::command]
 async fn download(
     app: tauri::AppHandle,
     version: u32,
+    acknowledge_eol: Option<bool>,
+    launch: Option<bool>,
     state: State<'_, DownloadState>,
-) -> Result<bool, String> {
+) -> error::Result<bool> {
+    run_install(
+        app,
+        version,
+        acknowledge_eol.unwrap_or(false),
+        launch,
+        None,
+        state,
+    )
+    .await
+}
+
+/// Install `version` using a local manifest file (same schema as the remote one) instead
+/// of fetching it, e.g. for a pack handed out on a LAN. Only the manifest fetch is skipped:
+/// the game still downloads from Steam, and BepInEx and mods need Thunderstore unless their
+/// zips are already in the package cache (the package list falls back to an expired cache).
+#[tauri::command]
+async fn install_from_manifest_file(
+    app: tauri::AppHandle,
+    path: String,
+    version: u32,
+    acknowledge_eol: Option<bool>,
+    launch: Option<bool>,
+    state: State<'_, DownloadState>,
+) -> error::Result<bool> {
+    let text = std::fs::read_to_string(&path).map_err(|e| error::Error::io(&path, e))?;
+    let raw: serde_json::Value =
+        serde_json::from_str(&text).map_err(|e| error::Error::json(&path, e))?;
+    let manifest = mod_config::parse_remote_manifest(raw)?;
+    manifest.game_version(version)?;
+    log::info!(
+        "Installing v{version} from local manifest {path} (manifest v{})",
+        manifest.version
+    );
+    run_install(
+        app,
+        version,
+        acknowledge_eol.unwrap_or(false),
+        launch,
+        Some(manifest),
+        state,
+    )
+    .await
+}
+
+async fn run_install(
+    app: tauri::AppHandle,
+    version: u32,
+    acknowledge_eol: bool,
+    launch: Option<bool>,
+    manifest: Option<mod_config::RemoteManifest>,
+    state: State<'_, DownloadState>,
+) -> error::Result<bool> {
+    storage::check_version(version)?;
     // Only allow one active download at a time (simplifies cancel + UI state).
-    let cancel = Arc::new(AtomicBool::new(false));
+    let mut cancel = Arc::new(AtomicBool::new(false));
     {
         let mut guard = state
             .active
@@ -536,7 +708,8 @@ async fn download(
                 return Err(format!(
                     "download already in progress (v{}). Please cancel it first.",
                     active.version
-                ));
+                )
+                .into());
             }
         }
         *guard = Some(ActiveDownload {
@@ -545,7 +718,36 @@ async fn download(
         });
     }
 
-    let res = installer::download_and_setup(app.clone(), version, cancel.clone()).await;
+    let res = loop {
+        let task_id = tasks::begin(&app, version, tasks::TaskKind::Install);
+        tasks::set_cancel_flag(&app, version, cancel.clone());
+        let res = installer::download_and_setup(
+            app.clone(),
+            version,
+            acknowledge_eol,
+            cancel.clone(),
+            manifest.clone(),
+        )
+        .await;
+        let retry = res.is_err()
+            && tasks::cancel_reason(&app, version) == Some(progress::CancelReason::Retry);
+        tasks::end(&app, version, &task_id);
+        if !retry {
+            break res;
+        }
+
+        // `retry_task`: the staging dir was kept, so this resumes the stalled install.
+        log::info!("Retrying the install of v{version}");
+        cancel = Arc::new(AtomicBool::new(false));
+        let mut guard = state
+            .active
+            .lock()
+            .map_err(|_| "download state lock poisoned".to_string())?;
+        match guard.as_mut() {
+            Some(active) if active.version == version => active.cancel = cancel.clone(),
+            _ => break res,
+        }
+    };
 
     // Clear active download state (best-effort).
     {
@@ -557,15 +759,56 @@ async fn download(
             *guard = None;
         }
     }
-    res
+
+    if matches!(res, Ok(true))
+        && launch.unwrap_or_else(|| settings::load(&app).auto_launch_after_install)
+    {
+        auto_launch(&app, version);
+    }
+    Ok(res?)
+}
+
+/// Start `version` right after its install, reporting each transition as `auto-launch`.
+/// A failed launch doesn't fail the install; it's reported and the user can retry.
+fn auto_launch(app: &tauri::AppHandle, version: u32) {
+    let emit = |stage: &'static str, pid: Option<u32>, detail: Option<String>| {
+        progress::emit_auto_launch(
+            app,
+            progress::AutoLaunchPayload {
+                version,
+                stage,
+                pid,
+                detail,
+            },
+        );
+    };
+    if is_game_running(app) {
+        emit("skipped", None, Some("the game is already running".to_string()));
+        return;
+    }
+    emit("launching", None, None);
+    match launch_game(app.clone(), Some(version), app.state::<GameState>()) {
+        Ok(pid) => {
+            log::info!("Auto-launched v{version} after install (pid {pid})");
+            emit("launched", Some(pid), None);
+        }
+        Err(e) => {
+            log::warn!("Auto-launch of v{version} failed: {e}");
+            emit("failed", None, Some(e));
+        }
+    }
 }
 
+/// Abort the install of `version`: kills DepotDownloader and stops the BepInEx/mod
+/// downloads. The installer then removes the partial install and emits `task-cancelled`
+/// (and `download://cancelled`); the previous install of `version`, if any, stays.
 #[tauri::command]
-fn cancel_download(
+fn cancel_install(
     app: tauri::AppHandle,
     version: u32,
     state: State<'_, DownloadState>,
-) -> Result<bool, String> {
+) -> error::Result<bool> {
+    storage::check_version(version)?;
     let mut did_signal = false;
     {
         let guard = state
@@ -574,50 +817,190 @@ fn cancel_download(
             .map_err(|_| "download state lock poisoned".to_string())?;
         if let Some(active) = guard.as_ref() {
             if active.version == version {
+                tasks::set_cancel_reason(&app, version, progress::CancelReason::User);
                 active.cancel.store(true, Ordering::Relaxed);
                 did_signal = true;
             }
         }
     }
 
-    // Best-effort cleanup: delete the partial version folder.
-    // If files are still in use, the installer will retry cleanup on exit.
-    if did_signal {
-        if let Ok(dir) = version_dir(&app, version) {
-            let _ = std::fs::remove_dir_all(&dir);
-        }
+    Ok(did_signal)
+}
+
+/// The launcher is closing mid-install. The runtime won't get to finish the installer's
+/// own cancel path, so clean up here; a resumable game download is kept for next time.
+fn cancel_install_on_exit(app: &tauri::AppHandle) {
+    let Some(state) = app.try_state::<DownloadState>() else {
+        return;
+    };
+    let active = state
+        .active
+        .lock()
+        .ok()
+        .and_then(|g| g.as_ref().map(|a| (a.version, a.cancel.clone())));
+    let Some((version, cancel)) = active else {
+        return;
+    };
+    if cancel.load(Ordering::Relaxed) {
+        // Already cancelled by the user; the installer handles that one.
+        return;
     }
+    tasks::set_cancel_reason(app, version, progress::CancelReason::Shutdown);
+    cancel.store(true, Ordering::Relaxed);
+    log::info!("Launcher closing; cancelling the install of v{version}");
+    progress::emit_cancelled(
+        app,
+        installer::clean_up_cancelled_install(app, version, progress::CancelReason::Shutdown),
+    );
+}
 
-    Ok(did_signal)
+/// Older name of `cancel_install`.
+#[tauri::command]
+fn cancel_download(
+    app: tauri::AppHandle,
+    version: u32,
+    state: State<'_, DownloadState>,
+) -> error::Result<bool> {
+    cancel_install(app, version, state)
+}
+
+/// Restart task `task_id` of `version` after `task-stalled` offered it (`can_retry`). The
+/// task is stopped, keeping what can be resumed, and started again; `task-cancelled` is
+/// emitted with reason `retry` in between. Returns whether the task was stopped.
+#[tauri::command]
+fn retry_task(app: tauri::AppHandle, version: u32, task_id: String) -> error::Result<bool> {
+    storage::check_version(version)?;
+    let retrying = tasks::request_retry(&app, version, &task_id);
+    if retrying {
+        log::info!("Retry requested for task {task_id}");
+    }
+    Ok(retrying)
 }
 
+/// Startup sync. `config`/`mods` (default both) pick the steps to run; `force` re-runs
+/// them even when up to date, for re-syncing just the broken part.
 #[tauri::command]
-async fn sync_latest_install_from_manifest(app: tauri::AppHandle) -> Result<bool, String> {
-    installer::sync_latest_install_from_manifest(app).await?;
+async fn sync_latest_install_from_manifest(
+    app: tauri::AppHandle,
+    config: Option<bool>,
+    mods: Option<bool>,
+    force: Option<bool>,
+) -> Result<bool, String> {
+    let steps = installer::SyncSteps {
+        config: config.unwrap_or(true),
+        mods: mods.unwrap_or(true),
+        force: force.unwrap_or(false),
+    };
+    installer::sync_latest_install_from_manifest(app, steps).await?;
     Ok(true)
 }
 
 #[tauri::command]
 async fn open_version_folder(app: tauri::AppHandle) -> Result<bool, String> {
-    let dir = app
-        .path()
-        .app_data_dir()
-        .map_err(|e| format!("failed to resolve app data dir: {e}"))?
-        .join("versions");
+    let dir = storage::versions_dir(&app)?;
     let _ = opener::open(dir).map_err(|e| e.to_string())?;
     Ok(true)
 }
 
+/// Folders the UI can reveal via `open_path`.
+#[derive(Debug, Clone, Copy, Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum PathKind {
+    Versions,
+    GameDir,
+    PluginsDir,
+    SharedConfig,
+    Logs,
+    Cache,
+}
+
+/// Open a launcher folder in the system file manager (explorer / xdg-open / Finder).
+///
+/// `version` applies to `game_dir`/`plugins_dir` and defaults to the active version.
+/// Returns the opened path.
+#[tauri::command]
+fn open_path(
+    app: tauri::AppHandle,
+    kind: PathKind,
+    version: Option<u32>,
+) -> Result<String, String> {
+    let dir = match kind {
+        PathKind::Versions => storage::versions_dir(&app)?,
+        PathKind::GameDir => version_dir(&app, resolve_version(&app, version)?)?,
+        PathKind::PluginsDir => {
+            mods::plugins_dir(&version_dir(&app, resolve_version(&app, version)?)?)
+        }
+        PathKind::SharedConfig => storage::shared_config_dir(&app)?,
+        PathKind::Logs => storage::logs_dir(&app)?,
+        PathKind::Cache => storage::cache_dir(&app)?,
+    };
+
+    match kind {
+        PathKind::GameDir | PathKind::PluginsDir if !dir.is_dir() => {
+            return Err(format!("folder not found: {}", dir.to_string_lossy()));
+        }
+        _ => std::fs::create_dir_all(&dir).map_err(|e| e.to_string())?,
+    }
+    opener::open(&dir).map_err(|e| e.to_string())?;
+    Ok(dir.to_string_lossy().to_string())
+}
+
+/// Explicit version, or the active one for callers without a version (tray, deep links).
+fn resolve_version(app: &tauri::AppHandle, version: Option<u32>) -> Result<u32, String> {
+    if let Some(v) = version {
+        return Ok(storage::check_version(v)?);
+    }
+    installer::active_version_dir(app, &[])?
+        .map(|(v, _)| v)
+        .ok_or_else(|| "no game version installed".to_string())
+}
+
+#[derive(Debug, Clone, Serialize)]
+struct ActiveVersionChange {
+    version: Option<u32>,
+    /// Mods moved to `plugins_disabled` because they don't support the new version.
+    disabled_mods: Vec<mods::DisabledIncompatibleMod>,
+}
+
+#[tauri::command]
+fn get_active_version(app: tauri::AppHandle) -> Result<Option<u32>, String> {
+    Ok(installer::active_version_dir(&app, &[])?.map(|(v, _)| v))
+}
+
+/// Persist the version used when none is given (`None` = highest installed).
+#[tauri::command]
+async fn set_active_version(
+    app: tauri::AppHandle,
+    version: Option<u32>,
+) -> error::Result<ActiveVersionChange> {
+    if let Some(v) = version {
+        storage::installed_version_dir(&app, v)?;
+    }
+    let mut settings = settings::load(&app);
+    settings.active_version = version;
+    settings::save(&app, &settings)?;
+
+    // Switching versions is when out-of-range mods would start crashing the game.
+    let mut disabled_mods = vec![];
+    if let Some((v, root)) = installer::active_version_dir(&app, &[])? {
+        let client = reqwest::Client::new();
+        match ModsConfig::fetch_manifest(&client).await {
+            Ok((_, cfg, _, _)) => disabled_mods = mods::disable_incompatible_mods(&root, v, &cfg)?,
+            Err(e) => log::warn!("Skipping compatibility check for v{v}: {e}"),
+        }
+    }
+
+    Ok(ActiveVersionChange {
+        version,
+        disabled_mods,
+    })
+}
+
 #[tauri::command]
 async fn check_mod_updates(app: tauri::AppHandle, version: u32) -> Result<bool, String> {
     let client = reqwest::Client::new();
 
-    let dir = app
-        .path()
-        .app_data_dir()
-        .map_err(|e| format!("failed to resolve app data dir: {e}"))?
-        .join("versions");
-    let extract_dir = dir.join(format!("v{version}"));
+    let extract_dir = storage::installed_version_dir(&app, version)?;
     let (_, mods_cfg, _, _) = ModsConfig::fetch_manifest(&client).await?;
 
     let mut updatable_mods: Vec<String> = vec![];
@@ -654,6 +1037,7 @@ async fn check_mod_updates(app: tauri::AppHandle, version: u32) -> Result<bool,
             TaskErrorPayload {
                 version,
                 message: e.clone(),
+                ..Default::default()
             },
         );
         return Err(e);
@@ -664,6 +1048,7 @@ async fn check_mod_updates(app: tauri::AppHandle, version: u32) -> Result<bool,
         TaskFinishedPayload {
             version,
             path: extract_dir.to_string_lossy().to_string(),
+            summary: None,
         },
     );
     Ok(true)
@@ -671,25 +1056,17 @@ async fn check_mod_updates(app: tauri::AppHandle, version: u32) -> Result<bool,
 
 #[tauri::command]
 async fn apply_mod_updates(app: tauri::AppHandle, version: u32) -> Result<bool, String> {
-    let res: Result<(), String> = async {
+    let task_id = tasks::begin(&app, version, tasks::TaskKind::UpdateMods);
+    let cancel = tasks::cancel_flag(&app, version);
+    let res: Result<Vec<ModChange>, String> = async {
         let client = reqwest::Client::new();
 
-        let dir = app
-            .path()
-            .app_data_dir()
-            .map_err(|e| format!("failed to resolve app data dir: {e}"))?
-            .join("versions");
-        let game_root = dir.join(format!("v{version}"));
-        if !game_root.exists() {
-            return Err(format!(
-                "version folder not found: {}",
-                game_root.to_string_lossy()
-            ));
-        }
+        let game_root = storage::installed_version_dir(&app, version)?;
 
         let (_, mods_cfg, _, _) = ModsConfig::fetch_manifest(&client).await?;
 
-        const STEPS_TOTAL: u32 = 2;
+        const STEPS_TOTAL: u32 = UPDATE_STEPS.len() as u32;
+        progress::emit_plan(&app, version, &UPDATE_STEPS);
         progress::emit_progress(
             &app,
             TaskProgressPayload {
@@ -743,6 +1120,9 @@ async fn apply_mod_updates(app: tauri::AppHandle, version: u32) -> Result<bool,
             },
         )
         .await?;
+        if cancel.load(Ordering::Relaxed) {
+            return Err("Cancelled".to_string());
+        }
 
         if updatable.is_empty() {
             progress::emit_progress(
@@ -761,7 +1141,7 @@ async fn apply_mod_updates(app: tauri::AppHandle, version: u32) -> Result<bool,
                     total_files: None,
                 },
             );
-            return Ok(());
+            return Ok(Vec::new());
         }
 
         progress::emit_progress(
@@ -781,18 +1161,15 @@ async fn apply_mod_updates(app: tauri::AppHandle, version: u32) -> Result<bool,
             },
         );
 
-        mods::update_mods_with_progress(
+        let changes = mods::update_mods_with_progress(
             &app,
             &game_root,
             version,
             &mods_cfg,
             updatable.clone(),
-            |done, total, detail| {
-                let step_progress = if total == 0 {
-                    1.0
-                } else {
-                    (done as f64 / total as f64).clamp(0.0, 1.0)
-                };
+            Some(cancel.as_ref()),
+            |p| {
+                let step_progress = p.fraction();
                 progress::emit_progress(
                     &app,
                     TaskProgressPayload {
@@ -802,28 +1179,40 @@ async fn apply_mod_updates(app: tauri::AppHandle, version: u32) -> Result<bool,
                         step_name: "Update Mods".to_string(),
                         step_progress,
                         overall_percent: overall_from_step(2, step_progress, STEPS_TOTAL),
-                        detail,
-                        downloaded_bytes: None,
-                        total_bytes: None,
-                        extracted_files: Some(done),
-                        total_files: Some(total),
+                        detail: p.detail,
+                        downloaded_bytes: (p.total_bytes > 0).then_some(p.done_bytes),
+                        total_bytes: (p.total_bytes > 0).then_some(p.total_bytes),
+                        extracted_files: Some(p.done_mods),
+                        total_files: Some(p.total_mods),
                     },
                 );
             },
         )
         .await?;
 
-        Ok(())
+        Ok(changes)
     }
     .await;
+    tasks::end(&app, version, &task_id);
 
     match res {
-        Ok(()) => {
+        Ok(changes) => {
+            if !changes.is_empty() {
+                history::record(
+                    &app,
+                    version,
+                    history::HistoryKind::ModUpdate,
+                    None,
+                    format!("Updated {} mod(s)", changes.len()),
+                    changes.clone(),
+                );
+            }
             progress::emit_finished(
                 &app,
                 TaskFinishedPayload {
                     version,
                     path: version_dir(&app, version)?.to_string_lossy().to_string(),
+                    summary: Some(InstallSummary::from_changes(changes)),
                 },
             );
             Ok(true)
@@ -834,6 +1223,7 @@ async fn apply_mod_updates(app: tauri::AppHandle, version: u32) -> Result<bool,
                 TaskErrorPayload {
                     version,
                     message: e.clone(),
+                    ..Default::default()
                 },
             );
             Err(e)
@@ -841,20 +1231,24 @@ async fn apply_mod_updates(app: tauri::AppHandle, version: u32) -> Result<bool,
     }
 }
 
+/// The Steam client installation, if there is one.
+fn find_steam_client_path() -> Option<std::path::PathBuf> {
+    let home_dir = dirs::home_dir()?;
+    let steam_paths = [
+        home_dir.join(".steam/steam"),
+        home_dir.join(".local/share/Steam"),
+        home_dir.join(".var/app/com.valvesoftware.Steam/.local/share/Steam"),
+    ];
+    steam_paths
+        .into_iter()
+        .find(|path| path.exists() && path.join("steamapps").exists())
+}
+
 #[cfg(target_os = "linux")]
 fn get_steam_client_path(launcher_root: &std::path::Path) -> std::path::PathBuf {
-    if let Some(home_dir) = dirs::home_dir() {
-        let steam_paths = [
-            home_dir.join(".steam/steam"),
-            home_dir.join(".local/share/Steam"),
-            home_dir.join(".var/app/com.valvesoftware.Steam/.local/share/Steam"),
-        ];
-        for path in steam_paths.iter() {
-            if path.exists() && path.join("steamapps").exists() {
-                println!("Found real Steam installation at: {:?}", path);
-                return path.clone();
-            }
-        }
+    if let Some(path) = find_steam_client_path() {
+        println!("Found real Steam installation at: {:?}", path);
+        return path;
     }
 
     println!("Steam not found. Mocking client path.");
@@ -864,19 +1258,17 @@ fn get_steam_client_path(launcher_root: &std::path::Path) -> std::path::PathBuf
 #[tauri::command]
 fn launch_game(
     app: tauri::AppHandle,
-    version: u32,
+    version: Option<u32>,
     state: State<'_, GameState>,
 ) -> Result<u32, String> {
-    let dir = version_dir(&app, version)?;
-    if !dir.exists() {
-        return Err(format!(
-            "version folder not found: {}",
-            dir.to_string_lossy()
-        ));
+    let version = resolve_version(&app, version)?;
+    if let Err(e) = installer::repair_config_links(&app) {
+        log::warn!("Failed to repair config links before launch: {e}");
     }
+    let dir = storage::installed_version_dir(&app, version)?;
 
     let _app_path = app.path().app_data_dir().map_err(|e| format!("app path not found: {e}"))?;
-    let exe_name = "Lethal Company.exe";
+    let exe_name = game::current().executable;
     let exe_path = dir.join(exe_name);
     let exe_path = if exe_path.exists() {
         exe_path
@@ -885,10 +1277,6 @@ fn launch_game(
             .ok_or_else(|| format!("{exe_name} not found under {}", dir.to_string_lossy()))?
     };
 
-    let exe_dir = exe_path
-        .parent()
-        .ok_or_else(|| "invalid exe path".to_string())?;
-
     // If already running, return an error.
     {
         let mut guard = state
@@ -908,9 +1296,22 @@ fn launch_game(
 
     // Ensure disabled mods are applied for this version before launch.
     let _ = apply_disabled_mods_for_version(&app, version);
+    #[cfg(target_os = "linux")]
+    if let Some(exe_dir) = exe_path.parent() {
+        if let Err(e) = dxvk::deploy(&app, version, &dir, exe_dir) {
+            log::warn!("Failed to deploy DXVK for v{version}: {e}");
+        }
+    }
+    plugin_scan::warn_duplicate_guids(&app, version, &dir);
     // For HQoL specifically, also ensure `.old` matches disablemod.json on normal runs.
     let _ = sync_hqol_with_disablemod_for_version(&app, version);
 
+    // Protected game files: run from an overlay so the version dir stays untouched.
+    let exe_path = overlay::prepare(&app, version, &dir, exe_path)?;
+    let exe_dir = exe_path
+        .parent()
+        .ok_or_else(|| "invalid exe path".to_string())?;
+
     #[cfg(target_os = "windows")]
     let mut command = std::process::Command::new(&exe_path);
     
@@ -922,9 +1323,12 @@ fn launch_game(
         cmd
     };
 
+    #[cfg(target_os = "linux")]
+    vulkan::ensure_usable(&app)?;
+
     #[cfg(target_os = "linux")]
     let (proton_binary, compat_data_path) = {
-        let proton_env_path = installer::proton_env_dir(&app).map_err(|e| format!("proton_env path not found: {e}"))?;
+        let proton_env_path = storage::proton_env_dir(&app).map_err(|e| format!("proton_env path not found: {e}"))?;
         let proton_bin_path = installer::get_current_proton_dir_impl(&app)
             .map_err(|e| format!("proton path not found: {e}"))?
             .ok_or("found proton path but is None")?;
@@ -938,19 +1342,40 @@ fn launch_game(
         )
     };
 
+    #[cfg(target_os = "linux")]
+    prefix_tweaks::apply(
+        &proton_binary,
+        &compat_data_path,
+        &get_steam_client_path(&_app_path),
+    );
+
     #[cfg(target_os = "linux")]
     let mut command = {
         let steam_path = get_steam_client_path(&_app_path);
-        let mut cmd = std::process::Command::new(&proton_binary);
-        cmd.arg("run");
-        cmd.arg(&exe_path);
-        cmd.env("STEAM_COMPAT_DATA_PATH", &compat_data_path);
-        cmd.env("STEAM_COMPAT_CLIENT_INSTALL_PATH", &steam_path);
-        cmd.env("WINEDLLOVERRIDES", "winhttp=n,b");
+        let mut cmd = match umu::runner(&app, find_steam_client_path().is_some()) {
+            Some(umu_run) => {
+                log::info!("Launching through {}", umu_run.display());
+                umu::command(&umu_run, &proton_binary, &compat_data_path, &exe_path)
+            }
+            None => {
+                let mut cmd = std::process::Command::new(&proton_binary);
+                cmd.arg("run");
+                cmd.arg(&exe_path);
+                cmd.env("STEAM_COMPAT_DATA_PATH", &compat_data_path);
+                cmd.env("STEAM_COMPAT_CLIENT_INSTALL_PATH", &steam_path);
+                cmd
+            }
+        };
+        cmd.env(
+            "WINEDLLOVERRIDES",
+            launch_options::wine_dll_overrides(&app, version),
+        );
         cmd.env_remove("PYTHONPATH"); 
         cmd.env_remove("PYTHONHOME");
         cmd
     };
+    shader_cache::apply_env(&app, version, exe_dir, &mut command);
+    command.envs(launch_options::launch_env(&app, version));
 
     let child = command
         .current_dir(exe_dir)
@@ -963,28 +1388,28 @@ fn launch_game(
         .lock()
         .map_err(|_| "game state lock poisoned".to_string())?;
     *guard = Some(child);
+    drop(guard);
+    crash_guard::supervise(&app, version, pid);
     Ok(pid)
 }
 
 #[tauri::command]
 async fn launch_game_practice(
     app: tauri::AppHandle,
-    version: u32,
+    version: Option<u32>,
     state: State<'_, GameState>,
 ) -> Result<u32, String> {
-    let dir = version_dir(&app, version)?;
-    if !dir.exists() {
-        return Err(format!(
-            "version folder not found: {}",
-            dir.to_string_lossy()
-        ));
+    let version = resolve_version(&app, version)?;
+    if let Err(e) = installer::repair_config_links(&app) {
+        log::warn!("Failed to repair config links before launch: {e}");
     }
+    let dir = storage::installed_version_dir(&app, version)?;
 
     let _app_path = app
         .path()
         .app_data_dir()
         .map_err(|e| format!("app path not found: {e}"))?;
-    let exe_name = "Lethal Company.exe";
+    let exe_name = game::current().executable;
     let exe_path = dir.join(exe_name);
     let exe_path = if exe_path.exists() {
         exe_path
@@ -993,10 +1418,6 @@ async fn launch_game_practice(
             .ok_or_else(|| format!("{exe_name} not found under {}", dir.to_string_lossy()))?
     };
 
-    let exe_dir = exe_path
-        .parent()
-        .ok_or_else(|| "invalid exe path".to_string())?;
-
     // If already running, return an error.
     {
         let mut guard = state
@@ -1016,6 +1437,19 @@ async fn launch_game_practice(
 
     // Ensure disabled mods are applied for this version before launch.
     let _ = apply_disabled_mods_for_version(&app, version);
+    #[cfg(target_os = "linux")]
+    if let Some(exe_dir) = exe_path.parent() {
+        if let Err(e) = dxvk::deploy(&app, version, &dir, exe_dir) {
+            log::warn!("Failed to deploy DXVK for v{version}: {e}");
+        }
+    }
+    plugin_scan::warn_duplicate_guids(&app, version, &dir);
+
+    // Protected game files: run from an overlay so the version dir stays untouched.
+    let exe_path = overlay::prepare(&app, version, &dir, exe_path)?;
+    let exe_dir = exe_path
+        .parent()
+        .ok_or_else(|| "invalid exe path".to_string())?;
 
     #[cfg(target_os = "windows")]
     let mut command = std::process::Command::new(&exe_path);
@@ -1028,9 +1462,12 @@ async fn launch_game_practice(
         cmd
     };
 
+    #[cfg(target_os = "linux")]
+    vulkan::ensure_usable(&app)?;
+
     #[cfg(target_os = "linux")]
     let (proton_binary, compat_data_path) = {
-        let proton_env_path = installer::proton_env_dir(&app)
+        let proton_env_path = storage::proton_env_dir(&app)
             .map_err(|e| format!("proton_env path not found: {e}"))?;
         let proton_bin_path = installer::get_current_proton_dir_impl(&app)
             .map_err(|e| format!("proton path not found: {e}"))?
@@ -1045,18 +1482,39 @@ async fn launch_game_practice(
         )
     };
 
+    #[cfg(target_os = "linux")]
+    prefix_tweaks::apply(
+        &proton_binary,
+        &compat_data_path,
+        &get_steam_client_path(&_app_path),
+    );
+
     #[cfg(target_os = "linux")]
     let mut command = {
         let steam_path = get_steam_client_path(&_app_path);
-        let mut cmd = std::process::Command::new(&proton_binary);
-        cmd.arg("run");
-        cmd.arg(&exe_path);
-        cmd.env("STEAM_COMPAT_DATA_PATH", &compat_data_path);
-        cmd.env("STEAM_COMPAT_CLIENT_INSTALL_PATH", &steam_path);
-        cmd.env("WINEDLLOVERRIDES", "winhttp=n,b");
+        let mut cmd = match umu::runner(&app, find_steam_client_path().is_some()) {
+            Some(umu_run) => {
+                log::info!("Launching through {}", umu_run.display());
+                umu::command(&umu_run, &proton_binary, &compat_data_path, &exe_path)
+            }
+            None => {
+                let mut cmd = std::process::Command::new(&proton_binary);
+                cmd.arg("run");
+                cmd.arg(&exe_path);
+                cmd.env("STEAM_COMPAT_DATA_PATH", &compat_data_path);
+                cmd.env("STEAM_COMPAT_CLIENT_INSTALL_PATH", &steam_path);
+                cmd
+            }
+        };
+        cmd.env(
+            "WINEDLLOVERRIDES",
+            launch_options::wine_dll_overrides(&app, version),
+        );
         println!("{:?}", cmd);
         cmd
     };
+    shader_cache::apply_env(&app, version, exe_dir, &mut command);
+    command.envs(launch_options::launch_env(&app, version));
 
     let child = command
         .current_dir(exe_dir)
@@ -1069,6 +1527,8 @@ async fn launch_game_practice(
         .lock()
         .map_err(|_| "game state lock poisoned".to_string())?;
     *guard = Some(child);
+    drop(guard);
+    crash_guard::supervise(&app, version, pid);
     Ok(pid)
 }
 
@@ -1101,12 +1561,13 @@ fn get_game_status(state: State<'_, GameState>) -> Result<GameStatus, String> {
 }
 
 #[tauri::command]
-fn stop_game(state: State<'_, GameState>) -> Result<bool, String> {
+fn stop_game(app: tauri::AppHandle, state: State<'_, GameState>) -> Result<bool, String> {
     let mut guard = state
         .child
         .lock()
         .map_err(|_| "game state lock poisoned".to_string())?;
     if let Some(mut child) = guard.take() {
+        crash_guard::note_user_stop(&app);
         let _ = child.kill();
         let _ = child.wait();
         Ok(true)
@@ -1127,13 +1588,16 @@ fn apply_disabled_mods(app: tauri::AppHandle, version: u32) -> Result<bool, Stri
 }
 
 #[tauri::command]
-fn set_mod_enabled(
+async fn set_mod_enabled(
     app: tauri::AppHandle,
     version: u32,
     dev: String,
     name: String,
     enabled: bool,
 ) -> Result<bool, String> {
+    if !enabled {
+        ensure_not_required(&dev, &name).await?;
+    }
     let mut list = read_disablemod(&app)?;
 
     // Use normalized ids in the file.
@@ -1152,9 +1616,43 @@ fn set_mod_enabled(
     if let Some(dir) = mod_dir_for(&plugins, &dev, &name) {
         let _ = set_mod_files_old_suffix(&dir, enabled);
     }
+    history::record(
+        &app,
+        version,
+        history::HistoryKind::ModToggle,
+        None,
+        format!(
+            "{} {dev}-{name}",
+            if enabled { "Enabled" } else { "Disabled" }
+        ),
+        vec![],
+    );
     Ok(true)
 }
 
+/// Refuse to disable a mod the manifest tags `required`.
+///
+/// Without a manifest (offline) the tag is unknown and the toggle goes through.
+async fn ensure_not_required(dev: &str, name: &str) -> Result<(), String> {
+    let client = reqwest::Client::new();
+    let cfg = match ModsConfig::fetch_manifest(&client).await {
+        Ok((_, cfg, _, _)) => cfg,
+        Err(e) => {
+            log::warn!("Could not check tags of {dev}-{name}: {e}");
+            return Ok(());
+        }
+    };
+    let required = cfg.mods.iter().any(|m| {
+        m.dev.eq_ignore_ascii_case(dev) && m.name.eq_ignore_ascii_case(name) && m.is_required()
+    });
+    if required {
+        return Err(format!(
+            "{dev}-{name} is required by the manifest and can't be disabled"
+        ));
+    }
+    Ok(())
+}
+
 #[tauri::command]
 fn list_installed_mod_versions(
     app: tauri::AppHandle,
@@ -1170,6 +1668,16 @@ fn list_installed_mod_versions(
         return Ok(out);
     };
 
+    // Popularity comes from the last package list fetch; never hit the network here.
+    let stats: std::collections::HashMap<(String, String), thunderstore::PackageStats> =
+        thunderstore_cache_path(&app)
+            .ok()
+            .and_then(|p| thunderstore::read_cached_packages(&p))
+            .unwrap_or_default()
+            .iter()
+            .map(|p| ((p.owner.to_lowercase(), p.name.to_lowercase()), p.stats()))
+            .collect();
+
     for e in rd.flatten() {
         let path = e.path();
         if !path.is_dir() {
@@ -1196,10 +1704,13 @@ fn list_installed_mod_versions(
 
         match manifest {
             Ok(m) => {
+                let stat = stats.get(&(dev.to_lowercase(), name.to_lowercase()));
                 out.push(InstalledModVersion {
                     dev: dev.to_string(),
                     name: name.to_string(),
                     version: m.version_number,
+                    downloads: stat.map(|s| s.downloads),
+                    rating_score: stat.map(|s| s.rating_score),
                 });
             }
             Err(err) => {
@@ -1217,26 +1728,176 @@ fn list_installed_mod_versions(
     Ok(out)
 }
 
+/// The current manifest, or the last cached one (`origin.offline`) when no launcher base
+/// answers. `force_refresh` re-downloads it in full and fails instead of falling back.
 #[tauri::command]
-async fn get_manifest() -> Result<ManifestDto, String> {
+async fn get_manifest(
+    app: tauri::AppHandle,
+    force_refresh: Option<bool>,
+) -> Result<ManifestDto, String> {
     let client = reqwest::Client::new();
-    let (version, cfg, chain_config, manifests) =
-        mod_config::ModsConfig::fetch_manifest(&client).await?;
+    let (manifest, origin) =
+        mod_config::fetch_remote_manifest_or_cached(&client, force_refresh.unwrap_or(false))
+            .await?;
+    motd::notify_new(&app, &manifest);
     Ok(ManifestDto {
+        version: manifest.version,
+        manifests: manifest.manifest_ids(),
+        chain_config: manifest.chain_config,
+        mods: manifest.mods,
+        origin,
+    })
+}
+
+/// Download counts / rating for the given mods (all packages when `mods` is empty).
+///
+/// Backed by the Thunderstore package list cache, refreshed at most once per hour.
+#[tauri::command]
+async fn get_mod_stats(
+    app: tauri::AppHandle,
+    mods: Vec<DisabledMod>,
+) -> Result<Vec<thunderstore::PackageStats>, String> {
+    let cache_path = thunderstore_cache_path(&app)?;
+    let client = reqwest::Client::new();
+    let packages = thunderstore::fetch_community_packages(&client, &cache_path).await?;
+
+    let wanted: std::collections::HashSet<(String, String)> = mods
+        .iter()
+        .map(|m| (m.dev.to_lowercase(), m.name.to_lowercase()))
+        .collect();
+    Ok(packages
+        .iter()
+        .filter(|p| {
+            wanted.is_empty()
+                || wanted.contains(&(p.owner.to_lowercase(), p.name.to_lowercase()))
+        })
+        .map(|p| p.stats())
+        .collect())
+}
+
+/// Move installed plugins whose manifest range excludes `version` to `plugins_disabled`
+/// and report what was moved.
+#[tauri::command]
+async fn disable_incompatible_mods(
+    app: tauri::AppHandle,
+    version: u32,
+) -> Result<Vec<mods::DisabledIncompatibleMod>, String> {
+    let client = reqwest::Client::new();
+    let (_manifest_version, cfg, _chain_config, _manifests) =
+        ModsConfig::fetch_manifest(&client).await?;
+    let game_root = version_dir(&app, version)?;
+    mods::disable_incompatible_mods(&game_root, version, &cfg)
+}
+
+#[tauri::command]
+async fn get_package_readme(
+    app: tauri::AppHandle,
+    dev: String,
+    name: String,
+    version: String,
+) -> Result<thunderstore::PackageReadme, String> {
+    let cache_dir = readme_cache_dir(&app)?;
+    let client = reqwest::Client::new();
+    thunderstore::fetch_package_readme(&client, &cache_dir, &dev, &name, &version).await
+}
+
+#[derive(Debug, Clone, Serialize)]
+struct AvailableVersion {
+    version: u32,
+    manifest_id: String,
+    display_name: Option<String>,
+    release_date: Option<String>,
+    status: mod_config::GameVersionStatus,
+    status_note: Option<String>,
+    installed: bool,
+}
+
+/// Game versions offered by the remote manifest, newest first, with their labels.
+#[tauri::command]
+async fn list_available_versions(app: tauri::AppHandle) -> Result<Vec<AvailableVersion>, String> {
+    let client = reqwest::Client::new();
+    let remote = mod_config::fetch_remote_manifest(&client).await?;
+    let installed = list_installed_versions(app)?;
+    Ok(remote
+        .manifests
+        .into_iter()
+        .rev()
+        .map(|(version, info)| AvailableVersion {
+            version,
+            manifest_id: info.manifest_id,
+            display_name: info.display_name,
+            release_date: info.release_date,
+            status: info.status,
+            status_note: info.status_note,
+            installed: installed.contains(&version),
+        })
+        .collect())
+}
+
+#[derive(Debug, Clone, Serialize)]
+struct RemoteManifestDto {
+    source_url: String,
+    /// Unix seconds.
+    fetched_at: u64,
+    raw: serde_json::Value,
+    manifest: mod_config::RemoteManifest,
+}
+
+/// The remote manifest, both as served and as parsed by the launcher.
+#[tauri::command]
+async fn get_remote_manifest() -> Result<RemoteManifestDto, String> {
+    let client = reqwest::Client::new();
+    let urls = endpoints::manifest_urls(&mirrors::all_bases());
+    let timeout = tasks::step_timeout("manifest_fetch").unwrap_or(std::time::Duration::MAX);
+    let (source_url, raw) = mirrors::first_success(&urls, timeout, |url| {
+        let client = &client;
+        async move {
+            let raw: serde_json::Value = http_cache::get_json(client, &url).await?;
+            Ok((url, raw))
+        }
+    })
+    .await?;
+    let manifest = mod_config::parse_remote_manifest(raw.clone())?;
+    let fetched_at = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0);
+    Ok(RemoteManifestDto {
+        source_url,
+        fetched_at,
+        raw,
+        manifest,
+    })
+}
+
+#[derive(Debug, Clone, Serialize)]
+struct AppliedManifestDto {
+    version: u32,
+    /// `None` if no sync has fully applied a manifest to this install yet.
+    manifest_version: Option<u32>,
+    applied_at: Option<u64>,
+    files: usize,
+}
+
+/// What the lockfile says was last applied to `version` (default: active version).
+#[tauri::command]
+fn get_applied_manifest(
+    app: tauri::AppHandle,
+    version: Option<u32>,
+) -> Result<AppliedManifestDto, String> {
+    let version = resolve_version(&app, version)?;
+    let lock = integrity::read_lockfile(&version_dir(&app, version)?);
+    Ok(AppliedManifestDto {
         version,
-        chain_config,
-        mods: cfg.mods,
-        manifests,
+        manifest_version: lock.manifest_version,
+        applied_at: lock.applied_at,
+        files: lock.files.len(),
     })
 }
 
 #[tauri::command]
 fn list_installed_versions(app: tauri::AppHandle) -> Result<Vec<u32>, String> {
-    let base = app
-        .path()
-        .app_data_dir()
-        .map_err(|e| format!("failed to resolve app data dir: {e}"))?
-        .join("versions");
+    let base = storage::versions_dir(&app)?;
 
     let mut out: Vec<u32> = vec![];
     let Ok(rd) = std::fs::read_dir(&base) else {
@@ -1286,7 +1947,10 @@ fn list_config_files(app: tauri::AppHandle) -> Result<Vec<String>, String> {
             if !ty.is_file() {
                 continue;
             }
-            let canon = std::fs::canonicalize(&path).map_err(|e| e.to_string())?;
+            // Dangling links can't be opened in the editor either.
+            let Ok(canon) = std::fs::canonicalize(&path) else {
+                continue;
+            };
             if !canon.starts_with(&base_canon) {
                 continue;
             }
@@ -1328,7 +1992,9 @@ fn list_config_files_for_version_impl(
             if !ty.is_file() {
                 continue;
             }
-            let canon = std::fs::canonicalize(&path).map_err(|e| e.to_string())?;
+            let Ok(canon) = std::fs::canonicalize(&path) else {
+                continue;
+            };
             if !canon.starts_with(&base_canon) {
                 continue;
             }
@@ -1425,12 +2091,7 @@ fn list_config_files_for_mod_for_version(
 
 #[tauri::command]
 fn read_config_file(app: tauri::AppHandle, rel_path: String) -> Result<String, String> {
-    let base = shared_config_dir(&app)?;
-    let rel = std::path::Path::new(&rel_path);
-    if !is_safe_rel_path(rel) {
-        return Err("invalid path".to_string());
-    }
-    let path = base.join(rel);
+    let path = config_file_path(&shared_config_dir(&app)?, &rel_path)?;
     std::fs::read_to_string(&path).map_err(|e| e.to_string())
 }
 
@@ -1439,12 +2100,7 @@ fn read_bepinex_cfg(
     app: tauri::AppHandle,
     rel_path: String,
 ) -> Result<bepinex_cfg::FileData, String> {
-    let base = shared_config_dir(&app)?;
-    let rel = std::path::Path::new(&rel_path);
-    if !is_safe_rel_path(rel) {
-        return Err("invalid path".to_string());
-    }
-    let path = base.join(rel);
+    let path = config_file_path(&shared_config_dir(&app)?, &rel_path)?;
     let text = std::fs::read_to_string(&path).map_err(|e| e.to_string())?;
     bepinex_cfg::parse(&text)
 }
@@ -1455,12 +2111,7 @@ fn read_bepinex_cfg_for_version(
     version: u32,
     rel_path: String,
 ) -> Result<bepinex_cfg::FileData, String> {
-    let base = version_config_dir(&app, version)?;
-    let rel = std::path::Path::new(&rel_path);
-    if !is_safe_rel_path(rel) {
-        return Err("invalid path".to_string());
-    }
-    let path = base.join(rel);
+    let path = config_file_path(&version_config_dir(&app, version)?, &rel_path)?;
     let text = std::fs::read_to_string(&path).map_err(|e| e.to_string())?;
     bepinex_cfg::parse(&text)
 }
@@ -1475,15 +2126,9 @@ struct SetBepInExEntryArgs {
 
 #[tauri::command]
 fn set_bepinex_cfg_entry(app: tauri::AppHandle, args: SetBepInExEntryArgs) -> Result<bool, String> {
-    let base = shared_config_dir(&app)?;
-    let rel = std::path::Path::new(&args.rel_path);
-
     log::info!("set_bepinex_cfg_entry: {:?}", args);
 
-    if !is_safe_rel_path(rel) {
-        return Err("invalid path".to_string());
-    }
-    let path = base.join(rel);
+    let path = config_file_path(&shared_config_dir(&app)?, &args.rel_path)?;
 
     if let Some(parent) = path.parent() {
         std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
@@ -1527,7 +2172,7 @@ fn set_bepinex_cfg_entry(app: tauri::AppHandle, args: SetBepInExEntryArgs) -> Re
     }
 
     let new_text = bepinex_cfg::write(&file)?;
-    std::fs::write(&path, new_text).map_err(|e| e.to_string())?;
+    zip_utils::write_atomic(&path, new_text)?;
     Ok(true)
 }
 
@@ -1537,15 +2182,9 @@ fn set_bepinex_cfg_entry_for_version(
     version: u32,
     args: SetBepInExEntryArgs,
 ) -> Result<bool, String> {
-    let base = version_config_dir(&app, version)?;
-    let rel = std::path::Path::new(&args.rel_path);
-
     log::info!("set_bepinex_cfg_entry_for_version(v{version}): {:?}", args);
 
-    if !is_safe_rel_path(rel) {
-        return Err("invalid path".to_string());
-    }
-    let path = base.join(rel);
+    let path = config_file_path(&version_config_dir(&app, version)?, &args.rel_path)?;
 
     if let Some(parent) = path.parent() {
         std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
@@ -1589,7 +2228,7 @@ fn set_bepinex_cfg_entry_for_version(
     }
 
     let new_text = bepinex_cfg::write(&file)?;
-    std::fs::write(&path, new_text).map_err(|e| e.to_string())?;
+    zip_utils::write_atomic(&path, new_text)?;
     Ok(true)
 }
 
@@ -1601,16 +2240,11 @@ struct WriteConfigArgs {
 
 #[tauri::command]
 fn write_config_file(app: tauri::AppHandle, args: WriteConfigArgs) -> Result<bool, String> {
-    let base = shared_config_dir(&app)?;
-    let rel = std::path::Path::new(&args.rel_path);
-    if !is_safe_rel_path(rel) {
-        return Err("invalid path".to_string());
-    }
-    let path = base.join(rel);
+    let path = config_file_path(&shared_config_dir(&app)?, &args.rel_path)?;
     if let Some(parent) = path.parent() {
         std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
     }
-    std::fs::write(&path, args.contents).map_err(|e| e.to_string())?;
+    zip_utils::write_atomic(&path, args.contents)?;
     Ok(true)
 }
 
@@ -1653,15 +2287,9 @@ async fn check_app_update(app: tauri::AppHandle) -> Result<UpdateInfo, String> {
     let client = reqwest::Client::new();
     let github_release_url = "https://api.github.com/repos/p-asta/hq-launcher/releases/latest";
 
-    let github_release: GitHubRelease = client
-        .get(github_release_url)
-        .header("User-Agent", "hq-launcher-updater")
-        .send()
+    let github_release: GitHubRelease = http_cache::get_json(&client, github_release_url)
         .await
-        .map_err(|e| format!("Failed to fetch GitHub release: {e}"))?
-        .json()
-        .await
-        .map_err(|e| format!("Failed to parse GitHub release: {e}"))?;
+        .map_err(|e| format!("Failed to fetch GitHub release: {e}"))?;
 
     // 버전 비교 (tag_name에서 v 제거)
     let latest_version_str = github_release.tag_name.trim_start_matches('v').to_string();
@@ -1806,16 +2434,99 @@ fn get_app_version(app: tauri::AppHandle) -> Result<String, String> {
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
+    // Logging isn't up yet, so command line errors go straight to stderr.
+    let headless = match cli::parse(std::env::args().skip(1)) {
+        None => None,
+        Some(Ok(command)) => Some(command),
+        Some(Err(e)) => {
+            eprintln!("{e}\n{}", cli::USAGE);
+            std::process::exit(2);
+        }
+    };
+
+    // Hand this invocation to an already running launcher instead of racing it. A headless
+    // run only checks for one: forwarding would make the GUI act on the command too.
+    let acquired = if headless.is_some() {
+        single_instance::acquire_without_forwarding()
+    } else {
+        single_instance::acquire()
+    };
+    let (instance, guard_error) = match acquired {
+        Ok(Some(listener)) => (Some(listener), None),
+        Ok(None) if headless.is_some() => {
+            eprintln!("another launcher instance is running; close it before a headless run");
+            std::process::exit(1);
+        }
+        Ok(None) => return,
+        // Run without the guard rather than not at all; logged once logging is up.
+        Err(e) => (None, Some(e)),
+    };
+
     tauri::Builder::default()
         .plugin(tauri_plugin_global_shortcut::Builder::new().build())
         .plugin(tauri_plugin_updater::Builder::new().build())
         .manage(GameState::default())
+        .manage(crash_guard::CrashGuard::default())
         .manage(DownloadState::default())
+        .manage(tasks::TaskManager::default())
+        .manage(confirm::ConfirmState::default())
+        .manage(decisions::DecisionState::default())
         .manage(downloader::DepotLoginState::default())
-        .setup(|app| {
+        .setup(move |app| {
             // File logging (AppDataDir/logs/hq-launcher.log)
             logger::init(&app.handle()).map_err(|e| tauri::Error::Setup(e.into()))?;
 
+            if let Some(e) = guard_error {
+                log::warn!("Single-instance guard unavailable; running without it: {e}");
+            }
+
+            if let Some(listener) = instance {
+                single_instance::serve(listener, app.handle().clone());
+            }
+
+            // Headless: drop the window before the frontend loads (and starts its own tasks).
+            if headless.is_some() {
+                if let Some(window) = app.get_webview_window("main") {
+                    let _ = window.destroy();
+                }
+            }
+
+            // Conditional (ETag) requests for the manifest and release metadata.
+            match storage::cache_dir(app.handle()) {
+                Ok(dir) => http_cache::init(dir.join("http")),
+                Err(e) => log::warn!("HTTP cache disabled: {e}"),
+            }
+
+            // Bring data from older launchers up to date (e.g. move the pre-restructure
+            // `config/` tree) before anything reads from the new locations.
+            migrations::run(app.handle());
+
+            // Bandwidth cap and manifest channel live in module state, not read per call.
+            settings::apply(&settings::load(app.handle()));
+
+            // Fix `BepInEx/config` links left dangling by a deleted shared config dir.
+            if let Err(e) = installer::repair_config_links(app.handle()) {
+                log::warn!("Failed to repair config links on startup: {e}");
+            }
+
+            // Drop partial downloads left by a crash (nothing is running yet).
+            if let Err(e) = temp_files::sweep_orphans(app.handle()) {
+                log::warn!("Failed to sweep orphaned temp files: {e}");
+            }
+
+            // Old config backups, diagnostics bundles and logs past their retention.
+            if let Err(e) = retention::run(app.handle()) {
+                log::warn!("Failed to apply retention: {e}");
+            }
+
+            // Put back mods a safe-mode session left aside (launcher quit mid-game).
+            if let Err(e) = crash_guard::restore_leftovers(app.handle()) {
+                log::warn!("Failed to restore safe-mode mods: {e}");
+            }
+
+            // Report tasks that stop making progress (hung DepotDownloader, dead extraction).
+            tasks::spawn_watchdog(app.handle().clone());
+
             // Startup housekeeping (best-effort, won't block UI):
             // - Purge mods that remote manifest marks as enabled=false (and their configs)
             // - Ensure default config is downloaded if shared config dir is empty
@@ -1825,15 +2536,28 @@ pub fn run() {
                 {
                     log::warn!("Failed to purge remote-disabled mods on startup: {e}");
                 }
-                if let Err(e) = installer::ensure_default_config(app_handle.clone()).await {
+                if let Err(e) = installer::ensure_default_config(app_handle.clone(), false).await {
                     log::warn!("Failed to ensure default config on startup: {e}");
                 }
+                // Headless runs never launch the game, so they don't need Proton.
                 #[cfg(target_os = "linux")]
-                {
+                if headless.is_none() {
                     if let Err(e) = installer::install_proton_ge_impl(&app_handle).await {
                         log::warn!("Failed to install Proton-GE on startup: {e}");
                     }
                 }
+                if let Some(command) = headless {
+                    cli::run(app_handle, command).await;
+                    return;
+                }
+                if settings::load(&app_handle).auto_sync_on_startup {
+                    let steps = installer::SyncSteps::default();
+                    if let Err(e) =
+                        installer::sync_latest_install_from_manifest(app_handle.clone(), steps).await
+                    {
+                        log::warn!("Failed to sync installs on startup: {e}");
+                    }
+                }
             });
 
             Ok(())
@@ -1842,6 +2566,39 @@ pub fn run() {
         .invoke_handler(tauri::generate_handler![
             download,
             cancel_download,
+            cancel_install,
+            retry_task,
+            install_from_manifest_file,
+            installer::uninstall_version,
+            installer::move_install_root,
+            verify::verify_install,
+            decisions::resolve_decision,
+            decisions::set_decision_handler,
+            profiles::list_profiles,
+            profiles::create_profile,
+            profiles::rename_profile,
+            profiles::delete_profile,
+            profiles::activate_profile,
+            r2modman::import_r2modman_profile,
+            diagnostics::create_diagnostics,
+            diagnostics::submit_diagnostics,
+            doorstop::get_loader_config,
+            doorstop::set_loader_config,
+            history::get_install_history,
+            download_backend::get_download_backends,
+            config_backup::list_config_backups,
+            config_backup::restore_config_backup,
+            motd::get_announcements,
+            retention::run_retention,
+            #[cfg(target_os = "linux")]
+            umu::install_umu,
+            #[cfg(target_os = "linux")]
+            umu::get_umu_status,
+            overlay::get_overlay_writes,
+            footprint::get_profile_footprint,
+            storage_stats::get_storage_stats,
+            settings::get_settings,
+            settings::update_settings,
             sync_latest_install_from_manifest,
             check_mod_updates,
             apply_mod_updates,
@@ -1854,6 +2611,8 @@ pub fn run() {
             set_mod_enabled,
             list_installed_mod_versions,
             get_manifest,
+            get_remote_manifest,
+            get_applied_manifest,
             list_installed_versions,
             list_config_files,
             get_config_link_state,
@@ -1875,8 +2634,12 @@ pub fn run() {
             downloader::depot_login_submit_code,
             downloader::depot_get_login_state,
             downloader::depot_logout,
+            downloader::steam_login,
+            downloader::steam_submit_guard_code,
+            downloader::steam_logout,
             downloader::depot_download,
             downloader::depot_download_files,
+            downloader::discover_depot_manifests,
             check_app_update,
             download_app_update,
             install_app_update,
@@ -1884,8 +2647,53 @@ pub fn run() {
             installer::install_proton_ge,
             installer::get_current_proton_dir,
             open_version_folder,
+            open_path,
+            launch_options::get_launch_options,
+            launch_options::set_launch_options,
+            #[cfg(target_os = "linux")]
+            dxvk::list_dxvk,
+            #[cfg(target_os = "linux")]
+            dxvk::install_dxvk,
+            #[cfg(target_os = "linux")]
+            dxvk::update_dxvk,
+            #[cfg(target_os = "linux")]
+            dxvk::remove_dxvk,
+            shader_cache::get_shader_cache_info,
+            shader_cache::clear_shader_cache,
+            vulkan::check_vulkan,
+            gpu::list_gpus,
+            crash_guard::launch_game_safe_mode,
+            bisect::start_bisect,
+            bisect::bisect_report,
+            bisect::cancel_bisect,
+            task_log::get_task_log,
+            session::create_session,
+            session::join_session,
+            mirrors::get_mirrors,
+            mirrors::set_mirrors,
+            mirrors::run_mirror_speedtest,
+            plugin_scan::scan_plugins,
+            reset::reset_launcher,
+            allowlist::get_allowlist_status,
+            allowlist::export_allowlist,
+            allowlist::import_allowlist,
+            allowlist::set_strict_allowlist,
+            get_package_readme,
+            get_mod_stats,
+            disable_incompatible_mods,
+            list_available_versions,
+            get_active_version,
+            set_active_version,
             get_global_shortcut
         ])
-        .run(tauri::generate_context!())
-        .expect("error while running tauri application");
+        .build(tauri::generate_context!())
+        .expect("error while running tauri application")
+        .run(move |app, event| match event {
+            // Headless runs have no window; only `cli::run` ends them.
+            tauri::RunEvent::ExitRequested { code: None, api, .. } if headless.is_some() => {
+                api.prevent_exit();
+            }
+            tauri::RunEvent::Exit => cancel_install_on_exit(app),
+            _ => {}
+        });
 }
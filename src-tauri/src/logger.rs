@@ -9,22 +9,44 @@ use log4rs::{
         RollingFileAppender,
     },
     config::{Appender, Config, Root},
-    encode::pattern::PatternEncoder,
+    encode::{pattern::PatternEncoder, writer::simple::SimpleWriter, Encode},
 };
-use tauri::Manager;
 
 type AnyError = Box<dyn std::error::Error>;
 
+/// Forwards to log4rs and copies each line into the running tasks' logs (`task_log`).
+struct TeeLogger {
+    inner: log4rs::Logger,
+    encoder: PatternEncoder,
+}
+
+impl log::Log for TeeLogger {
+    fn enabled(&self, metadata: &log::Metadata) -> bool {
+        self.inner.enabled(metadata)
+    }
+
+    fn log(&self, record: &log::Record) {
+        if !self.inner.enabled(record.metadata()) {
+            return;
+        }
+        self.inner.log(record);
+        let mut line = SimpleWriter(Vec::new());
+        if self.encoder.encode(&mut line, record).is_ok() {
+            crate::task_log::capture(&String::from_utf8_lossy(&line.0));
+        }
+    }
+
+    fn flush(&self) {
+        self.inner.flush();
+    }
+}
+
 fn err(msg: impl Into<String>) -> AnyError {
     Box::new(std::io::Error::new(std::io::ErrorKind::Other, msg.into()))
 }
 
 fn log_paths(app: &tauri::AppHandle) -> Result<(PathBuf, PathBuf), AnyError> {
-    let logs_dir = app
-        .path()
-        .app_data_dir()
-        .map_err(|e| err(format!("failed to resolve app data dir: {e}")))?
-        .join("logs");
+    let logs_dir = crate::storage::logs_dir(app).map_err(|e| err(e.to_string()))?;
     let log_file = logs_dir.join("hq-launcher.log");
     Ok((logs_dir, log_file))
 }
@@ -90,9 +112,16 @@ pub fn init(app: &tauri::AppHandle) -> Result<(), AnyError> {
         .map_err(|e| err(e.to_string()))?;
 
     // Ignore error if already initialized.
-    if log4rs::init_config(cfg).is_err() {
+    let logger = log4rs::Logger::new(cfg);
+    let max_level = logger.max_log_level();
+    let tee = TeeLogger {
+        inner: logger,
+        encoder: PatternEncoder::new("{d(%H:%M:%S%.3f)} [{l}] {m}"),
+    };
+    if log::set_boxed_logger(Box::new(tee)).is_err() {
         return Ok(());
     }
+    log::set_max_level(max_level);
 
     std::panic::set_hook(Box::new(|info| {
         log::error!("panic: {info}");
@@ -0,0 +1,114 @@
+//! One-time fixes for installs and data left by older launcher versions.
+//!
+//! Each migration runs once, in order, at startup; the last applied one is recorded in
+//! `state/migrations.json`. Migrations must be idempotent: a crash between running one and
+//! recording it runs it again. A failing migration stops the chain and is retried on the
+//! next start. (Old temp file names need no migration: `temp_files::sweep_orphans` knows
+//! them and removes them once stale.)
+
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{installer, integrity, mods, storage};
+
+type Migration = fn(&tauri::AppHandle) -> Result<(), String>;
+
+/// `(id, migration)`, oldest first. Never reorder or remove entries; append new ones.
+const MIGRATIONS: [(&str, Migration); 2] = [
+    (
+        "legacy_config_layout",
+        installer::migrate_legacy_config_layout,
+    ),
+    ("adopt_pre_lockfile_installs", adopt_pre_lockfile_installs),
+];
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct MigrationState {
+    /// Number of `MIGRATIONS` applied.
+    version: u32,
+    #[serde(default)]
+    applied: Vec<AppliedMigration>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct AppliedMigration {
+    id: String,
+    at: u64,
+}
+
+fn state_path(app: &tauri::AppHandle) -> Result<PathBuf, String> {
+    Ok(storage::state_dir(app)?.join("migrations.json"))
+}
+
+fn read_state(app: &tauri::AppHandle) -> MigrationState {
+    state_path(app)
+        .ok()
+        .and_then(|p| std::fs::read_to_string(p).ok())
+        .and_then(|text| serde_json::from_str(&text).ok())
+        .unwrap_or_default()
+}
+
+fn write_state(app: &tauri::AppHandle, state: &MigrationState) -> Result<(), String> {
+    let path = state_path(app)?;
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    let json = serde_json::to_string_pretty(state).map_err(|e| e.to_string())?;
+    crate::zip_utils::write_atomic(&path, json)
+}
+
+/// Installs from launchers that predate the lockfile: link a real `BepInEx/config` dir to
+/// the shared config (its files are merged in, add-only) and record the installed plugins
+/// as the lockfile baseline.
+///
+/// Keyed on the missing lockfile so installs unlinked on purpose since then stay unlinked.
+fn adopt_pre_lockfile_installs(app: &tauri::AppHandle) -> Result<(), String> {
+    for (version, root) in installer::installed_version_dirs(app)? {
+        if root.join(".hq-launcher").join("lock.json").exists() {
+            continue;
+        }
+        let config = root.join("BepInEx").join("config");
+        let is_real_dir = std::fs::symlink_metadata(&config).is_ok_and(|md| md.is_dir());
+        if is_real_dir {
+            log::info!("v{version}: linking legacy BepInEx/config dir to the shared config");
+            installer::link_config_for_version(app, version)?;
+        }
+        let plugins = mods::plugins_dir(&root);
+        if plugins.is_dir() {
+            let rel = plugins.strip_prefix(&root).map_err(|e| e.to_string())?;
+            let recorded = integrity::record(&root, &[rel.to_path_buf()])?;
+            log::info!(
+                "v{version}: recorded lockfile baseline ({} files)",
+                recorded.len()
+            );
+        }
+    }
+    Ok(())
+}
+
+/// Apply the migrations not yet recorded. Errors are logged, not returned: the launcher
+/// still starts, and the failed migration is retried next time.
+pub fn run(app: &tauri::AppHandle) {
+    let mut state = read_state(app);
+    for (index, (id, migrate)) in MIGRATIONS.iter().enumerate().skip(state.version as usize) {
+        log::info!("Running migration {} ({id})", index + 1);
+        if let Err(e) = migrate(app) {
+            log::warn!("Migration {id} failed; will retry on next start: {e}");
+            return;
+        }
+        state.version = index as u32 + 1;
+        state.applied.push(AppliedMigration {
+            id: id.to_string(),
+            at: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|d| d.as_secs())
+                .unwrap_or(0),
+        });
+        if let Err(e) = write_state(app, &state) {
+            log::warn!("Failed to record migration {id}: {e}");
+            return;
+        }
+    }
+}
@@ -0,0 +1,258 @@
+//! Launcher base mirrors: fallback order and the mirror speedtest.
+//!
+//! Mirrors come from `Settings::mirrors` and the `mirrors` list of the last fetched
+//! manifest. Downloads from the launcher base (manifest, `default_config.zip`) go through
+//! `first_success`, which tries each base in turn so one unreachable host isn't fatal.
+//!
+//! The speedtest gives each base one small ranged request for `default_config.zip`; the
+//! ranking is kept in `state/mirror_ranking.json` for `RANKING_TTL` so the probe doesn't
+//! run before every download. Without `Settings::mirror_speedtest` (or with a single base)
+//! the configured order is used as is.
+//!
+//! Only launcher-base downloads use the ranking, and of those only the default config
+//! (the manifest is small and fetched in the configured order). Game files come from
+//! Steam through DepotDownloader and mods from Thunderstore; neither has mirrors to rank.
+
+use std::future::Future;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{endpoints, mod_config, settings, storage};
+
+/// How long a ranking stays valid.
+const RANKING_TTL: Duration = Duration::from_secs(6 * 60 * 60);
+/// Bytes requested from each mirror.
+const PROBE_BYTES: u64 = 256 * 1024;
+const PROBE_TIMEOUT: Duration = Duration::from_secs(5);
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MirrorSpeed {
+    pub base: String,
+    /// `None` when the probe failed.
+    pub bytes_per_sec: Option<u64>,
+    pub error: Option<String>,
+}
+
+/// `state/mirror_ranking.json`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct Ranking {
+    measured_at: u64,
+    /// Fastest first; failed probes last.
+    mirrors: Vec<MirrorSpeed>,
+}
+
+fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+fn ranking_path(app: &tauri::AppHandle) -> Result<std::path::PathBuf, String> {
+    Ok(storage::state_dir(app)?.join("mirror_ranking.json"))
+}
+
+fn read_ranking(app: &tauri::AppHandle) -> Option<Ranking> {
+    let text = std::fs::read_to_string(ranking_path(app).ok()?).ok()?;
+    serde_json::from_str(&text).ok()
+}
+
+fn write_ranking(app: &tauri::AppHandle, ranking: &Ranking) -> Result<(), String> {
+    let path = ranking_path(app)?;
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    let json = serde_json::to_string_pretty(ranking).map_err(|e| e.to_string())?;
+    crate::zip_utils::write_atomic(&path, json)
+}
+
+async fn probe(client: &reqwest::Client, base: &str) -> Result<u64, String> {
+    use futures_util::StreamExt;
+
+    let started = Instant::now();
+    let response = client
+        .get(endpoints::default_config_url_for(base))
+        .header("Range", format!("bytes=0-{}", PROBE_BYTES - 1))
+        .timeout(PROBE_TIMEOUT)
+        .send()
+        .await
+        .map_err(|e| e.to_string())?
+        .error_for_status()
+        .map_err(|e| e.to_string())?;
+
+    // Servers ignoring `Range` send the whole file; stop reading after the probe size.
+    let mut received: u64 = 0;
+    let mut stream = response.bytes_stream();
+    while let Some(chunk) = stream.next().await {
+        received += chunk.map_err(|e| e.to_string())?.len() as u64;
+        if received >= PROBE_BYTES {
+            break;
+        }
+    }
+    let secs = started.elapsed().as_secs_f64().max(0.001);
+    Ok((received as f64 / secs) as u64)
+}
+
+async fn measure(client: &reqwest::Client, bases: &[String]) -> Vec<MirrorSpeed> {
+    let probes = bases.iter().map(|base| async move {
+        let result = probe(client, base).await;
+        MirrorSpeed {
+            base: base.clone(),
+            bytes_per_sec: result.as_ref().ok().copied(),
+            error: result.err(),
+        }
+    });
+    let mut out = futures_util::future::join_all(probes).await;
+    // Stable sort keeps the configured order among failed probes.
+    out.sort_by_key(|m| std::cmp::Reverse(m.bytes_per_sec.map(|b| b + 1).unwrap_or(0)));
+    out
+}
+
+async fn run_speedtest(app: &tauri::AppHandle, bases: &[String]) -> Vec<MirrorSpeed> {
+    let client = reqwest::Client::new();
+    let mirrors = measure(&client, bases).await;
+    for m in &mirrors {
+        match (&m.bytes_per_sec, &m.error) {
+            (Some(bps), _) => log::info!("Mirror {}: {} KiB/s", m.base, bps / 1024),
+            (None, e) => log::warn!(
+                "Mirror {} probe failed: {}",
+                m.base,
+                e.as_deref().unwrap_or("")
+            ),
+        }
+    }
+    let ranking = Ranking {
+        measured_at: now_secs(),
+        mirrors: mirrors.clone(),
+    };
+    if let Err(e) = write_ranking(app, &ranking) {
+        log::warn!("Failed to save mirror ranking: {e}");
+    }
+    mirrors
+}
+
+/// Primary launcher base, then the configured mirrors and those the last manifest lists.
+pub fn all_bases() -> Vec<String> {
+    let mut mirrors = endpoints::configured_mirrors();
+    if let Some(manifest) = mod_config::cached_remote_manifest() {
+        mirrors.extend(manifest.mirrors);
+    }
+    endpoints::launcher_bases(&mirrors)
+}
+
+/// Run `fetch` against each of `bases` in order, giving each `timeout`, until one
+/// succeeds. Fails with every base's error once all of them did.
+pub async fn first_success<T, F, Fut>(
+    bases: &[String],
+    timeout: Duration,
+    mut fetch: F,
+) -> Result<T, String>
+where
+    F: FnMut(String) -> Fut,
+    Fut: Future<Output = Result<T, String>>,
+{
+    let mut errors = vec![];
+    for base in bases {
+        let err = match tokio::time::timeout(timeout, fetch(base.clone())).await {
+            Ok(Ok(value)) => return Ok(value),
+            Ok(Err(e)) => e,
+            Err(_) => format!("no response within {}s", timeout.as_secs()),
+        };
+        if bases.len() > 1 {
+            log::warn!("{base} failed: {err}");
+        }
+        errors.push(if bases.len() > 1 {
+            format!("{base}: {err}")
+        } else {
+            err
+        });
+    }
+    if errors.is_empty() {
+        return Err("no launcher base configured".to_string());
+    }
+    Err(errors.join("; "))
+}
+
+/// Launcher bases in the order downloads should try them.
+///
+/// Uses a fresh ranking covering exactly the configured bases, otherwise runs the
+/// speedtest first when enabled.
+pub async fn ordered_bases(app: &tauri::AppHandle) -> Vec<String> {
+    let settings = settings::load(app);
+    let bases = all_bases();
+    if bases.len() < 2 || !settings.mirror_speedtest {
+        return bases;
+    }
+
+    let cached = read_ranking(app).filter(|r| {
+        let mut ranked: Vec<&String> = r.mirrors.iter().map(|m| &m.base).collect();
+        let mut configured: Vec<&String> = bases.iter().collect();
+        ranked.sort();
+        configured.sort();
+        now_secs().saturating_sub(r.measured_at) < RANKING_TTL.as_secs() && ranked == configured
+    });
+    let mirrors = match cached {
+        Some(r) => r.mirrors,
+        None => run_speedtest(app, &bases).await,
+    };
+    mirrors.into_iter().map(|m| m.base).collect()
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct MirrorStatus {
+    pub mirrors: Vec<String>,
+    pub speedtest: bool,
+    /// Last ranking, if any (may be stale).
+    pub ranking: Vec<MirrorSpeed>,
+    pub measured_at: Option<u64>,
+}
+
+fn status(app: &tauri::AppHandle) -> MirrorStatus {
+    let settings = settings::load(app);
+    let ranking = read_ranking(app);
+    MirrorStatus {
+        mirrors: settings.mirrors,
+        speedtest: settings.mirror_speedtest,
+        measured_at: ranking.as_ref().map(|r| r.measured_at),
+        ranking: ranking.map(|r| r.mirrors).unwrap_or_default(),
+    }
+}
+
+#[tauri::command]
+pub fn get_mirrors(app: tauri::AppHandle) -> MirrorStatus {
+    status(&app)
+}
+
+/// Replace the mirror list and/or toggle the speedtest. Drops the saved ranking.
+#[tauri::command]
+pub fn set_mirrors(
+    app: tauri::AppHandle,
+    mirrors: Option<Vec<String>>,
+    speedtest: Option<bool>,
+) -> Result<MirrorStatus, String> {
+    let mut settings = settings::load(&app);
+    if let Some(mirrors) = mirrors {
+        for m in &mirrors {
+            let url =
+                reqwest::Url::parse(m.trim()).map_err(|e| format!("invalid mirror {m}: {e}"))?;
+            if !matches!(url.scheme(), "http" | "https") {
+                return Err(format!("invalid mirror {m}: must be http(s)"));
+            }
+        }
+        endpoints::set_mirrors(mirrors.clone());
+        settings.mirrors = mirrors;
+        let _ = std::fs::remove_file(ranking_path(&app)?);
+    }
+    if let Some(speedtest) = speedtest {
+        settings.mirror_speedtest = speedtest;
+    }
+    settings::save(&app, &settings)?;
+    Ok(status(&app))
+}
+
+/// Probe every configured base now and save the ranking.
+#[tauri::command]
+pub async fn run_mirror_speedtest(app: tauri::AppHandle) -> Result<Vec<MirrorSpeed>, String> {
+    Ok(run_speedtest(&app, &all_bases()).await)
+}
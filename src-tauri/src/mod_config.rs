@@ -1,4 +1,5 @@
 use std::collections::BTreeMap;
+use std::time::Duration;
 
 use serde::Deserializer;
 use serde::{Deserialize, Serialize};
@@ -30,8 +31,25 @@ pub struct ModEntry {
     /// - game >= 73 uses 1.1.1 (overrides)
     #[serde(default, deserialize_with = "deserialize_version_config")]
     pub version_config: BTreeMap<u32, String>,
+
+    /// Policy tags, e.g. `required`, `client-only`, `server-synced`.
+    #[serde(default)]
+    pub tags: Vec<String>,
+
+    /// Expected SHA-256 (hex) of the package zip, per Thunderstore version.
+    ///
+    /// Example: { "1.1.1": "9f86d0..." }
+    #[serde(default)]
+    pub sha256: BTreeMap<String, String>,
 }
 
+/// The mod must stay enabled: players can't disable it.
+pub const TAG_REQUIRED: &str = "required";
+/// Only affects the local client, so toggling it never desyncs a lobby.
+pub const TAG_CLIENT_ONLY: &str = "client-only";
+/// Config is pushed by the host; informational only.
+pub const TAG_SERVER_SYNCED: &str = "server-synced";
+
 fn deserialize_version_config<'de, D>(deserializer: D) -> Result<BTreeMap<u32, String>, D::Error>
 where
     D: Deserializer<'de>,
@@ -47,40 +65,331 @@ where
         .collect()
 }
 
-fn deserialize_u32_string_map<'de, D>(deserializer: D) -> Result<BTreeMap<u32, String>, D::Error>
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ModsConfig {
+    pub mods: Vec<ModEntry>,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+// ---------- Public API ----------
+
+/// One game version entry of `RemoteManifest.manifests`.
+///
+/// The manifest accepts either a bare depot manifest id (`"73": "123..."`) or an object
+/// with a human-readable label (`"73": { "manifest_id": "123...", "display_name": "..." }`).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GameVersionInfo {
+    pub manifest_id: String,
+    #[serde(default)]
+    pub display_name: Option<String>,
+    /// Free-form date as published in the manifest (e.g. `2024-01-15`).
+    #[serde(default)]
+    pub release_date: Option<String>,
+    #[serde(default)]
+    pub status: GameVersionStatus,
+    /// Why the version was retired / what to use instead.
+    #[serde(default)]
+    pub status_note: Option<String>,
+    /// Estimated bytes on disk once installed (game, BepInEx and mods), for the disk space
+    /// check before an install.
+    #[serde(default)]
+    pub install_size: Option<u64>,
+}
+
+/// Support level of a game version, as published in the manifest.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum GameVersionStatus {
+    #[default]
+    Supported,
+    /// Still installable, but on its way out.
+    Deprecated,
+    /// No longer maintained; mods may not work.
+    Unsupported,
+}
+
+impl GameVersionInfo {
+    /// Deprecated/unsupported versions: warn before install, never pick as "latest".
+    pub fn is_retired(&self) -> bool {
+        self.status != GameVersionStatus::Supported
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum GameVersionEntry {
+    Id(String),
+    Info(GameVersionInfo),
+}
+
+fn deserialize_game_versions<'de, D>(
+    deserializer: D,
+) -> Result<BTreeMap<u32, GameVersionInfo>, D::Error>
 where
     D: Deserializer<'de>,
 {
-    let string_map: BTreeMap<String, String> = BTreeMap::deserialize(deserializer)?;
-    string_map
-        .into_iter()
+    let raw: BTreeMap<String, GameVersionEntry> = BTreeMap::deserialize(deserializer)?;
+    raw.into_iter()
         .map(|(k, v)| {
-            k.parse::<u32>()
-                .map(|key| (key, v))
-                .map_err(serde::de::Error::custom)
+            let key = k.parse::<u32>().map_err(serde::de::Error::custom)?;
+            let info = match v {
+                GameVersionEntry::Id(manifest_id) => GameVersionInfo {
+                    manifest_id,
+                    display_name: None,
+                    release_date: None,
+                    status: GameVersionStatus::Supported,
+                    status_note: None,
+                    install_size: None,
+                },
+                GameVersionEntry::Info(info) => info,
+            };
+            Ok((key, info))
         })
         .collect()
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-#[serde(rename_all = "camelCase")]
-pub struct ModsConfig {
-    pub mods: Vec<ModEntry>,
+/// BepInExPack installed when no `bepinex` manifest entry covers the game version.
+pub const DEFAULT_BEPINEXPACK_VERSION: &str = "5.4.2304";
+
+/// Runtime a BepInEx package is built for; decides its layout and doorstop setup.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BepInExLoader {
+    /// BepInEx 5 for Mono games (BepInExPack).
+    #[default]
+    Mono,
+    /// BepInEx 6 for IL2CPP games (`BepInEx-Unity.IL2CPP` builds), which bring their own
+    /// .NET runtime under `dotnet/`.
+    Il2cpp,
 }
 
-fn default_true() -> bool {
-    true
+/// Thunderstore package providing BepInEx for a range of game versions.
+///
+/// Example: `{ "namespace": "BepInEx", "name": "BepInExPack", "version": "5.4.2100",
+/// "high_cap": 49 }`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BepInExPackage {
+    #[serde(default = "default_bepinex_namespace")]
+    pub namespace: String,
+    #[serde(default = "default_bepinex_name")]
+    pub name: String,
+    pub version: String,
+    #[serde(default)]
+    pub loader: BepInExLoader,
+    /// Direct zip URL; `None` downloads the package from Thunderstore.
+    #[serde(default)]
+    pub url: Option<String>,
+    /// Expected SHA-256 (hex) of the zip.
+    #[serde(default)]
+    pub sha256: Option<String>,
+    /// Inclusive game version range; open-ended when unset.
+    #[serde(default)]
+    pub low_cap: Option<u32>,
+    #[serde(default)]
+    pub high_cap: Option<u32>,
 }
 
-// ---------- Public API ----------
+fn default_bepinex_namespace() -> String {
+    "BepInEx".to_string()
+}
+
+fn default_bepinex_name() -> String {
+    "BepInExPack".to_string()
+}
+
+impl BepInExPackage {
+    fn covers(&self, game_version: u32) -> bool {
+        self.low_cap.is_none_or(|lo| game_version >= lo)
+            && self.high_cap.is_none_or(|hi| game_version <= hi)
+    }
+
+    pub fn download_url(&self) -> String {
+        self.url.clone().unwrap_or_else(|| {
+            crate::endpoints::thunderstore_download_url(&self.namespace, &self.name, &self.version)
+        })
+    }
+
+    /// e.g. `BepInExPack 5.4.2304`.
+    pub fn label(&self) -> String {
+        format!("{} {}", self.name, self.version)
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AnnouncementSeverity {
+    #[default]
+    Info,
+    Warning,
+    Critical,
+}
+
+/// Message from the maintainers shown once per id (see `motd`), e.g. a moved session.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Announcement {
+    /// Stable id; changing it shows the announcement again.
+    pub id: String,
+    pub title: String,
+    #[serde(default)]
+    pub body: String,
+    #[serde(default)]
+    pub severity: AnnouncementSeverity,
+    /// Unix seconds after which the announcement is no longer shown.
+    #[serde(default)]
+    pub expires_at: Option<u64>,
+}
+
+impl Announcement {
+    pub fn is_active(&self, now: u64) -> bool {
+        !self.id.trim().is_empty() && self.expires_at.is_none_or(|t| now < t)
+    }
+}
+
+/// Wine registry value set in the Proton prefix before launch (see `prefix_tweaks`).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RegistryTweak {
+    /// Stable id; the tweak is imported again when its other fields change.
+    pub id: String,
+    /// e.g. `HKCU\Software\Wine\DllOverrides` (`HKCU`/`HKLM` only).
+    pub key: String,
+    /// Value name; empty for the key's default value.
+    #[serde(default)]
+    pub value: String,
+    /// `REG_SZ`, `REG_DWORD` (decimal or `0x` hex `data`) or `delete`.
+    #[serde(default = "default_registry_kind")]
+    pub kind: String,
+    #[serde(default)]
+    pub data: String,
+}
 
-#[derive(Debug, Clone, Deserialize)]
+fn default_registry_kind() -> String {
+    "REG_SZ".to_string()
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RemoteManifest {
     pub version: u32,
-    #[serde(default, deserialize_with = "deserialize_u32_string_map")]
-    pub manifests: BTreeMap<u32, String>,
+    #[serde(default, deserialize_with = "deserialize_game_versions")]
+    pub manifests: BTreeMap<u32, GameVersionInfo>,
     pub chain_config: Vec<Vec<String>>,
     pub mods: Vec<ModEntry>,
+    /// Default Wine DLL overrides (`dll=mode`, e.g. `version=n,b`) applied on every launch.
+    #[serde(default)]
+    pub dll_overrides: Vec<String>,
+    /// Recommended `PULSE_LATENCY_MSEC`; turns the audio latency fix on by default.
+    #[serde(default)]
+    pub pulse_latency_msec: Option<u32>,
+    /// Install the nearest older version when a pinned one was yanked from Thunderstore.
+    #[serde(default)]
+    pub yanked_fallback: bool,
+    /// Version of `default_config.zip`, bumped independently of `version` (mods).
+    #[serde(default)]
+    pub config_version: Option<u32>,
+    /// Expected SHA-256 (hex) of `default_config.zip`; checked before extraction.
+    #[serde(default)]
+    pub config_sha256: Option<String>,
+    /// Expected size in bytes of `default_config.zip`.
+    #[serde(default)]
+    pub config_size: Option<u64>,
+    /// Config files (relative to `BepInEx/config`) a config update replaces even when
+    /// they were edited locally, e.g. to fix a broken default.
+    #[serde(default)]
+    pub config_overwrite: Vec<String>,
+    /// Expected SHA-256 (hex) of the default BepInExPack zip, installed where `bepinex`
+    /// has no entry for the game version.
+    #[serde(default)]
+    pub bepinexpack_sha256: Option<String>,
+    /// BepInEx package per game version range; the first entry covering a version wins.
+    /// Versions no entry covers get BepInExPack `DEFAULT_BEPINEXPACK_VERSION`.
+    #[serde(default)]
+    pub bepinex: Vec<BepInExPackage>,
+    /// Folders under `BepInEx/data` (mod save data) linked to one shared copy, like
+    /// `BepInEx/config`, so every installed version sees the same data. Other paths are
+    /// ignored.
+    #[serde(default)]
+    pub shared_folders: Vec<String>,
+    /// Maintainer endpoint accepting diagnostics uploads (see `diagnostics`); unset
+    /// disables `submit_diagnostics`.
+    #[serde(default)]
+    pub diagnostics_url: Option<String>,
+    /// Registry tweaks for the Proton prefix, e.g. disabling `winemenubuilder.exe` or
+    /// setting the Windows version.
+    #[serde(default)]
+    pub registry_tweaks: Vec<RegistryTweak>,
+    /// Other bases serving this manifest and `default_config.zip`, tried when the primary
+    /// base is unreachable (after `Settings::mirrors`).
+    #[serde(default)]
+    pub mirrors: Vec<String>,
+    /// Messages for the group, announced once each (see `motd`).
+    #[serde(default)]
+    pub announcements: Vec<Announcement>,
+    /// Entries the launcher rewrote while parsing (see `MOD_ALIASES`); not part of the
+    /// published document.
+    #[serde(skip)]
+    pub corrections: Vec<ManifestCorrection>,
+}
+
+/// One manifest mod entry the launcher fixed up before using it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ManifestCorrection {
+    /// As published.
+    pub dev: String,
+    pub name: String,
+    /// As used.
+    pub corrected_dev: String,
+    pub corrected_name: String,
+    pub reason: String,
+}
+
+impl RemoteManifest {
+    /// Game versions the manifest marks as deprecated or unsupported.
+    pub fn retired_versions(&self) -> Vec<u32> {
+        self.manifests
+            .iter()
+            .filter(|(_, info)| info.is_retired())
+            .map(|(v, _)| *v)
+            .collect()
+    }
+
+    /// BepInEx package to install into `game_version`.
+    pub fn bepinex_for(&self, game_version: u32) -> BepInExPackage {
+        if let Some(pkg) = self.bepinex.iter().find(|p| p.covers(game_version)) {
+            return pkg.clone();
+        }
+        BepInExPackage {
+            namespace: default_bepinex_namespace(),
+            name: default_bepinex_name(),
+            version: DEFAULT_BEPINEXPACK_VERSION.to_string(),
+            loader: BepInExLoader::Mono,
+            url: None,
+            sha256: self.bepinexpack_sha256.clone(),
+            low_cap: None,
+            high_cap: None,
+        }
+    }
+
+    /// The manifest's entry for `version`.
+    pub fn game_version(&self, version: u32) -> crate::error::Result<&GameVersionInfo> {
+        crate::storage::check_version(version)?;
+        self.manifests
+            .get(&version)
+            .ok_or(crate::error::Error::UnknownVersion {
+                version,
+                reason: "not in the manifest",
+            })
+    }
+
+    /// Depot manifest id per game version.
+    pub fn manifest_ids(&self) -> BTreeMap<u32, String> {
+        self.manifests
+            .iter()
+            .map(|(v, info)| (*v, info.manifest_id.clone()))
+            .collect()
+    }
 }
 
 impl ModsConfig {
@@ -89,39 +398,182 @@ impl ModsConfig {
     pub async fn fetch_manifest(
         client: &reqwest::Client,
     ) -> Result<(u32, Self, Vec<Vec<String>>, BTreeMap<u32, String>), String> {
-        // Use stable manifest only.
-        let url = "https://f.asta.rs/hq-launcher/manifest.json";
-        log::info!("Fetching manifest from {url}");
-        let manifest = client
-            .get(url)
-            .send()
-            .await
-            .map_err(|e| e.to_string())?
-            .error_for_status()
-            .map_err(|e| e.to_string())?
-            .json::<RemoteManifest>()
-            .await
-            .map_err(|e| e.to_string())?;
-
-        let manifests = manifest.manifests.clone();
-        let mut cfg = ModsConfig {
+        // Compatibility checks run on this too, and must keep working offline.
+        let (manifest, _) = fetch_remote_manifest_or_cached(client, false).await?;
+        let manifests = manifest.manifest_ids();
+        let cfg = ModsConfig {
+            mods: manifest.mods,
+        };
+        Ok((manifest.version, cfg, manifest.chain_config, manifests))
+    }
+
+    /// Same as `fetch_manifest`, against an explicit URL.
+    pub async fn fetch_manifest_from(
+        client: &reqwest::Client,
+        url: &str,
+    ) -> Result<(u32, Self, Vec<Vec<String>>, BTreeMap<u32, String>), String> {
+        let manifest = fetch_remote_manifest_from(client, url).await?;
+        let manifests = manifest.manifest_ids();
+        let cfg = ModsConfig {
             mods: manifest.mods,
         };
-        let _ = normalize_aliases(&mut cfg);
         Ok((manifest.version, cfg, manifest.chain_config, manifests))
     }
 }
 
-fn normalize_aliases(cfg: &mut ModsConfig) -> bool {
-    let mut changed = false;
-    for m in &mut cfg.mods {
-        // Hardy-LCMaxSoundsFix (common typo: LCMaxSoundFix)
-        if m.dev == "Hardy" && m.name == "LCMaxSoundFix" {
-            m.name = "LCMaxSoundsFix".to_string();
-            changed = true;
+/// Time one base gets to serve the manifest before the next mirror is tried.
+fn manifest_mirror_timeout() -> Duration {
+    crate::tasks::step_timeout("manifest_fetch").unwrap_or(Duration::MAX)
+}
+
+/// Full remote manifest (including per-version labels), with mod aliases normalized.
+/// Falls back to the mirrors when the primary base doesn't answer.
+pub async fn fetch_remote_manifest(client: &reqwest::Client) -> Result<RemoteManifest, String> {
+    let bases = crate::mirrors::all_bases();
+    let urls = crate::endpoints::manifest_urls(&bases);
+    crate::mirrors::first_success(&urls, manifest_mirror_timeout(), |url| async move {
+        fetch_remote_manifest_from(client, &url).await
+    })
+    .await
+}
+
+pub async fn fetch_remote_manifest_from(
+    client: &reqwest::Client,
+    url: &str,
+) -> Result<RemoteManifest, String> {
+    log::info!("Fetching manifest from {url}");
+    let raw: serde_json::Value = crate::http_cache::get_json(client, url).await?;
+    parse_remote_manifest(raw)
+}
+
+/// The last manifest fetched through the HTTP cache, without touching the network.
+pub fn cached_remote_manifest() -> Option<RemoteManifest> {
+    cached_remote_manifest_with_info().map(|(manifest, _)| manifest)
+}
+
+fn cached_remote_manifest_with_info() -> Option<(RemoteManifest, crate::http_cache::CacheInfo)>
+{
+    // Only the primary base and configured mirrors: the manifest's own mirror list comes
+    // from here. Most recently fetched first, in case the user switched bases.
+    let bases = crate::endpoints::launcher_bases(&crate::endpoints::configured_mirrors());
+    let mut cached: Vec<_> = crate::endpoints::manifest_urls(&bases)
+        .iter()
+        .filter_map(|url| crate::http_cache::cache_info(url))
+        .collect();
+    cached.sort_by(|a, b| b.fetched_at.cmp(&a.fetched_at));
+    cached.into_iter().find_map(|info| {
+        let raw = crate::http_cache::cached_json(&info.url)?;
+        Some((parse_remote_manifest(raw).ok()?, info))
+    })
+}
+
+/// Where the manifest returned by `fetch_remote_manifest_or_cached` came from.
+#[derive(Debug, Clone, Serialize)]
+pub struct ManifestOrigin {
+    /// No base answered; this is the cached copy.
+    pub offline: bool,
+    pub url: Option<String>,
+    pub etag: Option<String>,
+    /// Unix seconds the manifest was last fetched (or revalidated).
+    pub fetched_at: Option<u64>,
+    /// Why the network fetch failed, when offline.
+    pub error: Option<String>,
+}
+
+/// `fetch_remote_manifest`, falling back to the last cached copy when no base answers.
+///
+/// `force_refresh` downloads the full manifest (no `If-None-Match`) and never falls back:
+/// the caller asked for a fresh copy, so a network failure is an error.
+pub async fn fetch_remote_manifest_or_cached(
+    client: &reqwest::Client,
+    force_refresh: bool,
+) -> Result<(RemoteManifest, ManifestOrigin), String> {
+    let urls = crate::endpoints::manifest_urls(&crate::mirrors::all_bases());
+    let timeout = manifest_mirror_timeout();
+    let fetched = crate::mirrors::first_success(&urls, timeout, |url| async move {
+        log::info!("Fetching manifest from {url}");
+        let raw: serde_json::Value = if force_refresh {
+            crate::http_cache::refresh_json(client, &url).await?
+        } else {
+            crate::http_cache::get_json(client, &url).await?
+        };
+        Ok((parse_remote_manifest(raw)?, url))
+    })
+    .await;
+
+    match fetched {
+        Ok((manifest, url)) => {
+            let info = crate::http_cache::cache_info(&url);
+            let origin = ManifestOrigin {
+                offline: false,
+                etag: info.as_ref().and_then(|i| i.etag.clone()),
+                fetched_at: info.map(|i| i.fetched_at),
+                url: Some(url),
+                error: None,
+            };
+            Ok((manifest, origin))
+        }
+        Err(e) if force_refresh => Err(e),
+        Err(e) => {
+            let Some((manifest, info)) = cached_remote_manifest_with_info() else {
+                return Err(e);
+            };
+            log::warn!(
+                "Manifest unreachable ({e}); offline, using the copy cached at {}",
+                info.fetched_at
+            );
+            let origin = ManifestOrigin {
+                offline: true,
+                url: Some(info.url),
+                etag: info.etag,
+                fetched_at: Some(info.fetched_at),
+                error: Some(e),
+            };
+            Ok((manifest, origin))
         }
     }
-    changed
+}
+
+/// Parse a raw manifest document, normalizing mod aliases.
+pub fn parse_remote_manifest(raw: serde_json::Value) -> Result<RemoteManifest, String> {
+    let mut manifest: RemoteManifest =
+        serde_json::from_value(raw).map_err(|e| format!("invalid manifest: {e}"))?;
+
+    let mut cfg = ModsConfig {
+        mods: std::mem::take(&mut manifest.mods),
+    };
+    manifest.corrections = normalize_aliases(&mut cfg);
+    manifest.mods = cfg.mods;
+    Ok(manifest)
+}
+
+/// Known misspellings in published manifests: `(dev, name)` as written, as on Thunderstore.
+const MOD_ALIASES: [((&str, &str), (&str, &str)); 1] = [
+    // Common typo: LCMaxSoundFix
+    (("Hardy", "LCMaxSoundFix"), ("Hardy", "LCMaxSoundsFix")),
+];
+
+/// Rewrite aliased entries in place. Returns what was rewritten.
+fn normalize_aliases(cfg: &mut ModsConfig) -> Vec<ManifestCorrection> {
+    let mut corrections = vec![];
+    for m in &mut cfg.mods {
+        let Some((_, (dev, name))) = MOD_ALIASES
+            .iter()
+            .find(|((dev, name), _)| m.dev == *dev && m.name == *name)
+        else {
+            continue;
+        };
+        corrections.push(ManifestCorrection {
+            dev: m.dev.clone(),
+            name: m.name.clone(),
+            corrected_dev: dev.to_string(),
+            corrected_name: name.to_string(),
+            reason: "known alias".to_string(),
+        });
+        m.dev = dev.to_string();
+        m.name = name.to_string();
+    }
+    corrections
 }
 
 impl ModEntry {
@@ -142,6 +594,18 @@ impl ModEntry {
         true
     }
 
+    pub fn has_tag(&self, tag: &str) -> bool {
+        self.tags.iter().any(|t| t.eq_ignore_ascii_case(tag))
+    }
+
+    pub fn is_required(&self) -> bool {
+        self.has_tag(TAG_REQUIRED)
+    }
+
+    pub fn is_client_only(&self) -> bool {
+        self.has_tag(TAG_CLIENT_ONLY)
+    }
+
     pub fn pinned_version_for(&self, game_version: u32) -> Option<&str> {
         // Interpret `version_config` as "threshold pinning":
         // use the greatest key <= game_version.
@@ -158,3 +622,43 @@ impl ModEntry {
             })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::path::{Path, PathBuf};
+
+    use crate::test_support::{build_zip, TempDir};
+    use crate::zip_utils::copy_mod_config_defaults;
+
+    #[test]
+    fn mod_config_defaults_are_copied_add_only() {
+        let tmp = TempDir::new("mod-config-defaults");
+        let zip_path = tmp.path().join("Pack.zip");
+        let shared = tmp.path().join("game_config").join("shared");
+        std::fs::create_dir_all(&shared).unwrap();
+        std::fs::write(shared.join("Existing.cfg"), "user edited").unwrap();
+        std::fs::write(
+            &zip_path,
+            build_zip(&[
+                ("config/Pack.cfg", "pack default"),
+                ("BepInEx/config/Sub/Other.cfg", "other default"),
+                ("config/Existing.cfg", "pack default"),
+                ("BepInEx/plugins/Pack.dll", "dll"),
+            ]),
+        )
+        .unwrap();
+
+        let mut added = copy_mod_config_defaults(&zip_path, &shared).expect("copy");
+        added.sort();
+
+        assert_eq!(
+            added,
+            vec![
+                PathBuf::from("Pack.cfg"),
+                Path::new("Sub").join("Other.cfg"),
+            ]
+        );
+        assert_eq!(std::fs::read_to_string(shared.join("Existing.cfg")).unwrap(), "user edited");
+        assert!(!shared.join("Pack.dll").exists());
+    }
+}
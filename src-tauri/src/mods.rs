@@ -1,14 +1,25 @@
 use std::cmp::Ordering;
 use std::collections::HashMap;
 use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering as AtomicOrdering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
 
 use crate::bepinex_cfg::read_manifest;
+use crate::cache;
+use crate::allowlist;
+use crate::download_backend;
+use crate::downloader::HttpDownloadError;
+use crate::endpoints;
 use crate::mod_config::{ModEntry, ModsConfig};
-use crate::thunderstore::{self, PackageListing};
-use crate::zip_utils::extract_thunderstore_into_plugins_with_progress;
+use crate::integrity;
+use crate::progress::{self, ModChange, ModVersionFallbackPayload};
+use crate::thunderstore::{self, PackageListing, PackageVersion};
+use crate::zip_utils::{copy_mod_config_defaults, extract_thunderstore_into_plugins_with_progress};
+use futures_util::StreamExt;
 use semver::Version;
 
-fn read_manifest_allow_old(mod_dir: &Path) -> Result<crate::bepinex_cfg::BepInExManifest, String> {
+pub(crate) fn read_manifest_allow_old(mod_dir: &Path) -> Result<crate::bepinex_cfg::BepInExManifest, String> {
     let manifest = mod_dir.join("manifest.json");
     if manifest.exists() {
         return read_manifest(&manifest);
@@ -56,13 +67,321 @@ fn latest_pkg_version<'a>(
         .max_by(|a, b| cmp_version_str(&a.version_number, &b.version_number))
 }
 
-fn thunderstore_download_url(dev: &str, name: &str, version: &str) -> String {
-    // Direct download endpoint (zip):
-    // https://thunderstore.io/package/download/{dev}/{modname}/{version}/
-    format!(
-        "https://thunderstore.io/package/download/{}/{}/{}/",
-        dev, name, version
-    )
+/// Version a spec resolves to (pin if listed, else latest); mirrors the install loops.
+fn planned_version<'a>(
+    spec: &ModEntry,
+    pkg: &'a PackageListing,
+    game_version: u32,
+) -> Option<&'a PackageVersion> {
+    spec.pinned_version_for(game_version)
+        .and_then(|pin| pkg.versions.iter().find(|v| v.version_number == pin))
+        .or_else(|| latest_pkg_version(&pkg.versions))
+}
+
+fn planned_size(
+    package_map: &HashMap<(String, String), PackageListing>,
+    spec: &ModEntry,
+    game_version: u32,
+) -> u64 {
+    package_map
+        .get(&(spec.dev.to_lowercase(), spec.name.to_lowercase()))
+        .and_then(|pkg| planned_version(spec, pkg, game_version))
+        .map(|v| v.file_size)
+        .unwrap_or(0)
+}
+
+/// Snapshot reported by the mod install/update passes.
+#[derive(Debug, Clone)]
+pub struct ModsProgress {
+    pub done_mods: u64,
+    pub total_mods: u64,
+    /// Planned bytes of finished mods plus the in-flight download. Skipped or failed mods
+    /// count as fully done so this always ends at `total_bytes`.
+    pub done_bytes: u64,
+    /// Sum of Thunderstore `file_size` over the planned versions (0 when unknown).
+    pub total_bytes: u64,
+    pub detail: Option<String>,
+}
+
+impl ModsProgress {
+    /// Fraction of the pass done: by bytes when sizes are known, by mod count otherwise.
+    pub fn fraction(&self) -> f64 {
+        if self.total_bytes > 0 {
+            (self.done_bytes as f64 / self.total_bytes as f64).clamp(0.0, 1.0)
+        } else if self.total_mods > 0 {
+            (self.done_mods as f64 / self.total_mods as f64).clamp(0.0, 1.0)
+        } else {
+            1.0
+        }
+    }
+}
+
+/// Minimum time between download progress reports; mod milestones are always reported.
+const DOWNLOAD_REPORT_INTERVAL: Duration = Duration::from_millis(250);
+
+struct ModsTally {
+    done_mods: u64,
+    total_mods: u64,
+    finished_bytes: u64,
+    /// Received bytes of each running download, keyed by its slot (the mod's index).
+    in_flight: HashMap<usize, u64>,
+    total_bytes: u64,
+    /// Last download progress report, across all slots.
+    last_download_report: Option<Instant>,
+}
+
+impl ModsTally {
+    fn new(total_mods: u64, total_bytes: u64) -> Self {
+        Self {
+            done_mods: 0,
+            total_mods,
+            finished_bytes: 0,
+            in_flight: HashMap::new(),
+            total_bytes,
+            last_download_report: None,
+        }
+    }
+
+    fn report(&self, detail: Option<String>) -> ModsProgress {
+        let in_flight: u64 = self.in_flight.values().sum();
+        ModsProgress {
+            done_mods: self.done_mods,
+            total_mods: self.total_mods,
+            done_bytes: self
+                .finished_bytes
+                .saturating_add(in_flight)
+                .min(self.total_bytes),
+            total_bytes: self.total_bytes,
+            detail,
+        }
+    }
+
+    /// Track a running download, capped at its planned size (listing sizes can be stale).
+    /// Returns whether a progress report is due (at most one per `DOWNLOAD_REPORT_INTERVAL`
+    /// however many downloads run).
+    fn set_in_flight(&mut self, slot: usize, received: u64, planned_bytes: u64) -> bool {
+        self.in_flight.insert(slot, received.min(planned_bytes));
+        let now = Instant::now();
+        if self
+            .last_download_report
+            .is_some_and(|at| now.duration_since(at) < DOWNLOAD_REPORT_INTERVAL)
+        {
+            return false;
+        }
+        self.last_download_report = Some(now);
+        true
+    }
+
+    fn finish_mod(&mut self, slot: usize, planned_bytes: u64) {
+        self.done_mods = self.done_mods.saturating_add(1);
+        self.finished_bytes = self.finished_bytes.saturating_add(planned_bytes);
+        self.in_flight.remove(&slot);
+    }
+}
+
+/// Streams `url` into `path`, reporting the running byte count as it grows (polled by
+/// `download_backend`, not per chunk). Goes through the configured `download_backend`.
+pub(crate) async fn download_with_progress<F>(
+    client: &reqwest::Client,
+    url: &str,
+    path: &Path,
+    mut on_chunk: F,
+) -> Result<u64, String>
+where
+    F: FnMut(u64),
+{
+    try_download(client, url, path, &mut on_chunk)
+        .await
+        .map_err(String::from)
+}
+
+async fn try_download<F>(
+    client: &reqwest::Client,
+    url: &str,
+    path: &Path,
+    mut on_chunk: F,
+) -> Result<u64, HttpDownloadError>
+where
+    F: FnMut(u64),
+{
+    download_backend::download(client, url, path, None, |received, _| on_chunk(received)).await
+}
+
+/// Resolves once `cancel` is set; never without a flag. Raced against downloads so a
+/// cancelled install doesn't wait for the current mod to finish.
+async fn cancelled(cancel: Option<&AtomicBool>) {
+    let Some(cancel) = cancel else {
+        return std::future::pending().await;
+    };
+    while !cancel.load(AtomicOrdering::Relaxed) {
+        tokio::time::sleep(Duration::from_millis(200)).await;
+    }
+}
+
+/// Whether yanked pins fall back to the nearest older version. The setting wins over the
+/// manifest's `yanked_fallback`.
+fn yanked_fallback_enabled(app: &tauri::AppHandle) -> bool {
+    crate::settings::load(app)
+        .yanked_fallback
+        .unwrap_or_else(|| {
+            crate::mod_config::cached_remote_manifest().is_some_and(|m| m.yanked_fallback)
+        })
+}
+
+/// Listed versions of `pkg` older than `version`, newest first.
+fn older_versions(pkg: &PackageListing, version: &str) -> Vec<String> {
+    let mut older: Vec<&PackageVersion> = pkg
+        .versions
+        .iter()
+        .filter(|v| cmp_version_str(&v.version_number, version) == Ordering::Less)
+        .collect();
+    older.sort_by(|a, b| cmp_version_str(&b.version_number, &a.version_number));
+    older
+        .into_iter()
+        .map(|v| v.version_number.clone())
+        .collect()
+}
+
+/// A yanked pin replaced by an older version: `(requested, reason)`.
+type Fallback = (String, &'static str);
+
+/// Version to install: the pin if listed, else latest. With `yanked_fallback`, an unlisted
+/// pin resolves to the nearest older listed version instead. `None` without versions.
+fn resolve_install_version(
+    spec: &ModEntry,
+    pkg: &PackageListing,
+    game_version: u32,
+    yanked_fallback: bool,
+) -> Option<(String, Option<Fallback>)> {
+    let latest = || latest_pkg_version(&pkg.versions).map(|v| v.version_number.clone());
+    let Some(pin) = spec.pinned_version_for(game_version) else {
+        return latest().map(|v| (v, None));
+    };
+    if pkg.versions.iter().any(|v| v.version_number == pin) {
+        return Some((pin.to_string(), None));
+    }
+    if yanked_fallback {
+        if let Some(older) = older_versions(pkg, pin).into_iter().next() {
+            return Some((
+                older,
+                Some((pin.to_string(), "missing from the package list")),
+            ));
+        }
+    }
+    log::warn!(
+        "Pinned version not found for {}-{}: {pin} (falling back to latest)",
+        spec.dev,
+        spec.name
+    );
+    latest().map(|v| (v, None))
+}
+
+/// Fetch `ver` of `spec` into the package cache `cache_dir`, reusing a cached zip. When
+/// `fallback_404` is set, a 404 retries the nearest older listed versions. Returns the
+/// version and zip path, plus the 404'd request it replaces (if any).
+async fn download_mod_zip<F>(
+    client: &reqwest::Client,
+    spec: &ModEntry,
+    pkg: &PackageListing,
+    ver: String,
+    fallback_404: bool,
+    cache_dir: &Path,
+    mut on_chunk: F,
+) -> Result<(String, PathBuf, Option<Fallback>), String>
+where
+    F: FnMut(u64),
+{
+    let mut candidates = vec![ver.clone()];
+    if fallback_404 {
+        candidates.extend(older_versions(pkg, &ver));
+    }
+    let mut fallback: Option<Fallback> = None;
+    let mut last_err = String::new();
+    for candidate in candidates {
+        let zip_path = cache::package_path(cache_dir, &spec.dev, &spec.name, &candidate);
+        let label = format!("{}-{} v{candidate}", spec.dev, spec.name);
+        let expected = spec.sha256.get(&candidate).map(String::as_str);
+        if cache::is_cached(&zip_path) {
+            // A cached zip that fails the check is evicted and downloaded again below.
+            match cache::verify_sha256(&zip_path, &label, expected) {
+                Ok(()) => {
+                    log::info!("Using cached {label}");
+                    cache::touch(&zip_path);
+                    return Ok((candidate, zip_path, fallback));
+                }
+                Err(e) => log::warn!("{e}"),
+            }
+        }
+        let url = endpoints::thunderstore_download_url(&spec.dev, &spec.name, &candidate);
+        log::info!("Downloading {}-{} from {url}", spec.dev, spec.name);
+        match try_download(client, &url, &zip_path, &mut on_chunk).await {
+            Ok(_) => {
+                cache::verify_sha256(&zip_path, &label, expected)?;
+                return Ok((candidate, zip_path, fallback));
+            }
+            Err(HttpDownloadError::NotFound(e)) if fallback_404 => {
+                log::warn!(
+                    "{}-{} v{candidate} is gone ({e}); trying an older version",
+                    spec.dev,
+                    spec.name
+                );
+                let _ = std::fs::remove_file(&zip_path);
+                fallback = Some((ver.clone(), "download returned 404"));
+                last_err = e;
+            }
+            Err(e) => return Err(e.into()),
+        }
+    }
+    Err(format!(
+        "No downloadable version of {}-{} at or below {ver}: {last_err}",
+        spec.dev, spec.name
+    ))
+}
+
+/// Record (or clear) the yanked-version note for a freshly installed mod.
+fn note_fallback(
+    app: &tauri::AppHandle,
+    game_root: &Path,
+    game_version: u32,
+    spec: &ModEntry,
+    fallback: Option<&Fallback>,
+    installed: &str,
+) {
+    let folder = format!("{}-{}", spec.dev, spec.name);
+    let Some((requested, reason)) = fallback else {
+        if let Err(e) = integrity::clear_version_fallback(game_root, &folder) {
+            log::warn!("Failed to clear version fallback of {folder}: {e}");
+        }
+        return;
+    };
+    log::warn!("Installed {folder} v{installed} instead of yanked v{requested} ({reason})");
+    if let Err(e) =
+        integrity::note_version_fallback(game_root, &folder, requested, installed, reason)
+    {
+        log::warn!("Failed to note version fallback of {folder}: {e}");
+    }
+    progress::emit_mod_version_fallback(
+        app,
+        ModVersionFallbackPayload {
+            version: game_version,
+            dev: spec.dev.clone(),
+            name: spec.name.clone(),
+            requested: requested.clone(),
+            installed: installed.to_string(),
+            reason: reason.to_string(),
+        },
+    );
+}
+
+/// A resolved mod waiting for its zip, queued by `install_mods_with_progress`.
+struct ModDownload<'a> {
+    idx: usize,
+    spec: &'a ModEntry,
+    pkg: &'a PackageListing,
+    ver: String,
+    fallback: Option<Fallback>,
+    previous_version: Option<String>,
+    fallback_404: bool,
 }
 
 pub fn plugins_dir(game_root: &Path) -> PathBuf {
@@ -71,17 +390,22 @@ pub fn plugins_dir(game_root: &Path) -> PathBuf {
 
 /// Downloads and installs a list of Thunderstore packages into `BepInEx/plugins`.
 ///
-/// Progress callback reports `(installed_mods, total_mods, detail)`.
+/// Progress callback receives a `ModsProgress` snapshot (byte-weighted when sizes are known).
+/// Up to `Settings::mod_download_concurrency` zips download at once; extraction stays serial.
+/// Setting `cancel` aborts with `"Cancelled"`, mid-download included.
+/// Returns the mods that were actually written (added or replaced).
 pub async fn install_mods_with_progress<F>(
     app: &tauri::AppHandle,
     game_root: &Path,
     game_version: u32,
     cfg: &ModsConfig,
+    cancel: Option<&AtomicBool>,
     mut on_progress: F,
-) -> Result<(), String>
+) -> Result<Vec<ModChange>, String>
 where
-    F: FnMut(u64, u64, Option<String>),
+    F: FnMut(ModsProgress),
 {
+    crate::bisect::ensure_inactive(game_root, game_version)?;
     let client = reqwest::Client::new();
 
     // Fetch Thunderstore package list once (per-package API is unreliable/404).
@@ -93,25 +417,35 @@ where
         package_map.insert((p.owner.to_lowercase(), p.name.to_lowercase()), p);
     }
 
+    // Strict mode: mods missing from the pinned allowlist are never installed.
+    let (cfg, _rejected) = allowlist::enforce(app, cfg)?;
+    let cfg = &cfg;
+    let yanked_fallback = yanked_fallback_enabled(app);
+
     let target_plugins = plugins_dir(game_root);
     std::fs::create_dir_all(&target_plugins).map_err(|e| e.to_string())?;
     log::info!("Target plugins dir: {}", target_plugins.to_string_lossy());
 
-    // Temp workspace inside game folder (keeps things simple and visible for debugging).
-    let temp_root = game_root.join(".hq-launcher").join("tmp").join("mods");
-    if temp_root.exists() {
-        std::fs::remove_dir_all(&temp_root).map_err(|e| e.to_string())?;
-    }
-    std::fs::create_dir_all(&temp_root).map_err(|e| e.to_string())?;
+    let cache_dir = cache::packages_dir(app)?;
 
-    let total_mods = cfg.mods.len() as u64;
-    let mut installed: u64 = 0;
-    on_progress(0, total_mods, Some("Starting...".to_string()));
+    // Weight progress by download size so one huge mod doesn't stall the bar on a count.
+    let planned: Vec<u64> = cfg
+        .mods
+        .iter()
+        .map(|spec| planned_size(&package_map, spec, game_version))
+        .collect();
+    let mut tally = ModsTally::new(cfg.mods.len() as u64, planned.iter().sum());
+    on_progress(tally.report(Some("Starting...".to_string())));
+    let mut jobs: Vec<ModDownload> = Vec::new();
 
     for (idx, spec) in cfg.mods.iter().enumerate() {
+        if cancel.is_some_and(|c| c.load(AtomicOrdering::Relaxed)) {
+            return Err("Cancelled".to_string());
+        }
         // Add-only: if a plugin folder already exists for this mod, skip it.
         // Folder name is deterministic (does not include the mod version).
         let already_dir = target_plugins.join(format!("{}-{}", spec.dev, spec.name));
+        let mut previous_version: Option<String> = None;
         if already_dir.exists() {
             log::info!(
                 "{}/{}  |  {}-{} Start Check",
@@ -122,13 +456,13 @@ where
             );
 
             let manifest = read_manifest_allow_old(&already_dir)?;
+            previous_version = Some(manifest.version_number.clone());
 
             let version_limit = spec
                 .version_config
                 .get(&game_version)
                 .unwrap_or(&"0.0.0".to_string())
                 .clone();
-            installed = installed.saturating_add(1);
             if version_limit == "0.0.0" {
                 let new_version = packages
                     .clone()
@@ -146,6 +480,7 @@ where
                     .unwrap_or_else(|| "0.0.0".to_string());
 
                 if manifest.version_number == new_version {
+                    tally.finish_mod(idx, planned[idx]);
                     continue;
                 }
                 log::info!(
@@ -154,7 +489,14 @@ where
                     spec.name,
                     old_version = manifest.version_number
                 );
-            } else if manifest.version_number != version_limit {
+            } else if manifest.version_number != version_limit
+                && !integrity::is_known_fallback(
+                    game_root,
+                    &format!("{}-{}", spec.dev, spec.name),
+                    &version_limit,
+                    &manifest.version_number,
+                )
+            {
                 log::info!(
                     "Updating {}-{} from {old_version} to {version_limit}",
                     spec.dev,
@@ -162,17 +504,14 @@ where
                     old_version = manifest.version_number
                 );
             } else {
-                on_progress(
-                    installed,
-                    total_mods,
-                    Some(format!(
-                        "Skipped {}/{}  |  {}-{} (version equal)",
-                        idx + 1,
-                        cfg.mods.len(),
-                        spec.dev,
-                        spec.name
-                    )),
-                );
+                tally.finish_mod(idx, planned[idx]);
+                on_progress(tally.report(Some(format!(
+                    "Skipped {}/{}  |  {}-{} (version equal)",
+                    idx + 1,
+                    cfg.mods.len(),
+                    spec.dev,
+                    spec.name
+                ))));
                 continue;
             }
 
@@ -182,134 +521,160 @@ where
         let mod_label = format!("{}-{}", spec.dev, spec.name);
 
         if !spec.is_compatible(game_version) {
-            installed = installed.saturating_add(1);
+            tally.finish_mod(idx, planned[idx]);
             let why = incompatible_reason(spec, game_version);
-            log::warn!("Skipping {mod_label}{why}");
-            on_progress(
-                installed,
-                total_mods,
-                Some(format!("Skipped {mod_label}{why}")),
-            );
+            if spec.is_client_only() {
+                log::info!("Skipping client-only {mod_label}{why}");
+            } else {
+                log::warn!("Skipping {mod_label}{why}");
+            }
+            on_progress(tally.report(Some(format!("Skipped {mod_label}{why}"))));
             continue;
         }
 
-        on_progress(
-            installed,
-            total_mods,
-            Some(format!("Resolving {mod_label}")),
-        );
+        on_progress(tally.report(Some(format!("Resolving {mod_label}"))));
 
         let key = (spec.dev.to_lowercase(), spec.name.to_lowercase());
         let Some(pkg) = package_map.get(&key) else {
-            installed = installed.saturating_add(1);
+            tally.finish_mod(idx, planned[idx]);
             log::error!("Package not found in list: {}-{}", spec.dev, spec.name);
-            on_progress(
-                installed,
-                total_mods,
-                Some(format!(
-                    "Failed to resolve {mod_label} (not found in package list)"
-                )),
-            );
+            on_progress(tally.report(Some(format!(
+                "Failed to resolve {mod_label} (not found in package list)"
+            ))));
             continue;
         };
 
-        let pinned = spec.pinned_version_for(game_version);
-        let ver = if let Some(pin) = pinned {
-            // Prefer the pinned version only if it exists in the listing.
-            if pkg.versions.iter().any(|v| v.version_number == pin) {
-                pin.to_string()
-            } else {
-                log::warn!(
-                    "Pinned version not found for {mod_label}: {pin} (falling back to latest)"
-                );
-                latest_pkg_version(&pkg.versions)
-                    .map(|v| v.version_number.clone())
-                    .unwrap_or_else(|| "0.0.0".to_string())
-            }
-        } else {
-            latest_pkg_version(&pkg.versions)
-                .map(|v| v.version_number.clone())
-                .unwrap_or_else(|| "0.0.0".to_string())
-        };
-
-        if ver == "0.0.0" {
-            installed = installed.saturating_add(1);
+        let Some((ver, fallback)) =
+            resolve_install_version(spec, pkg, game_version, yanked_fallback)
+        else {
+            tally.finish_mod(idx, planned[idx]);
             log::error!("No versions for {}-{}", spec.dev, spec.name);
-            on_progress(
-                installed,
-                total_mods,
-                Some(format!("Failed to resolve {mod_label} (no versions)")),
-            );
+            on_progress(tally.report(Some(format!("Failed to resolve {mod_label} (no versions)"))));
             continue;
-        }
-
-        let download_url = thunderstore_download_url(&spec.dev, &spec.name, &ver);
+        };
         log::info!("Resolved {mod_label} => v{ver}");
 
-        let zip_path = temp_root.join(format!("{}-{}-{}.zip", spec.dev, spec.name, ver));
+        jobs.push(ModDownload {
+            idx,
+            spec,
+            pkg,
+            ver,
+            fallback,
+            previous_version,
+            fallback_404: yanked_fallback && spec.pinned_version_for(game_version).is_some(),
+        });
+    }
 
-        // Download zip
-        on_progress(
-            installed,
-            total_mods,
-            Some(format!("Downloading {mod_label}")),
-        );
-        log::info!("Downloading {mod_label} from {download_url}");
-        let bytes = client
-            .get(&download_url)
-            .send()
-            .await
-            .map_err(|e| e.to_string())?
-            .error_for_status()
-            .map_err(|e| e.to_string())?
-            .bytes()
-            .await
-            .map_err(|e| e.to_string())?;
-
-        std::fs::write(&zip_path, &bytes).map_err(|e| e.to_string())?;
-
-        // Extract directly into BepInEx/plugins, then delete the zip.
-        on_progress(
-            installed,
-            total_mods,
-            Some(format!("Extracting {mod_label}")),
-        );
-        let folder_name = format!("{}-{}", spec.dev, spec.name);
+    // Downloads run `concurrency` at a time; extraction happens here as each one lands,
+    // so only one mod is ever written into `BepInEx/plugins` at once.
+    let concurrency = crate::settings::load(app).mod_download_concurrency.max(1);
+    // Mutexes rather than cells: the install future has to stay `Send`.
+    let tally = Mutex::new(tally);
+    let on_progress = Mutex::new(on_progress);
+    let report = |detail: String| {
+        let Ok(snapshot) = tally.lock().map(|t| t.report(Some(detail))) else {
+            return;
+        };
+        if let Ok(mut on_progress) = on_progress.lock() {
+            on_progress(snapshot);
+        }
+    };
+    let finish = |idx: usize| {
+        if let Ok(mut t) = tally.lock() {
+            t.finish_mod(idx, planned[idx]);
+        }
+    };
+    let mut downloads = futures_util::stream::iter(jobs.into_iter().map(|job| {
+        let (client, cache_dir, tally, report) = (&client, &cache_dir, &tally, &report);
+        let (idx, planned_bytes) = (job.idx, planned[job.idx]);
+        async move {
+            let mod_label = format!("{}-{}", job.spec.dev, job.spec.name);
+            report(format!("Downloading {mod_label}"));
+            let res = download_mod_zip(
+                client,
+                job.spec,
+                job.pkg,
+                job.ver.clone(),
+                job.fallback_404,
+                cache_dir,
+                |received| {
+                    let due = tally
+                        .lock()
+                        .is_ok_and(|mut t| t.set_in_flight(idx, received, planned_bytes));
+                    if due {
+                        report(format!("Downloading {mod_label}"));
+                    }
+                },
+            )
+            .await;
+            (job, res)
+        }
+    }))
+    .buffer_unordered(concurrency);
+
+    let mut changes: Vec<(usize, ModChange)> = Vec::new();
+    loop {
+        let next = tokio::select! {
+            next = downloads.next() => next,
+            _ = cancelled(cancel) => return Err("Cancelled".to_string()),
+        };
+        let Some((job, res)) = next else {
+            break;
+        };
+        let (ver, zip_path, fallback_from_404) = res?;
+        let spec = job.spec;
+        // A yanked pin is the request, even if its stand-in then 404'd too.
+        let fallback = job.fallback.or(fallback_from_404);
+
+        // Extract directly into BepInEx/plugins; the zip stays in the package cache.
+        let mod_label = format!("{}-{}", spec.dev, spec.name);
+        report(format!("Extracting {mod_label}"));
 
         if let Err(e) = extract_thunderstore_into_plugins_with_progress(
             &zip_path,
             &target_plugins,
-            &folder_name,
+            &mod_label,
             |_d, _t, _n| {},
         ) {
-            installed = installed.saturating_add(1);
+            finish(job.idx);
             log::error!("Failed to extract into plugins {mod_label}: {e}");
-            on_progress(
-                installed,
-                total_mods,
-                Some(format!("Failed to extract {mod_label} ({e})")),
-            );
-            let _ = std::fs::remove_file(&zip_path);
+            report(format!("Failed to extract {mod_label} ({e})"));
+            cache::evict(&zip_path);
             continue;
         }
 
-        // Cleanup per-mod artifacts
-        if let Err(e) = std::fs::remove_file(&zip_path) {
-            log::warn!("Failed to delete zip {}: {}", zip_path.to_string_lossy(), e);
+        // First install: the configs the pack expects to tweak exist before a first launch
+        // would generate them. Add-only, so nothing the player or the pack set is replaced.
+        if job.previous_version.is_none() {
+            let copied = crate::storage::shared_config_dir(app)
+                .map_err(String::from)
+                .and_then(|dir| copy_mod_config_defaults(&zip_path, &dir));
+            match copied {
+                Ok(added) if !added.is_empty() => {
+                    log::info!("{mod_label}: added {} default config file(s)", added.len())
+                }
+                Ok(_) => {}
+                Err(e) => log::warn!("Failed to copy default configs of {mod_label}: {e}"),
+            }
         }
 
-        installed = installed.saturating_add(1);
-        on_progress(
-            installed,
-            total_mods,
-            Some(format!("Installed {mod_label}")),
-        );
+        note_fallback(app, game_root, game_version, spec, fallback.as_ref(), &ver);
+        finish(job.idx);
+        report(format!("Installed {mod_label}"));
+        changes.push((
+            job.idx,
+            ModChange {
+                dev: spec.dev.clone(),
+                name: spec.name.clone(),
+                from_version: job.previous_version,
+                to_version: ver,
+            },
+        ));
     }
 
-    // Best-effort cleanup of temp workspace.
-    let _ = std::fs::remove_dir_all(&temp_root);
-
-    Ok(())
+    // Downloads finish in any order; report changes in manifest order.
+    changes.sort_by_key(|(idx, _)| *idx);
+    Ok(changes.into_iter().map(|(_, change)| change).collect())
 }
 
 pub async fn updatable_mods_with_progress<F>(
@@ -324,6 +689,10 @@ where
 {
     let client = reqwest::Client::new();
 
+    // Strict mode: don't offer updates for mods that can't be installed.
+    let (cfg, _rejected) = allowlist::enforce(app, cfg)?;
+    let cfg = &cfg;
+
     let total_mods = cfg.mods.len() as u64;
     on_progress(0, total_mods, Some("Starting...".to_string()), None);
 
@@ -386,6 +755,18 @@ where
                     .unwrap_or_else(|| "0.0.0".to_string())
             };
 
+            // A noted stand-in for a yanked pin is as current as it gets.
+            let desired_version = if integrity::is_known_fallback(
+                game_root,
+                &mod_label,
+                &desired_version,
+                &manifest.version_number,
+            ) {
+                manifest.version_number.clone()
+            } else {
+                desired_version
+            };
+
             if desired_version == "0.0.0" {
                 log::warn!("Could not resolve desired version for {mod_label} (no versions)");
                 on_progress(
@@ -477,18 +858,22 @@ where
     Ok(())
 }
 
-
+/// Downloads and installs the newer versions of `updatable_mods` into `BepInEx/plugins`.
+///
+/// Setting `cancel` aborts with `"Cancelled"`, mid-download included.
 pub async fn update_mods_with_progress<F>(
     app: &tauri::AppHandle,
     game_root: &Path,
     game_version: u32,
     cfg: &ModsConfig,
     updatable_mods: Vec<String>,
+    cancel: Option<&AtomicBool>,
     mut on_progress: F,
-) -> Result<(), String>
+) -> Result<Vec<ModChange>, String>
 where
-    F: FnMut(u64, u64, Option<String>),
+    F: FnMut(ModsProgress),
 {
+    crate::bisect::ensure_inactive(game_root, game_version)?;
     let client = reqwest::Client::new();
 
     // Fetch Thunderstore package list once (per-package API is unreliable/404).
@@ -500,22 +885,31 @@ where
         package_map.insert((p.owner.to_lowercase(), p.name.to_lowercase()), p);
     }
 
+    // Strict mode: mods missing from the pinned allowlist are never installed.
+    let (cfg, _rejected) = allowlist::enforce(app, cfg)?;
+    let cfg = &cfg;
+    let yanked_fallback = yanked_fallback_enabled(app);
+
     let target_plugins = plugins_dir(game_root);
     std::fs::create_dir_all(&target_plugins).map_err(|e| e.to_string())?;
     log::info!("Target plugins dir: {}", target_plugins.to_string_lossy());
 
-    // Temp workspace inside game folder (keeps things simple and visible for debugging).
-    let temp_root = game_root.join(".hq-launcher").join("tmp").join("mods");
-    if temp_root.exists() {
-        std::fs::remove_dir_all(&temp_root).map_err(|e| e.to_string())?;
-    }
-    std::fs::create_dir_all(&temp_root).map_err(|e| e.to_string())?;
+    let cache_dir = cache::packages_dir(app)?;
 
-    let total_mods = updatable_mods.len() as u64;
-    let mut installed: u64 = 0;
-    on_progress(0, total_mods, Some("Starting...".to_string()));
+    let total_bytes = cfg
+        .mods
+        .iter()
+        .filter(|spec| updatable_mods.contains(&format!("{}-{}", spec.dev, spec.name)))
+        .map(|spec| planned_size(&package_map, spec, game_version))
+        .sum();
+    let mut tally = ModsTally::new(updatable_mods.len() as u64, total_bytes);
+    on_progress(tally.report(Some("Starting...".to_string())));
+    let mut changes: Vec<ModChange> = Vec::new();
 
     for (_idx, spec) in cfg.mods.iter().enumerate() {
+        if cancel.is_some_and(|c| c.load(AtomicOrdering::Relaxed)) {
+            return Err("Cancelled".to_string());
+        }
         // Add-only: if a plugin folder already exists for this mod, skip it.
         // Folder name is deterministic (does not include the mod version).
 
@@ -524,89 +918,60 @@ where
         if !updatable_mods.contains(&mod_label) {
             continue;
         }
+        let planned = planned_size(&package_map, spec, game_version);
 
-        on_progress(
-            installed,
-            total_mods,
-            Some(format!("Resolving {mod_label}")),
-        );
+        on_progress(tally.report(Some(format!("Resolving {mod_label}"))));
 
         let key = (spec.dev.to_lowercase(), spec.name.to_lowercase());
         let Some(pkg) = package_map.get(&key) else {
-            installed = installed.saturating_add(1);
+            tally.finish_mod(0, planned);
             log::error!("Package not found in list: {}-{}", spec.dev, spec.name);
-            on_progress(
-                installed,
-                total_mods,
-                Some(format!(
-                    "Failed to resolve {mod_label} (not found in package list)"
-                )),
-            );
+            on_progress(tally.report(Some(format!(
+                "Failed to resolve {mod_label} (not found in package list)"
+            ))));
             continue;
         };
 
-        let pinned = spec.pinned_version_for(game_version);
-        let ver = if let Some(pin) = pinned {
-            if pkg.versions.iter().any(|v| v.version_number == pin) {
-                pin.to_string()
-            } else {
-                log::warn!(
-                    "Pinned version not found for {mod_label}: {pin} (falling back to latest)"
-                );
-                latest_pkg_version(&pkg.versions)
-                    .map(|v| v.version_number.clone())
-                    .unwrap_or_else(|| "0.0.0".to_string())
-            }
-        } else {
-            latest_pkg_version(&pkg.versions)
-                .map(|v| v.version_number.clone())
-                .unwrap_or_else(|| "0.0.0".to_string())
-        };
-
-        if ver == "0.0.0" {
-            installed = installed.saturating_add(1);
+        let Some((ver, fallback)) =
+            resolve_install_version(spec, pkg, game_version, yanked_fallback)
+        else {
+            tally.finish_mod(0, planned);
             log::error!("No versions for {}-{}", spec.dev, spec.name);
-            on_progress(
-                installed,
-                total_mods,
-                Some(format!("Failed to resolve {mod_label} (no versions)")),
-            );
+            on_progress(tally.report(Some(format!("Failed to resolve {mod_label} (no versions)"))));
             continue;
-        }
-
-        let download_url = thunderstore_download_url(&spec.dev, &spec.name, &ver);
+        };
         log::info!("Resolved {mod_label} => v{ver}");
 
-        let zip_path = temp_root.join(format!("{}-{}-{}.zip", spec.dev, spec.name, ver));
-
         // Download zip
-        on_progress(
-            installed,
-            total_mods,
-            Some(format!("Downloading {mod_label}")),
-        );
-        log::info!("Downloading {mod_label} from {download_url}");
-        let bytes = client
-            .get(&download_url)
-            .send()
-            .await
-            .map_err(|e| e.to_string())?
-            .error_for_status()
-            .map_err(|e| e.to_string())?
-            .bytes()
-            .await
-            .map_err(|e| e.to_string())?;
-
-        std::fs::write(&zip_path, &bytes).map_err(|e| e.to_string())?;
-
-        // Extract directly into BepInEx/plugins, then delete the zip.
-        on_progress(
-            installed,
-            total_mods,
-            Some(format!("Extracting {mod_label}")),
+        on_progress(tally.report(Some(format!("Downloading {mod_label}"))));
+        let fallback_404 = yanked_fallback && spec.pinned_version_for(game_version).is_some();
+        let download = download_mod_zip(
+            &client,
+            spec,
+            pkg,
+            ver,
+            fallback_404,
+            &cache_dir,
+            |received| {
+                if tally.set_in_flight(0, received, planned) {
+                    on_progress(tally.report(Some(format!("Downloading {mod_label}"))));
+                }
+            },
         );
+        let (ver, zip_path, fallback_from_404) = tokio::select! {
+            res = download => res?,
+            _ = cancelled(cancel) => return Err("Cancelled".to_string()),
+        };
+        // A yanked pin is the request, even if its stand-in then 404'd too.
+        let fallback = fallback.or(fallback_from_404);
+
+        // Extract directly into BepInEx/plugins; the zip stays in the package cache.
+        on_progress(tally.report(Some(format!("Extracting {mod_label}"))));
         let folder_name = format!("{}-{}", spec.dev, spec.name);
         let existing = target_plugins.join(&folder_name);
+        let previous_version = read_manifest_allow_old(&existing)
+            .ok()
+            .map(|m| m.version_number);
         if existing.exists() {
             if let Err(e) = std::fs::remove_dir_all(&existing) {
                 log::warn!(
@@ -623,34 +988,115 @@ where
             &folder_name,
             |_d, _t, _n| {},
         ) {
-            installed = installed.saturating_add(1);
+            tally.finish_mod(0, planned);
             log::error!("Failed to extract into plugins {mod_label}: {e}");
-            on_progress(
-                installed,
-                total_mods,
-                Some(format!("Failed to extract {mod_label} ({e})")),
+            on_progress(tally.report(Some(format!("Failed to extract {mod_label} ({e})"))));
+            cache::evict(&zip_path);
+            continue;
+        }
+
+        note_fallback(app, game_root, game_version, spec, fallback.as_ref(), &ver);
+        tally.finish_mod(0, planned);
+        on_progress(tally.report(Some(format!("Installed {mod_label}"))));
+        changes.push(ModChange {
+            dev: spec.dev.clone(),
+            name: spec.name.clone(),
+            from_version: previous_version,
+            to_version: ver,
+        });
+    }
+
+    Ok(changes)
+}
+
+/// A plugin folder moved out of `BepInEx/plugins` because its manifest range excludes
+/// the game version.
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct DisabledIncompatibleMod {
+    pub dev: String,
+    pub name: String,
+    pub folder: String,
+    pub low_cap: Option<u32>,
+    pub high_cap: Option<u32>,
+    pub reason: String,
+    /// Tagged `client-only`: nothing to warn the player about.
+    pub client_only: bool,
+}
+
+pub fn plugins_disabled_dir(game_root: &Path) -> PathBuf {
+    game_root.join("BepInEx").join("plugins_disabled")
+}
+
+/// Re-evaluate `ModEntry::is_compatible` for every installed plugin of `game_root` and move
+/// out-of-range ones to `BepInEx/plugins_disabled/{folder}`.
+///
+/// Only the game version range is considered: remote-disabled mods are purged elsewhere and
+/// user-disabled mods use the `.old` suffix. Plugins unknown to the manifest are left alone,
+/// and so are mods tagged `required`.
+pub fn disable_incompatible_mods(
+    game_root: &Path,
+    game_version: u32,
+    cfg: &ModsConfig,
+) -> Result<Vec<DisabledIncompatibleMod>, String> {
+    let plugins = plugins_dir(game_root);
+    let Ok(rd) = std::fs::read_dir(&plugins) else {
+        return Ok(vec![]);
+    };
+
+    let specs: HashMap<String, &ModEntry> = cfg
+        .mods
+        .iter()
+        .map(|m| (format!("{}-{}", m.dev, m.name).to_lowercase(), m))
+        .collect();
+
+    let disabled_root = plugins_disabled_dir(game_root);
+    let mut moved: Vec<DisabledIncompatibleMod> = vec![];
+    for e in rd.flatten() {
+        let path = e.path();
+        if !path.is_dir() {
+            continue;
+        }
+        let folder = e.file_name().to_string_lossy().to_string();
+        let Some(spec) = specs.get(&folder.to_lowercase()) else {
+            continue;
+        };
+        let in_range = spec.low_cap.is_none_or(|min| game_version >= min)
+            && spec.high_cap.is_none_or(|max| game_version <= max);
+        if in_range {
+            continue;
+        }
+        if spec.is_required() {
+            log::warn!(
+                "{folder} is required by the manifest; keeping it enabled on v{game_version}{}",
+                incompatible_reason(spec, game_version)
             );
-            let _ = std::fs::remove_file(&zip_path);
             continue;
         }
 
-        // Cleanup per-mod artifacts
-        if let Err(e) = std::fs::remove_file(&zip_path) {
-            log::warn!("Failed to delete zip {}: {}", zip_path.to_string_lossy(), e);
+        std::fs::create_dir_all(&disabled_root).map_err(|e| e.to_string())?;
+        let dest = disabled_root.join(&folder);
+        if dest.exists() {
+            std::fs::remove_dir_all(&dest).map_err(|e| e.to_string())?;
         }
+        std::fs::rename(&path, &dest).map_err(|e| e.to_string())?;
 
-        installed = installed.saturating_add(1);
-        on_progress(
-            installed,
-            total_mods,
-            Some(format!("Installed {mod_label}")),
-        );
+        let reason = incompatible_reason(spec, game_version);
+        if spec.is_client_only() {
+            log::debug!("Disabled client-only mod {folder} for v{game_version}{reason}");
+        } else {
+            log::info!("Disabled incompatible mod {folder} for v{game_version}{reason}");
+        }
+        moved.push(DisabledIncompatibleMod {
+            dev: spec.dev.clone(),
+            name: spec.name.clone(),
+            folder,
+            low_cap: spec.low_cap,
+            high_cap: spec.high_cap,
+            reason: reason.trim().to_string(),
+            client_only: spec.is_client_only(),
+        });
     }
-
-    // Best-effort cleanup of temp workspace.
-    let _ = std::fs::remove_dir_all(&temp_root);
-
-    Ok(())
+    Ok(moved)
 }
 
 fn incompatible_reason(spec: &ModEntry, game_version: u32) -> String {
@@ -0,0 +1,102 @@
+//! Group announcements pushed through the manifest's `announcements` list.
+//!
+//! Each fetched manifest is checked for active announcements (not expired) whose id this
+//! launcher hasn't shown yet; those go out as `announcement` events and their ids are
+//! remembered in `state/announcements_seen.json`, so every message appears once.
+
+use std::collections::BTreeSet;
+use std::path::PathBuf;
+
+use serde::{Deserialize, Serialize};
+
+use crate::mod_config::{Announcement, RemoteManifest};
+use crate::{progress, storage};
+
+/// `state/announcements_seen.json`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct SeenState {
+    seen: BTreeSet<String>,
+}
+
+fn now_secs() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+fn seen_path(app: &tauri::AppHandle) -> Result<PathBuf, String> {
+    Ok(storage::state_dir(app)?.join("announcements_seen.json"))
+}
+
+fn read_seen(app: &tauri::AppHandle) -> SeenState {
+    seen_path(app)
+        .ok()
+        .and_then(|p| std::fs::read_to_string(p).ok())
+        .and_then(|t| serde_json::from_str(&t).ok())
+        .unwrap_or_default()
+}
+
+fn write_seen(app: &tauri::AppHandle, state: &SeenState) -> Result<(), String> {
+    let path = seen_path(app)?;
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    let json = serde_json::to_string_pretty(state).map_err(|e| e.to_string())?;
+    crate::zip_utils::write_atomic(&path, json)
+}
+
+/// Event name: `announcement`
+#[derive(Debug, Clone, Serialize)]
+pub struct AnnouncementPayload {
+    pub manifest_version: u32,
+    #[serde(flatten)]
+    pub announcement: Announcement,
+}
+
+/// Announce the active announcements of `manifest` not shown before.
+pub fn notify_new(app: &tauri::AppHandle, manifest: &RemoteManifest) {
+    let now = now_secs();
+    let mut state = read_seen(app);
+    let fresh: Vec<&Announcement> = manifest
+        .announcements
+        .iter()
+        .filter(|a| a.is_active(now) && !state.seen.contains(&a.id))
+        .collect();
+    if fresh.is_empty() {
+        return;
+    }
+    for a in &fresh {
+        log::info!("Announcement {}: {}", a.id, a.title);
+        progress::emit(
+            app,
+            "announcement",
+            AnnouncementPayload {
+                manifest_version: manifest.version,
+                announcement: (*a).clone(),
+            },
+        );
+    }
+    // Only remember ids the manifest still lists, so the file doesn't grow forever.
+    let listed: BTreeSet<&String> = manifest.announcements.iter().map(|a| &a.id).collect();
+    state.seen.retain(|id| listed.contains(id));
+    state.seen.extend(fresh.iter().map(|a| a.id.clone()));
+    if let Err(e) = write_seen(app, &state) {
+        log::warn!("Failed to save seen announcements: {e}");
+    }
+}
+
+/// Active announcements of the last fetched manifest in manifest order, including ones
+/// already shown, for an announcements panel.
+#[tauri::command]
+pub fn get_announcements() -> Vec<Announcement> {
+    let now = now_secs();
+    let Some(manifest) = crate::mod_config::cached_remote_manifest() else {
+        return vec![];
+    };
+    manifest
+        .announcements
+        .into_iter()
+        .filter(|a| a.is_active(now))
+        .collect()
+}
@@ -0,0 +1,284 @@
+//! Per-launch overlays that keep the game's writes out of its version dir
+//! (`Settings::protect_game_files`).
+//!
+//! With protection on, the game never runs from the version dir; it runs from
+//! `overlays/v{version}/merged/` instead:
+//!
+//! - Linux with `fuse-overlayfs` on `PATH`: an unprivileged overlay mount whose writes land
+//!   in `upper/`. It is unmounted when the game exits.
+//! - Anywhere else: `merged/` is a copy of the version dir, refreshed before each launch so
+//!   files the last session changed, added or removed are put back.
+//!
+//! Either way the game's writes never reach the version dir, and the previous session's
+//! writes stay listable (`get_overlay_writes`) until the next launch. File permissions are
+//! left alone: the installer, verify and the user still write to the version dir as usual.
+//! Linked folders (`BepInEx/config`, shared data) keep pointing at their shared targets, so
+//! config edits made in game still persist.
+
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+use crate::{installer, settings, storage};
+
+fn overlay_dir(app: &tauri::AppHandle, version: u32) -> Result<PathBuf, String> {
+    let version = storage::check_version(version)?;
+    Ok(storage::overlays_dir(app)?.join(format!("v{version}")))
+}
+
+/// Whether launches run from an overlay instead of the version dir.
+pub fn enabled(app: &tauri::AppHandle) -> bool {
+    settings::load(app).protect_game_files
+}
+
+/// Exe to launch for `version`: `exe_path` itself, or its counterpart in a freshly prepared
+/// overlay of `game_root` when game files are protected.
+pub fn prepare(
+    app: &tauri::AppHandle,
+    version: u32,
+    game_root: &Path,
+    exe_path: PathBuf,
+) -> Result<PathBuf, String> {
+    if !enabled(app) {
+        return Ok(exe_path);
+    }
+    let rel = exe_path
+        .strip_prefix(game_root)
+        .map_err(|_| format!("{} is outside {}", exe_path.display(), game_root.display()))?
+        .to_path_buf();
+    let root = overlay_dir(app, version)?;
+    let merged = root.join("merged");
+    unmount(&merged);
+    if is_mounted(&merged) {
+        return Err(format!(
+            "previous overlay of v{version} is still mounted at {}",
+            merged.display()
+        ));
+    }
+
+    #[cfg(target_os = "linux")]
+    if let Some(fuse) = crate::fs_utils::find_on_path("fuse-overlayfs") {
+        match mount(&fuse, game_root, &root) {
+            Ok(()) => {
+                log::info!("Launching v{version} from overlay {}", merged.display());
+                return Ok(merged.join(rel));
+            }
+            Err(e) => log::warn!("Overlay mount failed for v{version} ({e}); using a copy"),
+        }
+    }
+
+    // A stale upper dir would be reported as this session's writes.
+    let _ = std::fs::remove_dir_all(root.join("upper"));
+    let copied = mirror(game_root, &merged)?;
+    log::info!(
+        "Launching v{version} from copy {} ({copied} file(s) refreshed)",
+        merged.display()
+    );
+    Ok(merged.join(rel))
+}
+
+/// Tear down the overlay of `version` after the game exited. The captured writes stay.
+pub fn finish(app: &tauri::AppHandle, version: u32) {
+    if let Ok(root) = overlay_dir(app, version) {
+        unmount(&root.join("merged"));
+    }
+}
+
+/// Mount `lower` read-only under `root/merged` with a fresh `upper/` and `work/`.
+#[cfg(target_os = "linux")]
+fn mount(fuse: &Path, lower: &Path, root: &Path) -> Result<(), String> {
+    let (upper, work, merged) = (root.join("upper"), root.join("work"), root.join("merged"));
+    for dir in [&upper, &work, &merged] {
+        // `merged` may still hold a copy from a launch without fuse-overlayfs.
+        let _ = std::fs::remove_dir_all(dir);
+        std::fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+    }
+    let paths = [lower, &upper, &work];
+    if paths
+        .iter()
+        .any(|p| p.to_string_lossy().contains([',', ':']))
+    {
+        return Err("paths containing ',' or ':' can't be passed to fuse-overlayfs".to_string());
+    }
+    let out = std::process::Command::new(fuse)
+        .arg("-o")
+        .arg(format!(
+            "lowerdir={},upperdir={},workdir={}",
+            lower.display(),
+            upper.display(),
+            work.display()
+        ))
+        .arg(&merged)
+        .output()
+        .map_err(|e| e.to_string())?;
+    if !out.status.success() {
+        return Err(String::from_utf8_lossy(&out.stderr).trim().to_string());
+    }
+    Ok(())
+}
+
+#[cfg(target_os = "linux")]
+fn is_mounted(path: &Path) -> bool {
+    let Ok(mounts) = std::fs::read_to_string("/proc/self/mounts") else {
+        return false;
+    };
+    // Mount points are octal-escaped; spaces are the only likely case in our paths.
+    let escaped = path.to_string_lossy().replace(' ', "\\040");
+    mounts
+        .lines()
+        .any(|l| l.split_whitespace().nth(1) == Some(escaped.as_str()))
+}
+
+#[cfg(not(target_os = "linux"))]
+fn is_mounted(_path: &Path) -> bool {
+    false
+}
+
+/// Lazily unmount `merged`; Proton's wineserver can outlive the game by a few seconds.
+fn unmount(merged: &Path) {
+    if !is_mounted(merged) {
+        return;
+    }
+    for tool in ["fusermount3", "fusermount"] {
+        let ok = std::process::Command::new(tool)
+            .args(["-u", "-z"])
+            .arg(merged)
+            .status()
+            .is_ok_and(|s| s.success());
+        if ok {
+            log::info!("Unmounted overlay {}", merged.display());
+            return;
+        }
+    }
+    log::warn!("Failed to unmount overlay {}", merged.display());
+}
+
+fn modified(md: &std::fs::Metadata) -> Option<SystemTime> {
+    md.modified().ok()
+}
+
+/// Same size and mtime; copies get the source mtime, so anything else was written to.
+fn same_file(a: &std::fs::Metadata, b: &std::fs::Metadata) -> bool {
+    a.len() == b.len() && modified(a) == modified(b)
+}
+
+fn remove_entry(path: &Path) -> Result<(), String> {
+    let md = std::fs::symlink_metadata(path).map_err(|e| e.to_string())?;
+    let res = if installer::is_reparse_point(path)? {
+        // Only the link itself; never what it points at.
+        std::fs::remove_file(path).or_else(|_| std::fs::remove_dir(path))
+    } else if md.is_dir() {
+        std::fs::remove_dir_all(path)
+    } else {
+        std::fs::remove_file(path)
+    };
+    res.map_err(|e| format!("failed to remove {}: {e}", path.display()))
+}
+
+/// Make `copy` match `src` again, recopying only what differs. Returns files copied.
+fn mirror(src: &Path, copy: &Path) -> Result<u64, String> {
+    std::fs::create_dir_all(copy).map_err(|e| e.to_string())?;
+
+    // Drop what the last session added (or turned into another kind of entry).
+    let rd = std::fs::read_dir(copy).map_err(|e| e.to_string())?;
+    for e in rd.flatten() {
+        let path = e.path();
+        let keep = std::fs::symlink_metadata(src.join(e.file_name())).is_ok_and(|md| {
+            installer::is_reparse_point(&path).ok()
+                == installer::is_reparse_point(&src.join(e.file_name())).ok()
+                && e.file_type().is_ok_and(|t| t.is_dir() == md.is_dir())
+        });
+        if !keep {
+            remove_entry(&path)?;
+        }
+    }
+
+    let mut copied = 0u64;
+    let rd = std::fs::read_dir(src).map_err(|e| e.to_string())?;
+    for e in rd.flatten() {
+        let from = e.path();
+        let to = copy.join(e.file_name());
+        if installer::is_reparse_point(&from)? {
+            let current = std::fs::read_link(&to).ok();
+            if current.is_none() || current != std::fs::read_link(&from).ok() {
+                if std::fs::symlink_metadata(&to).is_ok() {
+                    remove_entry(&to)?;
+                }
+                installer::copy_link(&from, &to)?;
+            }
+            continue;
+        }
+        let md = std::fs::metadata(&from).map_err(|e| e.to_string())?;
+        if md.is_dir() {
+            copied += mirror(&from, &to)?;
+            continue;
+        }
+        if std::fs::metadata(&to).is_ok_and(|cur| same_file(&md, &cur)) {
+            continue;
+        }
+        std::fs::copy(&from, &to).map_err(|e| format!("failed to copy {}: {e}", from.display()))?;
+        if let Some(mtime) = modified(&md) {
+            let f = std::fs::File::options()
+                .write(true)
+                .open(&to)
+                .map_err(|e| e.to_string())?;
+            f.set_modified(mtime).map_err(|e| e.to_string())?;
+        }
+        copied += 1;
+    }
+    Ok(copied)
+}
+
+fn push_rel(out: &mut Vec<String>, rel: &Path) {
+    out.push(rel.to_string_lossy().replace('\\', "/"));
+}
+
+/// Every non-directory entry under `dir` (an overlay upper dir, whiteouts included).
+fn list_upper(dir: &Path, rel: &Path, out: &mut Vec<String>) {
+    let Ok(rd) = std::fs::read_dir(dir) else {
+        return;
+    };
+    for e in rd.flatten() {
+        let rel = rel.join(e.file_name());
+        if e.file_type().is_ok_and(|t| t.is_dir()) {
+            list_upper(&e.path(), &rel, out);
+        } else {
+            push_rel(out, &rel);
+        }
+    }
+}
+
+/// Paths under `copy` that differ from `src`: changed, added or removed files.
+fn list_diff(src: &Path, copy: &Path, rel: &Path, out: &mut Vec<String>) {
+    let mut names = std::collections::BTreeSet::new();
+    for dir in [src, copy] {
+        if let Ok(rd) = std::fs::read_dir(dir) {
+            names.extend(rd.flatten().map(|e| e.file_name()));
+        }
+    }
+    for name in names {
+        let (a, b, rel) = (src.join(&name), copy.join(&name), rel.join(&name));
+        if installer::is_reparse_point(&a).unwrap_or(false) {
+            continue;
+        }
+        match (std::fs::metadata(&a), std::fs::symlink_metadata(&b)) {
+            (Ok(ma), Ok(mb)) if ma.is_dir() && mb.is_dir() => list_diff(&a, &b, &rel, out),
+            (Ok(ma), Ok(mb)) if same_file(&ma, &mb) => {}
+            _ => push_rel(out, &rel),
+        }
+    }
+}
+
+/// Files the last session of `version` wrote (or deleted), relative to the game root.
+#[tauri::command]
+pub fn get_overlay_writes(app: tauri::AppHandle, version: u32) -> Result<Vec<String>, String> {
+    let root = overlay_dir(&app, version)?;
+    let mut out = Vec::new();
+    if root.join("upper").is_dir() {
+        list_upper(&root.join("upper"), Path::new(""), &mut out);
+    } else if root.join("merged").is_dir() {
+        let game_root = crate::version_dir(&app, version)?;
+        list_diff(&game_root, &root.join("merged"), Path::new(""), &mut out);
+    }
+    out.sort();
+    Ok(out)
+}
@@ -0,0 +1,673 @@
+//! Minimal reader for .NET assembly metadata in PE files (ECMA-335 §II.24-25).
+//!
+//! Only what the plugin scanner needs: the `Assembly` row (name + version) and the
+//! arguments of `[BepInPlugin(guid, name, version)]`. Tables before `Assembly` are sized
+//! from the schema below so their rows can be skipped; nothing else is decoded.
+
+use std::path::Path;
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct AssemblyMeta {
+    pub name: String,
+    pub version: String,
+    /// `[BepInPlugin]` arguments, when the assembly declares a plugin.
+    pub plugin: Option<PluginAttribute>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PluginAttribute {
+    pub guid: String,
+    pub name: String,
+    pub version: String,
+}
+
+const TABLE_TYPE_REF: usize = 0x01;
+const TABLE_MEMBER_REF: usize = 0x0A;
+const TABLE_CUSTOM_ATTRIBUTE: usize = 0x0C;
+const TABLE_ASSEMBLY: usize = 0x20;
+const TABLE_COUNT: usize = 64;
+
+/// Coded index kinds: tag bits and the tables they can point at.
+#[derive(Clone, Copy)]
+enum Coded {
+    TypeDefOrRef,
+    HasConstant,
+    HasCustomAttribute,
+    HasFieldMarshal,
+    HasDeclSecurity,
+    MemberRefParent,
+    HasSemantics,
+    MethodDefOrRef,
+    MemberForwarded,
+    CustomAttributeType,
+    ResolutionScope,
+}
+
+impl Coded {
+    fn layout(self) -> (u32, &'static [usize]) {
+        match self {
+            Coded::TypeDefOrRef => (2, &[0x02, 0x01, 0x1B]),
+            Coded::HasConstant => (2, &[0x04, 0x08, 0x17]),
+            Coded::HasCustomAttribute => (
+                5,
+                &[
+                    0x06, 0x04, 0x01, 0x02, 0x08, 0x09, 0x0A, 0x00, 0x0E, 0x17, 0x14, 0x11, 0x1A,
+                    0x1B, 0x20, 0x23, 0x26, 0x27, 0x28, 0x2A, 0x2C, 0x2B,
+                ],
+            ),
+            Coded::HasFieldMarshal => (1, &[0x04, 0x08]),
+            Coded::HasDeclSecurity => (2, &[0x02, 0x06, 0x20]),
+            Coded::MemberRefParent => (3, &[0x02, 0x01, 0x1A, 0x06, 0x1B]),
+            Coded::HasSemantics => (1, &[0x14, 0x17]),
+            Coded::MethodDefOrRef => (1, &[0x06, 0x0A]),
+            Coded::MemberForwarded => (1, &[0x04, 0x06]),
+            Coded::CustomAttributeType => (3, &[0x06, 0x0A]),
+            Coded::ResolutionScope => (2, &[0x00, 0x1A, 0x23, 0x01]),
+        }
+    }
+}
+
+#[derive(Clone, Copy)]
+enum Col {
+    U8,
+    U16,
+    U32,
+    Str,
+    Guid,
+    Blob,
+    Index(usize),
+    Coded(Coded),
+}
+
+/// Columns of every table up to and including `Assembly`.
+fn schema(table: usize) -> &'static [Col] {
+    use Col::*;
+    match table {
+        0x00 => &[U16, Str, Guid, Guid, Guid],
+        0x01 => &[Coded(self::Coded::ResolutionScope), Str, Str],
+        0x02 => &[
+            U32,
+            Str,
+            Str,
+            Coded(self::Coded::TypeDefOrRef),
+            Index(0x04),
+            Index(0x06),
+        ],
+        0x03 => &[Index(0x04)],
+        0x04 => &[U16, Str, Blob],
+        0x05 => &[Index(0x06)],
+        0x06 => &[U32, U16, U16, Str, Blob, Index(0x08)],
+        0x07 => &[Index(0x08)],
+        0x08 => &[U16, U16, Str],
+        0x09 => &[Index(0x02), Coded(self::Coded::TypeDefOrRef)],
+        0x0A => &[Coded(self::Coded::MemberRefParent), Str, Blob],
+        0x0B => &[U8, U8, Coded(self::Coded::HasConstant), Blob],
+        0x0C => &[
+            Coded(self::Coded::HasCustomAttribute),
+            Coded(self::Coded::CustomAttributeType),
+            Blob,
+        ],
+        0x0D => &[Coded(self::Coded::HasFieldMarshal), Blob],
+        0x0E => &[U16, Coded(self::Coded::HasDeclSecurity), Blob],
+        0x0F => &[U16, U32, Index(0x02)],
+        0x10 => &[U32, Index(0x04)],
+        0x11 => &[Blob],
+        0x12 => &[Index(0x02), Index(0x14)],
+        0x13 => &[Index(0x14)],
+        0x14 => &[U16, Str, Coded(self::Coded::TypeDefOrRef)],
+        0x15 => &[Index(0x02), Index(0x17)],
+        0x16 => &[Index(0x17)],
+        0x17 => &[U16, Str, Blob],
+        0x18 => &[U16, Index(0x06), Coded(self::Coded::HasSemantics)],
+        0x19 => &[
+            Index(0x02),
+            Coded(self::Coded::MethodDefOrRef),
+            Coded(self::Coded::MethodDefOrRef),
+        ],
+        0x1A => &[Str],
+        0x1B => &[Blob],
+        0x1C => &[U16, Coded(self::Coded::MemberForwarded), Str, Index(0x1A)],
+        0x1D => &[U32, Index(0x04)],
+        0x1E => &[U32, U32],
+        0x1F => &[U32],
+        0x20 => &[U32, U16, U16, U16, U16, U32, Blob, Str, Str],
+        _ => &[],
+    }
+}
+
+fn u16_at(data: &[u8], off: usize) -> Option<u16> {
+    Some(u16::from_le_bytes(data.get(off..off + 2)?.try_into().ok()?))
+}
+
+fn u32_at(data: &[u8], off: usize) -> Option<u32> {
+    Some(u32::from_le_bytes(data.get(off..off + 4)?.try_into().ok()?))
+}
+
+fn u64_at(data: &[u8], off: usize) -> Option<u64> {
+    Some(u64::from_le_bytes(data.get(off..off + 8)?.try_into().ok()?))
+}
+
+struct Section {
+    virtual_address: u32,
+    virtual_size: u32,
+    raw_offset: u32,
+    raw_size: u32,
+}
+
+/// File offset of `rva`, via the section table.
+fn rva_to_offset(sections: &[Section], rva: u32) -> Option<usize> {
+    sections.iter().find_map(|s| {
+        // u64: a corrupt section header must not overflow the bounds check.
+        let start = s.virtual_address as u64;
+        let size = s.virtual_size.max(s.raw_size) as u64;
+        let rva = rva as u64;
+        (rva >= start && rva < start + size).then(|| (rva - start + s.raw_offset as u64) as usize)
+    })
+}
+
+/// File offset and size of the CLI metadata root, or `None` for native DLLs.
+fn metadata_range(data: &[u8]) -> Option<(usize, usize)> {
+    if data.get(..2)? != b"MZ" {
+        return None;
+    }
+    let pe = u32_at(data, 0x3C)? as usize;
+    if data.get(pe..pe + 4)? != b"PE\0\0" {
+        return None;
+    }
+    let coff = pe + 4;
+    let section_count = u16_at(data, coff + 2)? as usize;
+    let optional_size = u16_at(data, coff + 16)? as usize;
+    let optional = coff + 20;
+    let dirs = match u16_at(data, optional)? {
+        0x10b => optional + 96,
+        0x20b => optional + 112,
+        _ => return None,
+    };
+    // Data directory 14: CLI header.
+    let cli_rva = u32_at(data, dirs + 14 * 8)?;
+    if cli_rva == 0 {
+        return None;
+    }
+
+    let sections: Vec<Section> = (0..section_count)
+        .map(|i| optional + optional_size + i * 40)
+        .map(|s| {
+            Some(Section {
+                virtual_size: u32_at(data, s + 8)?,
+                virtual_address: u32_at(data, s + 12)?,
+                raw_size: u32_at(data, s + 16)?,
+                raw_offset: u32_at(data, s + 20)?,
+            })
+        })
+        .collect::<Option<_>>()?;
+    let cli = rva_to_offset(&sections, cli_rva)?;
+    let meta_rva = u32_at(data, cli + 8)?;
+    let meta_size = u32_at(data, cli + 12)? as usize;
+    Some((rva_to_offset(&sections, meta_rva)?, meta_size))
+}
+
+struct Heaps<'a> {
+    strings: &'a [u8],
+    blobs: &'a [u8],
+}
+
+impl Heaps<'_> {
+    fn string(&self, index: u32) -> Option<String> {
+        let rest = self.strings.get(index as usize..)?;
+        let end = rest.iter().position(|b| *b == 0)?;
+        Some(String::from_utf8_lossy(&rest[..end]).into_owned())
+    }
+
+    fn blob(&self, index: u32) -> Option<&[u8]> {
+        let rest = self.blobs.get(index as usize..)?;
+        let (len, used) = compressed_u32(rest)?;
+        rest.get(used..used + len as usize)
+    }
+}
+
+/// ECMA-335 compressed unsigned integer: (value, bytes used).
+fn compressed_u32(data: &[u8]) -> Option<(u32, usize)> {
+    let b0 = *data.first()? as u32;
+    if b0 & 0x80 == 0 {
+        Some((b0, 1))
+    } else if b0 & 0xC0 == 0x80 {
+        Some((((b0 & 0x3F) << 8) | *data.get(1)? as u32, 2))
+    } else {
+        let b = data.get(1..4)?;
+        Some((
+            ((b0 & 0x1F) << 24) | (b[0] as u32) << 16 | (b[1] as u32) << 8 | b[2] as u32,
+            4,
+        ))
+    }
+}
+
+/// Custom attribute `SerString`; `None` for the null string (0xFF).
+fn ser_string(data: &[u8], pos: &mut usize) -> Option<String> {
+    if *data.get(*pos)? == 0xFF {
+        *pos += 1;
+        return None;
+    }
+    let (len, used) = compressed_u32(data.get(*pos..)?)?;
+    let start = *pos + used;
+    let bytes = data.get(start..start + len as usize)?;
+    *pos = start + len as usize;
+    Some(String::from_utf8_lossy(bytes).into_owned())
+}
+
+struct Tables<'a> {
+    data: &'a [u8],
+    rows: [u32; TABLE_COUNT],
+    offsets: [usize; TABLE_COUNT],
+    row_sizes: [usize; TABLE_COUNT],
+    wide_strings: bool,
+    wide_guids: bool,
+    wide_blobs: bool,
+}
+
+impl Tables<'_> {
+    fn index_size(&self, table: usize) -> usize {
+        if self.rows.get(table).copied().unwrap_or(0) < 1 << 16 {
+            2
+        } else {
+            4
+        }
+    }
+
+    fn coded_size(&self, coded: Coded) -> usize {
+        let (bits, tables) = coded.layout();
+        let max = tables
+            .iter()
+            .map(|t| self.rows.get(*t).copied().unwrap_or(0))
+            .max()
+            .unwrap_or(0);
+        if max < 1 << (16 - bits) {
+            2
+        } else {
+            4
+        }
+    }
+
+    fn col_size(&self, col: Col) -> usize {
+        let heap = |wide: bool| if wide { 4 } else { 2 };
+        match col {
+            Col::U8 => 1,
+            Col::U16 => 2,
+            Col::U32 => 4,
+            Col::Str => heap(self.wide_strings),
+            Col::Guid => heap(self.wide_guids),
+            Col::Blob => heap(self.wide_blobs),
+            Col::Index(t) => self.index_size(t),
+            Col::Coded(c) => self.coded_size(c),
+        }
+    }
+
+    /// Column `col` of 1-based `row` in `table`.
+    fn cell(&self, table: usize, row: u32, col: usize) -> Option<u32> {
+        if row == 0 || row > self.rows[table] {
+            return None;
+        }
+        let cols = schema(table);
+        let mut off = self.offsets[table] + (row as usize - 1) * self.row_sizes[table];
+        for c in &cols[..col] {
+            off += self.col_size(*c);
+        }
+        match self.col_size(*cols.get(col)?) {
+            1 => self.data.get(off).map(|b| *b as u32),
+            2 => u16_at(self.data, off).map(u32::from),
+            _ => u32_at(self.data, off),
+        }
+    }
+}
+
+fn parse_tables(stream: &[u8]) -> Option<Tables<'_>> {
+    let heap_sizes = *stream.get(6)?;
+    let valid = u64_at(stream, 8)?;
+    let mut rows = [0u32; TABLE_COUNT];
+    let mut pos = 24;
+    for (t, count) in rows.iter_mut().enumerate() {
+        if valid & (1 << t) != 0 {
+            *count = u32_at(stream, pos)?;
+            pos += 4;
+        }
+    }
+    // Undocumented "extra data" flag used by some obfuscators.
+    if heap_sizes & 0x40 != 0 {
+        pos += 4;
+    }
+
+    let mut tables = Tables {
+        data: stream,
+        rows,
+        offsets: [0; TABLE_COUNT],
+        row_sizes: [0; TABLE_COUNT],
+        wide_strings: heap_sizes & 0x01 != 0,
+        wide_guids: heap_sizes & 0x02 != 0,
+        wide_blobs: heap_sizes & 0x04 != 0,
+    };
+    for (t, &count) in rows.iter().enumerate().take(TABLE_ASSEMBLY + 1) {
+        let cols = schema(t);
+        if cols.is_empty() && count > 0 {
+            // A present table we can't size: everything after it is unreachable.
+            return None;
+        }
+        let size: usize = cols.iter().map(|c| tables.col_size(*c)).sum();
+        tables.offsets[t] = pos;
+        tables.row_sizes[t] = size;
+        pos += size * count as usize;
+    }
+    Some(tables)
+}
+
+/// `[BepInPlugin]` arguments from the custom attribute table.
+fn plugin_attribute(tables: &Tables, heaps: &Heaps) -> Option<PluginAttribute> {
+    for row in 1..=tables.rows[TABLE_CUSTOM_ATTRIBUTE] {
+        let ctor = tables.cell(TABLE_CUSTOM_ATTRIBUTE, row, 1)?;
+        // CustomAttributeType tag 3: MemberRef (the attribute type is in another assembly).
+        if ctor & 0b111 != 3 {
+            continue;
+        }
+        let parent = tables.cell(TABLE_MEMBER_REF, ctor >> 3, 0)?;
+        // MemberRefParent tag 1: TypeRef.
+        if parent & 0b111 != 1 {
+            continue;
+        }
+        let type_name = heaps.string(tables.cell(TABLE_TYPE_REF, parent >> 3, 1)?)?;
+        if type_name != "BepInPlugin" {
+            continue;
+        }
+        let value = heaps.blob(tables.cell(TABLE_CUSTOM_ATTRIBUTE, row, 2)?)?;
+        if value.get(..2)? != [1, 0] {
+            continue;
+        }
+        let mut pos = 2;
+        return Some(PluginAttribute {
+            guid: ser_string(value, &mut pos)?,
+            name: ser_string(value, &mut pos).unwrap_or_default(),
+            version: ser_string(value, &mut pos).unwrap_or_default(),
+        });
+    }
+    None
+}
+
+/// Parse assembly metadata from PE bytes; `None` for native or unreadable files.
+pub fn parse(data: &[u8]) -> Option<AssemblyMeta> {
+    let (meta, meta_size) = metadata_range(data)?;
+    let root = data.get(meta..meta + meta_size)?;
+    if u32_at(root, 0)? != 0x424A_5342 {
+        return None;
+    }
+    let version_len = u32_at(root, 12)? as usize;
+    let mut pos = 16 + version_len;
+    let stream_count = u16_at(root, pos + 2)?;
+    pos += 4;
+
+    let (mut tables_stream, mut strings, mut blobs) = (None, &[][..], &[][..]);
+    for _ in 0..stream_count {
+        let offset = u32_at(root, pos)? as usize;
+        let size = u32_at(root, pos + 4)? as usize;
+        let name_start = pos + 8;
+        let name_len = root.get(name_start..)?.iter().position(|b| *b == 0)?;
+        let name = root.get(name_start..name_start + name_len)?;
+        // Names are NUL-terminated and padded to 4 bytes.
+        pos = name_start + (name_len + 4) / 4 * 4;
+        let body = root.get(offset..offset + size)?;
+        match name {
+            b"#~" | b"#-" => tables_stream = Some(body),
+            b"#Strings" => strings = body,
+            b"#Blob" => blobs = body,
+            _ => {}
+        }
+    }
+
+    let tables = parse_tables(tables_stream?)?;
+    let heaps = Heaps { strings, blobs };
+    let version = (1..=4)
+        .map(|c| tables.cell(TABLE_ASSEMBLY, 1, c).map(|v| v.to_string()))
+        .collect::<Option<Vec<_>>>()?
+        .join(".");
+    Some(AssemblyMeta {
+        name: heaps.string(tables.cell(TABLE_ASSEMBLY, 1, 7)?)?,
+        version,
+        plugin: plugin_attribute(&tables, &heaps),
+    })
+}
+
+/// Read and parse an assembly file.
+pub fn read(path: &Path) -> Result<Option<AssemblyMeta>, String> {
+    let data = std::fs::read(path).map_err(|e| e.to_string())?;
+    Ok(parse(&data))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A minimal PE32 .NET assembly: one `Assembly` row and, with `plugin`, a
+    /// `[BepInPlugin(guid, name, version)]` attribute on it. The CLI header starts the only
+    /// section (file offset 0x200, RVA 0x2000); the metadata root follows at 0x248.
+    fn build_assembly(
+        name: &str,
+        version: [u16; 4],
+        plugin: Option<(&str, &str, &str)>,
+    ) -> Vec<u8> {
+        fn push_u16(buf: &mut Vec<u8>, v: u16) {
+            buf.extend_from_slice(&v.to_le_bytes());
+        }
+        fn push_u32(buf: &mut Vec<u8>, v: u32) {
+            buf.extend_from_slice(&v.to_le_bytes());
+        }
+        fn pad4(buf: &mut Vec<u8>) {
+            buf.resize(buf.len().div_ceil(4) * 4, 0);
+        }
+        fn intern(heap: &mut Vec<u8>, s: &str) -> u16 {
+            let index = heap.len() as u16;
+            heap.extend_from_slice(s.as_bytes());
+            heap.push(0);
+            index
+        }
+
+        let mut strings = vec![0u8];
+        let mut blobs = vec![0u8];
+        // `#~` header with 2-byte heap indexes; every present table has one row.
+        let mut tables = Vec::new();
+        push_u32(&mut tables, 0);
+        tables.extend_from_slice(&[2, 0, 0, 1]);
+        let valid: u64 = match plugin {
+            Some(_) => 1 << 0x01 | 1 << 0x0A | 1 << 0x0C | 1 << 0x20,
+            None => 1 << 0x20,
+        };
+        tables.extend_from_slice(&valid.to_le_bytes());
+        tables.extend_from_slice(&0u64.to_le_bytes());
+        for _ in 0..valid.count_ones() {
+            push_u32(&mut tables, 1);
+        }
+        if let Some((guid, plugin_name, plugin_version)) = plugin {
+            // TypeRef: ResolutionScope, TypeName, TypeNamespace.
+            push_u16(&mut tables, 0);
+            push_u16(&mut tables, intern(&mut strings, "BepInPlugin"));
+            push_u16(&mut tables, intern(&mut strings, "BepInEx"));
+            // MemberRef: Class (TypeRef 1), Name, Signature.
+            push_u16(&mut tables, 1 << 3 | 1);
+            push_u16(&mut tables, intern(&mut strings, ".ctor"));
+            push_u16(&mut tables, 0);
+            // CustomAttribute: Parent (Assembly 1), Type (MemberRef 1), Value.
+            push_u16(&mut tables, 1 << 5 | 14);
+            push_u16(&mut tables, 1 << 3 | 3);
+            push_u16(&mut tables, blobs.len() as u16);
+            let mut value = vec![1, 0];
+            for arg in [guid, plugin_name, plugin_version] {
+                value.push(arg.len() as u8);
+                value.extend_from_slice(arg.as_bytes());
+            }
+            value.extend_from_slice(&[0, 0]);
+            blobs.push(value.len() as u8);
+            blobs.extend(value);
+        }
+        // Assembly: HashAlgId, Major/Minor/Build/Revision, Flags, PublicKey, Name, Culture.
+        push_u32(&mut tables, 0x8004);
+        for part in version {
+            push_u16(&mut tables, part);
+        }
+        push_u32(&mut tables, 0);
+        push_u16(&mut tables, 0);
+        push_u16(&mut tables, intern(&mut strings, name));
+        push_u16(&mut tables, 0);
+
+        let mut streams = [("#~", tables), ("#Strings", strings), ("#Blob", blobs)];
+        for (_, body) in &mut streams {
+            pad4(body);
+        }
+        let mut root = Vec::new();
+        push_u32(&mut root, 0x424A_5342);
+        push_u16(&mut root, 1);
+        push_u16(&mut root, 1);
+        push_u32(&mut root, 0);
+        let runtime = b"v4.0.30319\0\0";
+        push_u32(&mut root, runtime.len() as u32);
+        root.extend_from_slice(runtime);
+        push_u16(&mut root, 0);
+        push_u16(&mut root, streams.len() as u16);
+        let mut offset = root.len()
+            + streams
+                .iter()
+                .map(|(n, _)| 8 + (n.len() + 4) / 4 * 4)
+                .sum::<usize>();
+        for (stream_name, body) in &streams {
+            push_u32(&mut root, offset as u32);
+            push_u32(&mut root, body.len() as u32);
+            root.extend_from_slice(stream_name.as_bytes());
+            root.push(0);
+            pad4(&mut root);
+            offset += body.len();
+        }
+        for (_, body) in &streams {
+            root.extend_from_slice(body);
+        }
+
+        const SECTION_RVA: u32 = 0x2000;
+        const SECTION_OFFSET: usize = 0x200;
+        const CLI_HEADER_SIZE: u32 = 72;
+        let mut section = Vec::new();
+        push_u32(&mut section, CLI_HEADER_SIZE);
+        push_u16(&mut section, 2);
+        push_u16(&mut section, 5);
+        push_u32(&mut section, SECTION_RVA + CLI_HEADER_SIZE);
+        push_u32(&mut section, root.len() as u32);
+        section.resize(CLI_HEADER_SIZE as usize, 0);
+        section.extend(root);
+
+        let put = |image: &mut Vec<u8>, at: usize, bytes: &[u8]| {
+            image[at..at + bytes.len()].copy_from_slice(bytes);
+        };
+        let mut image = vec![0u8; SECTION_OFFSET];
+        put(&mut image, 0, b"MZ");
+        put(&mut image, 0x3C, &0x40u32.to_le_bytes());
+        put(&mut image, 0x40, b"PE\0\0");
+        // COFF header: i386, one section, 224-byte optional header.
+        put(&mut image, 0x44, &0x14Cu16.to_le_bytes());
+        put(&mut image, 0x46, &1u16.to_le_bytes());
+        put(&mut image, 0x54, &0xE0u16.to_le_bytes());
+        // PE32 optional header; data directory 14 is the CLI header.
+        put(&mut image, 0x58, &0x10Bu16.to_le_bytes());
+        put(&mut image, 0x58 + 96 + 14 * 8, &SECTION_RVA.to_le_bytes());
+        put(
+            &mut image,
+            0x58 + 96 + 14 * 8 + 4,
+            &CLI_HEADER_SIZE.to_le_bytes(),
+        );
+        // Section table.
+        let table = 0x58 + 0xE0;
+        let section_size = (section.len() as u32).to_le_bytes();
+        put(&mut image, table, b".text\0\0\0");
+        put(&mut image, table + 8, &section_size);
+        put(&mut image, table + 12, &SECTION_RVA.to_le_bytes());
+        put(&mut image, table + 16, &section_size);
+        put(
+            &mut image,
+            table + 20,
+            &(SECTION_OFFSET as u32).to_le_bytes(),
+        );
+        image.extend(section);
+        image
+    }
+
+    #[test]
+    fn assembly_metadata_is_read_from_a_minimal_pe() {
+        let plugin = build_assembly(
+            "HQoL",
+            [1, 2, 3, 4],
+            Some(("com.hqhqteam.hqol", "HQoL", "0.2.0")),
+        );
+        assert_eq!(
+            parse(&plugin),
+            Some(AssemblyMeta {
+                name: "HQoL".to_string(),
+                version: "1.2.3.4".to_string(),
+                plugin: Some(PluginAttribute {
+                    guid: "com.hqhqteam.hqol".to_string(),
+                    name: "HQoL".to_string(),
+                    version: "0.2.0".to_string(),
+                }),
+            })
+        );
+
+        let library = build_assembly("0Harmony", [2, 2, 2, 0], None);
+        let meta = parse(&library).expect("library metadata");
+        assert_eq!(meta.name, "0Harmony");
+        assert_eq!(meta.version, "2.2.2.0");
+        assert_eq!(meta.plugin, None);
+    }
+
+    #[test]
+    fn truncated_pe_files_are_not_assemblies() {
+        let image = build_assembly("HQoL", [1, 0, 0, 0], Some(("guid", "HQoL", "1.0.0")));
+        // The metadata ends the file, so every shorter prefix is missing part of it.
+        for len in 0..image.len() {
+            assert_eq!(parse(&image[..len]), None, "prefix of {len} bytes");
+        }
+    }
+
+    #[test]
+    fn malformed_pe_headers_are_rejected() {
+        let image = build_assembly("HQoL", [1, 0, 0, 0], None);
+        let patched = |at: usize, bytes: &[u8]| {
+            let mut image = image.clone();
+            image[at..at + bytes.len()].copy_from_slice(bytes);
+            image
+        };
+
+        // DOS magic.
+        assert_eq!(parse(&patched(0, b"ZM")), None);
+        // `e_lfanew` past the end of the file.
+        assert_eq!(parse(&patched(0x3C, &0x7FFF_0000u32.to_le_bytes())), None);
+        // PE signature.
+        assert_eq!(parse(&patched(0x40, b"NE\0\0")), None);
+        // Optional header magic that is neither PE32 nor PE32+.
+        assert_eq!(parse(&patched(0x58, &0x0107u16.to_le_bytes())), None);
+        // No CLI header: a native DLL.
+        assert_eq!(parse(&patched(0x128, &0u32.to_le_bytes())), None);
+        // CLI header RVA outside every section.
+        assert_eq!(parse(&patched(0x128, &0x9000u32.to_le_bytes())), None);
+        // Section sizes and RVAs near `u32::MAX` must not overflow the RVA lookup.
+        assert_eq!(
+            parse(&patched(0x138 + 8, &u32::MAX.to_le_bytes())),
+            parse(&image)
+        );
+        assert_eq!(parse(&patched(0x138 + 12, &u32::MAX.to_le_bytes())), None);
+        // Metadata root signature.
+        assert_eq!(parse(&patched(0x248, b"BSJA")), None);
+        // Metadata size larger than the file.
+        assert_eq!(parse(&patched(0x200 + 12, &u32::MAX.to_le_bytes())), None);
+
+        // Not a PE file at all.
+        assert_eq!(parse(b"MZ"), None);
+        assert_eq!(parse(b"fake HQHQTeam-HQoL-0.2.0"), None);
+
+        // Any single corrupt byte is either tolerated or rejected, never a panic.
+        for at in 0..image.len() {
+            let mut corrupt = image.clone();
+            corrupt[at] ^= 0xFF;
+            let _ = parse(&corrupt);
+        }
+    }
+}
@@ -0,0 +1,176 @@
+//! Scan of the plugin DLLs actually present in `BepInEx/plugins`, independent of the
+//! lockfile: whatever a mod manager or the player copied in shows up too.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::{Path, PathBuf};
+
+use serde::Serialize;
+
+use crate::progress::{self, GuidConflict, PluginConflictPayload};
+use crate::{mod_config, mods, pe_meta};
+
+/// The DLL's top-level folder is a mod from the launcher manifest.
+pub const SOURCE_MANIFEST: &str = "manifest";
+/// A Thunderstore package (has `manifest.json`) the launcher manifest doesn't list.
+pub const SOURCE_PACKAGE: &str = "package";
+/// Hand-copied: no package metadata at all.
+pub const SOURCE_UNKNOWN: &str = "unknown";
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ScannedPlugin {
+    /// `/`-separated path relative to `BepInEx/plugins`.
+    pub path: String,
+    /// Top-level folder under `plugins`; `None` for DLLs dropped in directly.
+    pub folder: Option<String>,
+    /// `manifest`, `package` or `unknown`.
+    pub source: String,
+    pub assembly_name: Option<String>,
+    pub assembly_version: Option<String>,
+    /// `[BepInPlugin]` GUID/name/version; `None` for libraries and native DLLs.
+    pub guid: Option<String>,
+    pub plugin_name: Option<String>,
+    pub plugin_version: Option<String>,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PluginScan {
+    pub version: u32,
+    pub plugins: Vec<ScannedPlugin>,
+    /// Plugins whose source is `unknown`.
+    pub unknown: usize,
+    pub duplicate_guids: Vec<GuidConflict>,
+}
+
+fn collect_dlls(dir: &Path, out: &mut Vec<PathBuf>) {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path.is_dir() {
+            collect_dlls(&path, out);
+        } else if path
+            .extension()
+            .is_some_and(|e| e.eq_ignore_ascii_case("dll"))
+        {
+            out.push(path);
+        }
+    }
+}
+
+/// Every DLL under `plugins` with its assembly metadata.
+pub fn scan(plugins: &Path) -> Vec<ScannedPlugin> {
+    let managed: BTreeSet<String> = mod_config::cached_remote_manifest()
+        .map(|m| {
+            m.mods
+                .iter()
+                .map(|m| format!("{}-{}", m.dev, m.name).to_lowercase())
+                .collect()
+        })
+        .unwrap_or_default();
+
+    let mut dlls: Vec<PathBuf> = vec![];
+    collect_dlls(plugins, &mut dlls);
+    dlls.sort();
+
+    dlls.into_iter()
+        .map(|path| {
+            let rel = path.strip_prefix(plugins).unwrap_or(&path);
+            let folder = (rel.components().count() > 1)
+                .then(|| rel.components().next())
+                .flatten()
+                .map(|c| c.as_os_str().to_string_lossy().to_string());
+            let source = match &folder {
+                Some(f) if managed.contains(&f.to_lowercase()) => SOURCE_MANIFEST,
+                Some(f) if plugins.join(f).join("manifest.json").is_file() => SOURCE_PACKAGE,
+                _ => SOURCE_UNKNOWN,
+            };
+            let (meta, error) = match pe_meta::read(&path) {
+                Ok(meta) => (meta, None),
+                Err(e) => (None, Some(e)),
+            };
+            let plugin = meta.as_ref().and_then(|m| m.plugin.clone());
+            ScannedPlugin {
+                path: rel.to_string_lossy().replace('\\', "/"),
+                folder,
+                source: source.to_string(),
+                assembly_name: meta.as_ref().map(|m| m.name.clone()),
+                assembly_version: meta.as_ref().map(|m| m.version.clone()),
+                guid: plugin.as_ref().map(|p| p.guid.clone()),
+                plugin_name: plugin.as_ref().map(|p| p.name.clone()),
+                plugin_version: plugin.map(|p| p.version),
+                error,
+            }
+        })
+        .collect()
+}
+
+/// GUIDs declared by plugins of two or more packages: a guaranteed runtime conflict.
+///
+/// The same DLL copied twice within one package isn't reported.
+pub fn duplicate_guids(plugins: &[ScannedPlugin]) -> Vec<GuidConflict> {
+    let mut by_guid: BTreeMap<&str, Vec<&ScannedPlugin>> = BTreeMap::new();
+    for p in plugins {
+        if let Some(guid) = p.guid.as_deref() {
+            by_guid.entry(guid).or_default().push(p);
+        }
+    }
+    by_guid
+        .into_iter()
+        .filter_map(|(guid, found)| {
+            let packages: BTreeSet<String> = found
+                .iter()
+                .map(|p| p.folder.clone().unwrap_or_else(|| p.path.clone()))
+                .collect();
+            (packages.len() > 1).then(|| GuidConflict {
+                guid: guid.to_string(),
+                packages: packages.into_iter().collect(),
+                paths: found.iter().map(|p| p.path.clone()).collect(),
+            })
+        })
+        .collect()
+}
+
+/// Pre-launch check: warn (log + `plugin-conflict`) about duplicate plugin GUIDs.
+pub fn warn_duplicate_guids(app: &tauri::AppHandle, version: u32, game_root: &Path) {
+    let conflicts = duplicate_guids(&scan(&mods::plugins_dir(game_root)));
+    if conflicts.is_empty() {
+        return;
+    }
+    for c in &conflicts {
+        log::warn!(
+            "Plugin GUID {} is shipped by several packages: {}",
+            c.guid,
+            c.packages.join(", ")
+        );
+    }
+    progress::emit_plugin_conflict(app, PluginConflictPayload { version, conflicts });
+}
+
+/// Scan the plugin DLLs of `version` (default: active version).
+#[tauri::command]
+pub async fn scan_plugins(
+    app: tauri::AppHandle,
+    version: Option<u32>,
+) -> Result<PluginScan, String> {
+    let version = crate::resolve_version(&app, version)?;
+    let plugins = mods::plugins_dir(&crate::version_dir(&app, version)?);
+    let plugins = tauri::async_runtime::spawn_blocking(move || scan(&plugins))
+        .await
+        .map_err(|e| e.to_string())?;
+    let unknown = plugins
+        .iter()
+        .filter(|p| p.source == SOURCE_UNKNOWN)
+        .count();
+    log::info!(
+        "Scanned {} plugin DLL(s) for v{version}: {unknown} unknown",
+        plugins.len()
+    );
+    Ok(PluginScan {
+        version,
+        duplicate_guids: duplicate_guids(&plugins),
+        plugins,
+        unknown,
+    })
+}
@@ -0,0 +1,230 @@
+//! Wine registry tweaks recommended by the manifest (`registry_tweaks`), applied to the
+//! launcher's Proton prefix before launch.
+//!
+//! Pending tweaks are written to one `.reg` file and imported with `proton run regedit /S`,
+//! which also creates the prefix when the game was never started. What was applied is
+//! recorded in `{compat_data}/hq-launcher-tweaks.json`, so a tweak is only imported again
+//! when the manifest changes it. Failures are logged and never block the launch.
+
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::process::Command;
+
+use serde::{Deserialize, Serialize};
+
+use crate::mod_config::{self, RegistryTweak};
+
+const STATE_FILE: &str = "hq-launcher-tweaks.json";
+const REG_FILE: &str = "hq-launcher-tweaks.reg";
+
+/// Tweak id -> fingerprint of what was imported.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct AppliedTweaks {
+    #[serde(default)]
+    applied: BTreeMap<String, String>,
+}
+
+fn fingerprint(tweak: &RegistryTweak) -> String {
+    format!(
+        "{}|{}|{}|{}",
+        tweak.key, tweak.value, tweak.kind, tweak.data
+    )
+}
+
+fn read_applied(compat_data: &Path) -> AppliedTweaks {
+    std::fs::read_to_string(compat_data.join(STATE_FILE))
+        .ok()
+        .and_then(|text| serde_json::from_str(&text).ok())
+        .unwrap_or_default()
+}
+
+/// `HKCU`/`HKLM` shorthands expanded; other hives are refused, and so are line breaks and
+/// brackets, which would end the `[key]` header and open a section of the manifest's choosing.
+fn full_key(key: &str) -> Option<String> {
+    let key = key.trim().trim_matches('\\');
+    if key.chars().any(|c| c.is_control() || c == '[' || c == ']') {
+        return None;
+    }
+    let (root, rest) = key.split_once('\\').unwrap_or((key, ""));
+    let root = match root.to_ascii_uppercase().as_str() {
+        "HKCU" | "HKEY_CURRENT_USER" => "HKEY_CURRENT_USER",
+        "HKLM" | "HKEY_LOCAL_MACHINE" => "HKEY_LOCAL_MACHINE",
+        _ => return None,
+    };
+    Some(if rest.is_empty() {
+        root.to_string()
+    } else {
+        format!("{root}\\{rest}")
+    })
+}
+
+fn reg_string(s: &str) -> String {
+    format!("\"{}\"", s.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+/// `"name"=data` line of a `.reg` file. Control characters are refused: a line break in
+/// the name or data would start a new line of the file.
+fn reg_line(tweak: &RegistryTweak) -> Result<String, String> {
+    if tweak.value.chars().any(char::is_control) {
+        return Err("control character in value name".to_string());
+    }
+    if tweak.data.chars().any(char::is_control) {
+        return Err("control character in data".to_string());
+    }
+    let name = if tweak.value.is_empty() {
+        "@".to_string()
+    } else {
+        reg_string(&tweak.value)
+    };
+    let data = match tweak.kind.to_ascii_uppercase().as_str() {
+        "REG_SZ" => reg_string(&tweak.data),
+        "REG_DWORD" => {
+            let raw = tweak.data.trim();
+            let n = match raw.strip_prefix("0x") {
+                Some(hex) => u32::from_str_radix(hex, 16),
+                None => raw.parse::<u32>(),
+            }
+            .map_err(|e| format!("invalid REG_DWORD {raw}: {e}"))?;
+            format!("dword:{n:08x}")
+        }
+        "DELETE" => "-".to_string(),
+        other => return Err(format!("unsupported value type {other}")),
+    };
+    Ok(format!("{name}={data}"))
+}
+
+/// Import the manifest's tweaks that aren't in the prefix yet.
+pub fn apply(proton_binary: &Path, compat_data: &Path, steam_client: &Path) {
+    let tweaks = mod_config::cached_remote_manifest()
+        .map(|m| m.registry_tweaks)
+        .unwrap_or_default();
+    let mut state = read_applied(compat_data);
+    let mut reg = String::from("REGEDIT4\n");
+    let mut pending = vec![];
+    for tweak in tweaks {
+        let print = fingerprint(&tweak);
+        if state.applied.get(&tweak.id) == Some(&print) {
+            continue;
+        }
+        let Some(key) = full_key(&tweak.key) else {
+            log::warn!(
+                "Skipping registry tweak {}: unsupported key {:?}",
+                tweak.id,
+                tweak.key
+            );
+            continue;
+        };
+        match reg_line(&tweak) {
+            Ok(line) => reg.push_str(&format!("\n[{key}]\n{line}\n")),
+            Err(e) => {
+                log::warn!("Skipping registry tweak {}: {e}", tweak.id);
+                continue;
+            }
+        }
+        pending.push((tweak.id, print));
+    }
+    if pending.is_empty() {
+        return;
+    }
+
+    let reg_path = compat_data.join(REG_FILE);
+    if let Err(e) = std::fs::write(&reg_path, reg) {
+        log::warn!("Failed to write registry tweaks: {e}");
+        return;
+    }
+    log::info!(
+        "Applying {} registry tweak(s) to the Proton prefix",
+        pending.len()
+    );
+    let status = Command::new(proton_binary)
+        .arg("run")
+        .arg("regedit")
+        .arg("/S")
+        .arg(&reg_path)
+        .env("STEAM_COMPAT_DATA_PATH", compat_data)
+        .env("STEAM_COMPAT_CLIENT_INSTALL_PATH", steam_client)
+        .env_remove("PYTHONPATH")
+        .env_remove("PYTHONHOME")
+        .status();
+    let _ = std::fs::remove_file(&reg_path);
+    match status {
+        Ok(s) if s.success() => {}
+        Ok(s) => {
+            log::warn!("regedit exited with {s}; registry tweaks not applied");
+            return;
+        }
+        Err(e) => {
+            log::warn!("Failed to run regedit: {e}");
+            return;
+        }
+    }
+
+    state.applied.extend(pending);
+    match serde_json::to_string_pretty(&state) {
+        Ok(json) => {
+            if let Err(e) = crate::zip_utils::write_atomic(&compat_data.join(STATE_FILE), json) {
+                log::warn!("Failed to record registry tweaks: {e}");
+            }
+        }
+        Err(e) => log::warn!("Failed to record registry tweaks: {e}"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tweak(key: &str, value: &str, kind: &str, data: &str) -> RegistryTweak {
+        RegistryTweak {
+            id: "test".to_string(),
+            key: key.to_string(),
+            value: value.to_string(),
+            kind: kind.to_string(),
+            data: data.to_string(),
+        }
+    }
+
+    #[test]
+    fn keys_expand_their_hive_shorthand() {
+        assert_eq!(
+            full_key(r"HKCU\Software\Wine\DllOverrides").as_deref(),
+            Some(r"HKEY_CURRENT_USER\Software\Wine\DllOverrides")
+        );
+        assert_eq!(full_key(r"HKEY_USERS\.DEFAULT"), None);
+    }
+
+    #[test]
+    fn keys_that_could_open_another_section_are_rejected() {
+        for key in [
+            "HKCU\\Foo]\n[HKEY_LOCAL_MACHINE\\Software\\Evil",
+            "HKCU\\Foo\r\nBar",
+            "HKCU\\Foo[Bar",
+            "HKCU\\Foo]",
+            "HKCU\\Foo\u{0}",
+        ] {
+            assert_eq!(full_key(key), None, "{key:?}");
+        }
+    }
+
+    #[test]
+    fn control_characters_in_names_and_data_are_rejected() {
+        let injected = "x\"\n[HKEY_LOCAL_MACHINE\\Software\\Evil]\n\"y\"=\"z";
+        assert!(reg_line(&tweak("HKCU\\Foo", injected, "REG_SZ", "1")).is_err());
+        assert!(reg_line(&tweak("HKCU\\Foo", "name", "REG_SZ", injected)).is_err());
+        assert!(reg_line(&tweak("HKCU\\Foo", "name", "REG_SZ", "a\rb")).is_err());
+        assert!(reg_line(&tweak("HKCU\\Foo", "name", "REG_DWORD", "1\n")).is_err());
+    }
+
+    #[test]
+    fn plain_values_become_reg_lines() {
+        assert_eq!(
+            reg_line(&tweak("HKCU\\Foo", "d3d11", "REG_SZ", r#"native,"builtin""#)).unwrap(),
+            r#""d3d11"="native,\"builtin\"""#
+        );
+        assert_eq!(
+            reg_line(&tweak("HKCU\\Foo", "", "REG_DWORD", "0x10")).unwrap(),
+            "@=dword:00000010"
+        );
+        assert_eq!(reg_line(&tweak("HKCU\\Foo", "old", "delete", "")).unwrap(), r#""old"=-"#);
+    }
+}
@@ -0,0 +1,388 @@
+//! Named mod profiles ("Speedrun", "Practice", "Vanilla+"): a subset of the manifest mods
+//! plus extra Thunderstore packages, kept in `state/profiles.json`.
+//!
+//! Activating a profile for a game version installs whatever it needs that isn't in
+//! `BepInEx/plugins` yet (zips come from the package cache when present) and then enables
+//! or disables mods the same way the mod toggle does: `.old` suffixes on disk and
+//! `disablemod.json` for the UI. Plugin folders the launcher doesn't know about are left
+//! alone, and `required` manifest mods stay enabled in every profile.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use serde::{Deserialize, Serialize};
+
+use crate::mod_config::{self, ModEntry, ModsConfig};
+use crate::progress::{
+    self, InstallSummary, TaskErrorPayload, TaskFinishedPayload, TaskProgressPayload, TaskStep,
+};
+use crate::tasks::{self, TaskKind};
+use crate::{mods, storage};
+
+/// Steps of `activate_profile` when it has mods to install.
+const PROFILE_STEPS: [TaskStep; 1] = [TaskStep {
+    id: "install_mods",
+    name: "Install Profile Mods",
+    weight: 1.0,
+}];
+
+const MAX_NAME_LEN: usize = 64;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProfileMod {
+    pub dev: String,
+    pub name: String,
+    /// Thunderstore version to install (extra mods only); latest when `None`.
+    #[serde(default)]
+    pub version: Option<String>,
+}
+
+impl ProfileMod {
+    fn id(&self) -> (String, String) {
+        (self.dev.to_lowercase(), self.name.to_lowercase())
+    }
+
+    fn to_entry(&self) -> ModEntry {
+        ModEntry {
+            dev: self.dev.clone(),
+            name: self.name.clone(),
+            enabled: true,
+            low_cap: None,
+            high_cap: None,
+            version_config: self
+                .version
+                .clone()
+                .map(|v| BTreeMap::from([(0, v)]))
+                .unwrap_or_default(),
+            tags: vec![],
+            sha256: BTreeMap::new(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Profile {
+    pub name: String,
+    /// Manifest mods enabled by this profile; the other manifest mods are disabled.
+    #[serde(default)]
+    pub mods: Vec<ProfileMod>,
+    /// Packages the manifest doesn't list.
+    #[serde(default)]
+    pub extra_mods: Vec<ProfileMod>,
+}
+
+/// `state/profiles.json`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ProfilesFile {
+    #[serde(default)]
+    pub profiles: Vec<Profile>,
+    /// Active profile name per game version.
+    #[serde(default)]
+    pub active: BTreeMap<u32, String>,
+}
+
+impl ProfilesFile {
+    fn position(&self, name: &str) -> Option<usize> {
+        self.profiles
+            .iter()
+            .position(|p| p.name.eq_ignore_ascii_case(name.trim()))
+    }
+}
+
+fn profiles_path(app: &tauri::AppHandle) -> Result<std::path::PathBuf, String> {
+    Ok(storage::state_dir(app)?.join("profiles.json"))
+}
+
+fn read_profiles(app: &tauri::AppHandle) -> Result<ProfilesFile, String> {
+    let path = profiles_path(app)?;
+    if !path.exists() {
+        return Ok(ProfilesFile::default());
+    }
+    let text = std::fs::read_to_string(&path).map_err(|e| e.to_string())?;
+    serde_json::from_str(&text).map_err(|e| format!("invalid profiles.json: {e}"))
+}
+
+fn write_profiles(app: &tauri::AppHandle, file: &ProfilesFile) -> Result<(), String> {
+    let path = profiles_path(app)?;
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    let json = serde_json::to_string_pretty(file).map_err(|e| e.to_string())?;
+    crate::zip_utils::write_atomic(&path, json)
+}
+
+fn validate_name(file: &ProfilesFile, name: &str) -> Result<String, String> {
+    let name = name.trim();
+    if name.is_empty() || name.len() > MAX_NAME_LEN {
+        return Err(format!("profile name must be 1-{MAX_NAME_LEN} characters"));
+    }
+    if file.position(name).is_some() {
+        return Err(format!("a profile named {name} already exists"));
+    }
+    Ok(name.to_string())
+}
+
+/// Save `profile` under its name, or `"{name} (2)"` etc. when that name is taken.
+pub(crate) fn add_profile(app: &tauri::AppHandle, mut profile: Profile) -> Result<Profile, String> {
+    let mut file = read_profiles(app)?;
+    let base: String = profile.name.trim().chars().take(MAX_NAME_LEN - 5).collect();
+    let mut name = base.clone();
+    let mut n = 2;
+    while file.position(&name).is_some() {
+        name = format!("{base} ({n})");
+        n += 1;
+    }
+    profile.name = validate_name(&file, &name)?;
+    file.profiles.push(profile.clone());
+    write_profiles(app, &file)?;
+    Ok(profile)
+}
+
+#[tauri::command]
+pub fn list_profiles(app: tauri::AppHandle) -> Result<ProfilesFile, String> {
+    read_profiles(&app)
+}
+
+#[tauri::command]
+pub fn create_profile(
+    app: tauri::AppHandle,
+    name: String,
+    mods: Vec<ProfileMod>,
+    extra_mods: Option<Vec<ProfileMod>>,
+) -> Result<Profile, String> {
+    let mut file = read_profiles(&app)?;
+    let profile = Profile {
+        name: validate_name(&file, &name)?,
+        mods,
+        extra_mods: extra_mods.unwrap_or_default(),
+    };
+    file.profiles.push(profile.clone());
+    write_profiles(&app, &file)?;
+    log::info!("Created profile {}", profile.name);
+    Ok(profile)
+}
+
+#[tauri::command]
+pub fn rename_profile(app: tauri::AppHandle, name: String, new_name: String) -> Result<(), String> {
+    let mut file = read_profiles(&app)?;
+    let idx = file
+        .position(&name)
+        .ok_or_else(|| format!("no profile named {name}"))?;
+    let old = file.profiles[idx].name.clone();
+    // Allow changing only the case of a name.
+    let new_name = if old.eq_ignore_ascii_case(new_name.trim()) {
+        new_name.trim().to_string()
+    } else {
+        validate_name(&file, &new_name)?
+    };
+    file.profiles[idx].name = new_name.clone();
+    for active in file.active.values_mut() {
+        if *active == old {
+            *active = new_name.clone();
+        }
+    }
+    write_profiles(&app, &file)?;
+    log::info!("Renamed profile {old} to {new_name}");
+    Ok(())
+}
+
+/// Delete a profile. Versions it was active for keep their current mods.
+#[tauri::command]
+pub fn delete_profile(app: tauri::AppHandle, name: String) -> Result<(), String> {
+    let mut file = read_profiles(&app)?;
+    let idx = file
+        .position(&name)
+        .ok_or_else(|| format!("no profile named {name}"))?;
+    let removed = file.profiles.remove(idx);
+    file.active.retain(|_, active| *active != removed.name);
+    write_profiles(&app, &file)?;
+    log::info!("Deleted profile {}", removed.name);
+    Ok(())
+}
+
+/// Install the profile mods missing from `plugins`, reporting progress for `version`.
+async fn install_missing(
+    app: &tauri::AppHandle,
+    version: u32,
+    game_root: &std::path::Path,
+    missing: Vec<ModEntry>,
+) -> Result<(), String> {
+    const STEPS_TOTAL: u32 = PROFILE_STEPS.len() as u32;
+    let task_id = tasks::begin(app, version, TaskKind::Profile);
+    let cancel = tasks::cancel_flag(app, version);
+    progress::emit_plan(app, version, &PROFILE_STEPS);
+    let cfg = ModsConfig { mods: missing };
+    let res = mods::install_mods_with_progress(
+        app,
+        game_root,
+        version,
+        &cfg,
+        Some(cancel.as_ref()),
+        |p| {
+            let step_progress = p.fraction();
+            progress::emit_progress(
+                app,
+                TaskProgressPayload {
+                    version,
+                    steps_total: STEPS_TOTAL,
+                    step: 1,
+                    step_name: PROFILE_STEPS[0].name.to_string(),
+                    step_progress,
+                    overall_percent: step_progress * 100.0,
+                    detail: p.detail,
+                    downloaded_bytes: (p.total_bytes > 0).then_some(p.done_bytes),
+                    total_bytes: (p.total_bytes > 0).then_some(p.total_bytes),
+                    extracted_files: Some(p.done_mods),
+                    total_files: Some(p.total_mods),
+                },
+            );
+        },
+    )
+    .await;
+    tasks::end(app, version, &task_id);
+
+    match res {
+        Ok(changes) => {
+            progress::emit_finished(
+                app,
+                TaskFinishedPayload {
+                    version,
+                    path: game_root.to_string_lossy().to_string(),
+                    summary: Some(InstallSummary::from_changes(changes)),
+                },
+            );
+            Ok(())
+        }
+        Err(e) => {
+            progress::emit_error(
+                app,
+                TaskErrorPayload {
+                    version,
+                    message: e.clone(),
+                    ..Default::default()
+                },
+            );
+            Err(e)
+        }
+    }
+}
+
+/// Saved profile called `name` (case-insensitive).
+pub(crate) fn find_profile(app: &tauri::AppHandle, name: &str) -> Result<Profile, String> {
+    let file = read_profiles(app)?;
+    file.position(name)
+        .map(|idx| file.profiles[idx].clone())
+        .ok_or_else(|| format!("no profile named {name}"))
+}
+
+/// Manifest mods, falling back to the cached manifest when offline.
+pub(crate) async fn manifest_mods(profile: &Profile) -> Vec<ModEntry> {
+    match mod_config::fetch_remote_manifest(&reqwest::Client::new()).await {
+        Ok(remote) => remote.mods,
+        Err(e) => {
+            log::warn!(
+                "Using the cached manifest for profile {}: {e}",
+                profile.name
+            );
+            mod_config::cached_remote_manifest()
+                .map(|m| m.mods)
+                .unwrap_or_default()
+        }
+    }
+}
+
+/// Mods `profile` runs with: its chosen manifest mods, every `required` one, and its extras.
+pub(crate) fn profile_mods(profile: &Profile, manifest_mods: &[ModEntry]) -> Vec<ModEntry> {
+    let chosen: BTreeSet<(String, String)> = profile.mods.iter().map(ProfileMod::id).collect();
+    let mut wanted: Vec<ModEntry> = manifest_mods
+        .iter()
+        .filter(|m| m.enabled)
+        .filter(|m| {
+            m.is_required() || chosen.contains(&(m.dev.to_lowercase(), m.name.to_lowercase()))
+        })
+        .cloned()
+        .collect();
+    wanted.extend(profile.extra_mods.iter().map(ProfileMod::to_entry));
+    wanted
+}
+
+/// Switch `version` (default: active version) to the mods of profile `name`.
+#[tauri::command]
+pub async fn activate_profile(
+    app: tauri::AppHandle,
+    name: String,
+    version: Option<u32>,
+) -> Result<(), String> {
+    let version = crate::resolve_version(&app, version)?;
+    let game_root = storage::installed_version_dir(&app, version)?;
+    if crate::is_game_running(&app) {
+        return Err("stop the game before switching profiles".to_string());
+    }
+    if tasks::task_id_for(&app, version).is_some() {
+        return Err(format!(
+            "a task is running for v{version}; wait for it to finish"
+        ));
+    }
+
+    let mut file = read_profiles(&app)?;
+    let profile = file
+        .position(&name)
+        .map(|idx| file.profiles[idx].clone())
+        .ok_or_else(|| format!("no profile named {name}"))?;
+
+    let manifest_mods = manifest_mods(&profile).await;
+    let wanted = profile_mods(&profile, &manifest_mods);
+
+    let plugins = crate::plugins_dir(&app, version)?;
+    let missing: Vec<ModEntry> = wanted
+        .iter()
+        .filter(|m| crate::mod_dir_for(&plugins, &m.dev, &m.name).is_none())
+        .cloned()
+        .collect();
+    if !missing.is_empty() {
+        log::info!(
+            "Profile {} needs {} mod(s) not installed for v{version}",
+            profile.name,
+            missing.len()
+        );
+        install_missing(&app, version, &game_root, missing).await?;
+    }
+
+    // Every mod a profile can name: toggle exactly these, nothing hand-installed.
+    let enabled: BTreeSet<(String, String)> = wanted
+        .iter()
+        .map(|m| (m.dev.to_lowercase(), m.name.to_lowercase()))
+        .collect();
+    let mut known: BTreeSet<(String, String)> = manifest_mods
+        .iter()
+        .map(|m| (m.dev.to_lowercase(), m.name.to_lowercase()))
+        .collect();
+    for p in &file.profiles {
+        known.extend(p.extra_mods.iter().map(ProfileMod::id));
+    }
+
+    let mut list = crate::read_disablemod(&app)?;
+    for (dev, name) in &known {
+        let on = enabled.contains(&(dev.clone(), name.clone()));
+        let id = crate::normalize_mod_id(dev, name);
+        list.mods.retain(|m| m != &id);
+        if !on {
+            list.mods.push(id);
+        }
+        if let Some(dir) = crate::mod_dir_for(&plugins, dev, name) {
+            let _ = crate::set_mod_files_old_suffix(&dir, on);
+        }
+    }
+    list.mods
+        .sort_by(|a, b| a.dev.cmp(&b.dev).then(a.name.cmp(&b.name)));
+    list.mods.dedup();
+    crate::write_disablemod(&app, &list)?;
+
+    file.active.insert(version, profile.name.clone());
+    write_profiles(&app, &file)?;
+    log::info!(
+        "Activated profile {} for v{version}: {} mod(s) enabled",
+        profile.name,
+        enabled.len()
+    );
+    Ok(())
+}
@@ -1,6 +1,11 @@
+use std::io::Write;
+use std::sync::OnceLock;
+
 use serde::Serialize;
 use tauri::{AppHandle, Emitter};
 
+use crate::{announce, taskbar};
+
 /// Frontend-facing progress event payload for long-running tasks.
 ///
 /// Event name: `download://progress`
@@ -23,6 +28,35 @@ pub struct TaskProgressPayload {
     pub total_files: Option<u64>,
 }
 
+/// Static description of one step in a multi-step task.
+#[derive(Debug, Clone, Copy)]
+pub struct TaskStep {
+    pub id: &'static str,
+    pub name: &'static str,
+    /// Relative share of the task's expected work (bytes/time); only ratios matter.
+    pub weight: f64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TaskPlanStepPayload {
+    pub step: u32, // 1-based, matches `TaskProgressPayload::step`
+    pub id: String,
+    pub name: String,
+    pub label_key: String, // i18n key for the step's display name
+    pub weight: f64,       // normalized share of overall progress (sums to 1.0)
+}
+
+/// Emitted once at task start so the UI can render a stepper without hardcoding steps.
+///
+/// Event name: `task-plan`
+#[derive(Debug, Clone, Serialize)]
+pub struct TaskPlanPayload {
+    pub version: u32,
+    pub task_id: Option<String>,
+    pub steps_total: u32,
+    pub steps: Vec<TaskPlanStepPayload>,
+}
+
 #[derive(Debug, Clone, Serialize)]
 pub struct TaskUpdatableProgressPayload {
     pub version: u32,
@@ -32,38 +66,434 @@ pub struct TaskUpdatableProgressPayload {
     pub detail: Option<String>,
 }
 
+/// One mod written by an install/sync/update. `from_version` is `None` for new mods.
+#[derive(Debug, Clone, Serialize, serde::Deserialize)]
+pub struct ModChange {
+    pub dev: String,
+    pub name: String,
+    pub from_version: Option<String>,
+    pub to_version: String,
+}
+
+/// What a finished task changed, for the "What's new in this sync" dialog.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct InstallSummary {
+    pub mods_added: Vec<ModChange>,
+    pub mods_updated: Vec<ModChange>,
+    pub config_files_added: Vec<String>, // relative to the shared config dir
+    pub bepinex_version: Option<String>, // set when the task (re)installed BepInEx
+}
+
+impl InstallSummary {
+    pub fn from_changes(changes: Vec<ModChange>) -> Self {
+        let (mods_updated, mods_added) =
+            changes.into_iter().partition(|c| c.from_version.is_some());
+        Self {
+            mods_added,
+            mods_updated,
+            ..Self::default()
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize)]
 pub struct TaskFinishedPayload {
     pub version: u32,
     pub path: String,
+    pub summary: Option<InstallSummary>,
 }
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Default, Serialize)]
 pub struct TaskErrorPayload {
     pub version: u32,
     pub message: String,
+    /// Task that failed; its full log is available via `get_task_log`.
+    /// Filled in on emit, like `log_tail`.
+    pub task_id: Option<String>,
+    /// Last log lines of the task, oldest first.
+    pub log_tail: Vec<String>,
+}
+
+impl TaskErrorPayload {
+    fn with_log(mut self) -> Self {
+        if let Some(task_id) = crate::task_log::latest_task(self.version) {
+            self.log_tail =
+                crate::task_log::tail(&task_id, crate::task_log::TAIL_LINES).unwrap_or_default();
+            self.task_id = Some(task_id);
+        }
+        self
+    }
+}
+
+/// Why a task stopped before finishing.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CancelReason {
+    /// `cancel_install`.
+    User,
+    /// The launcher was closed while the task ran.
+    Shutdown,
+    /// A step ran over its time budget (`tasks::step_timeout`).
+    Timeout,
+    /// `retry_task`: stopped to run again.
+    Retry,
+}
+
+/// Emitted instead of `download://error` when an install was cancelled, with the cleanup
+/// that was done so the UI can tell whether installing again resumes.
+///
+/// Event name: `download://cancelled` and `task-cancelled`
+#[derive(Debug, Clone, Serialize)]
+pub struct TaskCancelledPayload {
+    pub version: u32,
+    pub task_id: Option<String>,
+    pub reason: CancelReason,
+    /// Whether the partial install (`versions/.staging/v{version}`) is gone.
+    pub cleaned_up: bool,
+    /// The task's downloads in the app `temp/` dir were deleted.
+    pub temp_deleted: bool,
+    /// The partial install was kept because its game download can be resumed; installing
+    /// the version again picks it up.
+    pub staging_kept: bool,
+}
+
+/// Emitted by the task watchdog when a task has reported no progress for a while.
+///
+/// Event name: `task-stalled`
+#[derive(Debug, Clone, Serialize)]
+pub struct TaskStalledPayload {
+    pub version: u32,
+    pub task_id: String,
+    pub step: u32,
+    pub step_name: String,
+    pub idle_secs: u64,
+    pub can_cancel: bool,
+    /// `retry_task` can restart the task.
+    pub can_retry: bool,
+}
+
+/// Emitted by the task watchdog when a step runs over its time budget. The task is then
+/// cancelled: installs follow with `task-cancelled` (reason `timeout`), other tasks stop at
+/// their next safe point with a `Cancelled` error the UI can offer a retry for.
+///
+/// Event name: `task-timeout`
+#[derive(Debug, Clone, Serialize)]
+pub struct TaskTimeoutPayload {
+    pub version: u32,
+    pub task_id: String,
+    pub step: u32,
+    pub step_name: String,
+    pub budget_secs: u64,
+    /// False when the task was already being cancelled for another reason.
+    pub cancelled: bool,
+}
+
+/// Emitted by the post-sync integrity sweep when files written by the sync changed.
+///
+/// Event name: `integrity-warning`
+#[derive(Debug, Clone, Serialize)]
+pub struct IntegrityWarningPayload {
+    pub version: u32,
+    pub path: String,
+    pub missing: Vec<String>,    // relative to `path`
+    pub mismatched: Vec<String>, // relative to `path`
+}
+
+/// The game exited quickly several launches in a row.
+///
+/// Event name: `crash-loop`
+#[derive(Debug, Clone, Serialize)]
+pub struct CrashLoopPayload {
+    pub version: u32,
+    pub quick_exits: u32,
+    pub last_run_secs: u64,
+    /// The failing run already was a safe-mode launch (the culprit isn't a regular mod).
+    pub safe_mode_active: bool,
+}
+
+/// Download/unpack progress of a DXVK build.
+///
+/// Event name: `dxvk://progress`
+#[cfg(target_os = "linux")]
+#[derive(Debug, Clone, Serialize)]
+pub struct DxvkProgressPayload {
+    pub version: String,
+    pub downloaded_bytes: u64,
+    pub total_bytes: Option<u64>,
+    pub detail: Option<String>,
+}
+
+/// A downloaded `default_config.zip` didn't match the manifest; it was not extracted.
+///
+/// Event name: `config://integrity-error`
+#[derive(Debug, Clone, Serialize)]
+pub struct ConfigIntegrityErrorPayload {
+    pub url: String,
+    pub expected_sha256: Option<String>,
+    pub actual_sha256: String,
+    pub expected_size: Option<u64>,
+    pub actual_size: u64,
+}
+
+/// One BepInEx GUID shipped by plugins of more than one package.
+#[derive(Debug, Clone, Serialize)]
+pub struct GuidConflict {
+    pub guid: String,
+    /// Top-level plugin folders (or loose DLL paths) shipping the GUID.
+    pub packages: Vec<String>,
+    /// DLL paths relative to `BepInEx/plugins`.
+    pub paths: Vec<String>,
+}
+
+/// Duplicate plugin GUIDs found before a launch; BepInEx loads only one of each.
+///
+/// Event name: `plugin-conflict`
+#[derive(Debug, Clone, Serialize)]
+pub struct PluginConflictPayload {
+    pub version: u32,
+    pub conflicts: Vec<GuidConflict>,
+}
+
+/// A pinned mod version was yanked (download 404 or gone from the package list) and the
+/// nearest older version was installed instead.
+///
+/// Event name: `mod-version-fallback`
+#[derive(Debug, Clone, Serialize)]
+pub struct ModVersionFallbackPayload {
+    pub version: u32,
+    pub dev: String,
+    pub name: String,
+    pub requested: String,
+    pub installed: String,
+    pub reason: String,
+}
+
+/// An install was refused before touching the version dir: not enough free disk space.
+///
+/// Event name: `download://insufficient-space`
+#[derive(Debug, Clone, Serialize)]
+pub struct InsufficientSpacePayload {
+    pub version: u32,
+    /// Directory the install would write to.
+    pub path: String,
+    pub required_bytes: u64,
+    pub available_bytes: u64,
+    /// The manifest published a size for this version (otherwise a default was assumed).
+    pub estimated_from_manifest: bool,
+}
+
+/// Step of the Steam login flow (`steam_login`), and installs that need one first.
+///
+/// Event name: `steam-login`
+#[derive(Debug, Clone, Serialize)]
+pub struct SteamLoginPayload {
+    pub session_id: Option<u64>,
+    /// `started`, `guard_code_required`, `mobile_confirmation_required`, `code_submitted`,
+    /// `succeeded`, `failed`, `logged_out`, or `login_required` (an install found no
+    /// saved login).
+    pub status: &'static str,
+    pub message: Option<String>,
+}
+
+/// The manifest about to be applied had entries the launcher rewrote (also recorded in
+/// the version's lockfile).
+///
+/// Event name: `manifest-corrections`
+#[derive(Debug, Clone, Serialize)]
+pub struct ManifestCorrectionsPayload {
+    pub version: u32,
+    pub manifest_version: u32,
+    pub corrections: Vec<crate::mod_config::ManifestCorrection>,
+}
+
+/// No launcher base answered, so the last cached manifest is used: compatibility checks
+/// and launching keep working, updates are skipped until the network is back.
+///
+/// Event name: `manifest-offline`
+#[derive(Debug, Clone, Serialize)]
+pub struct ManifestOfflinePayload {
+    pub manifest_version: u32,
+    /// Unix seconds the cached copy was last fetched.
+    pub fetched_at: Option<u64>,
+    pub error: Option<String>,
+}
+
+/// Launch chained onto a finished install (`Settings::auto_launch_after_install` or the
+/// `launch` flag of the install commands).
+///
+/// Event name: `auto-launch`
+#[derive(Debug, Clone, Serialize)]
+pub struct AutoLaunchPayload {
+    pub version: u32,
+    /// `launching`, `launched`, `skipped` or `failed`.
+    pub stage: &'static str,
+    pub pid: Option<u32>,
+    pub detail: Option<String>,
+}
+
+/// Overall percent for `step` (1-based) at `step_progress`, weighting steps by `weights`.
+pub fn weighted_overall(weights: &[f64], step: u32, step_progress: f64) -> f64 {
+    let total: f64 = weights.iter().sum();
+    if weights.is_empty() || total <= 0.0 {
+        return 0.0;
+    }
+    let idx = (step.max(1) as usize - 1).min(weights.len() - 1);
+    let done: f64 = weights[..idx].iter().sum();
+    let current = weights[idx] * step_progress.clamp(0.0, 1.0);
+    ((done + current) / total * 100.0).clamp(0.0, 100.0)
 }
 
-pub fn emit_progress(app: &AppHandle, payload: TaskProgressPayload) {
-    let _ = app.emit(&format!("download://progress"), payload);
+/// NDJSON mode (`--ndjson`, `HQ_LAUNCHER_NDJSON=1` or a `--headless` run, see `cli`): every
+/// event is also written to stdout as one `{"event": ..., "payload": ...}` line.
+fn ndjson_enabled() -> bool {
+    static ENABLED: OnceLock<bool> = OnceLock::new();
+    *ENABLED.get_or_init(|| {
+        std::env::args().any(|a| a == "--ndjson")
+            || crate::cli::requested()
+            || std::env::var("HQ_LAUNCHER_NDJSON").is_ok_and(|v| !v.is_empty() && v != "0")
+    })
+}
+
+/// Emit `event` to the frontend (and to stdout in NDJSON mode).
+pub(crate) fn emit<S: Serialize + Clone>(app: &AppHandle, event: &str, payload: S) {
+    if ndjson_enabled() {
+        if let Ok(payload) = serde_json::to_value(&payload) {
+            let line = serde_json::json!({ "event": event, "payload": payload });
+            let mut out = std::io::stdout().lock();
+            let _ = writeln!(out, "{line}");
+            let _ = out.flush();
+        }
+    }
+    let _ = app.emit(event, payload);
+}
+
+pub fn emit_progress(app: &AppHandle, mut payload: TaskProgressPayload) {
+    // Rewrites `overall_percent` from the task's step weights, when a plan was emitted.
+    crate::tasks::record_progress(app, &mut payload);
+    taskbar::progress(app, payload.overall_percent);
+    announce::progress(app, &payload);
+    emit(app, "download://progress", payload);
+}
+
+pub fn emit_plan(app: &AppHandle, version: u32, steps: &[TaskStep]) {
+    let total: f64 = steps.iter().map(|s| s.weight).sum();
+    crate::tasks::set_plan(app, version, steps);
+    let payload = TaskPlanPayload {
+        version,
+        task_id: crate::tasks::task_id_for(app, version),
+        steps_total: steps.len() as u32,
+        steps: steps
+            .iter()
+            .enumerate()
+            .map(|(i, s)| TaskPlanStepPayload {
+                step: i as u32 + 1,
+                id: s.id.to_string(),
+                name: s.name.to_string(),
+                label_key: format!("task.step.{}", s.id),
+                weight: if total > 0.0 { s.weight / total } else { 0.0 },
+            })
+            .collect(),
+    };
+    emit(app, "task-plan", payload);
 }
 
 pub fn emit_finished(app: &AppHandle, payload: TaskFinishedPayload) {
-    let _ = app.emit("download://finished", payload);
+    taskbar::clear(app);
+    announce::finished(app, payload.version);
+    emit(app, "download://finished", payload);
 }
 
 pub fn emit_error(app: &AppHandle, payload: TaskErrorPayload) {
-    let _ = app.emit("download://error", payload);
+    taskbar::failed(app);
+    announce::failed(app, payload.version, &payload.message);
+    emit(app, "download://error", payload.with_log());
+}
+
+pub fn emit_cancelled(app: &AppHandle, payload: TaskCancelledPayload) {
+    taskbar::clear(app);
+    announce::cancelled(app, payload.version);
+    emit(app, "download://cancelled", payload.clone());
+    emit(app, "task-cancelled", payload);
+}
+
+pub fn emit_stalled(app: &AppHandle, payload: TaskStalledPayload) {
+    taskbar::stalled(app);
+    announce::stalled(app, payload.version, &payload.step_name);
+    emit(app, "task-stalled", payload);
+}
+
+pub fn emit_timeout(app: &AppHandle, payload: TaskTimeoutPayload) {
+    emit(app, "task-timeout", payload);
+}
+
+pub fn emit_integrity_warning(app: &AppHandle, payload: IntegrityWarningPayload) {
+    emit(app, "integrity-warning", payload);
 }
 
 pub fn emit_updatable_progress(app: &AppHandle, payload: TaskUpdatableProgressPayload) {
-    let _ = app.emit("updatable://progress", payload);
+    emit(app, "updatable://progress", payload);
 }
 
 pub fn emit_updatable_finished(app: &AppHandle, payload: TaskFinishedPayload) {
-    let _ = app.emit("updatable://finished", payload);
+    emit(app, "updatable://finished", payload);
 }
 
 pub fn emit_updatable_error(app: &AppHandle, payload: TaskErrorPayload) {
-    let _ = app.emit("updatable://error", payload);
+    emit(app, "updatable://error", payload.with_log());
+}
+
+pub fn emit_crash_loop(app: &AppHandle, payload: CrashLoopPayload) {
+    emit(app, "crash-loop", payload);
+}
+
+#[cfg(target_os = "linux")]
+pub fn emit_dxvk_progress(app: &AppHandle, payload: DxvkProgressPayload) {
+    emit(app, "dxvk://progress", payload);
+}
+
+pub fn emit_plugin_conflict(app: &AppHandle, payload: PluginConflictPayload) {
+    emit(app, "plugin-conflict", payload);
+}
+
+pub fn emit_mod_version_fallback(app: &AppHandle, payload: ModVersionFallbackPayload) {
+    emit(app, "mod-version-fallback", payload);
+}
+
+pub fn emit_config_integrity_error(app: &AppHandle, payload: ConfigIntegrityErrorPayload) {
+    emit(app, "config://integrity-error", payload);
+}
+
+/// Log and emit the corrections of the manifest applied to `version`; no-op without any.
+pub fn emit_manifest_corrections(app: &AppHandle, payload: ManifestCorrectionsPayload) {
+    if payload.corrections.is_empty() {
+        return;
+    }
+    for c in &payload.corrections {
+        log::warn!(
+            "Manifest {}: using {}-{} for {}-{} ({})",
+            payload.manifest_version,
+            c.corrected_dev,
+            c.corrected_name,
+            c.dev,
+            c.name,
+            c.reason
+        );
+    }
+    emit(app, "manifest-corrections", payload);
+}
+
+pub fn emit_manifest_offline(app: &AppHandle, payload: ManifestOfflinePayload) {
+    emit(app, "manifest-offline", payload);
+}
+
+pub fn emit_steam_login(app: &AppHandle, payload: SteamLoginPayload) {
+    emit(app, "steam-login", payload);
+}
+
+pub fn emit_auto_launch(app: &AppHandle, payload: AutoLaunchPayload) {
+    emit(app, "auto-launch", payload);
+}
+
+pub fn emit_insufficient_space(app: &AppHandle, payload: InsufficientSpacePayload) {
+    emit(app, "download://insufficient-space", payload);
 }
@@ -0,0 +1,210 @@
+//! Import of r2modman / Thunderstore Mod Manager profile share codes.
+//!
+//! A share code resolves to `#r2modman` followed by a base64 zip holding `export.r2x`
+//! (YAML: `profileName` and `mods`) plus the profile's config files. Profile folders
+//! copied by hand carry the same list as `mods.yml`. Only the mod list is imported: the
+//! config stays with the launcher's shared config, and BepInExPack is installed by the
+//! launcher itself.
+
+use std::io::Read;
+
+use base64::Engine;
+use serde::{Deserialize, Serialize};
+
+use crate::profiles::{self, Profile, ProfileMod};
+use crate::{endpoints, mod_config};
+
+const SHARE_PREFIX: &str = "#r2modman";
+/// Share codes are small; anything bigger isn't a profile.
+const MAX_PROFILE_BYTES: usize = 32 * 1024 * 1024;
+/// Mod list entries inside the profile zip, in order of preference.
+const MOD_LIST_NAMES: [&str; 2] = ["export.r2x", "mods.yml"];
+/// The launcher installs BepInEx itself.
+const SKIPPED_PACKAGES: [&str; 1] = ["bepinex-bepinexpack"];
+
+#[derive(Debug, Clone, Deserialize)]
+struct R2Version {
+    major: u32,
+    minor: u32,
+    patch: u32,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct R2Mod {
+    /// `Owner-Name`.
+    name: String,
+    version: Option<R2Version>,
+    #[serde(default = "enabled_default")]
+    enabled: bool,
+}
+
+fn enabled_default() -> bool {
+    true
+}
+
+/// `export.r2x` is a map with `mods`; `mods.yml` is the bare list.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(untagged)]
+enum R2ModList {
+    Export {
+        #[serde(rename = "profileName")]
+        profile_name: Option<String>,
+        mods: Vec<R2Mod>,
+    },
+    List(Vec<R2Mod>),
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct R2ImportResult {
+    pub profile: Profile,
+    /// Mods listed in the launcher manifest.
+    pub manifest_mods: usize,
+    /// Other packages, added as the profile's extra mods.
+    pub extra_mods: usize,
+    /// `Owner-Name` entries left out (disabled in r2modman, BepInExPack, malformed).
+    pub skipped: Vec<String>,
+    /// Version the profile was activated for.
+    pub version: u32,
+}
+
+fn is_valid_code(code: &str) -> bool {
+    !code.is_empty()
+        && code.len() <= 64
+        && code.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')
+}
+
+async fn download_profile(code: &str) -> Result<Vec<u8>, String> {
+    let url = endpoints::thunderstore_profile_url(code);
+    log::info!("Fetching r2modman profile {code}");
+    let text = reqwest::Client::new()
+        .get(&url)
+        .send()
+        .await
+        .map_err(|e| e.to_string())?
+        .error_for_status()
+        .map_err(|e| format!("profile code {code} not found or expired: {e}"))?
+        .text()
+        .await
+        .map_err(|e| e.to_string())?;
+    if text.len() > MAX_PROFILE_BYTES {
+        return Err("profile is too large".to_string());
+    }
+    let payload = text
+        .trim()
+        .strip_prefix(SHARE_PREFIX)
+        .ok_or_else(|| "not an r2modman profile".to_string())?;
+    let payload: String = payload.chars().filter(|c| !c.is_whitespace()).collect();
+    base64::engine::general_purpose::STANDARD
+        .decode(payload)
+        .map_err(|e| format!("invalid profile data: {e}"))
+}
+
+/// The mod list file from the profile zip.
+fn read_mod_list(zip_bytes: &[u8]) -> Result<R2ModList, String> {
+    let mut archive = zip::ZipArchive::new(std::io::Cursor::new(zip_bytes))
+        .map_err(|e| format!("invalid profile zip: {e}"))?;
+    for name in MOD_LIST_NAMES {
+        let Ok(mut entry) = archive.by_name(name) else {
+            continue;
+        };
+        let mut text = String::new();
+        entry
+            .read_to_string(&mut text)
+            .map_err(|e| format!("{name}: {e}"))?;
+        return serde_yaml::from_str(&text).map_err(|e| format!("{name}: {e}"));
+    }
+    Err(format!(
+        "profile has no mod list ({})",
+        MOD_LIST_NAMES.join(" or ")
+    ))
+}
+
+/// Create a launcher profile from r2modman share `code` and activate it for `version`
+/// (default: active version), installing its mods.
+#[tauri::command]
+pub async fn import_r2modman_profile(
+    app: tauri::AppHandle,
+    code: String,
+    name: Option<String>,
+    version: Option<u32>,
+) -> Result<R2ImportResult, String> {
+    let code = code.trim();
+    if !is_valid_code(code) {
+        return Err(format!("invalid profile code: {code}"));
+    }
+    let version = crate::resolve_version(&app, version)?;
+    let zip_bytes = download_profile(code).await?;
+    let (profile_name, r2_mods) = match read_mod_list(&zip_bytes)? {
+        R2ModList::Export { profile_name, mods } => (profile_name, mods),
+        R2ModList::List(mods) => (None, mods),
+    };
+
+    let manifest = match mod_config::fetch_remote_manifest(&reqwest::Client::new()).await {
+        Ok(m) => Some(m),
+        Err(e) => {
+            log::warn!("Importing r2modman profile with the cached manifest: {e}");
+            mod_config::cached_remote_manifest()
+        }
+    };
+    let listed = |dev: &str, name: &str| {
+        manifest.as_ref().is_some_and(|m| {
+            m.mods
+                .iter()
+                .any(|e| e.dev.eq_ignore_ascii_case(dev) && e.name.eq_ignore_ascii_case(name))
+        })
+    };
+
+    let mut profile = Profile {
+        name: name
+            .or(profile_name)
+            .filter(|n| !n.trim().is_empty())
+            .unwrap_or_else(|| "r2modman".to_string()),
+        mods: vec![],
+        extra_mods: vec![],
+    };
+    let mut skipped = vec![];
+    for m in r2_mods {
+        let split = m.name.split_once('-');
+        let Some((dev, pkg)) = split.filter(|_| m.enabled) else {
+            skipped.push(m.name);
+            continue;
+        };
+        if SKIPPED_PACKAGES.contains(&m.name.to_lowercase().as_str()) {
+            skipped.push(m.name);
+            continue;
+        }
+        if listed(dev, pkg) {
+            profile.mods.push(ProfileMod {
+                dev: dev.to_string(),
+                name: pkg.to_string(),
+                version: None,
+            });
+        } else {
+            profile.extra_mods.push(ProfileMod {
+                dev: dev.to_string(),
+                name: pkg.to_string(),
+                version: m
+                    .version
+                    .map(|v| format!("{}.{}.{}", v.major, v.minor, v.patch)),
+            });
+        }
+    }
+
+    let profile = profiles::add_profile(&app, profile)?;
+    log::info!(
+        "Imported r2modman profile {code} as {}: {} manifest mod(s), {} extra, {} skipped",
+        profile.name,
+        profile.mods.len(),
+        profile.extra_mods.len(),
+        skipped.len()
+    );
+    profiles::activate_profile(app.clone(), profile.name.clone(), Some(version)).await?;
+
+    Ok(R2ImportResult {
+        manifest_mods: profile.mods.len(),
+        extra_mods: profile.extra_mods.len(),
+        profile,
+        skipped,
+        version,
+    })
+}
@@ -0,0 +1,97 @@
+use std::path::PathBuf;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{confirm, storage};
+
+/// What `reset_launcher` deletes. Game installs and `game_config/` are never touched.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ResetScope {
+    Settings,
+    Credentials,
+    Cache,
+    ManifestState,
+    All,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ResetResult {
+    pub scope: ResetScope,
+    /// Paths that will be (or were) removed.
+    pub paths: Vec<String>,
+    /// Set on the dry run; pass it back to actually perform the reset.
+    pub confirm_token: Option<String>,
+    pub done: bool,
+}
+
+fn targets_for(app: &tauri::AppHandle, scope: ResetScope) -> Result<Vec<PathBuf>, String> {
+    let state = storage::state_dir(app)?;
+    Ok(match scope {
+        ResetScope::Settings => vec![state.join("settings.json")],
+        ResetScope::Credentials => vec![storage::depot_config_dir(app)?],
+        ResetScope::Cache => vec![
+            storage::cache_dir(app)?,
+            storage::depot_cache_dir(app)?,
+            storage::temp_dir(app)?,
+        ],
+        ResetScope::ManifestState => vec![state.join("manifest_state.json")],
+        ResetScope::All => {
+            let mut all = vec![state];
+            all.extend(targets_for(app, ResetScope::Credentials)?);
+            all.extend(targets_for(app, ResetScope::Cache)?);
+            all
+        }
+    })
+}
+
+fn remove_path(path: &std::path::Path) -> Result<(), String> {
+    let Ok(md) = std::fs::symlink_metadata(path) else {
+        return Ok(());
+    };
+    if md.is_dir() {
+        std::fs::remove_dir_all(path).map_err(|e| format!("{}: {e}", path.display()))
+    } else {
+        std::fs::remove_file(path).map_err(|e| format!("{}: {e}", path.display()))
+    }
+}
+
+/// Two-phase reset of launcher-owned data.
+///
+/// Called without `confirm_token` it only reports what would be removed and returns a
+/// token; calling again with that token (same scope) performs it. See `confirm`.
+#[tauri::command]
+pub fn reset_launcher(
+    app: tauri::AppHandle,
+    scope: ResetScope,
+    confirm_token: Option<String>,
+) -> Result<ResetResult, String> {
+    let targets = targets_for(&app, scope)?;
+    let paths: Vec<String> = targets
+        .iter()
+        .map(|p| p.to_string_lossy().to_string())
+        .collect();
+    let action = format!("reset_launcher:{scope:?}");
+
+    let Some(token) = confirm_token else {
+        return Ok(ResetResult {
+            scope,
+            paths,
+            confirm_token: Some(confirm::issue(&app, &action)?),
+            done: false,
+        });
+    };
+    confirm::consume(&app, &action, &token)?;
+
+    log::warn!("Resetting launcher data (scope={scope:?}): {paths:?}");
+    for target in &targets {
+        remove_path(target)?;
+    }
+
+    Ok(ResetResult {
+        scope,
+        paths,
+        confirm_token: None,
+        done: true,
+    })
+}
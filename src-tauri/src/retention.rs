@@ -0,0 +1,264 @@
+//! Retention for files the launcher keeps piling up: config backups, diagnostics bundles
+//! (crash and bug report archives) and rolled launcher logs.
+//!
+//! Each category has count, age and size caps (`Settings::retention`). The newest files
+//! are kept first; a file is purged when it is older than `max_age_days`, or when keeping
+//! it would exceed `max_count` or `max_bytes`. Enforced on startup and after each config
+//! backup; `run_retention` with `dry_run` reports what would go without deleting it.
+
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{settings, storage};
+
+/// The live log file; log4rs holds it open.
+const ACTIVE_LOG: &str = "hq-launcher.log";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Category {
+    ConfigBackups,
+    Diagnostics,
+    Logs,
+}
+
+impl Category {
+    const ALL: [Category; 3] = [Self::ConfigBackups, Self::Diagnostics, Self::Logs];
+
+    fn dir(self, app: &tauri::AppHandle) -> Result<PathBuf, String> {
+        Ok(match self {
+            Self::ConfigBackups => storage::config_backups_dir(app)?,
+            Self::Diagnostics => storage::diagnostics_dir(app)?,
+            Self::Logs => storage::logs_dir(app)?,
+        })
+    }
+
+    fn is_candidate(self, name: &str) -> bool {
+        match self {
+            Self::ConfigBackups | Self::Diagnostics => name.ends_with(".zip"),
+            Self::Logs => name.ends_with(".log") && name != ACTIVE_LOG,
+        }
+    }
+}
+
+/// Caps for one category; `None` leaves that dimension unlimited.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RetentionPolicy {
+    #[serde(default)]
+    pub max_count: Option<usize>,
+    #[serde(default)]
+    pub max_age_days: Option<u32>,
+    #[serde(default)]
+    pub max_bytes: Option<u64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Retention {
+    #[serde(default = "default_config_backups")]
+    pub config_backups: RetentionPolicy,
+    #[serde(default = "default_diagnostics")]
+    pub diagnostics: RetentionPolicy,
+    #[serde(default = "default_logs")]
+    pub logs: RetentionPolicy,
+}
+
+fn default_config_backups() -> RetentionPolicy {
+    RetentionPolicy {
+        max_count: Some(20),
+        max_age_days: Some(90),
+        max_bytes: None,
+    }
+}
+
+fn default_diagnostics() -> RetentionPolicy {
+    RetentionPolicy {
+        max_count: Some(10),
+        max_age_days: Some(30),
+        max_bytes: Some(500 * 1024 * 1024),
+    }
+}
+
+fn default_logs() -> RetentionPolicy {
+    RetentionPolicy {
+        max_count: None,
+        max_age_days: Some(30),
+        max_bytes: None,
+    }
+}
+
+impl Default for Retention {
+    fn default() -> Self {
+        Self {
+            config_backups: default_config_backups(),
+            diagnostics: default_diagnostics(),
+            logs: default_logs(),
+        }
+    }
+}
+
+impl Retention {
+    fn policy(&self, category: Category) -> RetentionPolicy {
+        match category {
+            Category::ConfigBackups => self.config_backups,
+            Category::Diagnostics => self.diagnostics,
+            Category::Logs => self.logs,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PurgeReason {
+    Age,
+    Count,
+    Size,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PurgeEntry {
+    pub category: Category,
+    pub path: String,
+    pub size_bytes: u64,
+    /// Unix seconds.
+    pub modified_at: u64,
+    pub reason: PurgeReason,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct PurgeReport {
+    /// Nothing was deleted; `entries` is what would be.
+    pub dry_run: bool,
+    pub entries: Vec<PurgeEntry>,
+    pub freed_bytes: u64,
+}
+
+struct Candidate {
+    path: PathBuf,
+    size: u64,
+    modified: SystemTime,
+}
+
+fn candidates(dir: &Path, category: Category) -> Vec<Candidate> {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return vec![];
+    };
+    entries
+        .flatten()
+        .filter(|e| e.file_type().is_ok_and(|t| t.is_file()))
+        .filter(|e| category.is_candidate(&e.file_name().to_string_lossy()))
+        .filter_map(|e| {
+            let meta = e.metadata().ok()?;
+            Some(Candidate {
+                path: e.path(),
+                size: meta.len(),
+                modified: meta.modified().unwrap_or(UNIX_EPOCH),
+            })
+        })
+        .collect()
+}
+
+/// Files of `files` to purge under `policy`, with the reason, newest kept first.
+fn select(
+    mut files: Vec<Candidate>,
+    policy: RetentionPolicy,
+    now: SystemTime,
+) -> Vec<(Candidate, PurgeReason)> {
+    files.sort_by(|a, b| b.modified.cmp(&a.modified));
+    let max_age = policy
+        .max_age_days
+        .map(|d| Duration::from_secs(u64::from(d) * 24 * 60 * 60));
+    let mut kept_count = 0usize;
+    let mut kept_bytes = 0u64;
+    let mut purge = vec![];
+    for file in files {
+        let age = now.duration_since(file.modified).unwrap_or_default();
+        let reason = if max_age.is_some_and(|max| age > max) {
+            Some(PurgeReason::Age)
+        } else if policy.max_count.is_some_and(|max| kept_count >= max) {
+            Some(PurgeReason::Count)
+        } else if policy
+            .max_bytes
+            .is_some_and(|max| kept_bytes.saturating_add(file.size) > max)
+        {
+            Some(PurgeReason::Size)
+        } else {
+            None
+        };
+        match reason {
+            Some(reason) => purge.push((file, reason)),
+            None => {
+                kept_count += 1;
+                kept_bytes = kept_bytes.saturating_add(file.size);
+            }
+        }
+    }
+    purge
+}
+
+fn enforce(
+    app: &tauri::AppHandle,
+    retention: &Retention,
+    categories: &[Category],
+    dry_run: bool,
+) -> Result<PurgeReport, String> {
+    let now = SystemTime::now();
+    let mut report = PurgeReport {
+        dry_run,
+        ..Default::default()
+    };
+    for &category in categories {
+        let dir = category.dir(app)?;
+        let policy = retention.policy(category);
+        for (file, reason) in select(candidates(&dir, category), policy, now) {
+            if !dry_run {
+                if let Err(e) = std::fs::remove_file(&file.path) {
+                    log::warn!("Failed to remove {}: {e}", file.path.display());
+                    continue;
+                }
+            }
+            report.freed_bytes = report.freed_bytes.saturating_add(file.size);
+            report.entries.push(PurgeEntry {
+                category,
+                path: file.path.to_string_lossy().to_string(),
+                size_bytes: file.size,
+                modified_at: file
+                    .modified
+                    .duration_since(UNIX_EPOCH)
+                    .map(|d| d.as_secs())
+                    .unwrap_or(0),
+                reason,
+            });
+        }
+    }
+    if !dry_run && !report.entries.is_empty() {
+        log::info!(
+            "Retention removed {} file(s), {} bytes",
+            report.entries.len(),
+            report.freed_bytes
+        );
+    }
+    Ok(report)
+}
+
+/// Apply the retention settings to every category (startup maintenance).
+pub fn run(app: &tauri::AppHandle) -> Result<PurgeReport, String> {
+    enforce(app, &settings::load(app).retention, &Category::ALL, false)
+}
+
+/// Apply the retention settings to one category, e.g. after adding a file to it.
+pub fn run_for(app: &tauri::AppHandle, category: Category) -> Result<PurgeReport, String> {
+    enforce(app, &settings::load(app).retention, &[category], false)
+}
+
+/// Purge what the retention settings don't keep; with `dry_run` (default) only report it.
+#[tauri::command]
+pub fn run_retention(app: tauri::AppHandle, dry_run: Option<bool>) -> Result<PurgeReport, String> {
+    enforce(
+        &app,
+        &settings::load(&app).retention,
+        &Category::ALL,
+        dry_run.unwrap_or(true),
+    )
+}
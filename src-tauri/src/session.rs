@@ -0,0 +1,259 @@
+//! Session codes: a short string the host shares so everyone can check they're in sync
+//! before a game night.
+//!
+//! A code is `{game_version}-{manifest_version}-{config}-{mods}`, e.g. `72-14-3F0A-9C21B7`:
+//! - `config` fingerprints the shared config files of `server-synced` mods;
+//! - `mods` fingerprints the lockfile entries of every plugin that isn't `client-only`;
+//!   `000000` when the install has no lockfile yet (never synced), which matches anything.
+//!
+//! `join_session` recomputes the same parts locally and says which one to fix.
+
+use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
+
+use serde::Serialize;
+
+use crate::integrity::{self, Fnv1a};
+use crate::{mod_config, storage};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct SessionParts {
+    game_version: u32,
+    manifest_version: u32,
+    config: u16,
+    /// `None`: unknown, the install was never synced.
+    mods: Option<u32>,
+}
+
+impl SessionParts {
+    fn code(&self) -> String {
+        format!(
+            "{}-{}-{:04X}-{:06X}",
+            self.game_version,
+            self.manifest_version,
+            self.config,
+            self.mods.unwrap_or(0)
+        )
+    }
+
+    fn parse(code: &str) -> Option<Self> {
+        let mut parts = code.trim().split('-');
+        let parsed = Self {
+            game_version: parts.next()?.parse().ok()?,
+            manifest_version: parts.next()?.parse().ok()?,
+            config: u16::from_str_radix(parts.next()?, 16).ok()?,
+            mods: Some(u32::from_str_radix(parts.next()?, 16).ok()?).filter(|m| *m != 0),
+        };
+        (parts.next().is_none() && parsed.mods.unwrap_or(0) <= 0xFF_FFFF).then_some(parsed)
+    }
+}
+
+/// `dev-name` (lowercase) of manifest mods carrying `tag`, from the cached manifest.
+fn tagged_mods(tag: &str) -> Vec<(String, String)> {
+    mod_config::cached_remote_manifest()
+        .map(|m| {
+            m.mods
+                .iter()
+                .filter(|m| m.has_tag(tag))
+                .map(|m| (m.dev.to_lowercase(), m.name.to_lowercase()))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Hash of the plugin files the launcher installed, minus `client-only` mods. `None`
+/// without a lockfile, so a never-synced install isn't reported as different.
+fn mods_fingerprint(game_root: &Path) -> Option<u32> {
+    let client_only: BTreeSet<String> = tagged_mods(mod_config::TAG_CLIENT_ONLY)
+        .into_iter()
+        .map(|(dev, name)| format!("bepinex/plugins/{dev}-{name}/"))
+        .collect();
+    let files = integrity::read_lockfile(game_root).files;
+    if files.is_empty() {
+        return None;
+    }
+    let mut hasher = Fnv1a::new();
+    for (key, file) in files {
+        let lower = key.to_lowercase();
+        if client_only.iter().any(|prefix| lower.starts_with(prefix)) {
+            continue;
+        }
+        hasher.update(key.as_bytes());
+        hasher.update(file.hash.as_bytes());
+    }
+    // `0` stands for "unknown" in a code.
+    Some(((hasher.finish() & 0xFF_FFFF) as u32).max(1))
+}
+
+fn collect_files(dir: &Path, out: &mut Vec<PathBuf>) {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path.is_dir() {
+            collect_files(&path, out);
+        } else {
+            out.push(path);
+        }
+    }
+}
+
+/// Hash of the shared config files belonging to `server-synced` mods.
+///
+/// Files are matched to mods by dev or name in their relative path, as on uninstall.
+fn config_fingerprint(app: &tauri::AppHandle) -> u16 {
+    let synced = tagged_mods(mod_config::TAG_SERVER_SYNCED);
+    let Ok(shared) = storage::shared_config_dir(app) else {
+        return 0;
+    };
+    let mut files: Vec<PathBuf> = vec![];
+    collect_files(&shared, &mut files);
+    let mut keyed: Vec<(String, PathBuf)> = files
+        .into_iter()
+        .filter_map(|path| {
+            let rel = path
+                .strip_prefix(&shared)
+                .ok()?
+                .to_string_lossy()
+                .replace('\\', "/");
+            let lower = rel.to_lowercase();
+            synced
+                .iter()
+                .any(|(dev, name)| lower.contains(dev) || lower.contains(name))
+                .then_some((rel, path))
+        })
+        .collect();
+    keyed.sort();
+
+    let mut hasher = Fnv1a::new();
+    for (rel, path) in keyed {
+        hasher.update(rel.as_bytes());
+        // CRLF vs LF shouldn't make Windows and Linux players look out of sync.
+        if let Ok(text) = std::fs::read(&path) {
+            let normalized: Vec<u8> = text.into_iter().filter(|b| *b != b'\r').collect();
+            hasher.update(&normalized);
+        }
+    }
+    (hasher.finish() & 0xFFFF) as u16
+}
+
+fn local_parts(app: &tauri::AppHandle, version: u32) -> Result<SessionParts, String> {
+    let game_root = crate::storage::installed_version_dir(app, version)?;
+    Ok(SessionParts {
+        game_version: version,
+        manifest_version: integrity::read_lockfile(&game_root)
+            .manifest_version
+            .unwrap_or(0),
+        config: config_fingerprint(app),
+        mods: mods_fingerprint(&game_root),
+    })
+}
+
+/// Session code for `version` (default: active version).
+#[tauri::command]
+pub fn create_session(app: tauri::AppHandle, version: Option<u32>) -> Result<String, String> {
+    let version = crate::resolve_version(&app, version)?;
+    let code = local_parts(&app, version)?.code();
+    log::info!("Created session code {code} for v{version}");
+    Ok(code)
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SessionIssue {
+    /// `missing_version`, `manifest_behind`, `manifest_ahead`, `mods_differ` or
+    /// `config_differ`.
+    pub kind: String,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SessionCheck {
+    pub game_version: u32,
+    pub ready: bool,
+    /// This install's code, to compare by eye with the host's.
+    pub local_code: Option<String>,
+    pub issues: Vec<SessionIssue>,
+}
+
+fn issue(kind: &str, message: String) -> SessionIssue {
+    SessionIssue {
+        kind: kind.to_string(),
+        message,
+    }
+}
+
+/// Compare the local install with a host's session code.
+#[tauri::command]
+pub fn join_session(app: tauri::AppHandle, code: String) -> Result<SessionCheck, String> {
+    let host = SessionParts::parse(&code).ok_or_else(|| format!("invalid session code: {code}"))?;
+    let version = host.game_version;
+    let local = match local_parts(&app, version) {
+        Ok(local) => local,
+        Err(_) => {
+            return Ok(SessionCheck {
+                game_version: version,
+                ready: false,
+                local_code: None,
+                issues: vec![issue(
+                    "missing_version",
+                    format!("Install v{version} to play this session."),
+                )],
+            });
+        }
+    };
+
+    let mut issues: Vec<SessionIssue> = vec![];
+    if local.manifest_version < host.manifest_version {
+        issues.push(issue(
+            "manifest_behind",
+            format!(
+                "Your mods are from manifest v{} but the host has v{}. Sync mods for v{version}.",
+                local.manifest_version, host.manifest_version
+            ),
+        ));
+    } else if local.manifest_version > host.manifest_version {
+        issues.push(issue(
+            "manifest_ahead",
+            format!(
+                "You are on manifest v{} but the host is on v{}. The host needs to sync mods for v{version}.",
+                local.manifest_version, host.manifest_version
+            ),
+        ));
+    } else if matches!((local.mods, host.mods), (Some(l), Some(h)) if l != h) {
+        issues.push(issue(
+            "mods_differ",
+            format!(
+                "Your mod files differ from the host's. Sync mods for v{version}, then run a \
+                 file check if the code still doesn't match."
+            ),
+        ));
+    }
+    if local.config != host.config {
+        let names: Vec<String> = tagged_mods(mod_config::TAG_SERVER_SYNCED)
+            .into_iter()
+            .map(|(dev, name)| format!("{dev}-{name}"))
+            .collect();
+        issues.push(issue(
+            "config_differ",
+            format!(
+                "Config of server-synced mods differs from the host's ({}). Copy the host's \
+                 config files for these mods or reset them to the manifest defaults.",
+                names.join(", ")
+            ),
+        ));
+    }
+
+    log::info!(
+        "Joined session {} as {}: {} issue(s)",
+        host.code(),
+        local.code(),
+        issues.len()
+    );
+    Ok(SessionCheck {
+        game_version: version,
+        ready: issues.is_empty(),
+        local_code: Some(local.code()),
+        issues,
+    })
+}
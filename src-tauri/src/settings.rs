@@ -0,0 +1,277 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+/// The persistent DepotDownloader cache is off unless a size is set.
+const DEFAULT_DEPOT_CACHE_MAX_BYTES: u64 = 0;
+
+/// Default upper bound for cached mod zips no version or manifest still uses (2 GiB).
+const DEFAULT_PACKAGE_CACHE_MAX_BYTES: u64 = 2 * 1024 * 1024 * 1024;
+
+/// Mod zips downloaded at once by the install pass.
+const DEFAULT_MOD_DOWNLOAD_CONCURRENCY: usize = 4;
+
+/// Launcher-wide settings persisted as `state/settings.json`.
+///
+/// Every field has a serde default so older/partial files keep loading.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Settings {
+    /// Max size of the depot chunk cache shared between game versions; oldest files are
+    /// trimmed past it. `0` (default) disables the cache and downloads go straight into
+    /// the version dir: outside copy-on-write filesystems the cache is a second full copy
+    /// of the game next to each install.
+    #[serde(default = "default_depot_cache_max_bytes")]
+    pub depot_cache_max_bytes: u64,
+
+    /// Max size of the mod zip cache after a sync. Zips that no installed version or the
+    /// current manifest uses are evicted, least recently used first, until it fits;
+    /// `0` evicts all of them.
+    #[serde(default = "default_package_cache_max_bytes")]
+    pub package_cache_max_bytes: u64,
+
+    /// Install from a generated fixture tree instead of Steam (offline demo mode).
+    #[serde(default)]
+    pub fake_downloader: bool,
+
+    /// Version launched/synced when none is given explicitly.
+    /// `None` falls back to the highest installed version.
+    #[serde(default)]
+    pub active_version: Option<u32>,
+
+    /// Only install mods on the imported allowlist (see `allowlist`).
+    #[serde(default)]
+    pub strict_allowlist: bool,
+
+    /// Check for a usable Vulkan device before Proton launches (see `vulkan`).
+    #[serde(default = "default_true")]
+    pub vulkan_check: bool,
+
+    /// Extra launcher base URLs serving the same files, tried after the primary one.
+    #[serde(default)]
+    pub mirrors: Vec<String>,
+
+    /// Measure mirror speed before downloading the default config and try the fastest
+    /// mirror first. Game and mod downloads don't use mirrors (see `mirrors`).
+    #[serde(default)]
+    pub mirror_speedtest: bool,
+
+    /// Override the manifest's `yanked_fallback`; `None` follows the manifest.
+    #[serde(default)]
+    pub yanked_fallback: Option<bool>,
+
+    /// Launch Proton through umu-launcher (see `umu`). `None` uses it when Steam isn't
+    /// installed.
+    #[serde(default)]
+    pub umu: Option<bool>,
+
+    /// How many mod zips download in parallel (extraction stays one at a time).
+    #[serde(default = "default_mod_download_concurrency")]
+    pub mod_download_concurrency: usize,
+
+    /// Never let the game write to its version dir; launches run from an overlay instead
+    /// (see `overlay`).
+    #[serde(default)]
+    pub protect_game_files: bool,
+
+    /// Cap on HTTP download speed in bytes per second; `0` is unlimited.
+    #[serde(default)]
+    pub bandwidth_limit: u64,
+
+    /// Tool for HTTP file downloads: `reqwest` (built in), `aria2c` or `curl`. A tool that
+    /// isn't installed falls back to `reqwest` (see `download_backend`).
+    #[serde(default)]
+    pub download_backend: crate::download_backend::DownloadBackendKind,
+
+    /// Proton-GE release tag to install and launch with (e.g. `GE-Proton10-28`).
+    /// `None` uses the launcher's tested default.
+    #[serde(default)]
+    pub proton_version: Option<String>,
+
+    /// Sync config and mods of the installed versions when the launcher starts.
+    #[serde(default)]
+    pub auto_sync_on_startup: bool,
+
+    /// Launch the game right after an install finishes (the install commands' `launch`
+    /// flag overrides this per install).
+    #[serde(default)]
+    pub auto_launch_after_install: bool,
+
+    /// Manifest channel: `stable` (same as `None`), `beta`, another channel name
+    /// (`manifest-{channel}.json`), or a custom manifest URL. See `endpoints::ManifestChannel`.
+    #[serde(default)]
+    pub manifest_channel: Option<String>,
+
+    /// Folder holding the installed versions; `None` is `versions/` in the app data dir.
+    /// Changed through `move_install_root`, which moves existing installs along.
+    #[serde(default)]
+    pub install_root: Option<String>,
+
+    /// Time budget in seconds per step id (e.g. `install_bepinex`, `manifest_fetch`),
+    /// overriding the defaults in `tasks`; `0` removes a step's budget.
+    #[serde(default)]
+    pub step_timeouts: BTreeMap<String, u64>,
+
+    /// Announce task steps, completion and failures to screen readers (see `announce`).
+    #[serde(default = "default_true")]
+    pub screen_reader_announcements: bool,
+
+    /// What a config update does with local config files that differ from the new
+    /// defaults (see `config_sync`). Files the manifest lists in `config_overwrite` are
+    /// replaced regardless.
+    #[serde(default)]
+    pub config_sync_strategy: crate::config_sync::ConfigSyncStrategy,
+
+    /// Count/age/size caps for config backups, diagnostics bundles and rolled logs,
+    /// enforced on startup (see `retention`).
+    #[serde(default)]
+    pub retention: crate::retention::Retention,
+
+    /// Unlock maintainer tools such as `discover_depot_manifests`.
+    #[serde(default)]
+    pub maintainer_mode: bool,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            depot_cache_max_bytes: default_depot_cache_max_bytes(),
+            package_cache_max_bytes: default_package_cache_max_bytes(),
+            fake_downloader: false,
+            active_version: None,
+            strict_allowlist: false,
+            vulkan_check: true,
+            mirrors: vec![],
+            mirror_speedtest: false,
+            yanked_fallback: None,
+            umu: None,
+            mod_download_concurrency: default_mod_download_concurrency(),
+            protect_game_files: false,
+            bandwidth_limit: 0,
+            download_backend: Default::default(),
+            proton_version: None,
+            auto_sync_on_startup: false,
+            auto_launch_after_install: false,
+            manifest_channel: None,
+            install_root: None,
+            step_timeouts: BTreeMap::new(),
+            screen_reader_announcements: true,
+            config_sync_strategy: Default::default(),
+            retention: Default::default(),
+            maintainer_mode: false,
+        }
+    }
+}
+
+fn default_depot_cache_max_bytes() -> u64 {
+    DEFAULT_DEPOT_CACHE_MAX_BYTES
+}
+
+fn default_package_cache_max_bytes() -> u64 {
+    DEFAULT_PACKAGE_CACHE_MAX_BYTES
+}
+
+fn default_mod_download_concurrency() -> usize {
+    DEFAULT_MOD_DOWNLOAD_CONCURRENCY
+}
+
+fn default_true() -> bool {
+    true
+}
+
+fn settings_path(app: &tauri::AppHandle) -> Result<std::path::PathBuf, String> {
+    Ok(crate::storage::state_dir(app)?.join("settings.json"))
+}
+
+/// Read settings, falling back to defaults when the file is missing or unreadable.
+pub fn load(app: &tauri::AppHandle) -> Settings {
+    let Ok(path) = settings_path(app) else {
+        return Settings::default();
+    };
+    let Ok(text) = std::fs::read_to_string(&path) else {
+        return Settings::default();
+    };
+    match serde_json::from_str(&text) {
+        Ok(s) => s,
+        Err(e) => {
+            log::warn!("Invalid settings file {}: {e}; using defaults", path.display());
+            Settings::default()
+        }
+    }
+}
+
+pub fn save(app: &tauri::AppHandle, settings: &Settings) -> Result<(), String> {
+    let path = settings_path(app)?;
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    let json = serde_json::to_string_pretty(settings).map_err(|e| e.to_string())?;
+    crate::zip_utils::write_atomic(&path, json)
+}
+
+/// Push the settings other modules read without an app handle.
+pub fn apply(settings: &Settings) {
+    crate::downloader::set_bandwidth_limit(settings.bandwidth_limit);
+    crate::download_backend::set_preferred(settings.download_backend);
+    crate::download_backend::set_download_slots(settings.mod_download_concurrency);
+    let channel = crate::endpoints::ManifestChannel::parse(settings.manifest_channel.as_deref())
+        .unwrap_or_else(|e| {
+            log::warn!("{e}; following the stable manifest");
+            crate::endpoints::ManifestChannel::Stable
+        });
+    crate::endpoints::set_manifest_channel(channel);
+    crate::endpoints::set_mirrors(settings.mirrors.clone());
+    crate::tasks::set_step_timeouts(settings.step_timeouts.clone());
+    crate::announce::set_enabled(settings.screen_reader_announcements);
+}
+
+/// Letters, digits, `-`, `_` and `.` only: the value ends up in a path or URL.
+pub(crate) fn is_safe_name(s: &str) -> bool {
+    !s.is_empty()
+        && s != "."
+        && s != ".."
+        && s.chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'))
+}
+
+fn validate(settings: &Settings) -> Result<(), String> {
+    if settings.mod_download_concurrency == 0 {
+        return Err("mod download concurrency must be at least 1".to_string());
+    }
+    if let Some(v) = &settings.proton_version {
+        if !v.starts_with("GE-Proton") || !is_safe_name(v) {
+            return Err(format!("invalid Proton-GE version: {v}"));
+        }
+    }
+    crate::endpoints::ManifestChannel::parse(settings.manifest_channel.as_deref())?;
+    Ok(())
+}
+
+#[tauri::command]
+pub fn get_settings(app: tauri::AppHandle) -> Settings {
+    load(&app)
+}
+
+/// Merge `patch` (any subset of the settings fields) into the saved settings.
+#[tauri::command]
+pub fn update_settings(
+    app: tauri::AppHandle,
+    patch: serde_json::Value,
+) -> Result<Settings, String> {
+    let current = serde_json::to_value(load(&app)).map_err(|e| e.to_string())?;
+    let (serde_json::Value::Object(mut merged), serde_json::Value::Object(patch)) =
+        (current, patch)
+    else {
+        return Err("settings patch must be an object".to_string());
+    };
+    merged.extend(patch);
+    let settings: Settings = serde_json::from_value(serde_json::Value::Object(merged))
+        .map_err(|e| format!("invalid settings: {e}"))?;
+    if settings.install_root != load(&app).install_root {
+        return Err("use move_install_root to change the install root".to_string());
+    }
+    validate(&settings)?;
+    save(&app, &settings)?;
+    apply(&settings);
+    Ok(settings)
+}
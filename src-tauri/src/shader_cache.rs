@@ -0,0 +1,107 @@
+//! Per-version DXVK/VKD3D shader caches kept under `shader_cache/v{version}/`.
+//!
+//! Left alone, DXVK writes `Lethal Company.dxvk-cache` next to the executable, so a
+//! reinstall or prefix reset throws it away and the next session stutters while
+//! pipelines recompile. Launches point the caches at the app data dir instead; DXVK
+//! compiles everything in its state cache at startup, which pre-warms the next run.
+
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use serde::Serialize;
+
+use crate::{downloader, storage};
+
+/// Cache files DXVK/VKD3D-Proton drop next to the executable without an explicit path.
+const STRAY_SUFFIXES: &[&str] = &[".dxvk-cache", ".vkd3d-proton.cache"];
+
+fn version_cache_dir(app: &tauri::AppHandle, version: u32) -> Result<PathBuf, String> {
+    let version = storage::check_version(version)?;
+    Ok(storage::shader_cache_dir(app)?.join(format!("v{version}")))
+}
+
+/// Move caches a launch without the env vars left beside the executable.
+fn adopt_stray_caches(exe_dir: &Path, cache_dir: &Path) {
+    let Ok(rd) = std::fs::read_dir(exe_dir) else {
+        return;
+    };
+    for e in rd.flatten() {
+        let name = e.file_name().to_string_lossy().to_string();
+        if !STRAY_SUFFIXES.iter().any(|s| name.ends_with(s)) {
+            continue;
+        }
+        let dest = cache_dir.join(&name);
+        if dest.exists() {
+            continue;
+        }
+        match std::fs::rename(e.path(), &dest) {
+            Ok(()) => log::info!("Moved shader cache {name} to {}", cache_dir.display()),
+            Err(e) => log::warn!("Failed to move shader cache {name}: {e}"),
+        }
+    }
+}
+
+/// Point DXVK and VKD3D-Proton at the persistent cache dir of `version`.
+pub fn apply_env(app: &tauri::AppHandle, version: u32, exe_dir: &Path, cmd: &mut Command) {
+    let dir = match version_cache_dir(app, version) {
+        Ok(dir) => dir,
+        Err(e) => {
+            log::warn!("Shader cache disabled for v{version}: {e}");
+            return;
+        }
+    };
+    if let Err(e) = std::fs::create_dir_all(&dir) {
+        log::warn!("Shader cache disabled for v{version}: {e}");
+        return;
+    }
+    adopt_stray_caches(exe_dir, &dir);
+    cmd.env("DXVK_STATE_CACHE_PATH", &dir);
+    cmd.env("VKD3D_SHADER_CACHE_PATH", &dir);
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ShaderCacheInfo {
+    pub version: u32,
+    pub path: String,
+    pub bytes: u64,
+}
+
+/// Shader cache size per game version that has one.
+#[tauri::command]
+pub fn get_shader_cache_info(app: tauri::AppHandle) -> Result<Vec<ShaderCacheInfo>, String> {
+    let root = storage::shader_cache_dir(&app)?;
+    let Ok(rd) = std::fs::read_dir(&root) else {
+        return Ok(vec![]);
+    };
+    let mut out: Vec<ShaderCacheInfo> = rd
+        .flatten()
+        .filter_map(|e| {
+            let name = e.file_name().to_string_lossy().to_string();
+            let version = name.strip_prefix('v')?.parse::<u32>().ok()?;
+            let path = e.path();
+            Some(ShaderCacheInfo {
+                version,
+                bytes: downloader::dir_size_bytes(&path),
+                path: path.to_string_lossy().to_string(),
+            })
+        })
+        .collect();
+    out.sort_by_key(|c| c.version);
+    Ok(out)
+}
+
+/// Delete the shader cache of `version` (all versions when `None`). Returns bytes freed.
+#[tauri::command]
+pub fn clear_shader_cache(app: tauri::AppHandle, version: Option<u32>) -> Result<u64, String> {
+    let dir = match version {
+        Some(v) => version_cache_dir(&app, v)?,
+        None => storage::shader_cache_dir(&app)?,
+    };
+    if !dir.exists() {
+        return Ok(0);
+    }
+    let freed = downloader::dir_size_bytes(&dir);
+    std::fs::remove_dir_all(&dir).map_err(|e| e.to_string())?;
+    log::info!("Cleared shader cache {} ({freed} bytes)", dir.display());
+    Ok(freed)
+}
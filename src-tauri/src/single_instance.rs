@@ -0,0 +1,254 @@
+//! Single-instance guard.
+//!
+//! The first launcher process listens on a local socket (`hq-launcher-{user}.sock` in the
+//! runtime dir; a per-user named pipe on Windows). Later processes forward their CLI
+//! arguments there and exit; the primary focuses its window and re-emits the arguments as
+//! `second-instance`, so double-clicking the icon mid-download can't start a second
+//! launcher racing on the same files.
+
+use serde::{Deserialize, Serialize};
+use tauri::{Emitter, Manager};
+
+/// Event name for invocations forwarded by a second process.
+pub const SECOND_INSTANCE_EVENT: &str = "second-instance";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SecondInstancePayload {
+    pub args: Vec<String>,
+    pub cwd: Option<String>,
+}
+
+impl SecondInstancePayload {
+    fn current() -> Self {
+        Self {
+            args: std::env::args().skip(1).collect(),
+            cwd: std::env::current_dir()
+                .ok()
+                .map(|p| p.to_string_lossy().to_string()),
+        }
+    }
+}
+
+fn user_tag() -> String {
+    std::env::var("USER")
+        .or_else(|_| std::env::var("USERNAME"))
+        .unwrap_or_default()
+        .chars()
+        .filter(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_'))
+        .collect()
+}
+
+fn on_second_instance(app: &tauri::AppHandle, payload: SecondInstancePayload) {
+    log::info!(
+        "Second launcher instance started with {:?}; focusing",
+        payload.args
+    );
+    if let Some(window) = app.get_webview_window("main") {
+        let _ = window.unminimize();
+        let _ = window.show();
+        let _ = window.set_focus();
+    }
+    let _ = app.emit(SECOND_INSTANCE_EVENT, payload);
+}
+
+fn encode(payload: &SecondInstancePayload) -> Result<Vec<u8>, String> {
+    let mut msg = serde_json::to_vec(payload).map_err(|e| e.to_string())?;
+    msg.push(b'\n');
+    Ok(msg)
+}
+
+fn decode(line: &str) -> Option<SecondInstancePayload> {
+    match serde_json::from_str(line.trim()) {
+        Ok(p) => Some(p),
+        Err(e) => {
+            log::warn!("Ignoring malformed second-instance message: {e}");
+            None
+        }
+    }
+}
+
+pub use imp::Listener;
+
+/// Become the primary instance, or forward this invocation to the running one.
+///
+/// Returns `Ok(None)` when another instance took the invocation (the caller should exit).
+pub fn acquire() -> Result<Option<Listener>, String> {
+    imp::acquire(Some(&encode(&SecondInstancePayload::current())?))
+}
+
+/// Like [`acquire`], but only checks for a running instance without handing it this
+/// invocation (headless runs refuse to start instead of driving the GUI).
+///
+/// Returns `Ok(None)` when another instance is running.
+pub fn acquire_without_forwarding() -> Result<Option<Listener>, String> {
+    imp::acquire(None)
+}
+
+/// Serve forwarded invocations for the lifetime of the app.
+pub fn serve(listener: Listener, app: tauri::AppHandle) {
+    imp::serve(listener, app)
+}
+
+#[cfg(unix)]
+mod imp {
+    use std::io::{BufRead, BufReader, ErrorKind, Read, Write};
+    use std::os::unix::net::{UnixListener, UnixStream};
+    use std::path::PathBuf;
+    use std::time::Duration;
+
+    pub struct Listener(UnixListener);
+
+    fn socket_path() -> PathBuf {
+        dirs::runtime_dir()
+            .unwrap_or_else(std::env::temp_dir)
+            .join(format!("hq-launcher-{}.sock", super::user_tag()))
+    }
+
+    fn forward(mut stream: UnixStream, msg: &[u8]) -> Result<(), String> {
+        stream
+            .set_read_timeout(Some(Duration::from_secs(5)))
+            .map_err(|e| e.to_string())?;
+        stream.write_all(msg).map_err(|e| e.to_string())?;
+        let mut ack = String::new();
+        let _ = stream.read_to_string(&mut ack);
+        Ok(())
+    }
+
+    pub fn acquire(msg: Option<&[u8]>) -> Result<Option<Listener>, String> {
+        let path = socket_path();
+        for _ in 0..2 {
+            match UnixStream::connect(&path) {
+                Ok(stream) => {
+                    // A probe just hangs up; the primary skips the empty message.
+                    if let Some(msg) = msg {
+                        forward(stream, msg)?;
+                    }
+                    return Ok(None);
+                }
+                // Socket file left by a crashed primary.
+                Err(e) if e.kind() == ErrorKind::ConnectionRefused => {
+                    let _ = std::fs::remove_file(&path);
+                }
+                Err(_) => {}
+            }
+            match UnixListener::bind(&path) {
+                Ok(listener) => return Ok(Some(Listener(listener))),
+                // Lost a startup race to another instance; forward to it instead.
+                Err(e) if e.kind() == ErrorKind::AddrInUse => continue,
+                Err(e) => return Err(format!("failed to bind {}: {e}", path.display())),
+            }
+        }
+        Err(format!("could not connect to or bind {}", path.display()))
+    }
+
+    pub fn serve(listener: Listener, app: tauri::AppHandle) {
+        std::thread::spawn(move || {
+            for stream in listener.0.incoming() {
+                let Ok(mut stream) = stream else {
+                    continue;
+                };
+                // A client that never sends must not block the listener.
+                if stream
+                    .set_read_timeout(Some(Duration::from_secs(5)))
+                    .is_err()
+                {
+                    continue;
+                }
+                let mut line = String::new();
+                if BufReader::new(&stream).read_line(&mut line).is_err() || line.trim().is_empty() {
+                    continue;
+                }
+                let _ = stream.write_all(b"ok\n");
+                if let Some(payload) = super::decode(&line) {
+                    super::on_second_instance(&app, payload);
+                }
+            }
+        });
+    }
+}
+
+#[cfg(windows)]
+mod imp {
+    use std::io::{Read, Write};
+
+    use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+    use tokio::net::windows::named_pipe::{NamedPipeServer, ServerOptions};
+
+    pub struct Listener(NamedPipeServer);
+
+    fn pipe_name() -> String {
+        format!(r"\\.\pipe\hq-launcher-{}", super::user_tag())
+    }
+
+    /// Connect to a running instance and, given a message, forward it.
+    fn try_forward(msg: Option<&[u8]>) -> bool {
+        let Ok(mut pipe) = std::fs::OpenOptions::new()
+            .read(true)
+            .write(true)
+            .open(pipe_name())
+        else {
+            return false;
+        };
+        // A probe just hangs up; the primary skips the empty message.
+        let Some(msg) = msg else {
+            return true;
+        };
+        if pipe.write_all(msg).is_err() {
+            return false;
+        }
+        let mut ack = [0u8; 3];
+        let _ = pipe.read(&mut ack);
+        true
+    }
+
+    fn create_first_server() -> std::io::Result<NamedPipeServer> {
+        // Creating a pipe registers it with the reactor, so it needs the async runtime.
+        tauri::async_runtime::block_on(async {
+            ServerOptions::new()
+                .first_pipe_instance(true)
+                .create(pipe_name())
+        })
+    }
+
+    pub fn acquire(msg: Option<&[u8]>) -> Result<Option<Listener>, String> {
+        if try_forward(msg) {
+            return Ok(None);
+        }
+        match create_first_server() {
+            Ok(server) => Ok(Some(Listener(server))),
+            // Lost a startup race to another instance; forward to it instead.
+            Err(_) if try_forward(msg) => Ok(None),
+            Err(e) => Err(format!("failed to create pipe {}: {e}", pipe_name())),
+        }
+    }
+
+    pub fn serve(listener: Listener, app: tauri::AppHandle) {
+        tauri::async_runtime::spawn(async move {
+            let mut server = listener.0;
+            loop {
+                let connected = server.connect().await;
+                // Open the next instance before serving this one so clients never miss it.
+                let next = match ServerOptions::new().create(pipe_name()) {
+                    Ok(next) => next,
+                    Err(e) => {
+                        log::warn!("Single-instance pipe stopped: {e}");
+                        return;
+                    }
+                };
+                if connected.is_err() {
+                    server = next;
+                    continue;
+                }
+                let mut line = String::new();
+                let mut conn = BufReader::new(server);
+                if conn.read_line(&mut line).await.is_ok() && !line.trim().is_empty() {
+                    let _ = conn.get_mut().write_all(b"ok\n").await;
+                    if let Some(payload) = super::decode(&line) {
+                        super::on_second_instance(&app, payload);
+                    }
+                }
+                server = next;
+            }
+        });
+    }
+}
@@ -0,0 +1,162 @@
+use std::path::PathBuf;
+
+use tauri::Manager;
+
+use crate::error::{Error, Result};
+
+/// On-disk layout under the app data dir:
+///
+/// - `versions/`           installed game versions, unless `Settings::install_root` moves them
+/// - `state/`              launcher-owned state (settings, manifest state, disabled mods)
+/// - `game_config/shared/` BepInEx config shared by every installed version
+/// - `game_config/shared_data/` mod data folders shared the same way (manifest `shared_folders`)
+/// - `temp/`               in-flight downloads, see `temp_files`
+/// - `shader_cache/`       per-version DXVK/VKD3D caches, see `shader_cache`
+/// - `cache/packages/`     downloaded Thunderstore zips shared by every version, see `cache`
+/// - `overlays/`           per-launch overlays of protected game files, see `overlay`
+/// - `backups/`            restore points (config snapshots) kept until pruned
+/// - `diagnostics/`        exported diagnostics bundles, see `diagnostics`
+/// - `logs/`               launcher logs, see `logger`
+/// - `downloader/`         the DepotDownloader build; `depot_config/` holds its Steam login
+/// - `depot_cache/`        DepotDownloader workspace shared by every version, see `downloader`
+/// - `proton_env/`         Proton-GE builds, the Wine prefix, DXVK and umu-launcher
+///
+/// Older builds kept both under `config/`; see `installer::migrate_legacy_config_layout`.
+fn app_data_dir(app: &tauri::AppHandle) -> Result<PathBuf> {
+    app.path()
+        .app_data_dir()
+        .map_err(|e| Error::Other(format!("failed to resolve app data dir: {e}")))
+}
+
+/// Highest game version number accepted as an argument. Real versions are two digits;
+/// the cap keeps garbage (e.g. a negative number cast to `u32`) out of paths and URLs.
+pub const MAX_GAME_VERSION: u32 = 9999;
+
+/// `version` if it is a plausible game version number.
+pub fn check_version(version: u32) -> Result<u32> {
+    if (1..=MAX_GAME_VERSION).contains(&version) {
+        Ok(version)
+    } else {
+        Err(Error::UnknownVersion {
+            version,
+            reason: "out of range",
+        })
+    }
+}
+
+/// `v{version}/` under `versions_dir`, installed or not. Every path built from a version
+/// argument goes through here (or `check_version`).
+pub fn version_dir(app: &tauri::AppHandle, version: u32) -> Result<PathBuf> {
+    Ok(versions_dir(app)?.join(format!("v{}", check_version(version)?)))
+}
+
+/// `version_dir` of an installed version.
+pub fn installed_version_dir(app: &tauri::AppHandle, version: u32) -> Result<PathBuf> {
+    let dir = version_dir(app, version)?;
+    if !dir.is_dir() {
+        return Err(Error::UnknownVersion {
+            version,
+            reason: "not installed",
+        });
+    }
+    Ok(dir)
+}
+
+/// Where installed versions (`v{N}/`) live: `Settings::install_root`, else `versions/`.
+pub fn versions_dir(app: &tauri::AppHandle) -> Result<PathBuf> {
+    match crate::settings::load(app).install_root {
+        Some(root) => Ok(PathBuf::from(root)),
+        None => default_versions_dir(app),
+    }
+}
+
+pub fn default_versions_dir(app: &tauri::AppHandle) -> Result<PathBuf> {
+    Ok(app_data_dir(app)?.join("versions"))
+}
+
+/// Launcher state. Safe to wipe on "reset launcher" without touching game configs.
+pub fn state_dir(app: &tauri::AppHandle) -> Result<PathBuf> {
+    Ok(app_data_dir(app)?.join("state"))
+}
+
+/// Game (BepInEx) configuration owned by the user.
+pub fn game_config_dir(app: &tauri::AppHandle) -> Result<PathBuf> {
+    Ok(app_data_dir(app)?.join("game_config"))
+}
+
+/// Shared BepInEx config dir that version `BepInEx/config` folders link to.
+pub fn shared_config_dir(app: &tauri::AppHandle) -> Result<PathBuf> {
+    Ok(game_config_dir(app)?.join("shared"))
+}
+
+/// Mod data folders the manifest shares across versions (`RemoteManifest::shared_folders`),
+/// mirroring their path under the game root.
+pub fn shared_data_dir(app: &tauri::AppHandle) -> Result<PathBuf> {
+    Ok(game_config_dir(app)?.join("shared_data"))
+}
+
+/// Pre-restructure `config/` dir (holds both state files and `shared/`).
+pub fn legacy_config_dir(app: &tauri::AppHandle) -> Result<PathBuf> {
+    Ok(app_data_dir(app)?.join("config"))
+}
+
+/// Staging dir for in-flight downloads; anything here may be swept on startup.
+pub fn temp_dir(app: &tauri::AppHandle) -> Result<PathBuf> {
+    Ok(app_data_dir(app)?.join("temp"))
+}
+
+/// Shader caches; kept across reinstalls and prefix resets.
+pub fn shader_cache_dir(app: &tauri::AppHandle) -> Result<PathBuf> {
+    Ok(app_data_dir(app)?.join("shader_cache"))
+}
+
+/// Per-launch overlays; only the last session's writes, safe to delete while the game is closed.
+pub fn overlays_dir(app: &tauri::AppHandle) -> Result<PathBuf> {
+    Ok(app_data_dir(app)?.join("overlays"))
+}
+
+/// Restore points the launcher made; safe to delete, but undo goes with them.
+pub fn backups_dir(app: &tauri::AppHandle) -> Result<PathBuf> {
+    Ok(app_data_dir(app)?.join("backups"))
+}
+
+/// Config snapshots taken before a sync replaces files, see `config_backup`.
+pub fn config_backups_dir(app: &tauri::AppHandle) -> Result<PathBuf> {
+    Ok(backups_dir(app)?.join("config"))
+}
+
+/// Diagnostics bundles the user exported.
+pub fn diagnostics_dir(app: &tauri::AppHandle) -> Result<PathBuf> {
+    Ok(app_data_dir(app)?.join("diagnostics"))
+}
+
+/// Launcher logs (`hq-launcher.log` and its rolled predecessors).
+pub fn logs_dir(app: &tauri::AppHandle) -> Result<PathBuf> {
+    Ok(app_data_dir(app)?.join("logs"))
+}
+
+/// Re-downloadable data (package zips); safe to delete at any time.
+pub fn cache_dir(app: &tauri::AppHandle) -> Result<PathBuf> {
+    Ok(app_data_dir(app)?.join("cache"))
+}
+
+/// The DepotDownloader build the launcher installed.
+pub fn downloader_dir(app: &tauri::AppHandle) -> Result<PathBuf> {
+    Ok(app_data_dir(app)?.join("downloader"))
+}
+
+/// DepotDownloader's saved Steam login; deleting it logs the account out.
+pub fn depot_config_dir(app: &tauri::AppHandle) -> Result<PathBuf> {
+    Ok(app_data_dir(app)?.join("depot_config"))
+}
+
+/// DepotDownloader workspace reused across versions; safe to delete, the next download is full.
+pub fn depot_cache_dir(app: &tauri::AppHandle) -> Result<PathBuf> {
+    Ok(app_data_dir(app)?.join("depot_cache"))
+}
+
+/// Proton-GE builds (`proton/`), the Wine prefix (`wine_prefix/`), DXVK releases (`dxvk/`)
+/// and umu-launcher (`umu/`).
+pub fn proton_env_dir(app: &tauri::AppHandle) -> Result<PathBuf> {
+    Ok(app_data_dir(app)?.join("proton_env"))
+}
@@ -0,0 +1,143 @@
+//! Disk usage of the launcher, for the storage settings screen.
+//!
+//! Sizes are walked on demand (no bookkeeping to drift), so `get_storage_stats` can take a
+//! moment on large installs and runs off the async runtime.
+
+use std::path::{Path, PathBuf};
+
+use serde::Serialize;
+
+use crate::downloader::dir_size_bytes;
+use crate::error::{Error, Result};
+use crate::{disk_space, storage};
+
+/// Top-level app data entries counted in a category of their own; everything else is
+/// `other`. (`versions` is left out even when the install root moved elsewhere.)
+const CATEGORIZED: [&str; 6] = [
+    "versions",
+    "cache",
+    "depot_cache",
+    "shader_cache",
+    "proton_env",
+    "backups",
+];
+
+#[derive(Debug, Clone, Serialize)]
+pub struct VolumeStats {
+    pub path: PathBuf,
+    pub free_bytes: u64,
+    pub total_bytes: u64,
+}
+
+/// Bytes used by the launcher, by category.
+#[derive(Debug, Clone, Serialize)]
+pub struct StorageUsage {
+    /// Installed game versions (`Settings::install_root`).
+    pub versions: u64,
+    /// Mod package zips, the depot chunk cache and shader caches; all re-creatable.
+    pub cache: u64,
+    /// Proton builds and the Wine prefix (Linux only).
+    pub prefixes: u64,
+    pub backups: u64,
+    /// Settings, shared config, logs, temp files and overlays.
+    pub other: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct StorageStats {
+    /// Volume of the install root; `None` when it can't be queried.
+    pub install_root: Option<VolumeStats>,
+    /// Volume of the cache dir, which may differ when the install root was moved.
+    pub cache: Option<VolumeStats>,
+    /// Both dirs are on the same volume (the UI can show one bar).
+    pub same_volume: bool,
+    pub usage: StorageUsage,
+}
+
+fn volume(path: &Path) -> Option<VolumeStats> {
+    match disk_space::volume_space(path) {
+        Ok(space) => Some(VolumeStats {
+            path: path.to_path_buf(),
+            free_bytes: space.available,
+            total_bytes: space.total,
+        }),
+        Err(e) => {
+            log::warn!("Storage stats: {e}");
+            None
+        }
+    }
+}
+
+#[cfg(unix)]
+fn same_device(a: &Path, b: &Path) -> bool {
+    use std::os::unix::fs::MetadataExt;
+    let dev = |p: &Path| {
+        p.ancestors()
+            .find_map(|p| std::fs::metadata(p).ok())
+            .map(|m| m.dev())
+    };
+    dev(a).is_some() && dev(a) == dev(b)
+}
+
+#[cfg(not(unix))]
+fn same_device(a: &Path, b: &Path) -> bool {
+    let root = |p: &Path| {
+        p.components()
+            .next()
+            .map(|c| c.as_os_str().to_ascii_lowercase())
+    };
+    root(a).is_some() && root(a) == root(b)
+}
+
+fn other_usage(app_data: &Path) -> u64 {
+    let Ok(rd) = std::fs::read_dir(app_data) else {
+        return 0;
+    };
+    rd.flatten()
+        .filter(|e| {
+            let name = e.file_name();
+            !CATEGORIZED.iter().any(|c| name == *c)
+        })
+        .map(|e| match e.file_type() {
+            Ok(t) if t.is_dir() => dir_size_bytes(&e.path()),
+            Ok(t) if t.is_file() => e.metadata().map(|m| m.len()).unwrap_or(0),
+            _ => 0,
+        })
+        .sum()
+}
+
+/// Free/total space of the install root and cache volumes, and what the launcher uses.
+#[tauri::command]
+pub async fn get_storage_stats(app: tauri::AppHandle) -> Result<StorageStats> {
+    let versions = storage::versions_dir(&app)?;
+    let cache = storage::cache_dir(&app)?;
+    let depot_cache = storage::depot_cache_dir(&app)?;
+    let shader_cache = storage::shader_cache_dir(&app)?;
+    // Proton (and so a Wine prefix) is only used on Linux.
+    #[cfg(target_os = "linux")]
+    let prefixes = Some(storage::proton_env_dir(&app)?);
+    #[cfg(not(target_os = "linux"))]
+    let prefixes: Option<PathBuf> = None;
+    let backups = storage::backups_dir(&app)?;
+    let app_data = cache
+        .parent()
+        .map(Path::to_path_buf)
+        .ok_or_else(|| Error::Other("app data dir not found".to_string()))?;
+
+    tauri::async_runtime::spawn_blocking(move || StorageStats {
+        install_root: volume(&versions),
+        cache: volume(&cache),
+        same_volume: same_device(&versions, &cache),
+        usage: StorageUsage {
+            versions: dir_size_bytes(&versions),
+            cache: dir_size_bytes(&cache)
+                .saturating_add(dir_size_bytes(&depot_cache))
+                .saturating_add(dir_size_bytes(&shader_cache)),
+            prefixes: prefixes.as_deref().map(dir_size_bytes).unwrap_or(0),
+            backups: dir_size_bytes(&backups),
+            other: other_usage(&app_data),
+        },
+    })
+    .await
+    .map_err(|e| Error::Other(e.to_string()))
+}
@@ -0,0 +1,100 @@
+//! Log lines captured per task, so an error can carry the context that led to it.
+//!
+//! `tasks::begin`/`tasks::end` open and close a capture; every log line written while a
+//! task is running goes into its buffer (tasks rarely overlap, and when they do the extra
+//! lines are still useful context). The last few finished tasks are kept for
+//! `get_task_log`.
+
+use std::collections::{BTreeMap, VecDeque};
+use std::sync::Mutex;
+
+/// Lines kept per task.
+const MAX_LINES: usize = 200;
+/// Lines attached to `TaskErrorPayload`.
+pub const TAIL_LINES: usize = 50;
+/// Finished task logs kept for `get_task_log`.
+const KEEP_FINISHED: usize = 16;
+
+struct TaskLogs {
+    logs: BTreeMap<String, VecDeque<String>>,
+    active: Vec<String>,
+    /// Finished task ids, oldest first.
+    finished: VecDeque<String>,
+    /// Latest task id per game version.
+    latest: BTreeMap<u32, String>,
+}
+
+static LOGS: Mutex<TaskLogs> = Mutex::new(TaskLogs {
+    logs: BTreeMap::new(),
+    active: Vec::new(),
+    finished: VecDeque::new(),
+    latest: BTreeMap::new(),
+});
+
+/// Start capturing log lines for `task_id`.
+pub fn start(version: u32, task_id: &str) {
+    let Ok(mut logs) = LOGS.lock() else {
+        return;
+    };
+    logs.logs.insert(task_id.to_string(), VecDeque::new());
+    logs.active.push(task_id.to_string());
+    logs.latest.insert(version, task_id.to_string());
+}
+
+/// Stop capturing for `task_id`; its log stays fetchable until evicted.
+pub fn finish(task_id: &str) {
+    let Ok(mut logs) = LOGS.lock() else {
+        return;
+    };
+    let before = logs.active.len();
+    logs.active.retain(|id| id != task_id);
+    if logs.active.len() == before {
+        return;
+    }
+    logs.finished.push_back(task_id.to_string());
+    while logs.finished.len() > KEEP_FINISHED {
+        if let Some(old) = logs.finished.pop_front() {
+            logs.logs.remove(&old);
+            logs.latest.retain(|_, id| *id != old);
+        }
+    }
+}
+
+/// Append a formatted line to every running task.
+pub(crate) fn capture(line: &str) {
+    let Ok(mut logs) = LOGS.lock() else {
+        return;
+    };
+    let TaskLogs { logs, active, .. } = &mut *logs;
+    for id in active.iter() {
+        if let Some(buf) = logs.get_mut(id) {
+            if buf.len() >= MAX_LINES {
+                buf.pop_front();
+            }
+            buf.push_back(line.to_string());
+        }
+    }
+}
+
+/// The last `max` lines captured for `task_id`.
+pub fn tail(task_id: &str, max: usize) -> Option<Vec<String>> {
+    let logs = LOGS.lock().ok()?;
+    let buf = logs.logs.get(task_id)?;
+    Some(
+        buf.iter()
+            .skip(buf.len().saturating_sub(max))
+            .cloned()
+            .collect(),
+    )
+}
+
+/// Id of the most recent task for `version` whose log is still kept.
+pub fn latest_task(version: u32) -> Option<String> {
+    LOGS.lock().ok()?.latest.get(&version).cloned()
+}
+
+/// Log lines captured for a running or recently finished task.
+#[tauri::command]
+pub fn get_task_log(task_id: String) -> Result<Vec<String>, String> {
+    tail(&task_id, MAX_LINES).ok_or_else(|| format!("no log kept for task {task_id}"))
+}
@@ -0,0 +1,91 @@
+//! Task progress on the taskbar/dock icon, so a minimized or hidden launcher still shows
+//! how far an install got.
+//!
+//! Driven from `progress` as events go out. Tauri maps it to `ITaskbarList3` on Windows,
+//! the Unity launcher API (libunity) on Linux desktops that provide it, and the dock tile
+//! on macOS; elsewhere the calls are no-ops.
+
+use std::sync::Mutex;
+
+use tauri::window::{ProgressBarState, ProgressBarStatus};
+use tauri::{AppHandle, Manager};
+
+/// Last `(status, percent)` pushed, to skip redundant native calls on every chunk.
+static SHOWN: Mutex<Option<(Status, u64)>> = Mutex::new(None);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Status {
+    Normal,
+    Paused,
+    Error,
+}
+
+/// Unity matches the launcher entry by its `.desktop` file (named after the product).
+fn desktop_filename(app: &AppHandle) -> Option<String> {
+    cfg!(target_os = "linux").then(|| format!("{}.desktop", app.package_info().name))
+}
+
+fn set(app: &AppHandle, state: Option<(Status, u64)>) {
+    {
+        let Ok(mut shown) = SHOWN.lock() else {
+            return;
+        };
+        if *shown == state {
+            return;
+        }
+        *shown = state;
+    }
+    let Some(window) = app.get_webview_window("main") else {
+        return;
+    };
+    let bar = match state {
+        Some((status, percent)) => ProgressBarState {
+            status: Some(match status {
+                Status::Normal => ProgressBarStatus::Normal,
+                Status::Paused => ProgressBarStatus::Paused,
+                Status::Error => ProgressBarStatus::Error,
+            }),
+            progress: Some(percent),
+            desktop_filename: desktop_filename(app),
+        },
+        None => ProgressBarState {
+            status: Some(ProgressBarStatus::None),
+            progress: None,
+            desktop_filename: desktop_filename(app),
+        },
+    };
+    if let Err(e) = window.set_progress_bar(bar) {
+        log::debug!("Taskbar progress unavailable: {e}");
+    }
+}
+
+fn current_percent() -> u64 {
+    SHOWN
+        .lock()
+        .ok()
+        .and_then(|s| s.map(|(_, p)| p))
+        .unwrap_or(0)
+}
+
+/// Show `percent` (0..=100) of the running task.
+pub fn progress(app: &AppHandle, percent: f64) {
+    set(
+        app,
+        Some((Status::Normal, percent.clamp(0.0, 100.0).round() as u64)),
+    );
+}
+
+/// The running task stopped reporting progress (see `tasks` watchdog).
+pub fn stalled(app: &AppHandle) {
+    set(app, Some((Status::Paused, current_percent())));
+}
+
+/// The task failed; stays red until the next task reports progress.
+pub fn failed(app: &AppHandle) {
+    set(app, Some((Status::Error, current_percent())));
+}
+
+/// The task finished or was cancelled.
+pub fn clear(app: &AppHandle) {
+    set(app, None);
+}
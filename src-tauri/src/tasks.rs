@@ -0,0 +1,397 @@
+use std::collections::{BTreeMap, HashMap};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::{Duration, Instant};
+
+use tauri::{AppHandle, Manager};
+
+use crate::progress::{
+    self, CancelReason, TaskProgressPayload, TaskStalledPayload, TaskStep, TaskTimeoutPayload,
+};
+
+/// A task that reports no progress for this long is considered stalled.
+const STALL_AFTER: Duration = Duration::from_secs(5 * 60);
+/// How often the watchdog looks at active tasks.
+const WATCHDOG_INTERVAL: Duration = Duration::from_secs(15);
+
+/// Time budget per step id in seconds, unless `Settings::step_timeouts` overrides it.
+/// Plan steps are enforced by the watchdog; the fetch steps (`manifest_fetch`,
+/// `config_download`) bound each mirror attempt. Steps not listed, or set to `0`, have no
+/// budget (the game download can take hours on a slow line; the stall check covers it).
+const DEFAULT_STEP_TIMEOUTS: [(&str, u64); 10] = [
+    ("manifest_fetch", 30),
+    ("config_download", 120),
+    ("login_check", 120),
+    ("install_bepinex", 10 * 60),
+    ("install_config", 10 * 60),
+    ("install_mods", 30 * 60),
+    ("sync_mods", 30 * 60),
+    ("check_updates", 2 * 60),
+    ("update_mods", 30 * 60),
+    ("practice_mods", 30 * 60),
+];
+
+static STEP_TIMEOUT_OVERRIDES: RwLock<BTreeMap<String, u64>> = RwLock::new(BTreeMap::new());
+
+/// Replace the per-step budget overrides (seconds by step id, `0` = no budget).
+pub fn set_step_timeouts(overrides: BTreeMap<String, u64>) {
+    if let Ok(mut current) = STEP_TIMEOUT_OVERRIDES.write() {
+        *current = overrides;
+    }
+}
+
+/// Budget of step `step_id`, if it has one.
+pub fn step_timeout(step_id: &str) -> Option<Duration> {
+    let secs = STEP_TIMEOUT_OVERRIDES
+        .read()
+        .ok()
+        .and_then(|o| o.get(step_id).copied())
+        .or_else(|| {
+            DEFAULT_STEP_TIMEOUTS
+                .iter()
+                .find(|(id, _)| *id == step_id)
+                .map(|(_, secs)| *secs)
+        })?;
+    (secs > 0).then(|| Duration::from_secs(secs))
+}
+
+/// Kind of long-running task, used to decide which recovery actions the UI can offer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TaskKind {
+    Install,
+    Sync,
+    UpdateMods,
+    PracticeMods,
+    Uninstall,
+    Verify,
+    Profile,
+    Move,
+}
+
+impl TaskKind {
+    fn as_str(self) -> &'static str {
+        match self {
+            TaskKind::Install => "install",
+            TaskKind::Sync => "sync",
+            TaskKind::UpdateMods => "update_mods",
+            TaskKind::PracticeMods => "practice_mods",
+            TaskKind::Uninstall => "uninstall",
+            TaskKind::Verify => "verify",
+            TaskKind::Profile => "profile",
+            TaskKind::Move => "move",
+        }
+    }
+
+    /// Only installs have a cancel path in the UI (`cancel_install`). The watchdog can stop
+    /// any task through its cancel token.
+    fn can_cancel(self) -> bool {
+        matches!(self, TaskKind::Install)
+    }
+
+    /// Only installs restart on `retry_task`: their partial install is kept, so the game
+    /// download resumes where it stopped.
+    fn can_retry(self) -> bool {
+        matches!(self, TaskKind::Install)
+    }
+}
+
+struct TaskEntry {
+    id: String,
+    kind: TaskKind,
+    last_progress_at: Instant,
+    step: u32,
+    step_name: String,
+    stall_reported: bool,
+    /// Paused on a player decision; not a stall however long it takes.
+    waiting: bool,
+    /// Per-step weights from the task plan (empty = no plan, keep callers' percent).
+    step_weights: Vec<f64>,
+    /// Step ids from the task plan, to look up their time budget.
+    step_ids: Vec<&'static str>,
+    step_started_at: Instant,
+    timeout_reported: bool,
+    /// Stops the task; set when a step runs over its budget.
+    cancel: Arc<AtomicBool>,
+    /// Set when the task was asked to stop.
+    cancel_reason: Option<CancelReason>,
+}
+
+/// Registry of running tasks keyed by game version (progress events are keyed by version too).
+#[derive(Default)]
+pub struct TaskManager {
+    tasks: Mutex<HashMap<u32, TaskEntry>>,
+}
+
+/// Register a task for `version` and return its id.
+pub fn begin(app: &AppHandle, version: u32, kind: TaskKind) -> String {
+    let ts = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_millis())
+        .unwrap_or(0);
+    let id = format!("{}-v{version}-{ts}", kind.as_str());
+    crate::task_log::start(version, &id);
+
+    if let Some(state) = app.try_state::<TaskManager>() {
+        if let Ok(mut tasks) = state.tasks.lock() {
+            tasks.insert(
+                version,
+                TaskEntry {
+                    id: id.clone(),
+                    kind,
+                    last_progress_at: Instant::now(),
+                    step: 0,
+                    step_name: String::new(),
+                    stall_reported: false,
+                    waiting: false,
+                    step_weights: vec![],
+                    step_ids: vec![],
+                    step_started_at: Instant::now(),
+                    timeout_reported: false,
+                    cancel: Arc::new(AtomicBool::new(false)),
+                    cancel_reason: None,
+                },
+            );
+        }
+    }
+    id
+}
+
+/// Id of the task currently running for `version`, if any.
+pub fn task_id_for(app: &AppHandle, version: u32) -> Option<String> {
+    let state = app.try_state::<TaskManager>()?;
+    let tasks = state.tasks.lock().ok()?;
+    tasks.get(&version).map(|t| t.id.clone())
+}
+
+/// Whether a task with this id is still running.
+pub fn is_active(app: &AppHandle, task_id: &str) -> bool {
+    let Some(state) = app.try_state::<TaskManager>() else {
+        return false;
+    };
+    let Ok(tasks) = state.tasks.lock() else {
+        return false;
+    };
+    tasks.values().any(|t| t.id == task_id)
+}
+
+/// Remove the task for `version` (if `task_id` still owns the slot).
+pub fn end(app: &AppHandle, version: u32, task_id: &str) {
+    crate::task_log::finish(task_id);
+    if let Some(state) = app.try_state::<TaskManager>() {
+        if let Ok(mut tasks) = state.tasks.lock() {
+            if tasks.get(&version).is_some_and(|t| t.id == task_id) {
+                tasks.remove(&version);
+            }
+        }
+    }
+}
+
+/// Mark the task of `version` as paused on (or resumed from) a player decision.
+pub(crate) fn set_waiting(app: &AppHandle, version: u32, waiting: bool) {
+    let Some(state) = app.try_state::<TaskManager>() else {
+        return;
+    };
+    let Ok(mut tasks) = state.tasks.lock() else {
+        return;
+    };
+    if let Some(task) = tasks.get_mut(&version) {
+        task.waiting = waiting;
+        task.last_progress_at = Instant::now();
+        // Time spent on the decision doesn't count against the step's budget.
+        task.step_started_at = Instant::now();
+    }
+}
+
+/// Cancel token of the task running for `version`, to be checked by the task's work.
+/// Without a task, a token nothing ever sets.
+pub(crate) fn cancel_flag(app: &AppHandle, version: u32) -> Arc<AtomicBool> {
+    app.try_state::<TaskManager>()
+        .and_then(|state| {
+            let tasks = state.tasks.lock().ok()?;
+            tasks.get(&version).map(|t| t.cancel.clone())
+        })
+        .unwrap_or_default()
+}
+
+/// Use `cancel` as the cancel token of the task of `version` (installs share theirs with
+/// `cancel_install`).
+pub(crate) fn set_cancel_flag(app: &AppHandle, version: u32, cancel: Arc<AtomicBool>) {
+    let Some(state) = app.try_state::<TaskManager>() else {
+        return;
+    };
+    let Ok(mut tasks) = state.tasks.lock() else {
+        return;
+    };
+    if let Some(task) = tasks.get_mut(&version) {
+        task.cancel = cancel;
+    }
+}
+
+/// Record why the task of `version` is being cancelled, before signalling it to stop.
+pub(crate) fn set_cancel_reason(app: &AppHandle, version: u32, reason: CancelReason) {
+    let Some(state) = app.try_state::<TaskManager>() else {
+        return;
+    };
+    let Ok(mut tasks) = state.tasks.lock() else {
+        return;
+    };
+    if let Some(task) = tasks.get_mut(&version) {
+        task.cancel_reason = Some(reason);
+    }
+}
+
+/// Stop task `task_id` of `version` so its runner starts it again. Returns whether the
+/// task was stopped; not for tasks that can't be retried or are already stopping.
+pub(crate) fn request_retry(app: &AppHandle, version: u32, task_id: &str) -> bool {
+    let Some(state) = app.try_state::<TaskManager>() else {
+        return false;
+    };
+    let Ok(mut tasks) = state.tasks.lock() else {
+        return false;
+    };
+    let Some(task) = tasks.get_mut(&version) else {
+        return false;
+    };
+    if task.id != task_id || !task.kind.can_retry() || task.cancel_reason.is_some() {
+        return false;
+    }
+    task.cancel_reason = Some(CancelReason::Retry);
+    task.cancel.store(true, Ordering::Relaxed);
+    true
+}
+
+/// Why the task of `version` was cancelled, if it was asked to stop.
+pub(crate) fn cancel_reason(app: &AppHandle, version: u32) -> Option<CancelReason> {
+    let state = app.try_state::<TaskManager>()?;
+    let tasks = state.tasks.lock().ok()?;
+    tasks.get(&version).and_then(|t| t.cancel_reason)
+}
+
+/// Remember the plan's step weights and ids for the task running for `version`.
+pub(crate) fn set_plan(app: &AppHandle, version: u32, steps: &[TaskStep]) {
+    let Some(state) = app.try_state::<TaskManager>() else {
+        return;
+    };
+    let Ok(mut tasks) = state.tasks.lock() else {
+        return;
+    };
+    if let Some(task) = tasks.get_mut(&version) {
+        task.step_weights = steps.iter().map(|s| s.weight).collect();
+        task.step_ids = steps.iter().map(|s| s.id).collect();
+    }
+}
+
+/// Refresh the last-progress timestamp of the task owning `payload.version` and
+/// recompute `overall_percent` from its step weights.
+pub(crate) fn record_progress(app: &AppHandle, payload: &mut TaskProgressPayload) {
+    let Some(state) = app.try_state::<TaskManager>() else {
+        return;
+    };
+    let Ok(mut tasks) = state.tasks.lock() else {
+        return;
+    };
+    if let Some(task) = tasks.get_mut(&payload.version) {
+        task.last_progress_at = Instant::now();
+        if task.step != payload.step {
+            task.step_started_at = Instant::now();
+            task.timeout_reported = false;
+        }
+        task.step = payload.step;
+        task.step_name = payload.step_name.clone();
+        if task.step_weights.len() == payload.steps_total as usize {
+            payload.overall_percent =
+                progress::weighted_overall(&task.step_weights, payload.step, payload.step_progress);
+        }
+        if task.stall_reported {
+            log::info!("Task {} is making progress again", task.id);
+            task.stall_reported = false;
+        }
+    }
+}
+
+/// Budget of the step `task` is on, if it ran over it.
+fn overrun(task: &TaskEntry) -> Option<Duration> {
+    let id = task.step_ids.get((task.step as usize).checked_sub(1)?)?;
+    let budget = step_timeout(id)?;
+    (task.step_started_at.elapsed() > budget).then_some(budget)
+}
+
+/// Periodically check active tasks: emit `task-stalled` once per stall, and emit `task-timeout`
+/// once when a step runs over its time budget, stopping the task. `cancelled` is false when
+/// the task was already stopping for another reason.
+pub fn spawn_watchdog(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let mut ticks = tokio::time::interval(WATCHDOG_INTERVAL);
+        loop {
+            ticks.tick().await;
+
+            let mut stalled: Vec<TaskStalledPayload> = vec![];
+            let mut timed_out: Vec<TaskTimeoutPayload> = vec![];
+            {
+                let state = app.state::<TaskManager>();
+                let Ok(mut tasks) = state.tasks.lock() else {
+                    continue;
+                };
+                for (version, task) in tasks.iter_mut() {
+                    if task.waiting {
+                        continue;
+                    }
+                    if !task.timeout_reported {
+                        if let Some(budget) = overrun(task) {
+                            task.timeout_reported = true;
+                            let cancelled = task.cancel_reason.is_none();
+                            if cancelled {
+                                task.cancel_reason = Some(CancelReason::Timeout);
+                                task.cancel.store(true, Ordering::Relaxed);
+                            }
+                            timed_out.push(TaskTimeoutPayload {
+                                version: *version,
+                                task_id: task.id.clone(),
+                                step: task.step,
+                                step_name: task.step_name.clone(),
+                                budget_secs: budget.as_secs(),
+                                cancelled,
+                            });
+                        }
+                    }
+                    let idle = task.last_progress_at.elapsed();
+                    if task.stall_reported || idle < STALL_AFTER {
+                        continue;
+                    }
+                    task.stall_reported = true;
+                    stalled.push(TaskStalledPayload {
+                        version: *version,
+                        task_id: task.id.clone(),
+                        step: task.step,
+                        step_name: task.step_name.clone(),
+                        idle_secs: idle.as_secs(),
+                        can_cancel: task.kind.can_cancel(),
+                        can_retry: task.kind.can_retry(),
+                    });
+                }
+            }
+
+            for payload in timed_out {
+                log::warn!(
+                    "Task {} ran over the {}s budget of step {} {}{}",
+                    payload.task_id,
+                    payload.budget_secs,
+                    payload.step,
+                    payload.step_name,
+                    if payload.cancelled { "; cancelling" } else { "" }
+                );
+                progress::emit_timeout(&app, payload);
+            }
+            for payload in stalled {
+                log::warn!(
+                    "Task {} stalled: no progress for {}s (step {} {})",
+                    payload.task_id,
+                    payload.idle_secs,
+                    payload.step,
+                    payload.step_name
+                );
+                progress::emit_stalled(&app, payload);
+            }
+        }
+    });
+}
@@ -0,0 +1,161 @@
+//! Temp file naming and orphan collection.
+//!
+//! Downloads staged in the app `temp/` dir are named `{owner}__{name}.part`, where
+//! `owner` is the id of the task writing them (`tasks::begin`) or `launcher` for work
+//! that runs outside a task (e.g. the Proton-GE download). Whatever a crash leaves
+//! behind is collected by `sweep_orphans` on startup, once it is `ORPHAN_MIN_AGE` old.
+
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use crate::{installer, storage, tasks};
+
+const OWNER_SEP: &str = "__";
+const PART_SUFFIX: &str = ".part";
+/// Owner for temp files not tied to a task.
+pub const LAUNCHER_OWNER: &str = "launcher";
+/// Suffix of `zip_utils::write_atomic` temp files.
+const ATOMIC_SUFFIX: &str = ".hq-tmp";
+/// Leftovers younger than this are kept: they may belong to another launcher process
+/// (e.g. a headless run) or be a download worth resuming.
+const ORPHAN_MIN_AGE: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// Path for a temp download owned by `owner` (task id or `LAUNCHER_OWNER`).
+pub fn temp_path(app: &tauri::AppHandle, owner: &str, name: &str) -> Result<PathBuf, String> {
+    let dir = storage::temp_dir(app)?;
+    std::fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+    Ok(dir.join(format!("{owner}{OWNER_SEP}{name}{PART_SUFFIX}")))
+}
+
+fn owner_of(file_name: &str) -> Option<&str> {
+    file_name
+        .strip_suffix(PART_SUFFIX)?
+        .split_once(OWNER_SEP)
+        .map(|(owner, _)| owner)
+}
+
+/// Temp names older launchers used in `temp/` before the `{owner}__` convention.
+fn is_legacy_name(file_name: &str) -> bool {
+    file_name == "default_config.zip"
+        || (file_name.starts_with("bepinexpack_") && file_name.ends_with(".zip"))
+        || (file_name.starts_with("GE-Proton") && file_name.ends_with(".tar.gz"))
+}
+
+/// Whether `path` was last modified at least `ORPHAN_MIN_AGE` ago.
+fn is_stale(path: &Path) -> bool {
+    std::fs::symlink_metadata(path)
+        .and_then(|md| md.modified())
+        .ok()
+        .and_then(|at| at.elapsed().ok())
+        .is_some_and(|age| age >= ORPHAN_MIN_AGE)
+}
+
+fn remove_entry(path: &Path) -> bool {
+    let res = if path.is_dir() {
+        std::fs::remove_dir_all(path)
+    } else {
+        std::fs::remove_file(path)
+    };
+    match res {
+        Ok(()) => true,
+        Err(e) => {
+            log::warn!("Failed to remove orphaned temp {}: {e}", path.display());
+            false
+        }
+    }
+}
+
+/// Entries of the app `temp/` dir owned by tasks of `version` (task ids embed `-v{version}-`).
+pub fn entries_for_version(app: &tauri::AppHandle, version: u32) -> Result<Vec<PathBuf>, String> {
+    let marker = format!("-v{version}-");
+    let Ok(entries) = std::fs::read_dir(storage::temp_dir(app)?) else {
+        return Ok(vec![]);
+    };
+    Ok(entries
+        .flatten()
+        .filter(|e| owner_of(&e.file_name().to_string_lossy()).is_some_and(|o| o.contains(&marker)))
+        .map(|e| e.path())
+        .collect())
+}
+
+/// Delete the temp downloads of task `task_id`. Returns whether none are left.
+pub fn remove_task_entries(app: &tauri::AppHandle, task_id: &str) -> bool {
+    let Ok(dir) = storage::temp_dir(app) else {
+        return true;
+    };
+    let Ok(entries) = std::fs::read_dir(&dir) else {
+        return true;
+    };
+    let mut all_removed = true;
+    for entry in entries.flatten() {
+        if owner_of(&entry.file_name().to_string_lossy()) == Some(task_id) {
+            all_removed &= remove_entry(&entry.path());
+        }
+    }
+    all_removed
+}
+
+/// Delete stale `*.hq-tmp` leftovers of `zip_utils::write_atomic` under `dir` (recursive).
+fn sweep_atomic_leftovers(dir: &Path) -> u32 {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return 0;
+    };
+    let mut removed = 0;
+    for entry in entries.flatten() {
+        let path = entry.path();
+        // Don't follow the per-version `BepInEx/config` links.
+        let is_dir = entry.file_type().map(|t| t.is_dir()).unwrap_or(false);
+        if is_dir {
+            removed += sweep_atomic_leftovers(&path);
+        } else if entry.file_name().to_string_lossy().ends_with(ATOMIC_SUFFIX)
+            && is_stale(&path)
+            && remove_entry(&path)
+        {
+            removed += 1;
+        }
+    }
+    removed
+}
+
+/// Delete stale launcher temp files not owned by a running task. Returns the number of
+/// removed entries.
+///
+/// Only the launcher's own names are touched: `{owner}__*.part` and the older names in the
+/// app `temp/` dir, per-version `.hq-launcher/tmp` mod workspaces, and `*.hq-tmp`
+/// half-written atomic writes in the state and shared config dirs. Anything else stays.
+pub fn sweep_orphans(app: &tauri::AppHandle) -> Result<u32, String> {
+    let mut removed: u32 = 0;
+
+    let temp = storage::temp_dir(app)?;
+    if let Ok(entries) = std::fs::read_dir(&temp) {
+        for entry in entries.flatten() {
+            let name = entry.file_name().to_string_lossy().to_string();
+            let ours = match owner_of(&name) {
+                Some(owner) => !tasks::is_active(app, owner),
+                None => is_legacy_name(&name),
+            };
+            if ours && is_stale(&entry.path()) && remove_entry(&entry.path()) {
+                removed += 1;
+            }
+        }
+    }
+
+    for (version, root) in installer::installed_version_dirs(app)? {
+        let workspace = root.join(".hq-launcher").join("tmp");
+        if workspace.exists()
+            && tasks::task_id_for(app, version).is_none()
+            && is_stale(&workspace)
+            && remove_entry(&workspace)
+        {
+            removed += 1;
+        }
+    }
+
+    removed += sweep_atomic_leftovers(&storage::state_dir(app)?);
+    removed += sweep_atomic_leftovers(&storage::shared_config_dir(app)?);
+
+    if removed > 0 {
+        log::info!("Removed {removed} orphaned temp file(s)");
+    }
+    Ok(removed)
+}
@@ -0,0 +1,44 @@
+//! Helpers for the unit tests in this crate (`tests/common` has the end-to-end ones).
+
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+static TEMP_SEQ: AtomicUsize = AtomicUsize::new(0);
+
+/// Scratch dir under the OS temp dir, removed on drop.
+pub struct TempDir(PathBuf);
+
+impl TempDir {
+    pub fn new(label: &str) -> Self {
+        let n = TEMP_SEQ.fetch_add(1, Ordering::SeqCst);
+        let dir = std::env::temp_dir().join(format!(
+            "hq-launcher-unit-{label}-{}-{n}",
+            std::process::id()
+        ));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).expect("create temp dir");
+        Self(dir)
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.0
+    }
+}
+
+impl Drop for TempDir {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.0);
+    }
+}
+
+/// Build an in-memory zip from `(path, contents)` pairs.
+pub fn build_zip(files: &[(&str, &str)]) -> Vec<u8> {
+    let mut w = zip::ZipWriter::new(std::io::Cursor::new(Vec::new()));
+    let opts = zip::write::SimpleFileOptions::default();
+    for (path, contents) in files {
+        w.start_file(*path, opts).expect("zip start_file");
+        w.write_all(contents.as_bytes()).expect("zip write");
+    }
+    w.finish().expect("zip finish").into_inner()
+}
@@ -4,6 +4,8 @@ use std::{
 };
 use serde::{Deserialize, Serialize};
 
+use crate::http_cache::{self, Conditional, Validators};
+
 /// Minimal Thunderstore package model used for install resolution.
 ///
 /// Endpoint: `https://thunderstore.io/c/{community}/api/v1/package/`
@@ -14,6 +16,11 @@ pub struct PackageListing {
     #[serde(rename = "full_name")]
     #[allow(dead_code)]
     pub full_name: String,
+    #[serde(default)]
+    pub rating_score: i64,
+    /// `{base}/c/{community}/p/{owner}/{name}/`; tells which community the package is from.
+    #[serde(default)]
+    pub package_url: String,
     pub versions: Vec<PackageVersion>,
 }
 
@@ -21,15 +28,63 @@ pub struct PackageListing {
 pub struct PackageVersion {
     pub version_number: String,
     pub download_url: String,
+    #[serde(default)]
+    pub downloads: u64,
+    /// Zip size in bytes, used to weight mod install progress.
+    #[serde(default)]
+    pub file_size: u64,
+}
+
+/// Popularity data surfaced next to mod metadata (sorting in the mod browser).
+#[derive(Debug, Clone, Serialize)]
+pub struct PackageStats {
+    pub dev: String,
+    pub name: String,
+    /// Sum over all versions.
+    pub downloads: u64,
+    pub rating_score: i64,
+}
+
+impl PackageListing {
+    /// Whether the package is listed under `community`. Listings without a package URL
+    /// (older caches, mirrors) are trusted.
+    pub fn belongs_to(&self, community: &str) -> bool {
+        self.package_url.is_empty() || self.package_url.contains(&format!("/c/{community}/"))
+    }
+
+    pub fn stats(&self) -> PackageStats {
+        PackageStats {
+            dev: self.owner.clone(),
+            name: self.name.clone(),
+            downloads: self.versions.iter().map(|v| v.downloads).sum(),
+            rating_score: self.rating_score,
+        }
+    }
+}
+
+/// Packages from the on-disk cache regardless of its age (no network).
+///
+/// For enriching local listings; `fetch_community_packages` owns the TTL refresh.
+pub fn read_cached_packages(cache_path: &Path) -> Option<Vec<PackageListing>> {
+    let content = std::fs::read_to_string(cache_path).ok()?;
+    serde_json::from_str::<ThunderstoreCache>(&content)
+        .ok()
+        .map(|c| c.packages)
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ThunderstoreCache {
     pub time: u64,
     pub packages: Vec<PackageListing>,
+    /// ETag/Last-Modified of the cached response, for conditional refreshes.
+    #[serde(default)]
+    pub validators: Validators,
+    /// Community the list was fetched for; a cache of another community is ignored.
+    #[serde(default)]
+    pub community: Option<String>,
 }
 
-/// Fetch all packages for a lethal company.
+/// Fetch all packages of the managed game's Thunderstore community.
 ///
 /// Note: Thunderstore's per-package endpoint may not be available (404),
 /// but the list endpoint returns full version/download_url data.
@@ -38,37 +93,74 @@ pub async fn fetch_community_packages(
     cache_path: &Path,
 ) -> Result<Vec<PackageListing>, String> {
     log::info!(target: "fetch_packages", "Cache path: {cache_path:?}");
+    let community = crate::game::current().thunderstore_community;
     let now = SystemTime::now()
         .duration_since(UNIX_EPOCH)
         .unwrap()
         .as_secs();
+    let mut stale: Option<ThunderstoreCache> = None;
     if cache_path.exists() {
         let content = std::fs::read_to_string(cache_path).map_err(|e| e.to_string())?;
         let cache: ThunderstoreCache = serde_json::from_str(&content).map_err(|e| e.to_string())?;
-        if now - cache.time < 60 * 60 {
+        if cache.community.as_deref().is_some_and(|c| c != community) {
+            log::info!(target: "fetch_packages", "Cache is for another community, refetching");
+        } else if now - cache.time < 60 * 60 {
             log::info!(target: "fetch_packages", "Using cached packages");
             return Ok(cache.packages);
+        } else {
+            log::info!(target: "fetch_packages", "Cache expired, fetching new packages");
+            stale = Some(cache);
         }
-        log::info!(target: "fetch_packages", "Cache expired, fetching new packages");
     }
 
-    let url = "https://thunderstore.io/c/lethal-company/api/v1/package/".to_string();
+    let url = crate::endpoints::thunderstore_package_list_url(community);
     log::info!(target: "fetch_packages", "Thunderstore GET {url}");
-    let packages: Vec<PackageListing> = client
-        .get(url)
-        .send()
-        .await
-        .map_err(|e| e.to_string())?
-        .error_for_status()
-        .map_err(|e| e.to_string())?
-        .json::<Vec<PackageListing>>()
-        .await
-        .map_err(|e| e.to_string())?;
-
-    let cache = ThunderstoreCache {
-        packages: packages.clone(),
-        time: now,
+    let validators = stale
+        .as_ref()
+        .map(|c| c.validators.clone())
+        .unwrap_or_default();
+    let response = match http_cache::conditional_get(client, &url, &validators).await {
+        Ok(response) => response,
+        // Offline (e.g. a LAN install): an old list beats failing the whole install.
+        Err(e) => match stale {
+            Some(stale) => {
+                log::warn!(
+                    target: "fetch_packages",
+                    "Package list fetch failed ({e}); using the expired cache"
+                );
+                return Ok(stale.packages);
+            }
+            None => return Err(e.into()),
+        },
+    };
+    let cache = match (response, stale) {
+        // Unchanged upstream: keep the list, just restart the TTL.
+        (Conditional::NotModified, Some(stale)) => {
+            log::info!(target: "fetch_packages", "Package list not modified");
+            ThunderstoreCache { time: now, ..stale }
+        }
+        (Conditional::NotModified, None) => return Err(format!("unexpected 304 from {url}")),
+        (Conditional::Fresh { body, validators }, _) => {
+            let mut packages: Vec<PackageListing> =
+                serde_json::from_str(&body).map_err(|e| e.to_string())?;
+            let before = packages.len();
+            packages.retain(|p| p.belongs_to(community));
+            if packages.len() != before {
+                log::warn!(
+                    target: "fetch_packages",
+                    "Dropped {} package(s) not listed under {community}",
+                    before - packages.len()
+                );
+            }
+            ThunderstoreCache {
+                packages,
+                time: now,
+                validators,
+                community: Some(community.to_string()),
+            }
+        }
     };
+    let packages = cache.packages.clone();
 
     // Best-effort persist; failure shouldn't crash installs/updates.
     if let Some(parent) = cache_path.parent() {
@@ -97,3 +189,77 @@ pub async fn fetch_community_packages(
 
     Ok(packages)
 }
+
+#[derive(Debug, Clone, Deserialize)]
+struct ReadmeResponse {
+    markdown: String,
+}
+
+/// Raw README markdown for the mod details panel.
+#[derive(Debug, Clone, Serialize)]
+pub struct PackageReadme {
+    pub markdown: String,
+    pub from_cache: bool,
+}
+
+fn is_safe_package_part(s: &str) -> bool {
+    !s.is_empty()
+        && s
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '.'))
+        && s != "."
+        && s != ".."
+}
+
+/// Fetch the README of one package version, caching it as `{cache_dir}/{dev}-{name}-{version}.md`.
+///
+/// Package versions are immutable on Thunderstore, so a cached copy is served as-is and
+/// also keeps the panel working offline.
+pub async fn fetch_package_readme(
+    client: &reqwest::Client,
+    cache_dir: &Path,
+    dev: &str,
+    name: &str,
+    version: &str,
+) -> Result<PackageReadme, String> {
+    if ![dev, name, version].iter().all(|p| is_safe_package_part(p)) {
+        return Err("invalid package id".to_string());
+    }
+
+    let cache_path = cache_dir.join(format!("{dev}-{name}-{version}.md"));
+    if let Ok(markdown) = std::fs::read_to_string(&cache_path) {
+        return Ok(PackageReadme {
+            markdown,
+            from_cache: true,
+        });
+    }
+
+    let url = crate::endpoints::thunderstore_readme_url(dev, name, version);
+    log::info!(target: "fetch_packages", "Thunderstore GET {url}");
+    let readme = client
+        .get(&url)
+        .send()
+        .await
+        .map_err(|e| e.to_string())?
+        .error_for_status()
+        .map_err(|e| e.to_string())?
+        .json::<ReadmeResponse>()
+        .await
+        .map_err(|e| e.to_string())?;
+
+    // Best-effort persist, same as the package list cache.
+    if let Err(e) = std::fs::create_dir_all(cache_dir)
+        .and_then(|_| std::fs::write(&cache_path, &readme.markdown))
+    {
+        log::warn!(
+            target: "fetch_packages",
+            "Failed to cache readme {}: {e}",
+            cache_path.to_string_lossy()
+        );
+    }
+
+    Ok(PackageReadme {
+        markdown: readme.markdown,
+        from_cache: false,
+    })
+}
@@ -0,0 +1,214 @@
+//! Launching through umu-launcher, the runtime wrapper that runs Proton outside Steam.
+//!
+//! A bare `proton run` skips the Steam Linux Runtime (pressure-vessel) that Steam would
+//! start the game in; `umu-run` sets that runtime up by itself, so players without the
+//! Steam client still get the environment Proton is tested against. A system `umu-run`
+//! on `PATH` is preferred; otherwise `install_umu` unpacks the release zipapp into
+//! `proton_env/umu/`. umu downloads the runtime itself on first launch.
+//!
+//! The launcher's prefix is kept: `WINEPREFIX` points at `{compat_data}/pfx`, which is
+//! where `proton run` puts it.
+
+use std::fs::File;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{fs_utils, game, http_cache, installer, settings, storage, temp_files};
+
+const RELEASES_URL: &str =
+    "https://api.github.com/repos/Open-Wine-Components/umu-launcher/releases";
+const ZIPAPP_SUFFIX: &str = "-zipapp.tar";
+
+#[derive(Debug, Clone, Deserialize)]
+struct GitHubRelease {
+    tag_name: String,
+    #[serde(default)]
+    prerelease: bool,
+    assets: Vec<GitHubAsset>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct GitHubAsset {
+    name: String,
+    browser_download_url: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct UmuStatus {
+    /// `umu-run` that launches would use, system or bundled.
+    pub path: Option<String>,
+    /// Version unpacked by `install_umu`, if any.
+    pub bundled_version: Option<String>,
+    pub steam_installed: bool,
+    /// Whether the next launch goes through umu.
+    pub will_use: bool,
+}
+
+fn umu_root(app: &tauri::AppHandle) -> Result<PathBuf, String> {
+    Ok(storage::proton_env_dir(app)?.join("umu"))
+}
+
+fn bundled_version_path(root: &Path) -> PathBuf {
+    root.join("version.txt")
+}
+
+fn find_bundled(app: &tauri::AppHandle) -> Option<PathBuf> {
+    let path = umu_root(app).ok()?.join("umu").join("umu-run");
+    path.is_file().then_some(path)
+}
+
+/// `umu-run` to use: the system one, else the one from `install_umu`.
+fn find_umu_run(app: &tauri::AppHandle) -> Option<PathBuf> {
+    fs_utils::find_on_path("umu-run").or_else(|| find_bundled(app))
+}
+
+/// `umu-run` to launch with, or `None` to run Proton directly.
+///
+/// The `umu` setting forces it on or off; unset, umu is used when Steam isn't installed.
+pub fn runner(app: &tauri::AppHandle, steam_installed: bool) -> Option<PathBuf> {
+    let wanted = settings::load(app).umu.unwrap_or(!steam_installed);
+    if !wanted {
+        return None;
+    }
+    let found = find_umu_run(app);
+    if found.is_none() {
+        log::warn!("umu-run not found; launching with Proton directly (see install_umu)");
+    }
+    found
+}
+
+/// `umu-run {exe}` with the launcher's Proton build and prefix.
+pub fn command(umu_run: &Path, proton_binary: &Path, compat_data: &Path, exe: &Path) -> Command {
+    let mut cmd = Command::new(umu_run);
+    cmd.arg(exe);
+    cmd.env("WINEPREFIX", compat_data.join("pfx"));
+    if let Some(proton_dir) = proton_binary.parent() {
+        cmd.env("PROTONPATH", proton_dir);
+    }
+    // Picks the game's protonfixes, as a Steam launch would.
+    cmd.env("GAMEID", format!("umu-{}", game::current().steam_app_id));
+    cmd.env("STORE", "none");
+    cmd.env_remove("PYTHONPATH");
+    cmd.env_remove("PYTHONHOME");
+    cmd
+}
+
+fn unpack_zipapp(tar_path: &Path, root: &Path) -> Result<(), String> {
+    let ts = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_millis())
+        .unwrap_or(0);
+    let parent = root.parent().ok_or_else(|| "invalid umu dir".to_string())?;
+    let extract_tmp = parent.join(format!(".tmp_extract_umu_{ts}"));
+    std::fs::create_dir_all(&extract_tmp).map_err(|e| e.to_string())?;
+
+    let res = (|| -> Result<(), String> {
+        let f = File::open(tar_path).map_err(|e| e.to_string())?;
+        let mut archive = tar::Archive::new(f);
+        for entry in archive.entries().map_err(|e| e.to_string())? {
+            let mut entry = entry.map_err(|e| e.to_string())?;
+            let raw_path = entry.path().map_err(|e| e.to_string())?.to_path_buf();
+            let Some(rel) = installer::sanitize_tar_rel_path(&raw_path) else {
+                log::warn!("Skipped unsafe tar path: {}", raw_path.to_string_lossy());
+                continue;
+            };
+            let out_path = extract_tmp.join(&rel);
+            if let Some(parent) = out_path.parent() {
+                std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+            }
+            entry.unpack(&out_path).map_err(|e| e.to_string())?;
+        }
+
+        let umu_run = extract_tmp.join("umu").join("umu-run");
+        if !umu_run.is_file() {
+            return Err("umu archive did not contain umu/umu-run".to_string());
+        }
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            std::fs::set_permissions(&umu_run, std::fs::Permissions::from_mode(0o755))
+                .map_err(|e| e.to_string())?;
+        }
+        if root.exists() {
+            std::fs::remove_dir_all(root).map_err(|e| e.to_string())?;
+        }
+        std::fs::rename(&extract_tmp, root).map_err(|e| e.to_string())
+    })();
+
+    if extract_tmp.exists() {
+        let _ = std::fs::remove_dir_all(&extract_tmp);
+    }
+    res
+}
+
+/// Download the latest umu-launcher zipapp into `proton_env/umu`. Returns the version.
+#[tauri::command]
+pub async fn install_umu(app: tauri::AppHandle) -> Result<String, String> {
+    let client = reqwest::Client::new();
+    let releases: Vec<GitHubRelease> = http_cache::get_json(&client, RELEASES_URL)
+        .await
+        .map_err(|e| format!("Failed to fetch umu-launcher releases: {e}"))?;
+    let (release, asset) = releases
+        .iter()
+        .filter(|r| !r.prerelease)
+        .find_map(|r| {
+            r.assets
+                .iter()
+                .find(|a| a.name.ends_with(ZIPAPP_SUFFIX))
+                .map(|a| (r, a))
+        })
+        .ok_or_else(|| "no umu-launcher release with a zipapp found".to_string())?;
+    let version = release.tag_name.trim_start_matches('v').to_string();
+
+    let root = umu_root(&app)?;
+    let installed = std::fs::read_to_string(bundled_version_path(&root)).ok();
+    if installed.as_deref().map(str::trim) == Some(version.as_str()) && find_bundled(&app).is_some()
+    {
+        log::info!("umu-launcher {version} already installed");
+        return Ok(version);
+    }
+
+    let tar_path = temp_files::temp_path(&app, temp_files::LAUNCHER_OWNER, &asset.name)?;
+    log::info!(
+        "Downloading umu-launcher {version} from {}",
+        asset.browser_download_url
+    );
+    crate::mods::download_with_progress(&client, &asset.browser_download_url, &tar_path, |_| {})
+        .await
+        .inspect_err(|_| {
+            let _ = std::fs::remove_file(&tar_path);
+        })?;
+
+    if let Some(parent) = root.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    let (tar, dest) = (tar_path.clone(), root.clone());
+    let res = tauri::async_runtime::spawn_blocking(move || unpack_zipapp(&tar, &dest))
+        .await
+        .map_err(|e| e.to_string())
+        .and_then(|r| r);
+    let _ = std::fs::remove_file(&tar_path);
+    res?;
+    std::fs::write(bundled_version_path(&root), &version).map_err(|e| e.to_string())?;
+
+    log::info!("umu-launcher {version} installed");
+    Ok(version)
+}
+
+#[tauri::command]
+pub fn get_umu_status(app: tauri::AppHandle) -> Result<UmuStatus, String> {
+    let steam_installed = crate::find_steam_client_path().is_some();
+    let path = find_umu_run(&app);
+    let bundled_version = std::fs::read_to_string(bundled_version_path(&umu_root(&app)?))
+        .ok()
+        .map(|v| v.trim().to_string());
+    let will_use = path.is_some() && settings::load(&app).umu.unwrap_or(!steam_installed);
+    Ok(UmuStatus {
+        path: path.map(|p| p.to_string_lossy().to_string()),
+        bundled_version,
+        steam_installed,
+        will_use,
+    })
+}
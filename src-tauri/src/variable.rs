@@ -36,6 +36,8 @@ pub fn get_practice_mod_list() -> Vec<ModEntry> {
                     (70, "1.1.1".to_string()),
                 ]
             ),
+            tags: vec![],
+            sha256: BTreeMap::new(),
         },
         ModEntry {
             dev: "Lordfirespeed".to_string(),
@@ -44,6 +46,8 @@ pub fn get_practice_mod_list() -> Vec<ModEntry> {
             low_cap: Some(56),
             high_cap: None,
             version_config: BTreeMap::new(),
+            tags: vec![],
+            sha256: BTreeMap::new(),
         },
         ModEntry {
             dev: "xilophor".to_string(),
@@ -59,6 +63,8 @@ pub fn get_practice_mod_list() -> Vec<ModEntry> {
                     (66, "3.3.1".to_string()),
                 ]
             ),
+            tags: vec![],
+            sha256: BTreeMap::new(),
         },
         ModEntry {
             dev: "megumin".to_string(),
@@ -67,6 +73,8 @@ pub fn get_practice_mod_list() -> Vec<ModEntry> {
             low_cap: Some(45),
             high_cap: None,
             version_config: BTreeMap::new(),
+            tags: vec![],
+            sha256: BTreeMap::new(),
         },
         ModEntry {
             dev: "aoirint".to_string(),
@@ -75,6 +83,8 @@ pub fn get_practice_mod_list() -> Vec<ModEntry> {
             low_cap: Some(56),
             high_cap: None,
             version_config: BTreeMap::new(),
+            tags: vec![],
+            sha256: BTreeMap::new(),
         },
         ModEntry {
             dev: "Shinobi".to_string(),
@@ -83,6 +93,8 @@ pub fn get_practice_mod_list() -> Vec<ModEntry> {
             low_cap: None,
             high_cap: Some(44),
             version_config: BTreeMap::new(),
+            tags: vec![],
+            sha256: BTreeMap::new(),
         },
         ModEntry {
             dev: "the_croods".to_string(),
@@ -91,6 +103,8 @@ pub fn get_practice_mod_list() -> Vec<ModEntry> {
             low_cap: None,
             high_cap: Some(49),
             version_config: BTreeMap::new(),
+            tags: vec![],
+            sha256: BTreeMap::new(),
         }
     ]
 }
\ No newline at end of file
@@ -0,0 +1,419 @@
+//! `verify_install`: check an installed version file by file and, optionally, repair only
+//! what's broken instead of reinstalling.
+//!
+//! - mod files are checked against the lockfile (size first, then hash) and each plugin
+//!   folder's `manifest.json` against the launcher manifest;
+//! - game files are only checked for presence here. Comparing them with the depot manifest
+//!   is left to DepotDownloader's `-validate`, which the repair pass runs and which
+//!   re-downloads just the chunks that differ.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+use serde::Serialize;
+
+use crate::history::{self, HistoryKind};
+use crate::mod_config::{self, ModsConfig};
+use crate::progress::{
+    self, IntegrityWarningPayload, TaskErrorPayload, TaskFinishedPayload, TaskProgressPayload,
+    TaskStep,
+};
+use crate::tasks::{self, TaskKind};
+use crate::{downloader, integrity, mods};
+
+/// Steps of `verify_install`; only the first runs without `repair`.
+const VERIFY_STEPS: [TaskStep; 3] = [
+    TaskStep { id: "check_files", name: "Check Files", weight: 3.0 },
+    TaskStep { id: "repair_mods", name: "Repair Mods", weight: 3.0 },
+    TaskStep { id: "repair_game", name: "Repair Game", weight: 4.0 },
+];
+
+/// Files every Lethal Company install has; any of them missing means the depot files
+/// are damaged.
+const GAME_FILES: [&str; 3] = [
+    crate::game::current().executable,
+    "UnityPlayer.dll",
+    "Lethal Company_Data/globalgamemanagers",
+];
+
+/// Emit a progress event every this many checked files.
+const PROGRESS_EVERY: usize = 50;
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct VerifyReport {
+    pub version: u32,
+    /// Lockfile entries checked.
+    pub checked_files: usize,
+    /// Paths relative to the game root.
+    pub missing: Vec<String>,
+    /// Size or hash differs from the lockfile.
+    pub corrupt: Vec<String>,
+    /// Entries of `GAME_FILES` that are gone.
+    pub missing_game_files: Vec<String>,
+    /// Plugin folders with missing/corrupt files or a `manifest.json` that doesn't
+    /// match the launcher manifest.
+    pub broken_mods: Vec<String>,
+    /// Broken plugin folders the launcher manifest doesn't list, so repair can't
+    /// re-fetch them.
+    pub unrepairable: Vec<String>,
+    /// Whether a repair pass ran. The lists above describe the state before it.
+    pub repaired: bool,
+}
+
+impl VerifyReport {
+    fn is_clean(&self) -> bool {
+        self.missing.is_empty()
+            && self.corrupt.is_empty()
+            && self.missing_game_files.is_empty()
+            && self.broken_mods.is_empty()
+    }
+}
+
+fn emit_verify_progress(
+    app: &tauri::AppHandle,
+    version: u32,
+    steps: &[TaskStep],
+    step: u32,
+    step_progress: f64,
+    detail: String,
+) {
+    let weights: Vec<f64> = steps.iter().map(|s| s.weight).collect();
+    progress::emit_progress(
+        app,
+        TaskProgressPayload {
+            version,
+            steps_total: steps.len() as u32,
+            step,
+            step_name: steps[step as usize - 1].name.to_string(),
+            step_progress,
+            overall_percent: progress::weighted_overall(&weights, step, step_progress),
+            detail: Some(detail),
+            downloaded_bytes: None,
+            total_bytes: None,
+            extracted_files: None,
+            total_files: None,
+        },
+    );
+}
+
+/// Launcher manifest mods keyed by lowercase plugin folder name.
+fn manifest_mods(
+    remote: Option<&mod_config::RemoteManifest>,
+) -> BTreeMap<String, &mod_config::ModEntry> {
+    remote
+        .map(|m| {
+            m.mods
+                .iter()
+                .map(|spec| (format!("{}-{}", spec.dev, spec.name).to_lowercase(), spec))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Walk the lockfile and the plugin manifests of `game_root`. Blocking; stops with
+/// `"Cancelled"` once `cancel` is set.
+fn check(
+    app: &tauri::AppHandle,
+    version: u32,
+    steps: &[TaskStep],
+    game_root: &Path,
+    remote: Option<&mod_config::RemoteManifest>,
+    cancel: &AtomicBool,
+) -> Result<VerifyReport, String> {
+    let mut report = VerifyReport {
+        version,
+        ..Default::default()
+    };
+    report.missing_game_files = GAME_FILES
+        .iter()
+        .filter(|f| !game_root.join(f).is_file())
+        .map(|f| f.to_string())
+        .collect();
+
+    // Mods moved to `plugins_disabled` on purpose keep their lockfile entries.
+    let disabled = mods::plugins_disabled_dir(game_root);
+    let mut lock = integrity::read_lockfile(game_root);
+    lock.files
+        .retain(|key, _| !integrity::plugin_folder(key).is_some_and(|f| disabled.join(f).is_dir()));
+    let total = lock.files.len().max(1) as f64;
+    let mut broken: BTreeSet<String> = BTreeSet::new();
+    for (i, (key, expected)) in lock.files.iter().enumerate() {
+        if i % PROGRESS_EVERY == 0 {
+            if cancel.load(Ordering::Relaxed) {
+                return Err("Cancelled".to_string());
+            }
+            emit_verify_progress(
+                app,
+                version,
+                steps,
+                1,
+                i as f64 / total,
+                format!("Checking {key}"),
+            );
+        }
+        let path = game_root.join(key);
+        let intact = match std::fs::metadata(&path) {
+            Err(_) => {
+                report.missing.push(key.clone());
+                false
+            }
+            Ok(md) if md.len() != expected.size => {
+                report.corrupt.push(key.clone());
+                false
+            }
+            Ok(_) if integrity::hash_file(&path).ok().as_ref() != Some(expected) => {
+                report.corrupt.push(key.clone());
+                false
+            }
+            Ok(_) => true,
+        };
+        if !intact {
+            if let Some(folder) = integrity::plugin_folder(key) {
+                broken.insert(folder.to_string());
+            }
+        }
+    }
+    report.checked_files = lock.files.len();
+
+    // Every launcher-installed plugin folder must still carry its own package manifest.
+    let plugins = mods::plugins_dir(game_root);
+    let by_folder = manifest_mods(remote);
+    let folders: BTreeSet<&str> = lock
+        .files
+        .keys()
+        .filter_map(|k| integrity::plugin_folder(k))
+        .collect();
+    for folder in folders {
+        let manifest = mods::read_manifest_allow_old(&plugins.join(folder));
+        let matches = match (&manifest, by_folder.get(&folder.to_lowercase())) {
+            (Err(_), _) => false,
+            (Ok(m), Some(spec)) => m.name.eq_ignore_ascii_case(&spec.name),
+            (Ok(_), None) => true,
+        };
+        if !matches {
+            log::warn!("Plugin folder {folder} has a missing or foreign manifest.json");
+            broken.insert(folder.to_string());
+        }
+    }
+    report.unrepairable = broken
+        .iter()
+        .filter(|f| !by_folder.contains_key(&f.to_lowercase()))
+        .cloned()
+        .collect();
+    report.broken_mods = broken.into_iter().collect();
+
+    emit_verify_progress(
+        app,
+        version,
+        steps,
+        1,
+        1.0,
+        format!(
+            "{} missing, {} corrupt, {} broken mod(s)",
+            report.missing.len(),
+            report.corrupt.len(),
+            report.broken_mods.len()
+        ),
+    );
+    Ok(report)
+}
+
+/// Delete the broken plugin folders and install them again (from the package cache when
+/// the zip is still there).
+async fn repair_mods(
+    app: &tauri::AppHandle,
+    version: u32,
+    steps: &[TaskStep],
+    game_root: &Path,
+    remote: &mod_config::RemoteManifest,
+    report: &VerifyReport,
+    cancel: &AtomicBool,
+) -> Result<(), String> {
+    let by_folder = manifest_mods(Some(remote));
+    let specs: Vec<mod_config::ModEntry> = report
+        .broken_mods
+        .iter()
+        .filter_map(|f| by_folder.get(&f.to_lowercase()).map(|spec| (*spec).clone()))
+        .collect();
+    if specs.is_empty() {
+        emit_verify_progress(app, version, steps, 2, 1.0, "No mods to repair".to_string());
+        return Ok(());
+    }
+
+    let plugins = mods::plugins_dir(game_root);
+    let mut folders: Vec<PathBuf> = Vec::new();
+    for spec in &specs {
+        let folder = format!("{}-{}", spec.dev, spec.name);
+        let dir = plugins.join(&folder);
+        if dir.exists() {
+            std::fs::remove_dir_all(&dir).map_err(|e| format!("{}: {e}", dir.display()))?;
+        }
+        folders.push(Path::new("BepInEx").join("plugins").join(folder));
+    }
+    log::info!("Reinstalling {} broken mod(s) for v{version}", specs.len());
+
+    let cfg = ModsConfig { mods: specs };
+    mods::install_mods_with_progress(app, game_root, version, &cfg, Some(cancel), |p| {
+        let detail = p.detail.clone().unwrap_or_default();
+        emit_verify_progress(app, version, steps, 2, p.fraction(), detail);
+    })
+    .await?;
+    integrity::record(game_root, &folders)?;
+    Ok(())
+}
+
+/// Let DepotDownloader check every game file against the depot manifest and fetch only
+/// the chunks that differ.
+async fn repair_game(
+    app: &tauri::AppHandle,
+    version: u32,
+    steps: &[TaskStep],
+    game_root: &Path,
+    remote: &mod_config::RemoteManifest,
+    cancel: Arc<AtomicBool>,
+) -> Result<(), String> {
+    let manifest_id = remote
+        .manifest_ids()
+        .get(&version)
+        .cloned()
+        .ok_or_else(|| {
+            format!("No depot manifest id for game version {version} in remote manifest.")
+        })?;
+    let downloader = downloader::select_game_downloader(app).await?;
+    downloader
+        .download_game(
+            Some(manifest_id),
+            game_root.to_path_buf(),
+            true,
+            Some(downloader::DownloadTaskContext {
+                version,
+                steps_total: steps.len() as u32,
+                step: 3,
+                step_name: steps[2].name.to_string(),
+            }),
+            Some(cancel),
+        )
+        .await
+}
+
+/// Check `version` (default: active version) for missing or corrupt files.
+///
+/// Problems are reported through `integrity-warning` and the returned report. With
+/// `repair`, broken mods are reinstalled and the game files validated against the depot
+/// manifest; nothing else in the folder is touched.
+#[tauri::command]
+pub async fn verify_install(
+    app: tauri::AppHandle,
+    version: Option<u32>,
+    repair: Option<bool>,
+) -> Result<VerifyReport, String> {
+    let version = crate::resolve_version(&app, version)?;
+    let repair = repair.unwrap_or(false);
+    let game_root = crate::storage::installed_version_dir(&app, version)?;
+    if tasks::task_id_for(&app, version).is_some() {
+        return Err(format!(
+            "a task is running for v{version}; wait for it to finish"
+        ));
+    }
+    if repair && crate::is_game_running(&app) {
+        return Err("stop the game before repairing".to_string());
+    }
+
+    let remote = match mod_config::fetch_remote_manifest(&reqwest::Client::new()).await {
+        Ok(remote) => Some(remote),
+        Err(e) if !repair => {
+            log::warn!("Verifying v{version} without a fresh manifest: {e}");
+            mod_config::cached_remote_manifest()
+        }
+        Err(e) => return Err(e),
+    };
+    let steps: &'static [TaskStep] = if repair {
+        &VERIFY_STEPS
+    } else {
+        &VERIFY_STEPS[..1]
+    };
+
+    let task_id = tasks::begin(&app, version, TaskKind::Verify);
+    let cancel = tasks::cancel_flag(&app, version);
+    progress::emit_plan(&app, version, steps);
+    let res: Result<VerifyReport, String> = async {
+        let (app2, root, remote2) = (app.clone(), game_root.clone(), remote.clone());
+        let cancel2 = cancel.clone();
+        let mut report = tauri::async_runtime::spawn_blocking(move || {
+            check(&app2, version, steps, &root, remote2.as_ref(), &cancel2)
+        })
+        .await
+        .map_err(|e| e.to_string())??;
+        log::info!(
+            "Verified v{version}: {} file(s), {} missing, {} corrupt, {} game file(s) missing",
+            report.checked_files,
+            report.missing.len(),
+            report.corrupt.len(),
+            report.missing_game_files.len()
+        );
+        if !report.is_clean() {
+            progress::emit_integrity_warning(
+                &app,
+                IntegrityWarningPayload {
+                    version,
+                    path: game_root.to_string_lossy().to_string(),
+                    missing: report
+                        .missing
+                        .iter()
+                        .chain(&report.missing_game_files)
+                        .cloned()
+                        .collect(),
+                    mismatched: report.corrupt.clone(),
+                },
+            );
+        }
+
+        if let (true, Some(remote)) = (repair, remote.as_ref()) {
+            repair_mods(&app, version, steps, &game_root, remote, &report, &cancel).await?;
+            repair_game(&app, version, steps, &game_root, remote, cancel.clone()).await?;
+            report.repaired = true;
+            history::record(
+                &app,
+                version,
+                HistoryKind::Repair,
+                Some(remote.version),
+                if report.broken_mods.is_empty() {
+                    "Repaired game files".to_string()
+                } else {
+                    format!("Repaired game files and {}", report.broken_mods.join(", "))
+                },
+                vec![],
+            );
+        }
+        Ok(report)
+    }
+    .await;
+    tasks::end(&app, version, &task_id);
+
+    match res {
+        Ok(report) => {
+            progress::emit_finished(
+                &app,
+                TaskFinishedPayload {
+                    version,
+                    path: game_root.to_string_lossy().to_string(),
+                    summary: None,
+                },
+            );
+            Ok(report)
+        }
+        Err(e) => {
+            progress::emit_error(
+                &app,
+                TaskErrorPayload {
+                    version,
+                    message: e.clone(),
+                    ..Default::default()
+                },
+            );
+            Err(e)
+        }
+    }
+}
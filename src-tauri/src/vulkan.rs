@@ -0,0 +1,135 @@
+//! Prelaunch Vulkan check for Proton launches.
+//!
+//! DXVK needs a hardware Vulkan device; without one the game exits before showing a
+//! window. `vulkaninfo --summary` is used when present; when it isn't installed the
+//! check is skipped rather than blocking the launch.
+
+use std::process::Command;
+
+use serde::Serialize;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct VulkanDevice {
+    pub name: String,
+    /// `discrete_gpu`, `integrated_gpu`, `virtual_gpu`, `cpu` or `other`.
+    pub device_type: String,
+    pub driver: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct VulkanCheck {
+    /// `false` when `vulkaninfo` is unavailable and nothing could be checked.
+    pub checked: bool,
+    pub usable: bool,
+    pub devices: Vec<VulkanDevice>,
+    /// What to do about it, when not usable.
+    pub guidance: Option<String>,
+}
+
+const NO_DRIVER_GUIDANCE: &str = "No Vulkan driver was found. Install the Vulkan driver for \
+    your GPU (Mesa: vulkan-radeon / vulkan-intel, NVIDIA: the proprietary driver), including \
+    the 32-bit (lib32) package, then restart the launcher.";
+
+const CPU_ONLY_GUIDANCE: &str = "Only a software Vulkan device (llvmpipe) is available, so \
+    the game can't start. Install the Vulkan driver for your GPU (Mesa: vulkan-radeon / \
+    vulkan-intel, NVIDIA: the proprietary driver) and make sure it is not blocked by \
+    VK_ICD_FILENAMES.";
+
+fn parse_device_type(raw: &str) -> String {
+    match raw.trim().trim_start_matches("PHYSICAL_DEVICE_TYPE_") {
+        "DISCRETE_GPU" => "discrete_gpu",
+        "INTEGRATED_GPU" => "integrated_gpu",
+        "VIRTUAL_GPU" => "virtual_gpu",
+        "CPU" => "cpu",
+        _ => "other",
+    }
+    .to_string()
+}
+
+/// Devices from the `Devices:` section of `vulkaninfo --summary`.
+fn parse_summary(text: &str) -> Vec<VulkanDevice> {
+    let mut devices: Vec<VulkanDevice> = vec![];
+    for line in text.lines() {
+        let Some((key, value)) = line.split_once('=') else {
+            continue;
+        };
+        let value = value.trim().to_string();
+        match key.trim() {
+            "deviceType" => devices.push(VulkanDevice {
+                name: String::new(),
+                device_type: parse_device_type(&value),
+                driver: None,
+            }),
+            "deviceName" => {
+                if let Some(d) = devices.last_mut() {
+                    d.name = value;
+                }
+            }
+            "driverName" => {
+                if let Some(d) = devices.last_mut() {
+                    d.driver = Some(value);
+                }
+            }
+            _ => {}
+        }
+    }
+    devices
+}
+
+fn run_check() -> VulkanCheck {
+    let output = match Command::new("vulkaninfo").arg("--summary").output() {
+        Ok(output) => output,
+        Err(e) => {
+            log::info!("Skipping Vulkan check: vulkaninfo unavailable ({e})");
+            return VulkanCheck {
+                checked: false,
+                usable: true,
+                devices: vec![],
+                guidance: None,
+            };
+        }
+    };
+    let devices = parse_summary(&String::from_utf8_lossy(&output.stdout));
+    let usable = devices.iter().any(|d| d.device_type != "cpu");
+    let guidance = if usable {
+        None
+    } else if devices.is_empty() || !output.status.success() {
+        Some(NO_DRIVER_GUIDANCE.to_string())
+    } else {
+        Some(CPU_ONLY_GUIDANCE.to_string())
+    };
+    VulkanCheck {
+        checked: true,
+        usable,
+        devices,
+        guidance,
+    }
+}
+
+#[tauri::command]
+pub async fn check_vulkan() -> Result<VulkanCheck, String> {
+    tauri::async_runtime::spawn_blocking(run_check)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Fail the launch with driver guidance when no hardware Vulkan device exists.
+///
+/// Skipped when disabled in settings and for wined3d launches (`PROTON_USE_WINED3D`),
+/// which don't use Vulkan.
+#[cfg(target_os = "linux")]
+pub fn ensure_usable(app: &tauri::AppHandle) -> Result<(), String> {
+    if !crate::settings::load(app).vulkan_check
+        || std::env::var_os("PROTON_USE_WINED3D").is_some_and(|v| v != "0")
+    {
+        return Ok(());
+    }
+    let check = run_check();
+    if check.usable {
+        return Ok(());
+    }
+    log::error!("Vulkan check failed: {:?}", check.devices);
+    Err(check
+        .guidance
+        .unwrap_or_else(|| NO_DRIVER_GUIDANCE.to_string()))
+}
@@ -1,8 +1,102 @@
+use std::collections::{BTreeMap, BTreeSet, HashMap};
 use std::fs::File;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
 
 use std::path::{Path, PathBuf};
 use zip::ZipArchive;
 
+/// Threads writing config files at once; small files are bound by per-file overhead, not
+/// bandwidth, so a few in flight hide most of the seek and create latency.
+const MAX_CONFIG_WORKERS: usize = 4;
+/// How often config extraction reports progress.
+const CONFIG_PROGRESS_TICK: Duration = Duration::from_millis(100);
+
+/// File type bits of a unix mode, and the symlink type.
+const S_IFMT: u32 = 0o170000;
+const S_IFLNK: u32 = 0o120000;
+
+/// Relative path an archive entry may be extracted to, or `None` if it must be skipped.
+///
+/// Packages are third-party content, so this is stricter than `enclosed_name()`: any `..`
+/// component is refused (even one that stays inside), as are absolute, UNC and
+/// drive-qualified paths with either separator, NULs, and symlink entries (a link could
+/// point anywhere, and later entries would be written through it).
+pub fn safe_entry_path(name: &str, unix_mode: Option<u32>) -> Option<PathBuf> {
+    if unix_mode.is_some_and(|m| m & S_IFMT == S_IFLNK) {
+        return None;
+    }
+    if name.contains('\0') || name.starts_with(['/', '\\']) {
+        return None;
+    }
+    let mut out = PathBuf::new();
+    for part in name.split(['/', '\\']) {
+        match part {
+            "" | "." => {}
+            ".." => return None,
+            // `C:`: drive-relative on Windows (also alternate data streams).
+            p if p.contains(':') => return None,
+            p => out.push(p),
+        }
+    }
+    (!out.as_os_str().is_empty()).then_some(out)
+}
+
+/// Give an extracted file its executable bits back (no-op on Windows).
+///
+/// `File::create` leaves everything `0644`, so `DepotDownloader`, `run_bepinex.sh` and the
+/// like can't be started. The entry's own unix mode is used when it has any execute bit
+/// (setuid/setgid/sticky are dropped). Zips built on Windows carry no execute bits at all,
+/// so a file that starts with an ELF header or a `#!` line is made `0755` regardless.
+pub fn restore_unix_permissions(path: &Path, unix_mode: Option<u32>) -> Result<(), String> {
+    #[cfg(unix)]
+    {
+        use std::io::Read;
+        use std::os::unix::fs::PermissionsExt;
+
+        let mode = match unix_mode.map(|m| m & 0o777) {
+            Some(m) if m & 0o111 != 0 => m | 0o600,
+            _ => {
+                let mut magic = [0u8; 4];
+                let n = File::open(path)
+                    .and_then(|mut f| f.read(&mut magic))
+                    .unwrap_or(0);
+                if magic[..n].starts_with(b"\x7fELF") || magic[..n].starts_with(b"#!") {
+                    0o755
+                } else {
+                    return Ok(());
+                }
+            }
+        };
+        std::fs::set_permissions(path, std::fs::Permissions::from_mode(mode))
+            .map_err(|e| format!("failed to set permissions on {}: {e}", path.display()))?;
+    }
+    #[cfg(not(unix))]
+    let _ = (path, unix_mode);
+    Ok(())
+}
+
+/// Temp path next to `path` used for write-then-rename, unique per process and call so
+/// concurrent writers of the same file never share one.
+fn temp_sibling(path: &Path) -> PathBuf {
+    static NEXT: AtomicU64 = AtomicU64::new(0);
+    let n = NEXT.fetch_add(1, Ordering::Relaxed);
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(format!(".{}-{n}.hq-tmp", std::process::id()));
+    path.with_file_name(name)
+}
+
+/// Write `contents` to `path` via a temp file + rename, so a concurrent reader (e.g. the
+/// running game loading its configs) never sees a half-written file.
+pub fn write_atomic(path: &Path, contents: impl AsRef<[u8]>) -> Result<(), String> {
+    let tmp = temp_sibling(path);
+    std::fs::write(&tmp, contents).map_err(|e| e.to_string())?;
+    std::fs::rename(&tmp, path).map_err(|e| {
+        let _ = std::fs::remove_file(&tmp);
+        e.to_string()
+    })
+}
+
 fn strip_prefix_components<'a>(
     comps: &'a [std::path::Component<'a>],
     prefix: &[&str],
@@ -18,9 +112,18 @@ fn strip_prefix_components<'a>(
     Some(prefix.len())
 }
 
+/// Whether the zip has a file named `name` at its root (not inside a folder).
+pub fn has_root_file(zip_path: &Path, name: &str) -> Result<bool, String> {
+    let file = File::open(zip_path).map_err(|e| e.to_string())?;
+    let archive = ZipArchive::new(file).map_err(|e| e.to_string())?;
+    Ok(archive
+        .file_names()
+        .any(|n| n.trim_start_matches("./").eq_ignore_ascii_case(name)))
+}
+
 /// Extracts a zip to `dest_dir`, emitting progress as `(done_entries, total_entries, detail)`.
 ///
-/// This uses `enclosed_name()` to prevent Zip Slip (path traversal).
+/// Entries are checked with `safe_entry_path` to prevent Zip Slip (path traversal).
 pub fn extract_zip_with_progress<F>(
     zip_path: &std::path::Path,
     dest_dir: &std::path::Path,
@@ -41,7 +144,7 @@ where
         let entry_name = Some(entry.name().to_string());
 
         // Prevent Zip Slip (path traversal). Skip unsafe paths.
-        let Some(safe_rel) = entry.enclosed_name().map(|p| p.to_owned()) else {
+        let Some(safe_rel) = safe_entry_path(entry.name(), entry.unix_mode()) else {
             extracted = extracted.saturating_add(1);
             on_progress(
                 extracted,
@@ -66,6 +169,8 @@ where
 
         let mut out_file = File::create(&out_path).map_err(|e| e.to_string())?;
         std::io::copy(&mut entry, &mut out_file).map_err(|e| e.to_string())?;
+        drop(out_file);
+        restore_unix_permissions(&out_path, entry.unix_mode())?;
 
         extracted = extracted.saturating_add(1);
         on_progress(extracted, total_files, entry_name);
@@ -74,6 +179,101 @@ where
     Ok(())
 }
 
+/// Path of a config zip entry relative to `BepInEx/config`, with an optional
+/// `config/` or `BepInEx/config/` prefix stripped.
+fn config_rel_path(safe_rel: &Path) -> PathBuf {
+    let comps: Vec<_> = safe_rel.components().collect();
+    let mut start = 0usize;
+    if let Some(s) = strip_prefix_components(&comps, &["BepInEx", "config"]) {
+        start = s;
+    } else if let Some(s) = strip_prefix_components(&comps, &["config"]) {
+        start = s;
+    }
+    comps[start..].iter().collect()
+}
+
+/// Config files of the zip that already exist in `config_dir` with different contents,
+/// as `/`-separated paths relative to `config_dir`. These are the files the add-only
+/// extraction leaves alone.
+pub fn changed_config_files(zip_path: &Path, config_dir: &Path) -> Result<Vec<String>, String> {
+    let file = File::open(zip_path).map_err(|e| e.to_string())?;
+    let mut archive = ZipArchive::new(file).map_err(|e| e.to_string())?;
+    let mut changed = Vec::new();
+    for i in 0..archive.len() {
+        let mut entry = archive.by_index(i).map_err(|e| e.to_string())?;
+        if entry.is_dir() {
+            continue;
+        }
+        let Some(rel_path) =
+            safe_entry_path(entry.name(), entry.unix_mode()).map(|p| config_rel_path(&p))
+        else {
+            continue;
+        };
+        let Ok(existing) = std::fs::read(config_dir.join(&rel_path)) else {
+            continue;
+        };
+        let mut packed = Vec::with_capacity(entry.size() as usize);
+        std::io::copy(&mut entry, &mut packed).map_err(|e| e.to_string())?;
+        if packed != existing {
+            changed.push(rel_path.to_string_lossy().replace('\\', "/"));
+        }
+    }
+    changed.sort();
+    Ok(changed)
+}
+
+/// Every file of a config zip by its `/`-separated path relative to `BepInEx/config`.
+/// Config zips are small; this is for comparing versions of the defaults.
+pub fn config_zip_files(zip_path: &Path) -> Result<BTreeMap<String, Vec<u8>>, String> {
+    let file = File::open(zip_path).map_err(|e| e.to_string())?;
+    let mut archive = ZipArchive::new(file).map_err(|e| e.to_string())?;
+    let mut files = BTreeMap::new();
+    for i in 0..archive.len() {
+        let mut entry = archive.by_index(i).map_err(|e| e.to_string())?;
+        if entry.is_dir() {
+            continue;
+        }
+        let Some(rel_path) =
+            safe_entry_path(entry.name(), entry.unix_mode()).map(|p| config_rel_path(&p))
+        else {
+            continue;
+        };
+        let mut packed = Vec::with_capacity(entry.size() as usize);
+        std::io::copy(&mut entry, &mut packed).map_err(|e| e.to_string())?;
+        files.insert(rel_path.to_string_lossy().replace('\\', "/"), packed);
+    }
+    Ok(files)
+}
+
+/// Overwrite `files` (as returned by `changed_config_files`) in `config_dir` with the
+/// zip's version.
+pub fn overwrite_config_files(
+    zip_path: &Path,
+    config_dir: &Path,
+    files: &[String],
+) -> Result<(), String> {
+    let file = File::open(zip_path).map_err(|e| e.to_string())?;
+    let mut archive = ZipArchive::new(file).map_err(|e| e.to_string())?;
+    for i in 0..archive.len() {
+        let mut entry = archive.by_index(i).map_err(|e| e.to_string())?;
+        if entry.is_dir() {
+            continue;
+        }
+        let Some(rel_path) =
+            safe_entry_path(entry.name(), entry.unix_mode()).map(|p| config_rel_path(&p))
+        else {
+            continue;
+        };
+        if !files.contains(&rel_path.to_string_lossy().replace('\\', "/")) {
+            continue;
+        }
+        let mut packed = Vec::with_capacity(entry.size() as usize);
+        std::io::copy(&mut entry, &mut packed).map_err(|e| e.to_string())?;
+        write_atomic(&config_dir.join(rel_path), packed)?;
+    }
+    Ok(())
+}
+
 /// Extract a config zip into a `BepInEx/config` directory.
 ///
 /// The zip may contain:
@@ -83,6 +283,9 @@ where
 ///
 /// This function strips those prefixes if present to avoid nesting like
 /// `BepInEx/config/BepInEx/config/...`.
+///
+/// Packs hold thousands of tiny files, so directories are created once up front and the
+/// files are written by a few worker threads; progress is reported per tick, not per file.
 pub fn extract_config_zip_into_bepinex_config_with_progress<F>(
     zip_path: &Path,
     config_dir: &Path,
@@ -95,72 +298,112 @@ where
     let mut archive = ZipArchive::new(file).map_err(|e| e.to_string())?;
 
     let total_entries = archive.len() as u64;
-    let mut processed: u64 = 0;
     on_progress(0, total_entries, Some("Starting...".to_string()));
 
-    std::fs::create_dir_all(config_dir).map_err(|e| e.to_string())?;
-
+    // Plan from the central directory alone: which files to write, which dirs they need.
+    let mut dirs = BTreeSet::new();
+    let mut writes: Vec<(usize, PathBuf)> = vec![];
+    // Entries mapping to the same file (`config/a.cfg` and `BepInEx/config/a.cfg`) are
+    // written once, from the last of them, so no two workers race on one path.
+    let mut planned: HashMap<PathBuf, usize> = HashMap::new();
     for i in 0..archive.len() {
-        let mut entry = archive.by_index(i).map_err(|e| e.to_string())?;
-        let entry_name = Some(entry.name().to_string());
-
-        let Some(safe_rel) = entry.enclosed_name().map(|p| p.to_owned()) else {
-            processed = processed.saturating_add(1);
-            on_progress(
-                processed,
-                total_entries,
-                Some("Skipped unsafe path".to_string()),
-            );
+        let entry = archive.by_index_raw(i).map_err(|e| e.to_string())?;
+        let Some(safe_rel) = safe_entry_path(entry.name(), entry.unix_mode()) else {
+            log::warn!("Skipped unsafe config path: {}", entry.name());
             continue;
         };
-
-        // Strip optional prefixes.
-        let comps: Vec<_> = safe_rel.components().collect();
-        let mut start = 0usize;
-        if let Some(s) = strip_prefix_components(&comps, &["BepInEx", "config"]) {
-            start = s;
-        } else if let Some(s) = strip_prefix_components(&comps, &["config"]) {
-            start = s;
-        }
-
-        let rel_path: PathBuf = comps[start..].iter().collect();
+        let rel_path = config_rel_path(&safe_rel);
         if rel_path.as_os_str().is_empty() {
-            processed = processed.saturating_add(1);
-            on_progress(processed, total_entries, entry_name);
             continue;
         }
-
         let out_path = config_dir.join(rel_path);
-
         if entry.is_dir() {
-            std::fs::create_dir_all(&out_path).map_err(|e| e.to_string())?;
-            processed = processed.saturating_add(1);
-            on_progress(processed, total_entries, entry_name);
+            dirs.insert(out_path);
             continue;
         }
-
         // Add-only: do not overwrite existing config files.
         if out_path.exists() {
-            processed = processed.saturating_add(1);
-            on_progress(
-                processed,
-                total_entries,
-                Some("Skipped existing file".to_string()),
-            );
             continue;
         }
-
+        if let Some(&pos) = planned.get(&out_path) {
+            writes[pos].0 = i;
+            continue;
+        }
         if let Some(parent) = out_path.parent() {
-            std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+            dirs.insert(parent.to_path_buf());
         }
+        planned.insert(out_path.clone(), writes.len());
+        writes.push((i, out_path));
+    }
+    drop(archive);
 
-        let mut out_file = File::create(&out_path).map_err(|e| e.to_string())?;
-        std::io::copy(&mut entry, &mut out_file).map_err(|e| e.to_string())?;
-
-        processed = processed.saturating_add(1);
-        on_progress(processed, total_entries, entry_name);
+    // Sorted, so parents come first and each dir is a single `mkdir`.
+    std::fs::create_dir_all(config_dir).map_err(|e| e.to_string())?;
+    for dir in &dirs {
+        if let Err(e) = std::fs::create_dir(dir) {
+            if e.kind() != std::io::ErrorKind::AlreadyExists {
+                std::fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+            }
+        }
     }
 
+    let skipped = total_entries - writes.len() as u64;
+    let written = AtomicU64::new(0);
+    let workers = std::thread::available_parallelism()
+        .map(|n| n.get())
+        .unwrap_or(1)
+        .clamp(1, MAX_CONFIG_WORKERS)
+        .min(writes.len().max(1));
+    let chunk_len = writes.len().div_ceil(workers).max(1);
+
+    std::thread::scope(|scope| -> Result<(), String> {
+        // Each worker keeps one archive handle open for its whole share of the files.
+        let handles: Vec<_> = writes
+            .chunks(chunk_len)
+            .map(|chunk| {
+                let written = &written;
+                scope.spawn(move || -> Result<(), String> {
+                    let file = File::open(zip_path).map_err(|e| e.to_string())?;
+                    let mut archive = ZipArchive::new(file).map_err(|e| e.to_string())?;
+                    for (index, out_path) in chunk {
+                        let mut entry = archive.by_index(*index).map_err(|e| e.to_string())?;
+                        // Config dirs may be in use by a running game: write aside, then
+                        // rename into place.
+                        let tmp_path = temp_sibling(out_path);
+                        let mut out_file =
+                            File::create(&tmp_path).map_err(|e| e.to_string())?;
+                        std::io::copy(&mut entry, &mut out_file).map_err(|e| e.to_string())?;
+                        drop(out_file);
+                        std::fs::rename(&tmp_path, out_path).map_err(|e| {
+                            let _ = std::fs::remove_file(&tmp_path);
+                            e.to_string()
+                        })?;
+                        written.fetch_add(1, Ordering::Relaxed);
+                    }
+                    Ok(())
+                })
+            })
+            .collect();
+
+        // One progress report per tick instead of one per file.
+        let mut reported = u64::MAX;
+        while !handles.iter().all(|h| h.is_finished()) {
+            let done = skipped + written.load(Ordering::Relaxed);
+            if done != reported {
+                on_progress(done, total_entries, None);
+                reported = done;
+            }
+            std::thread::sleep(CONFIG_PROGRESS_TICK);
+        }
+        for handle in handles {
+            handle
+                .join()
+                .map_err(|_| "config extraction worker panicked".to_string())??;
+        }
+        Ok(())
+    })?;
+
+    on_progress(total_entries, total_entries, Some("Done".to_string()));
     Ok(())
 }
 
@@ -171,7 +414,7 @@ where
 ///
 /// - ignores top-level files
 /// - strips the top-level directory
-/// - prevents Zip Slip via `enclosed_name()`
+/// - prevents Zip Slip via `safe_entry_path`
 #[allow(dead_code)]
 pub fn extract_thunderstore_package_with_progress<F>(
     zip_path: &std::path::Path,
@@ -193,7 +436,7 @@ where
         let mut entry = archive.by_index(i).map_err(|e| e.to_string())?;
         let entry_name = Some(entry.name().to_string());
 
-        let Some(safe_rel) = entry.enclosed_name().map(|p| p.to_owned()) else {
+        let Some(safe_rel) = safe_entry_path(entry.name(), entry.unix_mode()) else {
             processed = processed.saturating_add(1);
             on_progress(
                 processed,
@@ -230,6 +473,8 @@ where
 
         let mut out_file = File::create(&out_path).map_err(|e| e.to_string())?;
         std::io::copy(&mut entry, &mut out_file).map_err(|e| e.to_string())?;
+        drop(out_file);
+        restore_unix_permissions(&out_path, entry.unix_mode())?;
 
         processed = processed.saturating_add(1);
         on_progress(processed, total_entries, entry_name);
@@ -238,6 +483,56 @@ where
     Ok(())
 }
 
+/// `config/<rel>` at the package root or `BepInEx/config/<rel>` anywhere -> `<rel>`.
+fn mod_config_rel_path(path: &Path) -> Option<PathBuf> {
+    let comps: Vec<_> = path.components().map(|c| c.as_os_str()).collect();
+    let start = if comps.first().is_some_and(|c| c.eq_ignore_ascii_case("config")) {
+        1
+    } else {
+        comps
+            .windows(2)
+            .position(|w| w[0] == "BepInEx" && w[1].eq_ignore_ascii_case("config"))?
+            + 2
+    };
+    let rel: PathBuf = comps[start..].iter().collect();
+    (!rel.as_os_str().is_empty()).then_some(rel)
+}
+
+/// Copy the default config files a mod package ships (`config/...` or
+/// `BepInEx/config/...`) into `config_dir`, add-only: existing files are never touched.
+/// Returns the added paths, relative to `config_dir`.
+pub fn copy_mod_config_defaults(
+    zip_path: &Path,
+    config_dir: &Path,
+) -> Result<Vec<PathBuf>, String> {
+    let file = File::open(zip_path).map_err(|e| e.to_string())?;
+    let mut archive = ZipArchive::new(file).map_err(|e| e.to_string())?;
+
+    let mut added = vec![];
+    for i in 0..archive.len() {
+        let mut entry = archive.by_index(i).map_err(|e| e.to_string())?;
+        if entry.is_dir() {
+            continue;
+        }
+        let Some(rel) =
+            safe_entry_path(entry.name(), entry.unix_mode()).and_then(|p| mod_config_rel_path(&p))
+        else {
+            continue;
+        };
+        let out_path = config_dir.join(&rel);
+        if out_path.exists() {
+            continue;
+        }
+        if let Some(parent) = out_path.parent() {
+            std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+        }
+        let mut out_file = File::create(&out_path).map_err(|e| e.to_string())?;
+        std::io::copy(&mut entry, &mut out_file).map_err(|e| e.to_string())?;
+        added.push(rel);
+    }
+    Ok(added)
+}
+
 /// Extract a Thunderstore mod zip into a subfolder under `BepInEx/plugins`.
 ///
 /// User-requested behavior:
@@ -245,7 +540,7 @@ where
 /// - extract the zip into that folder (so you get `.../{folder_name}/<zip contents...>`)
 /// - BUT if the zip contains `BepInEx/plugins/**` or `plugins/**` anywhere in its path,
 ///   strip that prefix so the actual plugin payload lands under `{folder_name}/`.
-/// - prevents Zip Slip via `enclosed_name()`
+/// - prevents Zip Slip via `safe_entry_path`
 pub fn extract_thunderstore_into_plugins_with_progress<F>(
     zip_path: &Path,
     plugins_dir: &Path,
@@ -276,7 +571,7 @@ where
         let mut entry = archive.by_index(i).map_err(|e| e.to_string())?;
         let entry_name = Some(entry.name().to_string());
 
-        let Some(safe_rel) = entry.enclosed_name().map(|p| p.to_owned()) else {
+        let Some(safe_rel) = safe_entry_path(entry.name(), entry.unix_mode()) else {
             log::error!("Skipped unsafe path: {}", entry.name());
             processed = processed.saturating_add(1);
             on_progress(
@@ -351,6 +646,8 @@ where
 
         let mut out_file = File::create(&out_path).map_err(|e| e.to_string())?;
         std::io::copy(&mut entry, &mut out_file).map_err(|e| e.to_string())?;
+        drop(out_file);
+        restore_unix_permissions(&out_path, entry.unix_mode())?;
 
         processed = processed.saturating_add(1);
         on_progress(processed, total_entries, entry_name);
@@ -358,3 +655,132 @@ where
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{build_zip, TempDir};
+
+    #[test]
+    fn config_entries_for_the_same_file_are_written_once_last_wins() {
+        let tmp = TempDir::new("config-duplicates");
+        let zip_path = tmp.path().join("default_config.zip");
+        let shared = tmp.path().join("shared");
+        std::fs::write(
+            &zip_path,
+            build_zip(&[
+                ("config/HQoL.cfg", "first"),
+                ("BepInEx/config/HQoL.cfg", "second"),
+                ("Other.cfg", "other"),
+            ]),
+        )
+        .unwrap();
+
+        extract_config_zip_into_bepinex_config_with_progress(&zip_path, &shared, |_, _, _| {})
+            .expect("extract config");
+
+        assert_eq!(std::fs::read_to_string(shared.join("HQoL.cfg")).unwrap(), "second");
+        assert_eq!(std::fs::read_to_string(shared.join("Other.cfg")).unwrap(), "other");
+        let leftovers: Vec<_> = std::fs::read_dir(&shared)
+            .unwrap()
+            .flatten()
+            .filter(|e| e.file_name().to_string_lossy().ends_with(".hq-tmp"))
+            .collect();
+        assert!(leftovers.is_empty());
+    }
+
+    #[test]
+    fn unsafe_entry_paths_are_rejected() {
+        for name in [
+            "../evil.dll",
+            "BepInEx/../../evil.dll",
+            "plugins/../evil.dll",
+            "/etc/passwd",
+            "\\\\server\\share\\evil.dll",
+            "C:\\Windows\\evil.dll",
+            "C:evil.dll",
+            "..\\..\\evil.dll",
+        ] {
+            assert_eq!(safe_entry_path(name, None), None, "{name}");
+        }
+        assert_eq!(safe_entry_path("link", Some(0o120777)), None);
+        assert_eq!(
+            safe_entry_path("BepInEx/./plugins/Mod.dll", Some(0o100644)),
+            Some(Path::new("BepInEx").join("plugins").join("Mod.dll"))
+        );
+    }
+
+    #[test]
+    fn malicious_zip_entries_stay_inside_the_extraction_root() {
+        use std::io::Write;
+
+        let tmp = TempDir::new("zip-slip");
+        let dest = tmp.path().join("dest");
+        let outside = tmp.path().join("outside");
+        std::fs::create_dir_all(&outside).unwrap();
+        let absolute = tmp.path().join("absolute.txt");
+
+        let mut w = zip::ZipWriter::new(std::io::Cursor::new(Vec::new()));
+        let opts = zip::write::SimpleFileOptions::default();
+        for name in ["../escaped.txt", absolute.to_str().unwrap(), "good/file.txt"] {
+            w.start_file(name, opts).unwrap();
+            w.write_all(b"payload").unwrap();
+        }
+        w.add_symlink("escape", outside.to_str().unwrap(), opts).unwrap();
+        w.start_file("escape/pwned.txt", opts).unwrap();
+        w.write_all(b"payload").unwrap();
+        let zip_path = tmp.path().join("evil.zip");
+        std::fs::write(&zip_path, w.finish().unwrap().into_inner()).unwrap();
+
+        extract_zip_with_progress(&zip_path, &dest, |_, _, _| {}).expect("extract");
+        extract_thunderstore_into_plugins_with_progress(
+            &zip_path,
+            &dest.join("plugins"),
+            "Evil-Pack",
+            |_, _, _| {},
+        )
+        .expect("extract package");
+
+        assert!(dest.join("good").join("file.txt").is_file());
+        assert!(!tmp.path().join("escaped.txt").exists());
+        assert!(!absolute.exists());
+        assert!(!outside.join("pwned.txt").exists());
+        let escape = std::fs::symlink_metadata(dest.join("escape")).unwrap();
+        assert!(!escape.file_type().is_symlink());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn extracted_executables_keep_their_execute_bits() {
+        use std::io::Write;
+        use std::os::unix::fs::PermissionsExt;
+
+        let tmp = TempDir::new("exec-bits");
+        let dest = tmp.path().join("dest");
+
+        let mut w = zip::ZipWriter::new(std::io::Cursor::new(Vec::new()));
+        let unix = zip::write::SimpleFileOptions::default();
+        // Built on Windows: no execute bit recorded, so the shebang decides.
+        let dos = unix.unix_permissions(0o644);
+        let entries = [
+            ("DepotDownloader", unix.unix_permissions(0o4755), &b"\x7fELF..."[..]),
+            ("run_bepinex.sh", dos, &b"#!/bin/sh\n"[..]),
+            ("readme.txt", unix.unix_permissions(0o644), &b"hello"[..]),
+        ];
+        for (name, opts, contents) in entries {
+            w.start_file(name, opts).unwrap();
+            w.write_all(contents).unwrap();
+        }
+        let zip_path = tmp.path().join("tools.zip");
+        std::fs::write(&zip_path, w.finish().unwrap().into_inner()).unwrap();
+
+        extract_zip_with_progress(&zip_path, &dest, |_, _, _| {}).expect("extract");
+
+        let mode = |name: &str| {
+            std::fs::metadata(dest.join(name)).unwrap().permissions().mode() & 0o7777
+        };
+        assert_eq!(mode("DepotDownloader"), 0o755);
+        assert_eq!(mode("run_bepinex.sh"), 0o755);
+        assert_eq!(mode("readme.txt") & 0o111, 0);
+    }
+}
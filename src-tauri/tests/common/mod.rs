@@ -0,0 +1,257 @@
+//! Mock remote for integration tests.
+//!
+//! Serves fixture versions of everything the launcher fetches over HTTP:
+//! - `{launcher}/manifest.json` and `{launcher}/default_config.zip`
+//! - `{thunderstore}/c/lethal-company/api/v1/package/`
+//! - `{thunderstore}/package/download/{dev}/{name}/{version}/` (generated package zips;
+//!   `BepInEx` packages are laid out like BepInExPack)
+//!
+//! `MockServer::start` points the launcher's URL bases at the server through the env
+//! overrides in `hq_launcher_lib::endpoints`; it holds a process-wide lock so tests
+//! running in parallel don't observe each other's overrides.
+//!
+//! `run_headless` runs the launcher binary itself (`--headless`) against the server, with
+//! the fake game downloader and its data dirs under a scratch dir.
+
+#![allow(dead_code)]
+
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+use axum::extract::{Path as UrlPath, State};
+use axum::http::header;
+use axum::response::IntoResponse;
+use axum::routing::get;
+use axum::Router;
+use tokio::sync::{Mutex, MutexGuard};
+
+use hq_launcher_lib::endpoints;
+
+static ENV_LOCK: Mutex<()> = Mutex::const_new(());
+static TEMP_SEQ: AtomicUsize = AtomicUsize::new(0);
+
+const MANIFEST_JSON: &str = include_str!("../fixtures/manifest.json");
+const PACKAGES_JSON: &str = include_str!("../fixtures/packages.json");
+
+/// Per-route request counters, for asserting cache behavior.
+#[derive(Default)]
+pub struct Hits {
+    pub manifest: AtomicUsize,
+    pub default_config: AtomicUsize,
+    pub package_list: AtomicUsize,
+    pub package_download: AtomicUsize,
+}
+
+pub struct MockServer {
+    pub base: String,
+    pub hits: Arc<Hits>,
+    _env: MutexGuard<'static, ()>,
+}
+
+impl MockServer {
+    pub async fn start() -> Self {
+        let env = ENV_LOCK.lock().await;
+
+        let hits = Arc::new(Hits::default());
+        let app = Router::new()
+            .route("/hq-launcher/manifest.json", get(manifest))
+            .route("/hq-launcher/default_config.zip", get(default_config))
+            .route("/c/lethal-company/api/v1/package/", get(package_list))
+            .route(
+                "/package/download/{dev}/{name}/{version}/",
+                get(package_download),
+            )
+            .with_state(hits.clone());
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0")
+            .await
+            .expect("bind mock server");
+        let addr = listener.local_addr().expect("mock server addr");
+        tokio::spawn(async move {
+            let _ = axum::serve(listener, app).await;
+        });
+
+        let base = format!("http://{addr}");
+        std::env::set_var(endpoints::LAUNCHER_BASE_ENV, format!("{base}/hq-launcher"));
+        std::env::set_var(endpoints::THUNDERSTORE_BASE_ENV, &base);
+
+        Self {
+            base,
+            hits,
+            _env: env,
+        }
+    }
+}
+
+impl Drop for MockServer {
+    fn drop(&mut self) {
+        std::env::remove_var(endpoints::LAUNCHER_BASE_ENV);
+        std::env::remove_var(endpoints::THUNDERSTORE_BASE_ENV);
+    }
+}
+
+async fn manifest(State(hits): State<Arc<Hits>>) -> impl IntoResponse {
+    hits.manifest.fetch_add(1, Ordering::SeqCst);
+    ([(header::CONTENT_TYPE, "application/json")], MANIFEST_JSON)
+}
+
+async fn package_list(State(hits): State<Arc<Hits>>) -> impl IntoResponse {
+    hits.package_list.fetch_add(1, Ordering::SeqCst);
+    ([(header::CONTENT_TYPE, "application/json")], PACKAGES_JSON)
+}
+
+async fn default_config(State(hits): State<Arc<Hits>>) -> impl IntoResponse {
+    hits.default_config.fetch_add(1, Ordering::SeqCst);
+    zip_response(build_zip(&[
+        ("BepInEx/config/HQoL.cfg", "[General]\nEnabled = true\n"),
+        ("config/BepInEx.cfg", "[Logging]\nLogLevels = All\n"),
+    ]))
+}
+
+async fn package_download(
+    State(hits): State<Arc<Hits>>,
+    UrlPath((dev, name, version)): UrlPath<(String, String, String)>,
+) -> impl IntoResponse {
+    hits.package_download.fetch_add(1, Ordering::SeqCst);
+    if dev == "BepInEx" {
+        return zip_response(build_zip(&[
+            ("manifest.json", "{\"name\":\"BepInExPack\"}"),
+            ("BepInExPack/winhttp.dll", "fake doorstop"),
+            (
+                "BepInExPack/doorstop_config.ini",
+                "[General]\nenabled = true\ntarget_assembly = BepInEx\\core\\BepInEx.Preloader.dll\n",
+            ),
+            ("BepInExPack/BepInEx/core/BepInEx.Preloader.dll", "fake preloader"),
+        ]));
+    }
+    let manifest = format!(
+        "{{\"name\":\"{name}\",\"version_number\":\"{version}\",\"website_url\":\"\",\"description\":\"fixture\",\"dependencies\":[]}}"
+    );
+    let dll = format!("fake {dev}-{name}-{version}");
+    zip_response(build_zip(&[
+        ("manifest.json", manifest.as_str()),
+        (&format!("BepInEx/plugins/{name}.dll"), dll.as_str()),
+    ]))
+}
+
+fn zip_response(bytes: Vec<u8>) -> impl IntoResponse {
+    ([(header::CONTENT_TYPE, "application/zip")], bytes)
+}
+
+/// Build an in-memory zip from `(path, contents)` pairs.
+pub fn build_zip(files: &[(&str, &str)]) -> Vec<u8> {
+    let mut w = zip::ZipWriter::new(std::io::Cursor::new(Vec::new()));
+    let opts = zip::write::SimpleFileOptions::default();
+    for (path, contents) in files {
+        w.start_file(*path, opts).expect("zip start_file");
+        w.write_all(contents.as_bytes()).expect("zip write");
+    }
+    w.finish().expect("zip finish").into_inner()
+}
+
+/// Scratch dir under the OS temp dir, removed on drop.
+pub struct TempDir(PathBuf);
+
+impl TempDir {
+    pub fn new(label: &str) -> Self {
+        let n = TEMP_SEQ.fetch_add(1, Ordering::SeqCst);
+        let dir = std::env::temp_dir().join(format!(
+            "hq-launcher-test-{label}-{}-{n}",
+            std::process::id()
+        ));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).expect("create temp dir");
+        Self(dir)
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.0
+    }
+}
+
+impl Drop for TempDir {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.0);
+    }
+}
+
+/// GET `url` and write the body to `dest`.
+pub async fn download_to(url: &str, dest: &Path) {
+    let bytes = reqwest::Client::new()
+        .get(url)
+        .send()
+        .await
+        .expect("request")
+        .error_for_status()
+        .expect("status")
+        .bytes()
+        .await
+        .expect("body");
+    std::fs::write(dest, &bytes).expect("write download");
+}
+
+/// Outcome of one `run_headless` invocation.
+pub struct HeadlessRun {
+    /// Every NDJSON line the launcher printed, as `{"event": ..., "payload": ...}`.
+    pub events: Vec<serde_json::Value>,
+    pub status: std::process::ExitStatus,
+    pub stderr: String,
+}
+
+impl HeadlessRun {
+    /// Payload of the final `headless-result` event.
+    pub fn result(&self) -> Option<&serde_json::Value> {
+        self.events
+            .iter()
+            .rev()
+            .find(|e| e["event"] == "headless-result")
+            .map(|e| &e["payload"])
+    }
+
+    pub fn emitted(&self, event: &str) -> bool {
+        self.events.iter().any(|e| e["event"] == event)
+    }
+}
+
+/// App data dir of the launcher run by `run_headless` with `home`.
+pub fn launcher_data_dir(home: &Path) -> PathBuf {
+    home.join("data").join("asta.hq-launcher")
+}
+
+/// Run `hq-launcher --headless {args}` against the mock server started by the caller,
+/// with the fake game downloader and every XDG dir under `home`.
+///
+/// Linux only (the data dirs are redirected through XDG), and Tauri needs a display even
+/// though the headless mode closes its window right away: tests using this are
+/// `#[ignore]`d and run with `cargo test -- --ignored` on a desktop session.
+pub async fn run_headless(home: &Path, args: &[&str]) -> HeadlessRun {
+    let mut cmd = Command::new(env!("CARGO_BIN_EXE_hq-launcher"));
+    cmd.arg("--headless")
+        .args(args)
+        .env("HQ_LAUNCHER_FAKE_DOWNLOADER", "1");
+    for (var, dir) in [
+        ("XDG_DATA_HOME", "data"),
+        ("XDG_CONFIG_HOME", "config"),
+        ("XDG_CACHE_HOME", "cache"),
+        ("XDG_RUNTIME_DIR", "run"),
+    ] {
+        let dir = home.join(dir);
+        std::fs::create_dir_all(&dir).expect("create xdg dir");
+        cmd.env(var, dir);
+    }
+    let output = tokio::task::spawn_blocking(move || cmd.output())
+        .await
+        .expect("join launcher")
+        .expect("run launcher");
+    HeadlessRun {
+        events: String::from_utf8_lossy(&output.stdout)
+            .lines()
+            .filter_map(|l| serde_json::from_str(l).ok())
+            .collect(),
+        status: output.status,
+        stderr: String::from_utf8_lossy(&output.stderr).to_string(),
+    }
+}
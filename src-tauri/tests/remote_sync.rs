@@ -0,0 +1,257 @@
+//! End-to-end checks of the remote-facing install/sync building blocks against the
+//! mock server in `common`.
+
+mod common;
+
+use std::sync::atomic::Ordering;
+
+use common::{build_zip, download_to, MockServer, TempDir};
+#[cfg(target_os = "linux")]
+use common::{launcher_data_dir, run_headless};
+use hq_launcher_lib::mod_config::{self, ModsConfig};
+use hq_launcher_lib::{endpoints, thunderstore, zip_utils};
+
+#[tokio::test(flavor = "multi_thread")]
+async fn manifest_is_fetched_and_aliases_normalized() {
+    let server = MockServer::start().await;
+    let client = reqwest::Client::new();
+
+    let (version, cfg, chain_config, manifests) =
+        ModsConfig::fetch_manifest(&client).await.expect("fetch manifest");
+
+    assert_eq!(version, 7);
+    assert_eq!(manifests.get(&73).map(String::as_str), Some("2222222222222222222"));
+    assert_eq!(chain_config, vec![vec!["HQOL".to_string(), "HQoL".to_string()]]);
+
+    let hqol = cfg.mods.iter().find(|m| m.name == "HQoL").expect("HQoL entry");
+    assert_eq!(hqol.pinned_version_for(72), Some("0.1.0"));
+    assert_eq!(hqol.pinned_version_for(73), Some("0.2.0"));
+    assert!(!hqol.is_compatible(50));
+
+    // `LCMaxSoundFix` is a known typo that the fetch path rewrites.
+    let sounds = cfg.mods.iter().find(|m| m.dev == "Hardy").expect("Hardy entry");
+    assert_eq!(sounds.name, "LCMaxSoundsFix");
+    assert!(sounds.is_compatible(72));
+    assert!(!sounds.is_compatible(73));
+
+    assert_eq!(server.hits.manifest.load(Ordering::SeqCst), 1);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn manifest_alias_rewrites_are_reported() {
+    let _server = MockServer::start().await;
+    let client = reqwest::Client::new();
+
+    let remote = mod_config::fetch_remote_manifest(&client)
+        .await
+        .expect("fetch manifest");
+
+    assert_eq!(remote.corrections.len(), 1);
+    let fix = &remote.corrections[0];
+    assert_eq!((fix.dev.as_str(), fix.name.as_str()), ("Hardy", "LCMaxSoundFix"));
+    assert_eq!(fix.corrected_name, "LCMaxSoundsFix");
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn manifest_versions_accept_ids_and_labelled_objects() {
+    let _server = MockServer::start().await;
+    let client = reqwest::Client::new();
+
+    let remote = mod_config::fetch_remote_manifest(&client)
+        .await
+        .expect("fetch manifest");
+
+    let v56 = &remote.manifests[&56];
+    assert_eq!(v56.manifest_id, "1111111111111111111");
+    assert_eq!(v56.display_name, None);
+
+    let v73 = &remote.manifests[&73];
+    assert_eq!(v73.manifest_id, "2222222222222222222");
+    assert_eq!(v73.display_name.as_deref(), Some("Current Challenge Moon Patch"));
+    assert_eq!(v73.release_date.as_deref(), Some("2024-01-15"));
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn thunderstore_package_list_is_cached() {
+    let server = MockServer::start().await;
+    let client = reqwest::Client::new();
+    let tmp = TempDir::new("ts-cache");
+    let cache_path = tmp.path().join("cache").join("thunderstore.json");
+
+    let first = thunderstore::fetch_community_packages(&client, &cache_path)
+        .await
+        .expect("first fetch");
+    let second = thunderstore::fetch_community_packages(&client, &cache_path)
+        .await
+        .expect("cached fetch");
+
+    assert_eq!(first.len(), 2);
+    assert_eq!(second.len(), first.len());
+    assert!(cache_path.is_file());
+    assert_eq!(server.hits.package_list.load(Ordering::SeqCst), 1);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn default_config_extraction_is_add_only() {
+    let _server = MockServer::start().await;
+    let tmp = TempDir::new("default-config");
+    let zip_path = tmp.path().join("default_config.zip");
+    let shared = tmp.path().join("game_config").join("shared");
+    std::fs::create_dir_all(&shared).unwrap();
+    std::fs::write(shared.join("HQoL.cfg"), "user edited").unwrap();
+
+    download_to(&endpoints::default_config_url(), &zip_path).await;
+    zip_utils::extract_config_zip_into_bepinex_config_with_progress(&zip_path, &shared, |_, _, _| {})
+        .expect("extract config");
+
+    // Existing user file untouched, new file lands with its `config/` prefix stripped.
+    assert_eq!(std::fs::read_to_string(shared.join("HQoL.cfg")).unwrap(), "user edited");
+    assert!(shared.join("BepInEx.cfg").is_file());
+    assert!(!shared.join("config").exists());
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn mod_package_lands_in_its_plugins_folder() {
+    let server = MockServer::start().await;
+    let tmp = TempDir::new("mod-package");
+    let zip_path = tmp.path().join("HQoL.zip");
+    let plugins = tmp.path().join("BepInEx").join("plugins");
+
+    download_to(
+        &endpoints::thunderstore_download_url("HQHQTeam", "HQoL", "0.2.0"),
+        &zip_path,
+    )
+    .await;
+    zip_utils::extract_thunderstore_into_plugins_with_progress(
+        &zip_path,
+        &plugins,
+        "HQHQTeam-HQoL",
+        |_, _, _| {},
+    )
+    .expect("extract package");
+
+    let dll = plugins.join("HQHQTeam-HQoL").join("HQoL.dll");
+    assert_eq!(
+        std::fs::read_to_string(dll).unwrap(),
+        "fake HQHQTeam-HQoL-0.2.0"
+    );
+    assert_eq!(server.hits.package_download.load(Ordering::SeqCst), 1);
+}
+
+#[test]
+fn config_zip_files_are_keyed_relative_to_the_config_dir() {
+    let tmp = TempDir::new("config-zip-files");
+    let zip_path = tmp.path().join("default_config.zip");
+    std::fs::write(
+        &zip_path,
+        build_zip(&[
+            ("config/HQoL.cfg", "hqol"),
+            ("BepInEx/config/Sub/Other.cfg", "other"),
+            ("Plain.cfg", "plain"),
+        ]),
+    )
+    .unwrap();
+
+    let files = zip_utils::config_zip_files(&zip_path).expect("read zip");
+
+    let keys: Vec<&str> = files.keys().map(String::as_str).collect();
+    assert_eq!(keys, vec!["HQoL.cfg", "Plain.cfg", "Sub/Other.cfg"]);
+    assert_eq!(files["Sub/Other.cfg"], b"other");
+}
+
+#[cfg(target_os = "linux")]
+#[ignore = "needs a display"]
+#[tokio::test(flavor = "multi_thread")]
+async fn headless_install_then_sync_builds_a_playable_version() {
+    let server = MockServer::start().await;
+    let home = TempDir::new("headless-install");
+    let game = launcher_data_dir(home.path()).join("versions").join("v73");
+
+    let install = run_headless(home.path(), &["install", "73", "--acknowledge-eol"]).await;
+    assert_eq!(
+        install.result().map(|r| r["ok"].clone()),
+        Some(serde_json::Value::Bool(true)),
+        "install failed: {:?}\n{}",
+        install.result(),
+        install.stderr
+    );
+    assert!(install.status.success());
+    assert!(install.emitted("task-plan"));
+    assert!(install.emitted("download://progress"));
+
+    // Fake depot files, BepInEx from the package server, the v73 pin of the mod.
+    assert!(game.join("Lethal Company.exe").is_file());
+    assert!(game.join("BepInEx").join("core").join("BepInEx.Preloader.dll").is_file());
+    assert_eq!(
+        std::fs::read_to_string(game.join("BepInEx/plugins/HQHQTeam-HQoL/HQoL.dll")).unwrap(),
+        "fake HQHQTeam-HQoL-0.2.0"
+    );
+    // Default config came from the launcher base through the config link.
+    assert!(game.join("BepInEx").join("config").join("HQoL.cfg").is_file());
+
+    let downloads = server.hits.package_download.load(Ordering::SeqCst);
+    let sync = run_headless(home.path(), &["sync"]).await;
+    assert_eq!(
+        sync.result().map(|r| r["ok"].clone()),
+        Some(serde_json::Value::Bool(true)),
+        "sync failed: {:?}\n{}",
+        sync.result(),
+        sync.stderr
+    );
+    // Everything is current: the sync reuses the install instead of downloading again.
+    assert_eq!(server.hits.package_download.load(Ordering::SeqCst), downloads);
+    assert!(game.join("BepInEx/plugins/HQHQTeam-HQoL/HQoL.dll").is_file());
+}
+
+#[cfg(target_os = "linux")]
+#[ignore = "needs a display"]
+#[tokio::test(flavor = "multi_thread")]
+async fn headless_install_of_an_unknown_version_fails_cleanly() {
+    let _server = MockServer::start().await;
+    let home = TempDir::new("headless-unknown");
+
+    let run = run_headless(home.path(), &["install", "99", "--acknowledge-eol"]).await;
+
+    assert_eq!(run.status.code(), Some(1));
+    assert_eq!(
+        run.result().map(|r| r["ok"].clone()),
+        Some(serde_json::Value::Bool(false))
+    );
+    assert!(!launcher_data_dir(home.path()).join("versions").join("v99").exists());
+}
+
+#[cfg(target_os = "linux")]
+#[ignore = "needs a display"]
+#[tokio::test(flavor = "multi_thread")]
+async fn headless_sync_is_refused_during_a_mod_bisect() {
+    let _server = MockServer::start().await;
+    let home = TempDir::new("headless-bisect");
+    let game = launcher_data_dir(home.path()).join("versions").join("v73");
+
+    let install = run_headless(home.path(), &["install", "73", "--acknowledge-eol"]).await;
+    assert!(install.status.success(), "install failed: {}", install.stderr);
+
+    // What `start_bisect` leaves behind: the suspect moved aside, the state file.
+    let plugins = game.join("BepInEx").join("plugins");
+    let aside = game.join("BepInEx").join("plugins_bisect");
+    std::fs::create_dir_all(&aside).unwrap();
+    std::fs::rename(plugins.join("HQHQTeam-HQoL"), aside.join("HQHQTeam-HQoL")).unwrap();
+    std::fs::create_dir_all(game.join(".hq-launcher")).unwrap();
+    std::fs::write(
+        game.join(".hq-launcher").join("bisect.json"),
+        r#"{"step":1,"suspects":["HQHQTeam-HQoL"],"testing":[],"aside":["HQHQTeam-HQoL"]}"#,
+    )
+    .unwrap();
+
+    let sync = run_headless(home.path(), &["sync", "--mods-only", "--force"]).await;
+
+    assert_eq!(sync.status.code(), Some(1));
+    let error = sync.result().and_then(|r| r["error"].as_str().map(str::to_string));
+    assert!(
+        error.as_deref().is_some_and(|e| e.contains("bisect")),
+        "unexpected result: {error:?}"
+    );
+    // The sync left the bisect's layout alone.
+    assert!(!plugins.join("HQHQTeam-HQoL").exists());
+    assert!(aside.join("HQHQTeam-HQoL").join("HQoL.dll").is_file());
+}
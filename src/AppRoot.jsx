@@ -1,8 +1,10 @@
 import { useEffect, useRef, useState } from "react";
 import { invoke } from "@tauri-apps/api/core";
+import { listen } from "@tauri-apps/api/event";
 import LauncherPage from "./pages/LauncherPage";
 import { LoginDialog } from "./components/auth/LoginDialog";
 import { UpdateDialog } from "./components/UpdateDialog";
+import { DecisionDialog } from "./components/DecisionDialog";
 import Titlebar from "./Titlebar";
 
 function Splash({ message }) {
@@ -28,6 +30,7 @@ export default function AppRoot() {
   const [updateInfo, setUpdateInfo] = useState(null);
   const [updateDialogOpen, setUpdateDialogOpen] = useState(false);
   const updateCheckedRef = useRef(false);
+  const [announcement, setAnnouncement] = useState({ polite: "", assertive: "" });
 
   async function refreshLoginState() {
     try {
@@ -71,6 +74,24 @@ export default function AppRoot() {
     })();
   }, []);
 
+  // Screen-reader announcements from the backend (see src-tauri/src/announce.rs)
+  useEffect(() => {
+    let unlisten = null;
+    (async () => {
+      unlisten = await listen("a11y-announce", (event) => {
+        const { message, politeness } = event.payload ?? {};
+        if (!message) return;
+        const key = politeness === "assertive" ? "assertive" : "polite";
+        // Clear first so a repeated message is still a change and gets read again.
+        setAnnouncement((a) => ({ ...a, [key]: "" }));
+        setTimeout(() => setAnnouncement((a) => ({ ...a, [key]: message })), 50);
+      });
+    })();
+    return () => {
+      if (typeof unlisten === "function") unlisten();
+    };
+  }, []);
+
   function requestLogin() {
     setLoginOpen(true);
     return new Promise((resolve) => {
@@ -88,6 +109,12 @@ export default function AppRoot() {
   return (
     <div className="h-full w-full overflow-hidden">
       <Titlebar className="fixed top-0 left-0 h-10" />
+      <div className="sr-only" role="status" aria-live="polite" aria-atomic="true">
+        {announcement.polite}
+      </div>
+      <div className="sr-only" role="alert" aria-live="assertive" aria-atomic="true">
+        {announcement.assertive}
+      </div>
       <div className="relative h-[calc(100vh-32px)] w-full mt-10">
         {loginState.status === "loading" ? (
           <Splash message="Starting up..." />
@@ -119,6 +146,8 @@ export default function AppRoot() {
           onOpenChange={setUpdateDialogOpen}
           updateInfo={updateInfo}
         />
+
+        <DecisionDialog />
       </div>
     </div>
   );
@@ -0,0 +1,113 @@
+import { useEffect, useState } from "react";
+import { invoke } from "@tauri-apps/api/core";
+import { listen } from "@tauri-apps/api/event";
+import { Dialog, DialogContent } from "./ui/dialog";
+import { Button } from "./ui/button";
+
+// Questions a running task asks instead of guessing (see src-tauri/src/decisions.rs).
+export function DecisionDialog() {
+  const [queue, setQueue] = useState([]);
+  const [busy, setBusy] = useState(false);
+  const [error, setError] = useState("");
+
+  useEffect(() => {
+    let unlisten = null;
+    let cancelled = false;
+    const timers = [];
+    (async () => {
+      unlisten = await listen("task-decision-required", (event) => {
+        const p = event.payload;
+        if (!p?.task_id) return;
+        setQueue((q) => [...q.filter((d) => d.task_id !== p.task_id), p]);
+        // The backend picks the default once the question times out.
+        timers.push(
+          setTimeout(() => {
+            setQueue((q) => q.filter((d) => d.task_id !== p.task_id));
+          }, (p.timeout_secs ?? 0) * 1000),
+        );
+      });
+      if (cancelled) {
+        unlisten();
+        return;
+      }
+      // Until this runs, the backend answers questions with their default right away.
+      await invoke("set_decision_handler", { active: true }).catch(() => {});
+    })();
+    return () => {
+      cancelled = true;
+      timers.forEach(clearTimeout);
+      invoke("set_decision_handler", { active: false }).catch(() => {});
+      if (typeof unlisten === "function") unlisten();
+    };
+  }, []);
+
+  const current = queue[0] ?? null;
+
+  async function choose(choice) {
+    if (!current || busy) return;
+    setBusy(true);
+    setError("");
+    try {
+      await invoke("resolve_decision", { taskId: current.task_id, choice });
+      setQueue((q) => q.filter((d) => d.task_id !== current.task_id));
+    } catch (e) {
+      const msg = e?.message ?? String(e);
+      // Already answered (timed out): just drop it.
+      if (msg.includes("is not waiting for a decision")) {
+        setQueue((q) => q.filter((d) => d.task_id !== current.task_id));
+      } else {
+        setError(msg);
+      }
+    } finally {
+      setBusy(false);
+    }
+  }
+
+  return (
+    <Dialog open={!!current}>
+      <DialogContent
+        onEscapeKeyDown={(e) => e.preventDefault()}
+        onPointerDownOutside={(e) => e.preventDefault()}
+      >
+        {current && (
+          <div className="flex flex-col gap-4">
+            <div>
+              <div className="text-lg font-semibold">
+                {current.version != null ? `v${current.version}: ` : ""}Decision needed
+              </div>
+              <div className="mt-1 text-sm text-white/55">{current.message}</div>
+            </div>
+
+            {current.details?.length > 0 && (
+              <div className="max-h-40 overflow-auto rounded-2xl border border-white/10 bg-black/20 px-4 py-3 font-mono text-xs text-white/60">
+                {current.details.map((d) => (
+                  <div key={d}>{d}</div>
+                ))}
+              </div>
+            )}
+
+            {error && (
+              <div className="rounded-2xl border border-red-400/20 bg-red-400/10 px-3 py-2 text-xs text-red-200">
+                {error}
+              </div>
+            )}
+
+            <div className="flex items-center justify-end gap-2">
+              {current.options.map((o) => (
+                <Button
+                  key={o.id}
+                  variant={o.id === current.default ? "default" : "outline"}
+                  className="h-10"
+                  disabled={busy}
+                  onClick={() => choose(o.id)}
+                >
+                  {o.label}
+                </Button>
+              ))}
+            </div>
+          </div>
+        )}
+      </DialogContent>
+    </Dialog>
+  );
+}